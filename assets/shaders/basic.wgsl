@@ -2,6 +2,7 @@
 
 struct CameraUniform {
     view_proj: mat4x4<f32>,
+    view_position: vec4<f32>,
 };
 
 struct ModelUniform {
@@ -13,7 +14,13 @@ struct MaterialUniform {
     emissive: vec4<f32>,
     metallic: f32,
     roughness: f32,
-    _padding: vec2<f32>,
+    render_size: f32,
+    _padding: f32,
+    // See displacement.wgsl
+    displacement_noise: vec4<f32>,
+    displacement_wave: vec4<f32>,
+    displacement_spectrum: vec4<f32>,
+    displacement_bands: vec4<f32>,
 };
 
 @group(0) @binding(0)
@@ -25,11 +32,30 @@ var<uniform> model: ModelUniform;
 @group(2) @binding(0)
 var<uniform> material: MaterialUniform;
 
+@group(2) @binding(1)
+var material_sampler: sampler;
+
+@group(2) @binding(2)
+var albedo_map: texture_2d<f32>;
+
+@group(2) @binding(3)
+var normal_map: texture_2d<f32>;
+
+@group(2) @binding(4)
+var metallic_roughness_map: texture_2d<f32>;
+
+@group(2) @binding(5)
+var emissive_map: texture_2d<f32>;
+
+@group(2) @binding(6)
+var opacity_map: texture_2d<f32>;
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) normal: vec3<f32>,
     @location(2) uv: vec2<f32>,
     @location(3) color: vec3<f32>,
+    @location(4) tangent: vec4<f32>,
 };
 
 struct VertexOutput {
@@ -44,12 +70,13 @@ struct VertexOutput {
 fn vs_main(in: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     
-    let world_position = model.model * vec4<f32>(in.position, 1.0);
+    let displaced = displace_vertex(in.position, in.normal, in.tangent.xyz, in.uv);
+    let world_position = model.model * vec4<f32>(displaced.position, 1.0);
     out.world_position = world_position.xyz;
     out.clip_position = camera.view_proj * world_position;
     
     // Transform normal to world space (assuming uniform scaling)
-    out.world_normal = normalize((model.model * vec4<f32>(in.normal, 0.0)).xyz);
+    out.world_normal = normalize((model.model * vec4<f32>(displaced.normal, 0.0)).xyz);
     
     out.uv = in.uv;
     out.color = in.color;
@@ -68,9 +95,12 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let diffuse = max(dot(in.world_normal, light_dir), 0.0);
     let lighting = ambient + diffuse * 0.7;
     
-    // Combine material color with vertex color
-    let base_color = material.color.rgb * in.color;
-    let final_color = base_color * lighting + material.emissive.rgb;
+    // Combine material color with vertex color and texture maps
+    let albedo = textureSample(albedo_map, material_sampler, in.uv);
+    let emissive = material.emissive.rgb * textureSample(emissive_map, material_sampler, in.uv).rgb;
+    let opacity = textureSample(opacity_map, material_sampler, in.uv).r;
+    let base_color = material.color.rgb * in.color * albedo.rgb;
+    let final_color = base_color * lighting + emissive;
     
-    return vec4<f32>(final_color, material.color.a);
+    return vec4<f32>(final_color, material.color.a * albedo.a * opacity);
 }
@@ -1,10 +1,19 @@
-// Fullscreen blit shader for copying render target to preview window
+// Fullscreen blit shader for copying a render target to a window
+// The viewport places the image; `uv_min`/`uv_max` select the part shown
+
+struct BlitUniform {
+    uv_min: vec2<f32>,
+    uv_max: vec2<f32>,
+}
 
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) uv: vec2<f32>,
 }
 
+@group(0) @binding(2)
+var<uniform> params: BlitUniform;
+
 // Vertex shader - generates a fullscreen triangle
 @vertex
 fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
@@ -15,7 +24,7 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
     let y = f32(vertex_index & 2u);
     
     output.position = vec4<f32>(x * 2.0 - 1.0, 1.0 - y * 2.0, 0.0, 1.0);
-    output.uv = vec2<f32>(x, y);
+    output.uv = mix(params.uv_min, params.uv_max, vec2<f32>(x, y));
     
     return output;
 }
@@ -0,0 +1,93 @@
+// Layer compositing: blends one layer over the accumulated result,
+// or crossfades two deck results (mode 7)
+
+struct CompositeUniform {
+    // 0 normal, 1 add, 2 screen, 3 multiply, 4 overlay, 5 difference,
+    // 6 luma key, 7 crossfade
+    mode: u32,
+    opacity: f32,
+    key_threshold: f32,
+    key_softness: f32,
+    // Crossfade weights for the base (deck A) and layer (deck B)
+    weight_a: f32,
+    weight_b: f32,
+    _padding: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+// Vertex shader - generates a fullscreen triangle
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var output: VertexOutput;
+
+    let x = f32((vertex_index << 1u) & 2u);
+    let y = f32(vertex_index & 2u);
+
+    output.position = vec4<f32>(x * 2.0 - 1.0, 1.0 - y * 2.0, 0.0, 1.0);
+    output.uv = vec2<f32>(x, y);
+
+    return output;
+}
+
+@group(0) @binding(0)
+var t_base: texture_2d<f32>;
+@group(0) @binding(1)
+var t_layer: texture_2d<f32>;
+@group(0) @binding(2)
+var s_layer: sampler;
+@group(0) @binding(3)
+var<uniform> params: CompositeUniform;
+
+fn luma(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+fn overlay(base: vec3<f32>, layer: vec3<f32>) -> vec3<f32> {
+    let low = 2.0 * base * layer;
+    let high = 1.0 - 2.0 * (1.0 - base) * (1.0 - layer);
+    return select(high, low, base < vec3<f32>(0.5));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let base = textureSample(t_base, s_layer, in.uv);
+    let layer = textureSample(t_layer, s_layer, in.uv);
+
+    if (params.mode == 7u) {
+        return base * params.weight_a + layer * params.weight_b;
+    }
+
+    var blended = layer.rgb;
+    var coverage = layer.a * params.opacity;
+    switch params.mode {
+        case 1u: {
+            blended = base.rgb + layer.rgb;
+        }
+        case 2u: {
+            blended = 1.0 - (1.0 - base.rgb) * (1.0 - layer.rgb);
+        }
+        case 3u: {
+            blended = base.rgb * layer.rgb;
+        }
+        case 4u: {
+            blended = overlay(base.rgb, layer.rgb);
+        }
+        case 5u: {
+            blended = abs(base.rgb - layer.rgb);
+        }
+        case 6u: {
+            // Dark parts of the layer become transparent
+            let threshold = params.key_threshold;
+            coverage *= smoothstep(threshold, threshold + max(params.key_softness, 1e-4), luma(layer.rgb));
+        }
+        default: {}
+    }
+
+    let color = mix(base.rgb, blended, coverage);
+    let alpha = base.a + coverage * (1.0 - base.a);
+    return vec4<f32>(color, alpha);
+}
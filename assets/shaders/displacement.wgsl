@@ -0,0 +1,178 @@
+// Vertex displacement along normals, shared by the mesh shaders
+//
+// Prepended to basic/pbr/instanced; those declare `material` with the
+// displacement fields of MaterialUniform. The frame uniform lives here.
+
+struct FrameUniform {
+    time: f32,
+    delta: f32,
+    beat: f32,
+    _padding: f32,
+    resolution: vec2<f32>,
+    _padding2: vec2<f32>,
+    // Band levels in AudioBand order, energy last
+    bands: array<vec4<f32>, 2>,
+};
+
+@group(0) @binding(1)
+var<uniform> frame: FrameUniform;
+
+struct Displaced {
+    position: vec3<f32>,
+    normal: vec3<f32>,
+    tangent: vec3<f32>,
+};
+
+// Band level by AudioBand index (7 = energy); negative means no band
+fn band_level(index: f32) -> f32 {
+    if (index < 0.0) {
+        return 0.0;
+    }
+    let i = u32(index);
+    return frame.bands[i / 4u][i % 4u];
+}
+
+// Spectrum level across the seven bands, interpolated, for x in 0..1
+fn spectrum_level(x: f32) -> f32 {
+    let f = clamp(x, 0.0, 1.0) * 6.0;
+    let i = min(u32(f), 5u);
+    let a = frame.bands[i / 4u][i % 4u];
+    let b = frame.bands[(i + 1u) / 4u][(i + 1u) % 4u];
+    return mix(a, b, f - f32(i));
+}
+
+// 3D simplex noise in -1..1 (Gustavson / McEwan)
+fn mod289_3(x: vec3<f32>) -> vec3<f32> {
+    return x - floor(x * (1.0 / 289.0)) * 289.0;
+}
+
+fn mod289_4(x: vec4<f32>) -> vec4<f32> {
+    return x - floor(x * (1.0 / 289.0)) * 289.0;
+}
+
+fn permute(x: vec4<f32>) -> vec4<f32> {
+    return mod289_4(((x * 34.0) + 10.0) * x);
+}
+
+fn simplex3(v: vec3<f32>) -> f32 {
+    let c = vec2<f32>(1.0 / 6.0, 1.0 / 3.0);
+
+    // First corner
+    var i = floor(v + dot(v, vec3<f32>(c.y)));
+    let x0 = v - i + dot(i, vec3<f32>(c.x));
+
+    // Other corners
+    let g = step(x0.yzx, x0.xyz);
+    let l = 1.0 - g;
+    let i1 = min(g.xyz, l.zxy);
+    let i2 = max(g.xyz, l.zxy);
+    let x1 = x0 - i1 + c.x;
+    let x2 = x0 - i2 + c.y;
+    let x3 = x0 - 0.5;
+
+    // Permutations
+    i = mod289_3(i);
+    let p = permute(permute(permute(
+        i.z + vec4<f32>(0.0, i1.z, i2.z, 1.0))
+        + i.y + vec4<f32>(0.0, i1.y, i2.y, 1.0))
+        + i.x + vec4<f32>(0.0, i1.x, i2.x, 1.0));
+
+    // Gradients on a 7x7 grid over the octahedron
+    let ns = vec3<f32>(2.0 / 7.0, -0.5 + 1.0 / 49.0, 1.0 / 7.0);
+    let j = p - 49.0 * floor(p * ns.z * ns.z);
+    let x_ = floor(j * ns.z);
+    let y_ = floor(j - 7.0 * x_);
+    let x = x_ * ns.x + ns.y;
+    let y = y_ * ns.x + ns.y;
+    let h = 1.0 - abs(x) - abs(y);
+    let b0 = vec4<f32>(x.xy, y.xy);
+    let b1 = vec4<f32>(x.zw, y.zw);
+    let s0 = floor(b0) * 2.0 + 1.0;
+    let s1 = floor(b1) * 2.0 + 1.0;
+    let sh = -step(h, vec4<f32>(0.0));
+    let a0 = b0.xzyw + s0.xzyw * sh.xxyy;
+    let a1 = b1.xzyw + s1.xzyw * sh.zzww;
+
+    var p0 = vec3<f32>(a0.xy, h.x);
+    var p1 = vec3<f32>(a0.zw, h.y);
+    var p2 = vec3<f32>(a1.xy, h.z);
+    var p3 = vec3<f32>(a1.zw, h.w);
+    let norm = inverseSqrt(vec4<f32>(dot(p0, p0), dot(p1, p1), dot(p2, p2), dot(p3, p3)));
+    p0 *= norm.x;
+    p1 *= norm.y;
+    p2 *= norm.z;
+    p3 *= norm.w;
+
+    // Mix contributions from the four corners
+    var m = max(0.5 - vec4<f32>(dot(x0, x0), dot(x1, x1), dot(x2, x2), dot(x3, x3)), vec4<f32>(0.0));
+    m = m * m;
+    return 105.0 * dot(m * m, vec4<f32>(dot(p0, x0), dot(p1, x1), dot(p2, x2), dot(p3, x3)));
+}
+
+// Distance along the normal for an object-space point
+fn displacement_at(position: vec3<f32>, uv: vec2<f32>) -> f32 {
+    let noise = material.displacement_noise;
+    let wave = material.displacement_wave;
+    let spectrum = material.displacement_spectrum;
+    let bands = material.displacement_bands;
+    var d = 0.0;
+
+    // Noise drifts upward over time; the band pumps its amplitude
+    if (noise.x != 0.0) {
+        let sample_at = position * noise.y + vec3<f32>(0.0, -frame.time * noise.z, 0.0);
+        d += simplex3(sample_at) * noise.x * (1.0 + band_level(bands.x) * noise.w);
+    }
+
+    // Sine wave travelling up the object
+    if (wave.x != 0.0) {
+        let phase = position.y * wave.y - frame.time * wave.z;
+        d += sin(phase) * wave.x * (1.0 + band_level(bands.y) * wave.w);
+    }
+
+    // Spectrum: 0 maps by U, 1 by V, 2 by height within [z, w]
+    if (spectrum.x != 0.0) {
+        var x = uv.x;
+        if (spectrum.y > 1.5) {
+            x = (position.y - spectrum.z) / max(spectrum.w - spectrum.z, 1e-4);
+        } else if (spectrum.y > 0.5) {
+            x = uv.y;
+        }
+        d += spectrum_level(x) * spectrum.x;
+    }
+
+    return d;
+}
+
+// Displace a vertex and rebuild its normal and tangent from neighbouring
+// points offset along the surface. UV-mapped spectrum is treated as locally
+// constant, so it moves the surface without tilting the normal.
+fn displace_vertex(position: vec3<f32>, normal: vec3<f32>, tangent: vec3<f32>, uv: vec2<f32>) -> Displaced {
+    var out: Displaced;
+    out.position = position;
+    out.normal = normal;
+    out.tangent = tangent;
+    if (material.displacement_bands.z == 0.0) {
+        return out;
+    }
+
+    let n = normalize(normal);
+    var t = tangent - n * dot(n, tangent);
+    if (dot(t, t) < 1e-8) {
+        // No usable tangent: any perpendicular will do
+        t = cross(n, select(vec3<f32>(0.0, 1.0, 0.0), vec3<f32>(1.0, 0.0, 0.0), abs(n.y) > 0.9));
+    }
+    t = normalize(t);
+    let b = cross(n, t);
+
+    let epsilon = 0.01 / max(max(material.displacement_noise.y, material.displacement_wave.y), 1.0);
+    let p = position + n * displacement_at(position, uv);
+    let pt = position + t * epsilon;
+    let pb = position + b * epsilon;
+    let dt = pt + n * displacement_at(pt, uv) - p;
+    let db = pb + n * displacement_at(pb, uv) - p;
+
+    out.position = p;
+    out.normal = normalize(cross(dt, db));
+    out.tangent = normalize(dt);
+    return out;
+}
@@ -0,0 +1,120 @@
+// Instanced variant of the basic shader: one draw call, per-instance transform/color/custom
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    view_position: vec4<f32>,
+};
+
+struct ModelUniform {
+    model: mat4x4<f32>,
+};
+
+struct MaterialUniform {
+    color: vec4<f32>,
+    emissive: vec4<f32>,
+    metallic: f32,
+    roughness: f32,
+    render_size: f32,
+    _padding: f32,
+    // See displacement.wgsl
+    displacement_noise: vec4<f32>,
+    displacement_wave: vec4<f32>,
+    displacement_spectrum: vec4<f32>,
+    displacement_bands: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0)
+var<uniform> model: ModelUniform;
+
+@group(2) @binding(0)
+var<uniform> material: MaterialUniform;
+
+@group(2) @binding(1)
+var material_sampler: sampler;
+
+@group(2) @binding(2)
+var albedo_map: texture_2d<f32>;
+
+@group(2) @binding(3)
+var normal_map: texture_2d<f32>;
+
+@group(2) @binding(4)
+var metallic_roughness_map: texture_2d<f32>;
+
+@group(2) @binding(5)
+var emissive_map: texture_2d<f32>;
+
+@group(2) @binding(6)
+var opacity_map: texture_2d<f32>;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) color: vec3<f32>,
+    @location(4) tangent: vec4<f32>,
+};
+
+struct InstanceInput {
+    @location(5) model_0: vec4<f32>,
+    @location(6) model_1: vec4<f32>,
+    @location(7) model_2: vec4<f32>,
+    @location(8) model_3: vec4<f32>,
+    @location(9) color: vec4<f32>,
+    @location(10) custom: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) world_normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) color: vec4<f32>,
+    @location(4) custom: vec4<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput, instance: InstanceInput) -> VertexOutput {
+    var out: VertexOutput;
+    
+    let instance_model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    let world_model = model.model * instance_model;
+    
+    let displaced = displace_vertex(in.position, in.normal, in.tangent.xyz, in.uv);
+    let world_position = world_model * vec4<f32>(displaced.position, 1.0);
+    out.world_position = world_position.xyz;
+    out.clip_position = camera.view_proj * world_position;
+    
+    // Transform normal to world space (assuming uniform scaling)
+    out.world_normal = normalize((world_model * vec4<f32>(displaced.normal, 0.0)).xyz);
+    
+    out.uv = in.uv;
+    out.color = vec4<f32>(in.color, 1.0) * instance.color;
+    out.custom = instance.custom;
+    
+    return out;
+}
+
+// Fragment shader
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Simple directional light
+    let light_dir = normalize(vec3<f32>(0.5, 1.0, 0.3));
+    let ambient = 0.3;
+    
+    let diffuse = max(dot(normalize(in.world_normal), light_dir), 0.0);
+    let lighting = ambient + diffuse * 0.7;
+    
+    // Combine material color with instance/vertex color and texture maps
+    let albedo = textureSample(albedo_map, material_sampler, in.uv);
+    let emissive = material.emissive.rgb * textureSample(emissive_map, material_sampler, in.uv).rgb;
+    let opacity = textureSample(opacity_map, material_sampler, in.uv).r;
+    let base_color = material.color.rgb * in.color.rgb * albedo.rgb;
+    let final_color = base_color * lighting + emissive;
+    
+    return vec4<f32>(final_color, material.color.a * in.color.a * albedo.a * opacity);
+}
@@ -0,0 +1,15 @@
+// Plasma that speeds up with the bass and flashes on beats
+
+fn shade(uv: vec2<f32>) -> vec4<f32> {
+    let aspect = frame.resolution.x / max(frame.resolution.y, 1.0);
+    let p = (uv - 0.5) * vec2<f32>(aspect, 1.0) * 6.0;
+    let t = frame.time * (0.5 + band(1u));
+
+    var v = sin(p.x + t);
+    v += sin((p.y + t) * 0.5);
+    v += sin((p.x + p.y + t) * 0.5);
+    v += sin(length(p) + t * 1.5);
+
+    let color = 0.5 + 0.5 * cos(vec3<f32>(0.0, 2.1, 4.2) + v * 1.5);
+    return vec4<f32>(color * (0.8 + frame.beat * 0.4), 1.0);
+}
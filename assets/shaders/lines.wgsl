@@ -0,0 +1,150 @@
+// Thick lines as screen-space quads, for mesh edges and world-space polylines
+//
+// `vs_edges`/`fs_edges` draw a mesh's feature edges with its model transform,
+// material and displacement. `vs_main`/`fs_main` draw polylines in world space
+// and only use group 0.
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    view_position: vec4<f32>,
+};
+
+struct ModelUniform {
+    model: mat4x4<f32>,
+};
+
+struct MaterialUniform {
+    color: vec4<f32>,
+    emissive: vec4<f32>,
+    metallic: f32,
+    roughness: f32,
+    render_size: f32,
+    _padding: f32,
+    // See displacement.wgsl
+    displacement_noise: vec4<f32>,
+    displacement_wave: vec4<f32>,
+    displacement_spectrum: vec4<f32>,
+    displacement_bands: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0)
+var<uniform> model: ModelUniform;
+
+@group(2) @binding(0)
+var<uniform> material: MaterialUniform;
+
+@group(2) @binding(1)
+var material_sampler: sampler;
+
+@group(2) @binding(2)
+var albedo_map: texture_2d<f32>;
+
+@group(2) @binding(3)
+var normal_map: texture_2d<f32>;
+
+@group(2) @binding(4)
+var metallic_roughness_map: texture_2d<f32>;
+
+@group(2) @binding(5)
+var emissive_map: texture_2d<f32>;
+
+@group(2) @binding(6)
+var opacity_map: texture_2d<f32>;
+
+
+struct LineInput {
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) start: vec4<f32>,
+    @location(1) end: vec3<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) start_normal: vec3<f32>,
+    @location(4) end_normal: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) side: f32,
+    @location(1) color: vec4<f32>,
+};
+
+// Quad corners: x selects the endpoint, y the side of the line
+const QUAD = array<vec2<f32>, 6>(
+    vec2<f32>(0.0, -1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0),
+    vec2<f32>(0.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(0.0, 1.0),
+);
+
+// Corner of a `width` pixel quad around the clip-space segment a-b,
+// extended by half the width at both ends (square caps)
+fn segment_corner(a: vec4<f32>, b: vec4<f32>, width: f32, index: u32) -> vec4<f32> {
+    // Clip to just in front of the camera so projected endpoints stay valid
+    let near = 1e-4;
+    if (a.w < near && b.w < near) {
+        return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    }
+    var p0 = a;
+    var p1 = b;
+    if (p0.w < near) {
+        p0 = mix(p0, p1, (near - p0.w) / (p1.w - p0.w));
+    }
+    if (p1.w < near) {
+        p1 = mix(p1, p0, (near - p1.w) / (p0.w - p1.w));
+    }
+
+    let half_resolution = frame.resolution * 0.5;
+    let s0 = p0.xy / p0.w * half_resolution;
+    let s1 = p1.xy / p1.w * half_resolution;
+    var direction = vec2<f32>(1.0, 0.0);
+    if (distance(s0, s1) > 1e-6) {
+        direction = normalize(s1 - s0);
+    }
+    let normal = vec2<f32>(-direction.y, direction.x);
+
+    let corner = QUAD[index];
+    let p = select(p0, p1, corner.x > 0.5);
+    let offset = (normal * corner.y + direction * (corner.x * 2.0 - 1.0)) * width * 0.5;
+    return vec4<f32>(p.xy + offset / half_resolution * p.w, p.zw);
+}
+
+// Antialiased coverage across the line
+fn line_coverage(side: f32) -> f32 {
+    let distance = abs(side);
+    return 1.0 - smoothstep(1.0 - fwidth(distance), 1.0, distance);
+}
+
+@vertex
+fn vs_main(in: LineInput) -> VertexOutput {
+    var out: VertexOutput;
+    let a = camera.view_proj * vec4<f32>(in.start.xyz, 1.0);
+    let b = camera.view_proj * vec4<f32>(in.end, 1.0);
+    out.clip_position = segment_corner(a, b, in.start.w, in.vertex_index);
+    out.side = QUAD[in.vertex_index].y;
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(in.color.rgb, in.color.a * line_coverage(in.side));
+}
+
+@vertex
+fn vs_edges(in: LineInput) -> VertexOutput {
+    var out: VertexOutput;
+    let start = displace_vertex(in.start.xyz, in.start_normal, vec3<f32>(0.0), vec2<f32>(0.0));
+    let end = displace_vertex(in.end, in.end_normal, vec3<f32>(0.0), vec2<f32>(0.0));
+    let a = camera.view_proj * model.model * vec4<f32>(start.position, 1.0);
+    let b = camera.view_proj * model.model * vec4<f32>(end.position, 1.0);
+    out.clip_position = segment_corner(a, b, material.render_size, in.vertex_index);
+    out.side = QUAD[in.vertex_index].y;
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs_edges(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = material.color.rgb * in.color.rgb + material.emissive.rgb;
+    return vec4<f32>(color, material.color.a * in.color.a * line_coverage(in.side));
+}
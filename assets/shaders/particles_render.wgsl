@@ -0,0 +1,142 @@
+// Billboard rendering for GPU particles
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    view_position: vec4<f32>,
+};
+
+struct ParticleParams {
+    emitter_model: mat4x4<f32>,
+    // shape (0 point, 1 sphere, 2 mesh surface), radius, speed, speed variance
+    emitter: vec4<f32>,
+    // lifetime min, lifetime max, drag, unused
+    lifetime: vec4<f32>,
+    gravity: vec4<f32>,
+    // strength, frequency, time, unused
+    curl: vec4<f32>,
+    // xyz position, w strength
+    attractors: array<vec4<f32>, 4>,
+    color_keys: array<vec4<f32>, 4>,
+    color_times: vec4<f32>,
+    size_keys: vec4<f32>,
+    size_times: vec4<f32>,
+    camera_right: vec4<f32>,
+    camera_up: vec4<f32>,
+    // spawn count, frame, attractor count, triangle count
+    counts: vec4<u32>,
+    // color key count, size key count, max particles, unused
+    curve_counts: vec4<u32>,
+    // delta time, elapsed time, unused, unused
+    timing: vec4<f32>,
+};
+
+struct Particle {
+    position: vec3<f32>,
+    age: f32,
+    velocity: vec3<f32>,
+    lifetime: f32,
+    seed: f32,
+    size_scale: f32,
+    _padding: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0)
+var<uniform> params: ParticleParams;
+
+@group(1) @binding(1)
+var<storage, read> particles: array<Particle>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+// Piecewise-linear color over normalized age
+fn color_over_life(t: f32) -> vec4<f32> {
+    let count = params.curve_counts.x;
+    if (count == 0u) {
+        return vec4<f32>(1.0);
+    }
+    var color = params.color_keys[0];
+    for (var i = 1u; i < count; i++) {
+        let t0 = params.color_times[i - 1u];
+        let t1 = params.color_times[i];
+        if (t >= t0) {
+            let f = clamp((t - t0) / max(t1 - t0, 1e-5), 0.0, 1.0);
+            color = mix(params.color_keys[i - 1u], params.color_keys[i], f);
+        }
+    }
+    return color;
+}
+
+// Piecewise-linear size over normalized age
+fn size_over_life(t: f32) -> f32 {
+    let count = params.curve_counts.y;
+    if (count == 0u) {
+        return 0.1;
+    }
+    var size = params.size_keys[0];
+    for (var i = 1u; i < count; i++) {
+        let t0 = params.size_times[i - 1u];
+        let t1 = params.size_times[i];
+        if (t >= t0) {
+            let f = clamp((t - t0) / max(t1 - t0, 1e-5), 0.0, 1.0);
+            size = mix(params.size_keys[i - 1u], params.size_keys[i], f);
+        }
+    }
+    return size;
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let p = particles[instance_index];
+
+    // Two triangles forming a camera-facing quad
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+    out.uv = corner * 0.5 + 0.5;
+
+    if (p.age >= p.lifetime) {
+        // Dead particle: collapse to a degenerate triangle
+        out.clip_position = vec4<f32>(0.0, 0.0, 0.0, 1.0);
+        out.color = vec4<f32>(0.0);
+        return out;
+    }
+
+    let t = clamp(p.age / p.lifetime, 0.0, 1.0);
+    let size = size_over_life(t) * p.size_scale;
+    let world_position = p.position
+        + params.camera_right.xyz * corner.x * size
+        + params.camera_up.xyz * corner.y * size;
+
+    out.clip_position = camera.view_proj * vec4<f32>(world_position, 1.0);
+    out.color = color_over_life(t);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Soft round sprite
+    let d = length(in.uv * 2.0 - 1.0);
+    let falloff = 1.0 - smoothstep(0.5, 1.0, d);
+    let alpha = in.color.a * falloff;
+    if (alpha <= 0.0) {
+        discard;
+    }
+    return vec4<f32>(in.color.rgb, alpha);
+}
@@ -0,0 +1,236 @@
+// GPU particle simulation: emission, forces and aging
+
+struct ParticleParams {
+    emitter_model: mat4x4<f32>,
+    // shape (0 point, 1 sphere, 2 mesh surface), radius, speed, speed variance
+    emitter: vec4<f32>,
+    // lifetime min, lifetime max, drag, unused
+    lifetime: vec4<f32>,
+    gravity: vec4<f32>,
+    // strength, frequency, time, unused
+    curl: vec4<f32>,
+    // xyz position, w strength
+    attractors: array<vec4<f32>, 4>,
+    color_keys: array<vec4<f32>, 4>,
+    color_times: vec4<f32>,
+    size_keys: vec4<f32>,
+    size_times: vec4<f32>,
+    camera_right: vec4<f32>,
+    camera_up: vec4<f32>,
+    // spawn count, frame, attractor count, triangle count
+    counts: vec4<u32>,
+    // color key count, size key count, max particles, unused
+    curve_counts: vec4<u32>,
+    // delta time, elapsed time, unused, unused
+    timing: vec4<f32>,
+};
+
+struct Particle {
+    position: vec3<f32>,
+    age: f32,
+    velocity: vec3<f32>,
+    lifetime: f32,
+    seed: f32,
+    size_scale: f32,
+    _padding: vec2<f32>,
+};
+
+struct Counters {
+    spawned: atomic<u32>,
+    alive: atomic<u32>,
+};
+
+// Emission triangle: three corners and a face normal
+struct SurfaceTriangle {
+    a: vec4<f32>,
+    b: vec4<f32>,
+    c: vec4<f32>,
+    normal: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> params: ParticleParams;
+
+@group(0) @binding(1)
+var<storage, read_write> particles: array<Particle>;
+
+@group(0) @binding(2)
+var<storage, read_write> counters: Counters;
+
+@group(0) @binding(3)
+var<storage, read> triangles: array<SurfaceTriangle>;
+
+// Normalized cumulative triangle area, for area-weighted sampling
+@group(0) @binding(4)
+var<storage, read> triangle_cdf: array<f32>;
+
+// PCG hash based random numbers
+fn pcg(v: u32) -> u32 {
+    let state = v * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+fn random(state: ptr<function, u32>) -> f32 {
+    *state = pcg(*state);
+    return f32(*state) / 4294967295.0;
+}
+
+fn random_unit_vector(state: ptr<function, u32>) -> vec3<f32> {
+    let z = random(state) * 2.0 - 1.0;
+    let a = random(state) * 6.28318530718;
+    let r = sqrt(max(1.0 - z * z, 0.0));
+    return vec3<f32>(r * cos(a), r * sin(a), z);
+}
+
+// Smooth 3D value noise
+fn hash3(p: vec3<f32>) -> f32 {
+    let q = vec3<u32>(vec3<i32>(floor(p)) + vec3<i32>(1 << 20u));
+    return f32(pcg(q.x ^ pcg(q.y ^ pcg(q.z)))) / 4294967295.0;
+}
+
+fn value_noise(p: vec3<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+
+    let n000 = hash3(i);
+    let n100 = hash3(i + vec3<f32>(1.0, 0.0, 0.0));
+    let n010 = hash3(i + vec3<f32>(0.0, 1.0, 0.0));
+    let n110 = hash3(i + vec3<f32>(1.0, 1.0, 0.0));
+    let n001 = hash3(i + vec3<f32>(0.0, 0.0, 1.0));
+    let n101 = hash3(i + vec3<f32>(1.0, 0.0, 1.0));
+    let n011 = hash3(i + vec3<f32>(0.0, 1.0, 1.0));
+    let n111 = hash3(i + vec3<f32>(1.0, 1.0, 1.0));
+
+    let x00 = mix(n000, n100, u.x);
+    let x10 = mix(n010, n110, u.x);
+    let x01 = mix(n001, n101, u.x);
+    let x11 = mix(n011, n111, u.x);
+    return mix(mix(x00, x10, u.y), mix(x01, x11, u.y), u.z) * 2.0 - 1.0;
+}
+
+// Vector potential built from three decorrelated noise fields
+fn potential(p: vec3<f32>) -> vec3<f32> {
+    return vec3<f32>(
+        value_noise(p),
+        value_noise(p + vec3<f32>(31.416, 47.853, 12.793)),
+        value_noise(p + vec3<f32>(-17.231, 93.989, -61.277)),
+    );
+}
+
+// Divergence-free curl of the noise potential (finite differences)
+fn curl_noise(p: vec3<f32>) -> vec3<f32> {
+    let e = 0.01;
+    let dx = vec3<f32>(e, 0.0, 0.0);
+    let dy = vec3<f32>(0.0, e, 0.0);
+    let dz = vec3<f32>(0.0, 0.0, e);
+
+    let p_dx = (potential(p + dx) - potential(p - dx)) / (2.0 * e);
+    let p_dy = (potential(p + dy) - potential(p - dy)) / (2.0 * e);
+    let p_dz = (potential(p + dz) - potential(p - dz)) / (2.0 * e);
+
+    return vec3<f32>(
+        p_dy.z - p_dz.y,
+        p_dz.x - p_dx.z,
+        p_dx.y - p_dy.x,
+    );
+}
+
+// Binary search the area CDF for a triangle index
+fn pick_triangle(r: f32) -> u32 {
+    var lo = 0u;
+    var hi = params.counts.w - 1u;
+    while (lo < hi) {
+        let mid = (lo + hi) / 2u;
+        if (triangle_cdf[mid] < r) {
+            lo = mid + 1u;
+        } else {
+            hi = mid;
+        }
+    }
+    return lo;
+}
+
+fn spawn(rng: ptr<function, u32>) -> Particle {
+    var p: Particle;
+
+    let shape = u32(params.emitter.x);
+    let radius = params.emitter.y;
+    var local_position = vec3<f32>(0.0);
+    var local_direction = random_unit_vector(rng);
+
+    if (shape == 1u) {
+        // Uniformly inside a sphere
+        local_position = local_direction * radius * pow(random(rng), 1.0 / 3.0);
+    } else if (shape == 2u && params.counts.w > 0u) {
+        let tri = triangles[pick_triangle(random(rng))];
+        var u = random(rng);
+        var v = random(rng);
+        if (u + v > 1.0) {
+            u = 1.0 - u;
+            v = 1.0 - v;
+        }
+        local_position = tri.a.xyz + (tri.b.xyz - tri.a.xyz) * u + (tri.c.xyz - tri.a.xyz) * v;
+        // Bias emission along the surface normal
+        local_direction = normalize(tri.normal.xyz + local_direction * 0.5);
+    }
+
+    p.position = (params.emitter_model * vec4<f32>(local_position, 1.0)).xyz;
+    let direction = normalize((params.emitter_model * vec4<f32>(local_direction, 0.0)).xyz);
+    let speed = params.emitter.z + (random(rng) * 2.0 - 1.0) * params.emitter.w;
+    p.velocity = direction * speed;
+    p.age = 0.0;
+    p.lifetime = mix(params.lifetime.x, params.lifetime.y, random(rng));
+    p.seed = random(rng);
+    p.size_scale = mix(0.75, 1.25, random(rng));
+    return p;
+}
+
+@compute @workgroup_size(64)
+fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if (index >= params.curve_counts.z) {
+        return;
+    }
+
+    var p = particles[index];
+    let dt = params.timing.x;
+    var rng = pcg(index ^ pcg(params.counts.y));
+
+    // Dead particles try to claim one of this frame's spawn slots
+    if (p.age >= p.lifetime) {
+        let slot = atomicAdd(&counters.spawned, 1u);
+        if (slot >= params.counts.x) {
+            return;
+        }
+        p = spawn(&rng);
+    }
+
+    // Forces
+    var acceleration = params.gravity.xyz;
+
+    if (params.curl.x != 0.0) {
+        let sample_position = p.position * params.curl.y + vec3<f32>(0.0, params.curl.z, 0.0);
+        acceleration += curl_noise(sample_position) * params.curl.x;
+    }
+
+    for (var i = 0u; i < params.counts.z; i++) {
+        let attractor = params.attractors[i];
+        let offset = attractor.xyz - p.position;
+        // Softened inverse-square pull, safe when a particle sits on the attractor
+        let distance_sq = dot(offset, offset) + 0.05;
+        acceleration += offset * (attractor.w / (distance_sq * sqrt(distance_sq)));
+    }
+
+    p.velocity += acceleration * dt;
+    p.velocity *= 1.0 / (1.0 + params.lifetime.z * dt);
+    p.position += p.velocity * dt;
+    p.age += dt;
+
+    if (p.age < p.lifetime) {
+        atomicAdd(&counters.alive, 1u);
+    }
+
+    particles[index] = p;
+}
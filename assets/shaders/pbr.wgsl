@@ -0,0 +1,202 @@
+// Physically-based shader (Cook-Torrance, GGX) for metallic/roughness materials
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    view_position: vec4<f32>,
+};
+
+struct ModelUniform {
+    model: mat4x4<f32>,
+};
+
+struct MaterialUniform {
+    color: vec4<f32>,
+    emissive: vec4<f32>,
+    metallic: f32,
+    roughness: f32,
+    render_size: f32,
+    _padding: f32,
+    // See displacement.wgsl
+    displacement_noise: vec4<f32>,
+    displacement_wave: vec4<f32>,
+    displacement_spectrum: vec4<f32>,
+    displacement_bands: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0)
+var<uniform> model: ModelUniform;
+
+@group(2) @binding(0)
+var<uniform> material: MaterialUniform;
+
+@group(2) @binding(1)
+var material_sampler: sampler;
+
+@group(2) @binding(2)
+var albedo_map: texture_2d<f32>;
+
+@group(2) @binding(3)
+var normal_map: texture_2d<f32>;
+
+@group(2) @binding(4)
+var metallic_roughness_map: texture_2d<f32>;
+
+@group(2) @binding(5)
+var emissive_map: texture_2d<f32>;
+
+@group(2) @binding(6)
+var opacity_map: texture_2d<f32>;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) color: vec3<f32>,
+    @location(4) tangent: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) world_normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) color: vec3<f32>,
+    @location(4) world_tangent: vec4<f32>,
+};
+
+const PI: f32 = 3.14159265359;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    let displaced = displace_vertex(in.position, in.normal, in.tangent.xyz, in.uv);
+    let world_position = model.model * vec4<f32>(displaced.position, 1.0);
+    out.world_position = world_position.xyz;
+    out.clip_position = camera.view_proj * world_position;
+
+    // Transform normal to world space (assuming uniform scaling)
+    out.world_normal = normalize((model.model * vec4<f32>(displaced.normal, 0.0)).xyz);
+
+    out.world_tangent = vec4<f32>(normalize((model.model * vec4<f32>(displaced.tangent, 0.0)).xyz), in.tangent.w);
+
+    out.uv = in.uv;
+    out.color = in.color;
+
+    return out;
+}
+
+// GGX / Trowbridge-Reitz normal distribution
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let a = roughness * roughness;
+    let a2 = a * a;
+    let d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / max(PI * d * d, 1e-6);
+}
+
+// Smith geometry term with Schlick-GGX for direct lighting
+fn geometry_smith(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+    let r = roughness + 1.0;
+    let k = (r * r) / 8.0;
+    let gv = n_dot_v / (n_dot_v * (1.0 - k) + k);
+    let gl = n_dot_l / (n_dot_l * (1.0 - k) + k);
+    return gv * gl;
+}
+
+fn fresnel_schlick(cos_theta: f32, f0: vec3<f32>) -> vec3<f32> {
+    return f0 + (1.0 - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+fn fresnel_schlick_roughness(cos_theta: f32, f0: vec3<f32>, roughness: f32) -> vec3<f32> {
+    let f90 = max(vec3<f32>(1.0 - roughness), f0);
+    return f0 + (f90 - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+// Procedural sky/ground environment. Higher roughness flattens the gradient,
+// which stands in for a prefiltered (blurred) environment map.
+fn sample_environment(dir: vec3<f32>, roughness: f32) -> vec3<f32> {
+    let zenith = vec3<f32>(0.25, 0.35, 0.55);
+    let horizon = vec3<f32>(0.55, 0.55, 0.6);
+    let ground = vec3<f32>(0.12, 0.1, 0.09);
+
+    let sharpness = mix(8.0, 1.0, roughness);
+    let up = clamp(dir.y * sharpness, -1.0, 1.0);
+    let sky = mix(horizon, zenith, max(up, 0.0));
+    let env = mix(sky, ground, max(-up, 0.0));
+
+    // Soft sun highlight, fading out as the lobe widens
+    let sun_dir = normalize(vec3<f32>(0.5, 1.0, 0.3));
+    let sun_power = mix(256.0, 4.0, roughness);
+    let sun = pow(max(dot(dir, sun_dir), 0.0), sun_power) * mix(4.0, 0.2, roughness);
+
+    return env + vec3<f32>(sun);
+}
+
+// Analytic approximation of the split-sum BRDF integration (Karis 2014)
+fn env_brdf_approx(f0: vec3<f32>, roughness: f32, n_dot_v: f32) -> vec3<f32> {
+    let c0 = vec4<f32>(-1.0, -0.0275, -0.572, 0.022);
+    let c1 = vec4<f32>(1.0, 0.0425, 1.04, -0.04);
+    let r = roughness * c0 + c1;
+    let a004 = min(r.x * r.x, exp2(-9.28 * n_dot_v)) * r.x + r.y;
+    let ab = vec2<f32>(-1.04, 1.04) * a004 + r.zw;
+    return f0 * ab.x + ab.y;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let albedo_sample = textureSample(albedo_map, material_sampler, in.uv);
+    let albedo = material.color.rgb * in.color * albedo_sample.rgb;
+
+    // glTF layout: roughness in green, metallic in blue
+    let mr_sample = textureSample(metallic_roughness_map, material_sampler, in.uv);
+    let metallic = clamp(material.metallic * mr_sample.b, 0.0, 1.0);
+    // Clamp to avoid a singular highlight on perfectly smooth surfaces
+    let roughness = clamp(material.roughness * mr_sample.g, 0.04, 1.0);
+
+    // Tangent-space normal mapping
+    let geometric_normal = normalize(in.world_normal);
+    let t = normalize(in.world_tangent.xyz - geometric_normal * dot(geometric_normal, in.world_tangent.xyz));
+    let b = cross(geometric_normal, t) * in.world_tangent.w;
+    let tangent_normal = textureSample(normal_map, material_sampler, in.uv).xyz * 2.0 - 1.0;
+    let n = normalize(mat3x3<f32>(t, b, geometric_normal) * tangent_normal);
+    let v = normalize(camera.view_position.xyz - in.world_position);
+    let n_dot_v = max(dot(n, v), 1e-4);
+
+    let f0 = mix(vec3<f32>(0.04), albedo, metallic);
+
+    // Direct lighting from a single directional light
+    let light_dir = normalize(vec3<f32>(0.5, 1.0, 0.3));
+    let light_color = vec3<f32>(3.0);
+    let h = normalize(v + light_dir);
+    let n_dot_l = max(dot(n, light_dir), 0.0);
+    let n_dot_h = max(dot(n, h), 0.0);
+    let h_dot_v = max(dot(h, v), 0.0);
+
+    let d = distribution_ggx(n_dot_h, roughness);
+    let g = geometry_smith(n_dot_v, n_dot_l, roughness);
+    let f = fresnel_schlick(h_dot_v, f0);
+
+    let specular = d * g * f / max(4.0 * n_dot_v * n_dot_l, 1e-4);
+    let k_d = (vec3<f32>(1.0) - f) * (1.0 - metallic);
+    let direct = (k_d * albedo / PI + specular) * light_color * n_dot_l;
+
+    // Image-based ambient term from the procedural environment
+    let f_ambient = fresnel_schlick_roughness(n_dot_v, f0, roughness);
+    let k_d_ambient = (vec3<f32>(1.0) - f_ambient) * (1.0 - metallic);
+    let irradiance = sample_environment(n, 1.0);
+    let diffuse_ambient = k_d_ambient * irradiance * albedo;
+    let r = reflect(-v, n);
+    let prefiltered = sample_environment(r, roughness);
+    let specular_ambient = prefiltered * env_brdf_approx(f0, roughness, n_dot_v);
+    let ambient = diffuse_ambient + specular_ambient;
+
+    // Emissive is added unlit so values above 1.0 survive for HDR/bloom
+    let emissive = material.emissive.rgb * textureSample(emissive_map, material_sampler, in.uv).rgb;
+    let final_color = direct + ambient + emissive;
+
+    let opacity = textureSample(opacity_map, material_sampler, in.uv).r;
+    return vec4<f32>(final_color, material.color.a * albedo_sample.a * opacity);
+}
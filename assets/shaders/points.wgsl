@@ -0,0 +1,102 @@
+// Point cloud: a round, screen-aligned sprite at every vertex
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    view_position: vec4<f32>,
+};
+
+struct ModelUniform {
+    model: mat4x4<f32>,
+};
+
+struct MaterialUniform {
+    color: vec4<f32>,
+    emissive: vec4<f32>,
+    metallic: f32,
+    roughness: f32,
+    render_size: f32,
+    _padding: f32,
+    // See displacement.wgsl
+    displacement_noise: vec4<f32>,
+    displacement_wave: vec4<f32>,
+    displacement_spectrum: vec4<f32>,
+    displacement_bands: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0)
+var<uniform> model: ModelUniform;
+
+@group(2) @binding(0)
+var<uniform> material: MaterialUniform;
+
+@group(2) @binding(1)
+var material_sampler: sampler;
+
+@group(2) @binding(2)
+var albedo_map: texture_2d<f32>;
+
+@group(2) @binding(3)
+var normal_map: texture_2d<f32>;
+
+@group(2) @binding(4)
+var metallic_roughness_map: texture_2d<f32>;
+
+@group(2) @binding(5)
+var emissive_map: texture_2d<f32>;
+
+@group(2) @binding(6)
+var opacity_map: texture_2d<f32>;
+
+struct VertexInput {
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) color: vec3<f32>,
+    @location(4) tangent: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) corner: vec2<f32>,
+    @location(1) color: vec3<f32>,
+};
+
+// Two triangles covering -1..1
+const CORNERS = array<vec2<f32>, 6>(
+    vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0),
+    vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(-1.0, 1.0),
+);
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    // One instance per mesh vertex, six vertices per instance
+    let displaced = displace_vertex(in.position, in.normal, in.tangent.xyz, in.uv);
+    let center = camera.view_proj * model.model * vec4<f32>(displaced.position, 1.0);
+
+    // Offset in clip space so the sprite is render_size pixels wide at any depth
+    let corner = CORNERS[in.vertex_index];
+    let offset = corner * material.render_size / frame.resolution * center.w;
+    out.clip_position = vec4<f32>(center.xy + offset, center.zw);
+    out.corner = corner;
+    out.color = in.color;
+
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let radius = length(in.corner);
+    let coverage = 1.0 - smoothstep(1.0 - fwidth(radius), 1.0, radius);
+    if (coverage <= 0.0) {
+        discard;
+    }
+
+    let color = material.color.rgb * in.color + material.emissive.rgb;
+    return vec4<f32>(color, material.color.a * coverage);
+}
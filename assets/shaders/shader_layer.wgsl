@@ -0,0 +1,49 @@
+// Prelude for fullscreen shader layers
+//
+// Layer files are appended to this one and define
+//     fn shade(uv: vec2<f32>) -> vec4<f32>
+// returning straight (not premultiplied) alpha. `frame` holds time,
+// beat, resolution and band levels, as in displacement.wgsl.
+
+struct FrameUniform {
+    time: f32,
+    delta: f32,
+    beat: f32,
+    _padding: f32,
+    resolution: vec2<f32>,
+    _padding2: vec2<f32>,
+    // Band levels in AudioBand order, energy last
+    bands: array<vec4<f32>, 2>,
+};
+
+@group(0) @binding(0)
+var<uniform> frame: FrameUniform;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+// Band level by AudioBand index (7 = energy)
+fn band(index: u32) -> f32 {
+    return frame.bands[index / 4u][index % 4u];
+}
+
+// Vertex shader - generates a fullscreen triangle
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var output: VertexOutput;
+
+    let x = f32((vertex_index << 1u) & 2u);
+    let y = f32(vertex_index & 2u);
+
+    output.position = vec4<f32>(x * 2.0 - 1.0, 1.0 - y * 2.0, 0.0, 1.0);
+    output.uv = vec2<f32>(x, y);
+
+    return output;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return shade(in.uv);
+}
@@ -0,0 +1,192 @@
+// Tone mapping pass: HDR (Rgba16Float) scene to display or export format,
+// followed by colour grading and a 3D LUT on the display-encoded colour
+
+struct ToneMapUniform {
+    // 0 none (clamp), 1 Reinhard, 2 ACES, 3 AgX
+    curve: u32,
+    // 1 to encode sRGB in the shader (non-sRGB output formats)
+    encode_srgb: u32,
+    // Linear exposure multiplier, exp2(stops)
+    exposure: f32,
+    // Scene value mapped to display white
+    white_point: f32,
+}
+
+struct GradingUniform {
+    lift: vec4<f32>,
+    gamma: vec4<f32>,
+    gain: vec4<f32>,
+    // LUT input range
+    domain_min: vec4<f32>,
+    domain_max: vec4<f32>,
+    saturation: f32,
+    // Radians
+    hue: f32,
+    contrast: f32,
+    // 0 when no LUT is loaded
+    lut_intensity: f32,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+// Vertex shader - generates a fullscreen triangle
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var output: VertexOutput;
+
+    let x = f32((vertex_index << 1u) & 2u);
+    let y = f32(vertex_index & 2u);
+
+    output.position = vec4<f32>(x * 2.0 - 1.0, 1.0 - y * 2.0, 0.0, 1.0);
+    output.uv = vec2<f32>(x, y);
+
+    return output;
+}
+
+@group(0) @binding(0)
+var t_hdr: texture_2d<f32>;
+@group(0) @binding(1)
+var s_hdr: sampler;
+@group(0) @binding(2)
+var<uniform> params: ToneMapUniform;
+@group(0) @binding(3)
+var t_lut: texture_3d<f32>;
+@group(0) @binding(4)
+var<uniform> grading: GradingUniform;
+
+// Extended Reinhard on luminance, reaching 1.0 at the white point
+fn reinhard(color: vec3<f32>, white: f32) -> vec3<f32> {
+    let luminance = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    let mapped = luminance * (1.0 + luminance / (white * white)) / (1.0 + luminance);
+    return color * (mapped / max(luminance, 1e-6));
+}
+
+// ACES filmic fit (Narkowicz 2015)
+fn aces_curve(x: vec3<f32>) -> vec3<f32> {
+    return (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14);
+}
+
+fn aces(color: vec3<f32>, white: f32) -> vec3<f32> {
+    return aces_curve(color) / aces_curve(vec3<f32>(white));
+}
+
+// Minimal AgX (Wrensch 2023), sigmoid fitted to Blender's default look
+fn agx_contrast(x: vec3<f32>) -> vec3<f32> {
+    let x2 = x * x;
+    let x4 = x2 * x2;
+    return 15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x - 0.00232;
+}
+
+fn agx_curve(color: vec3<f32>) -> vec3<f32> {
+    let inset = mat3x3<f32>(
+        0.842479062253094, 0.0423282422610123, 0.0423756549057051,
+        0.0784335999999992, 0.878468636469772, 0.0784336,
+        0.0792237451477643, 0.0791661274605434, 0.879142973793104,
+    );
+    let outset = mat3x3<f32>(
+        1.19687900512017, -0.0528968517574562, -0.0529716355144438,
+        -0.0980208811401368, 1.15190312990417, -0.0980434501171241,
+        -0.0990297440797205, -0.0989611768448433, 1.15107367264116,
+    );
+    let min_ev = -12.47393;
+    let max_ev = 4.026069;
+
+    var v = inset * color;
+    v = clamp(log2(max(v, vec3<f32>(1e-10))), vec3<f32>(min_ev), vec3<f32>(max_ev));
+    v = (v - min_ev) / (max_ev - min_ev);
+    v = agx_contrast(v);
+    // Back to linear so the output format's sRGB encoding applies once
+    return pow(max(outset * v, vec3<f32>(0.0)), vec3<f32>(2.2));
+}
+
+fn agx(color: vec3<f32>, white: f32) -> vec3<f32> {
+    // AgX already maps ~16.3 to white; rescale so `white` lands there
+    return agx_curve(color * (16.29 / white));
+}
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, color <= vec3<f32>(0.04045));
+}
+
+const LUMA = vec3<f32>(0.2126, 0.7152, 0.0722);
+
+// Lift/gamma/gain, contrast, saturation and hue on display-encoded colour
+fn grade(color: vec3<f32>) -> vec3<f32> {
+    var c = grading.gain.rgb * (color + grading.lift.rgb * (1.0 - color));
+    c = pow(max(c, vec3<f32>(0.0)), 1.0 / grading.gamma.rgb);
+    c = (c - 0.5) * grading.contrast + 0.5;
+    c = mix(vec3<f32>(dot(c, LUMA)), c, grading.saturation);
+
+    // Rotate around the grey axis
+    let axis = vec3<f32>(0.57735027);
+    let cos_hue = cos(grading.hue);
+    c = c * cos_hue + cross(axis, c) * sin(grading.hue) + axis * dot(axis, c) * (1.0 - cos_hue);
+
+    return clamp(c, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Trilinear LUT lookup; float32 textures can't use a filtering sampler everywhere
+fn apply_lut(color: vec3<f32>) -> vec3<f32> {
+    let size = textureDimensions(t_lut);
+    let last = vec3<f32>(size - 1u);
+    let range = grading.domain_max.rgb - grading.domain_min.rgb;
+    let coord = clamp((color - grading.domain_min.rgb) / range, vec3<f32>(0.0), vec3<f32>(1.0)) * last;
+    let base = min(vec3<u32>(floor(coord)), size - 2u);
+    let t = coord - vec3<f32>(base);
+
+    var corners: array<vec3<f32>, 8>;
+    for (var i = 0u; i < 8u; i++) {
+        let offset = vec3<u32>(i & 1u, (i >> 1u) & 1u, (i >> 2u) & 1u);
+        corners[i] = textureLoad(t_lut, base + offset, 0).rgb;
+    }
+    let x0 = mix(mix(corners[0], corners[1], t.x), mix(corners[2], corners[3], t.x), t.y);
+    let x1 = mix(mix(corners[4], corners[5], t.x), mix(corners[6], corners[7], t.x), t.y);
+    return mix(x0, x1, t.z);
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let hdr = textureSample(t_hdr, s_hdr, input.uv);
+    let color = max(hdr.rgb * params.exposure, vec3<f32>(0.0));
+    let white = max(params.white_point, 1e-3);
+
+    var mapped: vec3<f32>;
+    switch params.curve {
+        case 1u: {
+            mapped = reinhard(color, white);
+        }
+        case 2u: {
+            mapped = aces(color, white);
+        }
+        case 3u: {
+            mapped = agx(color, white);
+        }
+        default: {
+            mapped = color / white;
+        }
+    }
+    mapped = clamp(mapped, vec3<f32>(0.0), vec3<f32>(1.0));
+
+    // Graders and LUTs expect display-encoded values
+    var display = grade(linear_to_srgb(mapped));
+    display = mix(display, apply_lut(display), grading.lut_intensity);
+    display = clamp(display, vec3<f32>(0.0), vec3<f32>(1.0));
+
+    // sRGB output formats encode on write
+    if (params.encode_srgb == 0u) {
+        display = srgb_to_linear(display);
+    }
+
+    return vec4<f32>(display, clamp(hdr.a, 0.0, 1.0));
+}
@@ -0,0 +1,60 @@
+// YUV to RGB conversion for decoded video frames
+// Y, Cb and Cr arrive as separate planes; subsampled chroma is upscaled by the sampler
+
+struct YuvUniform {
+    // Rows of the matrix from offset YCbCr to RGB, levels scaling included
+    red: vec4<f32>,
+    green: vec4<f32>,
+    blue: vec4<f32>,
+    // Black level and chroma zero in xyz
+    offset: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var t_y: texture_2d<f32>;
+@group(0) @binding(1)
+var t_u: texture_2d<f32>;
+@group(0) @binding(2)
+var t_v: texture_2d<f32>;
+@group(0) @binding(3)
+var s_planes: sampler;
+@group(0) @binding(4)
+var<uniform> params: YuvUniform;
+
+// Vertex shader - generates a fullscreen triangle
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var output: VertexOutput;
+    let x = f32((vertex_index << 1u) & 2u);
+    let y = f32(vertex_index & 2u);
+    output.position = vec4<f32>(x * 2.0 - 1.0, 1.0 - y * 2.0, 0.0, 1.0);
+    output.uv = vec2<f32>(x, y);
+    return output;
+}
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, color <= vec3<f32>(0.04045));
+}
+
+// Fragment shader - convert to display-encoded RGB, then to linear for the sRGB target
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let yuv = vec3<f32>(
+        textureSample(t_y, s_planes, input.uv).r,
+        textureSample(t_u, s_planes, input.uv).r,
+        textureSample(t_v, s_planes, input.uv).r,
+    ) - params.offset.xyz;
+    let rgb = vec3<f32>(
+        dot(params.red.xyz, yuv),
+        dot(params.green.xyz, yuv),
+        dot(params.blue.xyz, yuv),
+    );
+    return vec4<f32>(srgb_to_linear(clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0))), 1.0);
+}
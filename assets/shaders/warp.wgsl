@@ -0,0 +1,103 @@
+// Output warp shader: draws the tessellated, corner-pinned image with soft
+// edge blending and polygon masks, plus square handles for the warp editor
+
+struct WarpUniform {
+    uv_min: vec2<f32>,
+    uv_max: vec2<f32>,
+    // Left, right, top and bottom ramp widths
+    edge_blend: vec4<f32>,
+    target_size: vec2<f32>,
+    blend_gamma: f32,
+    mask_count: u32,
+    // First point, point count and invert flag of each mask
+    mask_ranges: array<vec4<u32>, 8>,
+    // Two points per element, in window coordinates (0..1)
+    mask_points: array<vec4<f32>, 32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    // Position in the image (0..1), before cropping
+    @location(0) uv: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var t_source: texture_2d<f32>;
+@group(0) @binding(1)
+var s_source: sampler;
+@group(0) @binding(2)
+var<uniform> params: WarpUniform;
+
+@vertex
+fn vs_main(@location(0) position: vec4<f32>, @location(1) uv: vec2<f32>) -> VertexOutput {
+    var output: VertexOutput;
+    output.position = position;
+    output.uv = uv;
+    return output;
+}
+
+// Smooth ramp from 0 at the edge to 1 at `width`; opposite ramps sum to 1
+fn ramp(distance: f32, width: f32) -> f32 {
+    if (width <= 0.0) {
+        return 1.0;
+    }
+    return smoothstep(0.0, 1.0, clamp(distance / width, 0.0, 1.0));
+}
+
+fn mask_point(index: u32) -> vec2<f32> {
+    let element = params.mask_points[index / 2u];
+    if (index % 2u == 0u) {
+        return element.xy;
+    }
+    return element.zw;
+}
+
+// Even-odd point in polygon test
+fn inside_mask(range: vec4<u32>, p: vec2<f32>) -> bool {
+    var inside = false;
+    var j = range.x + range.y - 1u;
+    for (var i = range.x; i < range.x + range.y; i = i + 1u) {
+        let a = mask_point(i);
+        let b = mask_point(j);
+        if ((a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x) {
+            inside = !inside;
+        }
+        j = i;
+    }
+    return inside;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let window_position = input.position.xy / params.target_size;
+    for (var m = 0u; m < params.mask_count; m = m + 1u) {
+        let range = params.mask_ranges[m];
+        if (inside_mask(range, window_position) != (range.z != 0u)) {
+            return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+        }
+    }
+
+    let color = textureSample(t_source, s_source, mix(params.uv_min, params.uv_max, input.uv));
+    let widths = params.edge_blend;
+    let blend = ramp(input.uv.x, widths.x) * ramp(1.0 - input.uv.x, widths.y)
+        * ramp(input.uv.y, widths.z) * ramp(1.0 - input.uv.y, widths.w);
+    return vec4<f32>(color.rgb * pow(blend, 1.0 / params.blend_gamma), color.a);
+}
+
+struct HandleOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vs_handle(@location(0) position: vec2<f32>, @location(1) color: vec4<f32>) -> HandleOutput {
+    var output: HandleOutput;
+    output.position = vec4<f32>(position, 0.0, 1.0);
+    output.color = color;
+    return output;
+}
+
+@fragment
+fn fs_handle(input: HandleOutput) -> @location(0) vec4<f32> {
+    return input.color;
+}
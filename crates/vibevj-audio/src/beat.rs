@@ -0,0 +1,70 @@
+use std::collections::VecDeque;
+
+/// Energy-based beat detector
+///
+/// Compares the current energy against a moving average of recent frames and
+/// reports a beat when it jumps above `sensitivity` times that average.
+pub struct BeatDetector {
+    history: VecDeque<f32>,
+    history_size: usize,
+    /// Multiplier over the average energy needed to trigger a beat
+    pub sensitivity: f32,
+    /// Minimum energy for a beat, so silence does not trigger on noise
+    pub min_energy: f32,
+    /// Minimum time between beats in seconds
+    pub cooldown: f32,
+    time_since_beat: f32,
+}
+
+impl BeatDetector {
+    /// Create a new beat detector averaging over `history_size` frames
+    pub fn new(history_size: usize) -> Self {
+        Self {
+            history: VecDeque::with_capacity(history_size),
+            history_size: history_size.max(1),
+            sensitivity: 1.4,
+            min_energy: 0.01,
+            cooldown: 0.15,
+            time_since_beat: f32::MAX,
+        }
+    }
+
+    /// Feed the energy for this frame and return whether a beat occurred
+    pub fn detect(&mut self, energy: f32, delta: f32) -> bool {
+        self.time_since_beat += delta;
+
+        let average = if self.history.is_empty() {
+            energy
+        } else {
+            self.history.iter().sum::<f32>() / self.history.len() as f32
+        };
+
+        if self.history.len() == self.history_size {
+            self.history.pop_front();
+        }
+        self.history.push_back(energy);
+
+        let is_beat = energy > average * self.sensitivity
+            && energy > self.min_energy
+            && self.time_since_beat >= self.cooldown;
+
+        if is_beat {
+            self.time_since_beat = 0.0;
+        }
+
+        is_beat
+    }
+
+    /// Clear the energy history
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.time_since_beat = f32::MAX;
+    }
+}
+
+impl Default for BeatDetector {
+    fn default() -> Self {
+        // Roughly one second of history at 60 FPS
+        Self::new(60)
+    }
+}
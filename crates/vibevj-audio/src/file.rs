@@ -0,0 +1,251 @@
+use std::path::Path;
+use vibevj_common::{AudioFeatures, Result, TimeInfo, VibeVJError};
+use crate::analyzer::AudioAnalyzer;
+use crate::beat::BeatDetector;
+
+/// Decoded audio file, downmixed to mono
+#[derive(Debug, Clone)]
+pub struct AudioFile {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+impl AudioFile {
+    /// Load a PCM WAV file (8/16/24/32-bit integer or 32-bit float)
+    pub fn load_wav<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let bytes = std::fs::read(path.as_ref())?;
+        Self::parse_wav(&bytes).map_err(|e| {
+            VibeVJError::AudioError(format!("{}: {}", path.as_ref().display(), e))
+        })
+    }
+
+    /// Parse WAV data from memory
+    pub fn parse_wav(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err(VibeVJError::AudioError("Not a RIFF/WAVE file".to_string()));
+        }
+
+        let mut format: Option<(u16, u16, u32, u16)> = None;
+        let mut data: Option<&[u8]> = None;
+
+        // Walk the chunk list
+        let mut offset = 12;
+        while offset + 8 <= bytes.len() {
+            let id = &bytes[offset..offset + 4];
+            let size = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+            let body = &bytes[offset + 8..(offset + 8 + size).min(bytes.len())];
+
+            match id {
+                b"fmt " if body.len() >= 16 => {
+                    let mut tag = u16::from_le_bytes([body[0], body[1]]);
+                    let channels = u16::from_le_bytes([body[2], body[3]]);
+                    let sample_rate = u32::from_le_bytes(body[4..8].try_into().unwrap());
+                    let bits = u16::from_le_bytes([body[14], body[15]]);
+                    // WAVE_FORMAT_EXTENSIBLE stores the real tag in the sub-format GUID
+                    if tag == 0xFFFE && body.len() >= 26 {
+                        tag = u16::from_le_bytes([body[24], body[25]]);
+                    }
+                    format = Some((tag, channels, sample_rate, bits));
+                }
+                b"data" => data = Some(body),
+                _ => {}
+            }
+
+            // Chunks are padded to an even size
+            offset += 8 + size + (size & 1);
+        }
+
+        let (tag, channels, sample_rate, bits) = format
+            .ok_or_else(|| VibeVJError::AudioError("Missing fmt chunk".to_string()))?;
+        let data = data.ok_or_else(|| VibeVJError::AudioError("Missing data chunk".to_string()))?;
+
+        if channels == 0 {
+            return Err(VibeVJError::AudioError("WAV file has no channels".to_string()));
+        }
+
+        let decode: fn(&[u8]) -> f32 = match (tag, bits) {
+            (1, 8) => |b| (b[0] as f32 - 128.0) / 128.0,
+            (1, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+            (1, 24) => |b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0,
+            (1, 32) => |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+            (3, 32) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            _ => {
+                return Err(VibeVJError::AudioError(format!(
+                    "Unsupported WAV encoding (format {}, {} bits)",
+                    tag, bits
+                )))
+            }
+        };
+
+        let bytes_per_sample = bits as usize / 8;
+        let frame_size = bytes_per_sample * channels as usize;
+        let samples = data
+            .chunks_exact(frame_size)
+            .map(|frame| {
+                let sum: f32 = frame.chunks_exact(bytes_per_sample).map(decode).sum();
+                sum / channels as f32
+            })
+            .collect();
+
+        Ok(Self { samples, sample_rate })
+    }
+
+    /// Duration in seconds
+    pub fn duration(&self) -> f64 {
+        self.samples.len() as f64 / self.sample_rate as f64
+    }
+
+    /// `len` samples ending at `time` seconds, zero-padded outside the file
+    pub fn window(&self, time: f64, len: usize) -> Vec<f32> {
+        let end = (time * self.sample_rate as f64).round() as i64;
+        let start = end - len as i64;
+        (start..end)
+            .map(|i| {
+                if i >= 0 && (i as usize) < self.samples.len() {
+                    self.samples[i as usize]
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
+/// Audio features computed from a file at explicit timestamps
+///
+/// Used for offline rendering, where frames are produced faster or slower
+/// than real time. Call `features_at` for consecutive frames in order so
+/// beat detection sees the same history on every run.
+pub struct FileAudioSource {
+    file: AudioFile,
+    analyzer: AudioAnalyzer,
+    beat_detector: BeatDetector,
+    fft_size: usize,
+}
+
+impl FileAudioSource {
+    /// Create a source with the default FFT size
+    pub fn new(file: AudioFile) -> Self {
+        Self::with_fft_size(file, 2048)
+    }
+
+    pub fn with_fft_size(file: AudioFile, fft_size: usize) -> Self {
+        Self {
+            file,
+            analyzer: AudioAnalyzer::new(fft_size),
+            beat_detector: BeatDetector::default(),
+            fft_size,
+        }
+    }
+
+    /// Load a WAV file as a source
+    pub fn open_wav<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::new(AudioFile::load_wav(path)?))
+    }
+
+    pub fn file(&self) -> &AudioFile {
+        &self.file
+    }
+
+    /// Analyze the audio leading up to a frame's timestamp
+    pub fn features_at(&mut self, time: &TimeInfo) -> Result<AudioFeatures> {
+        let window = self.file.window(time.elapsed, self.fft_size);
+        let bands = self.analyzer.analyze_bands(&window, self.file.sample_rate)?;
+        let beat = self.beat_detector.detect(bands.energy(), time.delta);
+        Ok(bands.to_features(beat))
+    }
+
+    /// Reset beat history, e.g. before re-rendering from frame 0
+    pub fn reset(&mut self) {
+        self.beat_detector.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut bytes = id.to_vec();
+        bytes.extend((body.len() as u32).to_le_bytes());
+        bytes.extend(body);
+        if body.len() % 2 == 1 {
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    /// WAV file with the given `fmt ` fields around `data`
+    fn wav(tag: u16, bits: u16, channels: u16, sample_rate: u32, data: &[u8]) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut fmt = Vec::new();
+        fmt.extend(tag.to_le_bytes());
+        fmt.extend(channels.to_le_bytes());
+        fmt.extend(sample_rate.to_le_bytes());
+        fmt.extend((sample_rate * block_align as u32).to_le_bytes());
+        fmt.extend(block_align.to_le_bytes());
+        fmt.extend(bits.to_le_bytes());
+
+        let mut body = b"WAVE".to_vec();
+        body.extend(chunk(b"fmt ", &fmt));
+        // Odd-sized chunks before the data are padded
+        body.extend(chunk(b"LIST", b"INFO!"));
+        body.extend(chunk(b"data", data));
+        chunk(b"RIFF", &body)
+    }
+
+    fn parse(bytes: &[u8]) -> Vec<f32> {
+        let file = AudioFile::parse_wav(bytes).unwrap();
+        assert_eq!(file.sample_rate, 8000);
+        file.samples
+    }
+
+    #[test]
+    fn reads_8_bit_unsigned() {
+        assert_eq!(parse(&wav(1, 8, 1, 8000, &[0, 128, 192])), [-1.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn reads_16_bit_and_downmixes_channels() {
+        let data: Vec<u8> = [i16::MIN, 0, 16384, 16384, -16384, 16384].iter().flat_map(|s| s.to_le_bytes()).collect();
+        assert_eq!(parse(&wav(1, 16, 2, 8000, &data)), [-0.5, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn reads_24_bit_with_sign() {
+        let data: Vec<u8> = [-0x80_0000i32, 0x40_0000, -0x40_0000]
+            .iter()
+            .flat_map(|s| s.to_le_bytes()[..3].to_vec())
+            .collect();
+        assert_eq!(parse(&wav(1, 24, 1, 8000, &data)), [-1.0, 0.5, -0.5]);
+    }
+
+    #[test]
+    fn reads_32_bit_float_and_extensible_headers() {
+        let samples = [0.25f32, -0.75, 1.0];
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        assert_eq!(parse(&wav(3, 32, 1, 8000, &data)), samples);
+
+        // WAVE_FORMAT_EXTENSIBLE with the float sub-format
+        let mut bytes = wav(3, 32, 1, 8000, &data);
+        let fmt = bytes.windows(4).position(|window| window == b"fmt ").unwrap();
+        bytes[fmt + 8..fmt + 10].copy_from_slice(&0xFFFEu16.to_le_bytes());
+        let mut extension = vec![22, 0, 32, 0, 0, 0, 0, 0];
+        extension.extend(3u16.to_le_bytes());
+        extension.extend([0; 14]);
+        let fmt_end = fmt + 8 + 16;
+        bytes.splice(fmt_end..fmt_end, extension.iter().copied());
+        bytes[fmt + 4..fmt + 8].copy_from_slice(&(16 + extension.len() as u32).to_le_bytes());
+        let riff_size = bytes.len() as u32 - 8;
+        bytes[4..8].copy_from_slice(&riff_size.to_le_bytes());
+        assert_eq!(parse(&bytes), samples);
+    }
+
+    #[test]
+    fn rejects_other_files_and_encodings() {
+        assert!(AudioFile::parse_wav(b"RIFF\0\0\0\0AVI ").is_err());
+        assert!(AudioFile::parse_wav(&wav(1, 12, 1, 8000, &[0; 6])).is_err());
+        assert!(AudioFile::parse_wav(&wav(1, 16, 0, 8000, &[0; 4])).is_err());
+        assert!(AudioFile::parse_wav(&chunk(b"RIFF", b"WAVE")).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use vibevj_common::AudioFeatures;
+
 /// Frequency data from FFT analysis
 #[derive(Debug, Clone)]
 pub struct FrequencyData {
@@ -96,6 +98,22 @@ impl FrequencyBands {
     pub fn treble_energy(&self) -> f32 {
         (self.presence + self.brilliance) / 2.0
     }
+
+    /// Convert to the backend-independent feature snapshot used by rendering
+    pub fn to_features(&self, beat: bool) -> AudioFeatures {
+        AudioFeatures {
+            bands: [
+                self.sub_bass,
+                self.bass,
+                self.low_mid,
+                self.mid,
+                self.high_mid,
+                self.presence,
+                self.brilliance,
+            ],
+            beat,
+        }
+    }
 }
 
 impl Default for FrequencyBands {
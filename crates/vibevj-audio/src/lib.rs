@@ -2,6 +2,7 @@
 /// 
 /// Provides real-time audio analysis including:
 /// - Audio input capture
+/// - WAV file playback for offline rendering
 /// - FFT analysis
 /// - Frequency band extraction (bass, mid, treble)
 /// - Beat detection
@@ -10,7 +11,11 @@
 pub mod analyzer;
 pub mod input;
 pub mod frequency;
+pub mod beat;
+pub mod file;
 
 pub use analyzer::AudioAnalyzer;
 pub use input::{AudioInput, AudioDeviceInfo};
 pub use frequency::{FrequencyBands, FrequencyData};
+pub use beat::BeatDetector;
+pub use file::{AudioFile, FileAudioSource};
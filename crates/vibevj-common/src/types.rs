@@ -98,3 +98,75 @@ pub struct TimeInfo {
     /// Current frame number
     pub frame: u64,
 }
+
+impl TimeInfo {
+    /// Time for a frame at a fixed frame rate
+    ///
+    /// Computed from the frame number rather than accumulated, so every
+    /// frame lands on an exact multiple of `1 / fps` regardless of length.
+    pub fn fixed_step(frame: u64, fps: f64) -> Self {
+        Self {
+            elapsed: frame as f64 / fps,
+            delta: (1.0 / fps) as f32,
+            frame,
+        }
+    }
+}
+
+/// Frequency band selector for audio-reactive parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AudioBand {
+    SubBass,
+    Bass,
+    LowMid,
+    Mid,
+    HighMid,
+    Presence,
+    Brilliance,
+    /// Average of all bands
+    Energy,
+}
+
+impl AudioBand {
+    /// Position in `AudioFeatures::bands`; `Energy` comes after the seven bands
+    pub fn index(&self) -> usize {
+        match self {
+            AudioBand::SubBass => 0,
+            AudioBand::Bass => 1,
+            AudioBand::LowMid => 2,
+            AudioBand::Mid => 3,
+            AudioBand::HighMid => 4,
+            AudioBand::Presence => 5,
+            AudioBand::Brilliance => 6,
+            AudioBand::Energy => 7,
+        }
+    }
+}
+
+/// Snapshot of audio analysis for one frame
+///
+/// Produced by the audio module and consumed by rendering, so audio-reactive
+/// features do not depend on the audio backend directly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AudioFeatures {
+    /// Band levels in `AudioBand` order (sub-bass to brilliance)
+    pub bands: [f32; 7],
+    /// True on the frame a beat was detected
+    pub beat: bool,
+}
+
+impl AudioFeatures {
+    /// Get the level of a band
+    pub fn band(&self, band: AudioBand) -> f32 {
+        match band {
+            AudioBand::SubBass => self.bands[0],
+            AudioBand::Bass => self.bands[1],
+            AudioBand::LowMid => self.bands[2],
+            AudioBand::Mid => self.bands[3],
+            AudioBand::HighMid => self.bands[4],
+            AudioBand::Presence => self.bands[5],
+            AudioBand::Brilliance => self.bands[6],
+            AudioBand::Energy => self.bands.iter().sum::<f32>() / self.bands.len() as f32,
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use image::AnimationDecoder;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use vibevj_common::{AudioFeatures, Result, TimeInfo, VibeVJError};
+use crate::texture::Texture;
+
+/// Order in which an animation's frames are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum PlaybackMode {
+    /// Start over after the last frame
+    #[default]
+    Loop,
+    /// Play forward, then backward
+    PingPong,
+    /// Stop on the last frame
+    Once,
+}
+
+impl PlaybackMode {
+    pub const ALL: [PlaybackMode; 3] = [PlaybackMode::Loop, PlaybackMode::PingPong, PlaybackMode::Once];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlaybackMode::Loop => "Loop",
+            PlaybackMode::PingPong => "Ping-Pong",
+            PlaybackMode::Once => "Once",
+        }
+    }
+}
+
+/// How an animated texture advances
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Playback {
+    /// Frames per second; 0 uses the delays stored in the file
+    pub fps: u32,
+    pub mode: PlaybackMode,
+    /// Step one frame per detected beat instead of following time
+    pub beat_step: bool,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            fps: 0,
+            mode: PlaybackMode::Loop,
+            beat_step: false,
+        }
+    }
+}
+
+/// Decoded frames of a GIF, APNG or numbered image sequence
+///
+/// Frames are kept decoded on the CPU and streamed to a single texture as
+/// playback reaches them, so long clips cost memory but no GPU array layers.
+#[derive(Debug, Clone)]
+pub struct AnimationFrames {
+    pub width: u32,
+    pub height: u32,
+    pub frames: Vec<image::RgbaImage>,
+    /// Display time of each frame in seconds
+    pub delays: Vec<f32>,
+}
+
+impl AnimationFrames {
+    /// Frame rate of image sequences and files without delays
+    pub const DEFAULT_FPS: f32 = 24.0;
+    /// Character marking the frame number digits in a sequence pattern
+    pub const SEQUENCE_DIGIT: char = '#';
+
+    /// Frames of equal size shown for `delay` seconds each
+    pub fn from_frames(frames: Vec<image::RgbaImage>, delay: f32) -> Result<Self> {
+        let first = frames
+            .first()
+            .ok_or_else(|| VibeVJError::InvalidOperation("Animation has no frames".to_string()))?;
+        let (width, height) = first.dimensions();
+        if let Some(index) = frames.iter().position(|frame| frame.dimensions() != (width, height)) {
+            return Err(VibeVJError::InvalidOperation(format!(
+                "Frame {} is {}x{}, expected {}x{}",
+                index,
+                frames[index].width(),
+                frames[index].height(),
+                width,
+                height
+            )));
+        }
+        let delays = vec![delay; frames.len()];
+        Ok(Self { width, height, frames, delays })
+    }
+
+    /// Decode a GIF, an APNG or any still image `image` can read
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let error = |e: image::ImageError| VibeVJError::RenderError(format!("Failed to decode animation: {}", e));
+        let frames = match image::guess_format(bytes).map_err(error)? {
+            image::ImageFormat::Gif => image::codecs::gif::GifDecoder::new(Cursor::new(bytes))
+                .and_then(|decoder| decoder.into_frames().collect_frames())
+                .map_err(error)?,
+            image::ImageFormat::Png => {
+                let decoder = image::codecs::png::PngDecoder::new(Cursor::new(bytes)).map_err(error)?;
+                if !decoder.is_apng() {
+                    let image = image::load_from_memory(bytes).map_err(error)?;
+                    return Self::from_frames(vec![image.to_rgba8()], 1.0 / Self::DEFAULT_FPS);
+                }
+                decoder.apng().into_frames().collect_frames().map_err(error)?
+            }
+            _ => {
+                let image = image::load_from_memory(bytes).map_err(error)?;
+                return Self::from_frames(vec![image.to_rgba8()], 1.0 / Self::DEFAULT_FPS);
+            }
+        };
+
+        let delays: Vec<f32> = frames
+            .iter()
+            .map(|frame| {
+                let (numerator, denominator) = frame.delay().numer_denom_ms();
+                let seconds = numerator as f32 / denominator.max(1) as f32 / 1000.0;
+                // Like browsers, treat near-zero delays as the common 10 fps default
+                if seconds < 0.02 { 0.1 } else { seconds }
+            })
+            .collect();
+        let mut animation = Self::from_frames(frames.into_iter().map(|frame| frame.into_buffer()).collect(), 0.0)?;
+        animation.delays = delays;
+        Ok(animation)
+    }
+
+    /// Load a numbered image sequence such as `clip/frame_####.png`
+    ///
+    /// The run of `#` is replaced by the zero-padded frame number, starting
+    /// at 0 or 1 and continuing until a number is missing.
+    pub fn load_sequence(pattern: &str) -> Result<Self> {
+        let start = pattern
+            .find(Self::SEQUENCE_DIGIT)
+            .ok_or_else(|| VibeVJError::InvalidOperation(format!("No '#' frame number in sequence '{}'", pattern)))?;
+        let digits = pattern[start..].chars().take_while(|&c| c == Self::SEQUENCE_DIGIT).count();
+        let path = |number: usize| {
+            format!("{}{:0width$}{}", &pattern[..start], number, &pattern[start + digits..], width = digits)
+        };
+
+        let first = (0..=1)
+            .find(|&number| std::path::Path::new(&path(number)).exists())
+            .ok_or_else(|| VibeVJError::ResourceNotFound(format!("No frames found for sequence '{}'", pattern)))?;
+        let mut frames = Vec::new();
+        for number in first.. {
+            let frame_path = path(number);
+            if !std::path::Path::new(&frame_path).exists() {
+                break;
+            }
+            let image = image::open(&frame_path)
+                .map_err(|e| VibeVJError::RenderError(format!("Failed to load frame {}: {}", frame_path, e)))?;
+            frames.push(image.to_rgba8());
+        }
+        Self::from_frames(frames, 1.0 / Self::DEFAULT_FPS)
+    }
+
+    /// Load a sequence if `path` contains `#`, otherwise a single image or animation file
+    pub fn load(path: &str) -> Result<Self> {
+        if path.contains(Self::SEQUENCE_DIGIT) {
+            Self::load_sequence(path)
+        } else {
+            Self::decode(&std::fs::read(path)?)
+        }
+    }
+
+    /// Index of the frame to show
+    ///
+    /// `time` drives playback unless `playback.beat_step` is set, in which
+    /// case `beats` is the number of steps taken.
+    pub fn frame_at(&self, playback: &Playback, time: f64, beats: u64) -> usize {
+        let count = self.frames.len();
+        if count <= 1 {
+            return 0;
+        }
+        // Ping-pong visits the inner frames twice per cycle
+        let cycle = match playback.mode {
+            PlaybackMode::PingPong => 2 * count - 2,
+            PlaybackMode::Loop | PlaybackMode::Once => count,
+        };
+        let frame_of = |step: usize| if step < count { step } else { 2 * count - 2 - step };
+
+        let step = if playback.beat_step {
+            beats
+        } else if playback.fps > 0 {
+            (time.max(0.0) * playback.fps as f64) as u64
+        } else {
+            // Walk the file's delays through one cycle
+            let durations = (0..cycle).map(|step| self.delays[frame_of(step)] as f64);
+            let total: f64 = durations.clone().sum();
+            let cycles = (time.max(0.0) / total).floor();
+            let mut remaining = time.max(0.0) - cycles * total;
+            let within = durations
+                .take_while(|&duration| {
+                    remaining -= duration;
+                    remaining >= 0.0
+                })
+                .count();
+            cycles as u64 * cycle as u64 + within.min(cycle - 1) as u64
+        };
+
+        match playback.mode {
+            PlaybackMode::Once => step.min(count as u64 - 1) as usize,
+            PlaybackMode::Loop | PlaybackMode::PingPong => frame_of((step % cycle as u64) as usize),
+        }
+    }
+}
+
+/// Texture showing the current frame of an animation
+///
+/// Call `update` once per rendered frame; time comes from `TimeInfo` so
+/// offline export shows the same frames on every run.
+pub struct AnimatedTexture {
+    pub frames: AnimationFrames,
+    pub texture: Texture,
+    current: usize,
+    // Beats seen so far, for beat stepping
+    beats: u64,
+    last_update: Option<u64>,
+}
+
+impl AnimatedTexture {
+    /// Upload the first frame of `frames`
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, frames: AnimationFrames, label: Option<&str>) -> Result<Self> {
+        let texture = Texture::from_image(device, queue, &frames.frames[0], label)?;
+        Ok(Self {
+            frames,
+            texture,
+            current: 0,
+            beats: 0,
+            last_update: None,
+        })
+    }
+
+    /// Load an image, GIF, APNG or `#` sequence (see `AnimationFrames::load`)
+    pub fn load(device: &wgpu::Device, queue: &wgpu::Queue, path: &str) -> Result<Self> {
+        Self::new(device, queue, AnimationFrames::load(path)?, Some(path))
+    }
+
+    /// Whether there is more than one frame
+    pub fn is_animated(&self) -> bool {
+        self.frames.frames.len() > 1
+    }
+
+    /// Index of the frame in the texture
+    pub fn current_frame(&self) -> usize {
+        self.current
+    }
+
+    /// Advance playback and upload the frame if it changed
+    /// Further calls for the same `time.frame` are ignored
+    pub fn update(&mut self, queue: &wgpu::Queue, playback: &Playback, time: &TimeInfo, audio: &AudioFeatures) {
+        if !self.is_animated() || self.last_update == Some(time.frame) {
+            return;
+        }
+        self.last_update = Some(time.frame);
+        if audio.beat {
+            self.beats += 1;
+        }
+
+        let frame = self.frames.frame_at(playback, time.elapsed, self.beats);
+        if frame != self.current {
+            self.current = frame;
+            self.texture.write(queue, &self.frames.frames[frame]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// GIF of `count` 16x12 frames shown for 0.1s each
+    fn test_gif(count: u8) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = image::codecs::gif::GifEncoder::new(&mut bytes);
+            let frames = (0..count).map(|index| {
+                let image = image::RgbaImage::from_pixel(16, 12, image::Rgba([60 * index, 0, 0, 255]));
+                image::Frame::from_parts(image, 0, 0, image::Delay::from_numer_denom_ms(100, 1))
+            });
+            encoder.encode_frames(frames).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn decodes_gif_frames_and_delays() {
+        let frames = AnimationFrames::decode(&test_gif(4)).unwrap();
+        assert_eq!((frames.frames.len(), frames.width, frames.height), (4, 16, 12));
+        assert!(frames.delays.iter().all(|&delay| (delay - 0.1).abs() < 1e-6));
+        assert_eq!(frames.frames[2].get_pixel(0, 0).0, [120, 0, 0, 255]);
+    }
+
+    #[test]
+    fn frame_at_follows_mode_rate_and_beats() {
+        let frames = AnimationFrames::from_frames(vec![image::RgbaImage::new(1, 1); 4], 0.1).unwrap();
+        let at = |playback: Playback, times: &[f64]| -> Vec<usize> {
+            times.iter().map(|&time| frames.frame_at(&playback, time, 0)).collect()
+        };
+
+        // File timing at 0.1s per frame, then a fixed rate, then beats
+        let times = [0.0, 0.15, 0.35, 0.45, 0.55, 0.65, 0.95];
+        let looped = Playback::default();
+        assert_eq!(at(looped, &times), [0, 1, 3, 0, 1, 2, 1]);
+        let ping_pong = Playback { mode: PlaybackMode::PingPong, ..looped };
+        assert_eq!(at(ping_pong, &times), [0, 1, 3, 2, 1, 0, 3]);
+        let once = Playback { mode: PlaybackMode::Once, ..looped };
+        assert_eq!(at(once, &times), [0, 1, 3, 3, 3, 3, 3]);
+        let fast = Playback { fps: 20, ..looped };
+        assert_eq!(at(fast, &[0.0, 0.05, 0.1, 0.2]), [0, 1, 2, 0]);
+        let beats = Playback { beat_step: true, mode: PlaybackMode::PingPong, ..looped };
+        let stepped: Vec<usize> = (0..8).map(|beat| frames.frame_at(&beats, 10.0, beat)).collect();
+        assert_eq!(stepped, [0, 1, 2, 3, 2, 1, 0, 1]);
+    }
+
+    #[test]
+    fn rejects_mismatched_frames() {
+        assert!(AnimationFrames::from_frames(Vec::new(), 0.1).is_err());
+        let frames = vec![image::RgbaImage::new(2, 2), image::RgbaImage::new(2, 3)];
+        assert!(AnimationFrames::from_frames(frames, 0.1).is_err());
+    }
+}
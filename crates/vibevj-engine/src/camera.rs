@@ -1,10 +1,23 @@
 use glam::{Mat4, Vec3};
+use serde::{Deserialize, Serialize};
+
+/// How the camera projects the scene
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Projection {
+    /// Perspective with the camera's vertical field of view
+    #[default]
+    Perspective,
+    /// Parallel projection showing `height` world units vertically
+    Orthographic { height: f32 },
+}
 
 /// Camera for 3D rendering
+#[derive(Debug, Clone, PartialEq)]
 pub struct Camera {
     pub position: Vec3,
     pub target: Vec3,
     pub up: Vec3,
+    pub projection: Projection,
     pub fov: f32,
     pub aspect: f32,
     pub near: f32,
@@ -18,6 +31,7 @@ impl Camera {
             position,
             target,
             up: Vec3::Y,
+            projection: Projection::Perspective,
             fov: 45.0_f32.to_radians(),
             aspect,
             near: 0.1,
@@ -32,7 +46,14 @@ impl Camera {
 
     /// Get the projection matrix
     pub fn projection_matrix(&self) -> Mat4 {
-        Mat4::perspective_rh(self.fov, self.aspect, self.near, self.far)
+        match self.projection {
+            Projection::Perspective => Mat4::perspective_rh(self.fov, self.aspect, self.near, self.far),
+            Projection::Orthographic { height } => {
+                let half_height = height * 0.5;
+                let half_width = half_height * self.aspect;
+                Mat4::orthographic_rh(-half_width, half_width, -half_height, half_height, self.near, self.far)
+            }
+        }
     }
 
     /// Get the combined view-projection matrix
@@ -61,17 +82,21 @@ impl Default for Camera {
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct CameraUniform {
     pub view_proj: [[f32; 4]; 4],
+    /// World-space camera position (w unused), needed for view-dependent shading
+    pub view_position: [f32; 4],
 }
 
 impl CameraUniform {
     pub fn new() -> Self {
         Self {
             view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+            view_position: [0.0, 0.0, 0.0, 1.0],
         }
     }
     
     pub fn update_view_proj(&mut self, camera: &Camera) {
         self.view_proj = camera.view_projection_matrix().to_cols_array_2d();
+        self.view_position = camera.position.extend(1.0).to_array();
     }
 }
 
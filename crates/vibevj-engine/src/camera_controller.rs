@@ -0,0 +1,780 @@
+use glam::{Quat, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use vibevj_common::{AudioBand, AudioFeatures, TimeInfo};
+use crate::camera::{Camera, Projection};
+
+/// Largest orbit and fly pitch, just short of straight up or down
+const MAX_PITCH: f32 = 89.0 * std::f32::consts::PI / 180.0;
+
+/// Furthest a held `BeatCuts` shot drifts, as a fraction of its distance to the target
+const MAX_DRIFT: f32 = 0.5;
+
+/// User input for one frame, gathered from the preview panel
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CameraInput {
+    /// Primary-button drag in pixels
+    pub rotate: Vec2,
+    /// Secondary or middle-button drag in pixels
+    pub pan: Vec2,
+    /// Scroll steps, positive zooms in
+    pub zoom: f32,
+    /// Fly movement in camera space (x right, y up, z forward), each -1..1
+    pub movement: Vec3,
+    /// Move faster while held
+    pub boost: bool,
+}
+
+impl CameraInput {
+    /// Add another batch of input, e.g. from a second event in the same frame
+    pub fn accumulate(&mut self, other: &CameraInput) {
+        self.rotate += other.rotate;
+        self.pan += other.pan;
+        self.zoom += other.zoom;
+        self.movement = (self.movement + other.movement).clamp(Vec3::splat(-1.0), Vec3::splat(1.0));
+        self.boost |= other.boost;
+    }
+}
+
+/// Unit vector from yaw (around Y, 0 = +Z) and pitch (0 = horizontal)
+fn direction(yaw: f32, pitch: f32) -> Vec3 {
+    Vec3::new(pitch.cos() * yaw.sin(), pitch.sin(), pitch.cos() * yaw.cos())
+}
+
+/// Yaw and pitch of a direction, inverse of `direction`
+fn yaw_pitch(direction: Vec3) -> (f32, f32) {
+    let direction = direction.normalize_or_zero();
+    (direction.x.atan2(direction.z), direction.y.clamp(-1.0, 1.0).asin())
+}
+
+/// Camera circling a target, driven by mouse drag and scroll
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OrbitController {
+    pub target: Vec3,
+    pub distance: f32,
+    /// Angle around the Y axis in radians
+    pub yaw: f32,
+    /// Angle above the horizon in radians
+    pub pitch: f32,
+    /// Radians per dragged pixel
+    pub rotate_speed: f32,
+    /// Fraction of the distance moved per dragged pixel
+    pub pan_speed: f32,
+    /// Fraction of the distance zoomed per scroll step
+    pub zoom_speed: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    /// Continuous rotation in radians per second
+    pub auto_rotate: f32,
+}
+
+impl OrbitController {
+    /// Orbit that starts at `position`, looking at `target`
+    pub fn looking_at(position: Vec3, target: Vec3) -> Self {
+        let (yaw, pitch) = yaw_pitch(position - target);
+        Self {
+            target,
+            distance: position.distance(target),
+            yaw,
+            pitch,
+            ..Default::default()
+        }
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.target + direction(self.yaw, self.pitch) * self.distance
+    }
+
+    pub fn update(&mut self, camera: &mut Camera, input: &CameraInput, delta: f32) {
+        self.yaw += self.auto_rotate * delta - input.rotate.x * self.rotate_speed;
+        self.pitch = (self.pitch + input.rotate.y * self.rotate_speed).clamp(-MAX_PITCH, MAX_PITCH);
+        self.distance = (self.distance * (1.0 - input.zoom * self.zoom_speed)).clamp(self.min_distance, self.max_distance);
+
+        // Pan in the view plane, scaled so the target tracks the cursor at any distance
+        if input.pan != Vec2::ZERO {
+            let forward = -direction(self.yaw, self.pitch);
+            let right = forward.cross(Vec3::Y).normalize_or_zero();
+            let up = right.cross(forward);
+            self.target += (-right * input.pan.x + up * input.pan.y) * self.pan_speed * self.distance;
+        }
+
+        camera.position = self.position();
+        camera.target = self.target;
+        camera.up = Vec3::Y;
+    }
+}
+
+impl Default for OrbitController {
+    fn default() -> Self {
+        Self {
+            target: Vec3::ZERO,
+            distance: 6.0,
+            yaw: 0.0,
+            pitch: 0.3,
+            rotate_speed: 0.01,
+            pan_speed: 0.002,
+            zoom_speed: 0.1,
+            min_distance: 0.5,
+            max_distance: 50.0,
+            auto_rotate: 0.0,
+        }
+    }
+}
+
+/// Free-flying camera: drag to look, movement keys to fly
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlyController {
+    pub position: Vec3,
+    /// Heading in radians, 0 looks down -Z
+    pub yaw: f32,
+    pub pitch: f32,
+    /// Units per second
+    pub speed: f32,
+    pub boost_multiplier: f32,
+    /// Radians per dragged pixel
+    pub look_speed: f32,
+}
+
+impl FlyController {
+    /// Fly camera at `position`, facing `target`
+    pub fn looking_at(position: Vec3, target: Vec3) -> Self {
+        let (yaw, pitch) = yaw_pitch(position - target);
+        Self {
+            position,
+            yaw,
+            pitch: -pitch,
+            ..Default::default()
+        }
+    }
+
+    pub fn forward(&self) -> Vec3 {
+        -direction(self.yaw, -self.pitch)
+    }
+
+    pub fn update(&mut self, camera: &mut Camera, input: &CameraInput, delta: f32) {
+        self.yaw -= input.rotate.x * self.look_speed;
+        self.pitch = (self.pitch - input.rotate.y * self.look_speed).clamp(-MAX_PITCH, MAX_PITCH);
+
+        let forward = self.forward();
+        let right = forward.cross(Vec3::Y).normalize_or_zero();
+        let speed = if input.boost { self.speed * self.boost_multiplier } else { self.speed };
+        let movement = right * input.movement.x + Vec3::Y * input.movement.y + forward * input.movement.z;
+        self.position += movement * speed * delta;
+
+        // Scroll dollies along the view direction
+        self.position += forward * input.zoom * self.speed * 0.1;
+
+        camera.position = self.position;
+        camera.target = self.position + forward;
+        camera.up = Vec3::Y;
+    }
+}
+
+impl Default for FlyController {
+    fn default() -> Self {
+        Self {
+            position: Vec3::new(0.0, 1.0, 6.0),
+            yaw: 0.0,
+            pitch: 0.0,
+            speed: 3.0,
+            boost_multiplier: 4.0,
+            look_speed: 0.005,
+        }
+    }
+}
+
+/// Camera pose at a point in time on a `CameraPath`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraKeyframe {
+    /// Seconds from the start of the path
+    pub time: f32,
+    pub position: Vec3,
+    pub target: Vec3,
+}
+
+/// Smooth (Catmull-Rom) flight through keyframes, timed by elapsed seconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraPath {
+    /// Keyframes in time order
+    pub keyframes: Vec<CameraKeyframe>,
+    /// Wrap time at the last keyframe
+    /// Give the last keyframe the first one's pose for a seamless loop
+    pub looped: bool,
+}
+
+impl CameraPath {
+    /// Closed loop around `target` at the given radius and height
+    pub fn circle(target: Vec3, radius: f32, height: f32, duration: f32, points: usize) -> Self {
+        let points = points.max(3);
+        let keyframes = (0..=points)
+            .map(|i| {
+                let t = i as f32 / points as f32;
+                let angle = t * std::f32::consts::TAU;
+                CameraKeyframe {
+                    time: t * duration,
+                    position: target + Vec3::new(angle.sin() * radius, height, angle.cos() * radius),
+                    target,
+                }
+            })
+            .collect();
+        Self { keyframes, looped: true }
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |k| k.time)
+    }
+
+    /// Position and target at `time` seconds
+    pub fn sample(&self, time: f32) -> Option<(Vec3, Vec3)> {
+        let (first, last) = (self.keyframes.first()?, self.keyframes.last()?);
+        let n = self.keyframes.len();
+        if n == 1 || last.time <= first.time {
+            return Some((first.position, first.target));
+        }
+
+        let time = if self.looped {
+            first.time + (time - first.time).rem_euclid(last.time - first.time)
+        } else {
+            time.clamp(first.time, last.time)
+        };
+        let i = self.keyframes.partition_point(|k| k.time <= time).clamp(1, n - 1) - 1;
+        let (k1, k2) = (&self.keyframes[i], &self.keyframes[i + 1]);
+        let u = ((time - k1.time) / (k2.time - k1.time).max(1e-6)).clamp(0.0, 1.0);
+
+        // Neighbours for the tangents; loops skip the duplicated end keyframe
+        let k0 = match (i, self.looped) {
+            (0, true) => &self.keyframes[n - 2],
+            (0, false) => k1,
+            _ => &self.keyframes[i - 1],
+        };
+        let k3 = match (i + 2 >= n, self.looped) {
+            (true, true) => &self.keyframes[1.min(n - 1)],
+            (true, false) => k2,
+            _ => &self.keyframes[i + 2],
+        };
+
+        Some((
+            catmull_rom(k0.position, k1.position, k2.position, k3.position, u),
+            catmull_rom(k0.target, k1.target, k2.target, k3.target, u),
+        ))
+    }
+
+    pub fn update(&self, camera: &mut Camera, time: f32) {
+        if let Some((position, target)) = self.sample(time) {
+            camera.position = position;
+            camera.target = target;
+            camera.up = Vec3::Y;
+        }
+    }
+}
+
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1 + (p2 - p0) * t + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2 + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+/// Orbit whose speed kicks forward on every beat and follows a band
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BeatOrbit {
+    pub target: Vec3,
+    pub radius: f32,
+    pub height: f32,
+    /// Base speed in radians per second
+    pub speed: f32,
+    /// Speed added on each beat, in radians per second
+    pub beat_kick: f32,
+    /// How fast the kick fades, per second
+    pub kick_decay: f32,
+    /// Band added to the speed: `speed + band_speed * level`
+    pub band: Option<AudioBand>,
+    pub band_speed: f32,
+    #[serde(skip)]
+    angle: f32,
+    #[serde(skip)]
+    kick: f32,
+}
+
+impl BeatOrbit {
+    pub fn update(&mut self, camera: &mut Camera, delta: f32, audio: &AudioFeatures) {
+        if audio.beat {
+            self.kick += self.beat_kick;
+        }
+        self.kick *= (-self.kick_decay * delta).exp();
+        let band_speed = self.band.map_or(0.0, |band| audio.band(band) * self.band_speed);
+        self.angle += (self.speed + self.kick + band_speed) * delta;
+
+        camera.position = self.target + Vec3::new(self.angle.sin() * self.radius, self.height, self.angle.cos() * self.radius);
+        camera.target = self.target;
+        camera.up = Vec3::Y;
+    }
+}
+
+impl Default for BeatOrbit {
+    fn default() -> Self {
+        Self {
+            target: Vec3::ZERO,
+            radius: 6.0,
+            height: 2.0,
+            speed: 0.2,
+            beat_kick: 1.5,
+            kick_decay: 3.0,
+            band: None,
+            band_speed: 0.0,
+            angle: 0.0,
+            kick: 0.0,
+        }
+    }
+}
+
+/// Fixed viewpoint used by `BeatCuts`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraShot {
+    pub position: Vec3,
+    pub target: Vec3,
+}
+
+/// Hard cuts between shots on beats, in a seeded random order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BeatCuts {
+    pub shots: Vec<CameraShot>,
+    /// Cut after this many beats
+    pub beats_per_cut: u32,
+    /// Shortest time between cuts in seconds, so fast tempos don't strobe
+    pub min_interval: f32,
+    /// Slow push towards the target while a shot is held, in units per second,
+    /// stopping halfway there
+    pub drift: f32,
+    pub seed: u32,
+    #[serde(skip)]
+    current: usize,
+    #[serde(skip)]
+    beats: u32,
+    #[serde(skip)]
+    cuts: u32,
+    #[serde(skip)]
+    last_cut: f32,
+}
+
+impl BeatCuts {
+    /// Shots spread evenly around `target` at alternating heights
+    pub fn around(target: Vec3, radius: f32, count: usize) -> Self {
+        let shots = (0..count.max(2))
+            .map(|i| {
+                let angle = i as f32 / count.max(2) as f32 * std::f32::consts::TAU;
+                let height = if i % 2 == 0 { 0.5 } else { radius * 0.6 };
+                CameraShot {
+                    position: target + Vec3::new(angle.sin() * radius, height, angle.cos() * radius),
+                    target,
+                }
+            })
+            .collect();
+        Self { shots, ..Default::default() }
+    }
+
+    /// Index of the shot currently shown
+    pub fn current_shot(&self) -> usize {
+        self.current
+    }
+
+    pub fn update(&mut self, camera: &mut Camera, time: f32, audio: &AudioFeatures) {
+        if self.shots.is_empty() {
+            return;
+        }
+
+        if audio.beat {
+            self.beats += 1;
+            if self.beats >= self.beats_per_cut.max(1) && time - self.last_cut >= self.min_interval && self.shots.len() > 1 {
+                // Pick any other shot, deterministically from the seed and cut count
+                let offset = 1 + hash(self.seed, self.cuts) as usize % (self.shots.len() - 1);
+                self.current = (self.current + offset) % self.shots.len();
+                self.cuts += 1;
+                self.beats = 0;
+                self.last_cut = time;
+            }
+        }
+
+        let shot = self.shots[self.current.min(self.shots.len() - 1)];
+        let toward = (shot.target - shot.position).normalize_or_zero();
+        let push = (self.drift * (time - self.last_cut).max(0.0)).min(shot.position.distance(shot.target) * MAX_DRIFT);
+        camera.position = shot.position + toward * push;
+        camera.target = shot.target;
+        camera.up = Vec3::Y;
+    }
+}
+
+impl Default for BeatCuts {
+    fn default() -> Self {
+        Self {
+            shots: Vec::new(),
+            beats_per_cut: 4,
+            min_interval: 0.5,
+            drift: 0.1,
+            seed: 0,
+            current: 0,
+            beats: 0,
+            cuts: 0,
+            last_cut: 0.0,
+        }
+    }
+}
+
+/// Selectable controller types, for menus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CameraControllerKind {
+    Static,
+    Orbit,
+    Fly,
+    Path,
+    BeatOrbit,
+    BeatCuts,
+}
+
+impl CameraControllerKind {
+    pub const ALL: [CameraControllerKind; 6] = [
+        CameraControllerKind::Static,
+        CameraControllerKind::Orbit,
+        CameraControllerKind::Fly,
+        CameraControllerKind::Path,
+        CameraControllerKind::BeatOrbit,
+        CameraControllerKind::BeatCuts,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CameraControllerKind::Static => "Static",
+            CameraControllerKind::Orbit => "Orbit",
+            CameraControllerKind::Fly => "Fly",
+            CameraControllerKind::Path => "Spline Path",
+            CameraControllerKind::BeatOrbit => "Beat Orbit",
+            CameraControllerKind::BeatCuts => "Beat Cuts",
+        }
+    }
+}
+
+/// What moves the camera each frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CameraController {
+    /// Fixed pose
+    Static { position: Vec3, target: Vec3 },
+    Orbit(OrbitController),
+    Fly(FlyController),
+    Path(CameraPath),
+    BeatOrbit(BeatOrbit),
+    BeatCuts(BeatCuts),
+}
+
+impl CameraController {
+    /// Controller of the given kind, starting from the camera's current view
+    pub fn from_camera(kind: CameraControllerKind, camera: &Camera) -> Self {
+        let offset = camera.position - camera.target;
+        let radius = Vec2::new(offset.x, offset.z).length().max(1.0);
+        match kind {
+            CameraControllerKind::Static => CameraController::Static {
+                position: camera.position,
+                target: camera.target,
+            },
+            CameraControllerKind::Orbit => CameraController::Orbit(OrbitController::looking_at(camera.position, camera.target)),
+            CameraControllerKind::Fly => CameraController::Fly(FlyController::looking_at(camera.position, camera.target)),
+            CameraControllerKind::Path => CameraController::Path(CameraPath::circle(camera.target, radius, offset.y, 20.0, 8)),
+            CameraControllerKind::BeatOrbit => CameraController::BeatOrbit(BeatOrbit {
+                target: camera.target,
+                radius,
+                height: offset.y,
+                angle: offset.x.atan2(offset.z),
+                ..Default::default()
+            }),
+            CameraControllerKind::BeatCuts => CameraController::BeatCuts(BeatCuts::around(camera.target, radius, 6)),
+        }
+    }
+
+    pub fn kind(&self) -> CameraControllerKind {
+        match self {
+            CameraController::Static { .. } => CameraControllerKind::Static,
+            CameraController::Orbit(_) => CameraControllerKind::Orbit,
+            CameraController::Fly(_) => CameraControllerKind::Fly,
+            CameraController::Path(_) => CameraControllerKind::Path,
+            CameraController::BeatOrbit(_) => CameraControllerKind::BeatOrbit,
+            CameraController::BeatCuts(_) => CameraControllerKind::BeatCuts,
+        }
+    }
+
+    /// Move the camera for this frame
+    /// Interactive controllers use `input`; automatic ones ignore it
+    pub fn update(&mut self, camera: &mut Camera, input: &CameraInput, time: &TimeInfo, audio: &AudioFeatures) {
+        match self {
+            CameraController::Static { position, target } => {
+                camera.position = *position;
+                camera.target = *target;
+                camera.up = Vec3::Y;
+            }
+            CameraController::Orbit(orbit) => orbit.update(camera, input, time.delta),
+            CameraController::Fly(fly) => fly.update(camera, input, time.delta),
+            CameraController::Path(path) => path.update(camera, time.elapsed as f32),
+            CameraController::BeatOrbit(orbit) => orbit.update(camera, time.delta, audio),
+            CameraController::BeatCuts(cuts) => cuts.update(camera, time.elapsed as f32, audio),
+        }
+    }
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        CameraController::Orbit(OrbitController::looking_at(Vec3::new(3.0, 2.0, 5.0), Vec3::ZERO))
+    }
+}
+
+/// Noise-driven camera shake from accumulated trauma
+///
+/// Trauma (0..1) rises on beats or loud bands and decays over time; the
+/// shake amount is trauma squared, so small hits stay subtle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraShake {
+    /// Largest positional offset in world units
+    pub max_offset: f32,
+    /// Largest roll in radians
+    pub max_roll: f32,
+    /// Noise frequency in Hz
+    pub frequency: f32,
+    /// Trauma lost per second
+    pub decay: f32,
+    /// Trauma added on each beat
+    pub beat_trauma: f32,
+    /// Band that adds `band_trauma * level` per second
+    pub band: Option<AudioBand>,
+    pub band_trauma: f32,
+    pub seed: u32,
+    #[serde(skip)]
+    trauma: f32,
+}
+
+impl CameraShake {
+    /// Add trauma, e.g. for a manual hit; clamped to 1
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Update trauma from audio and offset the camera
+    /// Call after the controller has set the unshaken pose
+    pub fn update(&mut self, camera: &mut Camera, time: &TimeInfo, audio: &AudioFeatures) {
+        if audio.beat {
+            self.add_trauma(self.beat_trauma);
+        }
+        if let Some(band) = self.band {
+            self.add_trauma(audio.band(band) * self.band_trauma * time.delta);
+        }
+        self.trauma = (self.trauma - self.decay * time.delta).max(0.0);
+
+        let shake = self.trauma * self.trauma;
+        if shake <= 0.0 {
+            return;
+        }
+
+        let t = time.elapsed as f32 * self.frequency;
+        let offset = Vec3::new(
+            noise1(t, self.seed),
+            noise1(t, self.seed.wrapping_add(1)),
+            noise1(t, self.seed.wrapping_add(2)),
+        ) * self.max_offset
+            * shake;
+        let roll = noise1(t, self.seed.wrapping_add(3)) * self.max_roll * shake;
+
+        let forward = (camera.target - camera.position).normalize_or_zero();
+        camera.position += offset;
+        camera.target += offset;
+        camera.up = Quat::from_axis_angle(forward, roll) * camera.up;
+    }
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            max_offset: 0.15,
+            max_roll: 0.05,
+            frequency: 12.0,
+            decay: 1.5,
+            beat_trauma: 0.0,
+            band: None,
+            band_trauma: 0.0,
+            seed: 0,
+            trauma: 0.0,
+        }
+    }
+}
+
+/// Smooth 1D value noise in -1..1
+fn noise1(x: f32, seed: u32) -> f32 {
+    let i = x.floor();
+    let f = x - i;
+    let s = f * f * (3.0 - 2.0 * f);
+    let value = |n: i32| hash(seed, n as u32) as f32 / u32::MAX as f32 * 2.0 - 1.0;
+    let (a, b) = (value(i as i32), value(i as i32 + 1));
+    a + (b - a) * s
+}
+
+fn hash(seed: u32, n: u32) -> u32 {
+    let mut h = seed.wrapping_mul(0x9E37_79B9) ^ n.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7FEB_352D);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846C_A68B);
+    h ^ (h >> 16)
+}
+
+/// Camera controller, shake and projection stored with a scene
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraRig {
+    pub controller: CameraController,
+    pub shake: CameraShake,
+    pub projection: Projection,
+}
+
+impl CameraRig {
+    /// Switch controller, continuing from the camera's current view
+    pub fn set_controller_kind(&mut self, kind: CameraControllerKind, camera: &Camera) {
+        if self.controller.kind() != kind {
+            self.controller = CameraController::from_camera(kind, camera);
+        }
+    }
+
+    /// Move the camera for this frame
+    /// Deterministic given the same inputs, so offline export matches live output
+    pub fn update(&mut self, camera: &mut Camera, input: &CameraInput, time: &TimeInfo, audio: &AudioFeatures) {
+        camera.projection = self.projection;
+        self.controller.update(camera, input, time, audio);
+        self.shake.update(camera, time, audio);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera() -> Camera {
+        Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO, 1.0)
+    }
+
+    fn line_path(looped: bool) -> CameraPath {
+        let keyframe = |time: f32, x: f32| CameraKeyframe {
+            time,
+            position: Vec3::new(x, 0.0, 0.0),
+            target: Vec3::ZERO,
+        };
+        CameraPath {
+            keyframes: vec![keyframe(0.0, 0.0), keyframe(1.0, 4.0), keyframe(3.0, 2.0), keyframe(4.0, 0.0)],
+            looped,
+        }
+    }
+
+    #[test]
+    fn path_passes_through_keyframes() {
+        for looped in [false, true] {
+            let path = line_path(looped);
+            for keyframe in &path.keyframes[..3] {
+                let (position, _) = path.sample(keyframe.time).unwrap();
+                assert!(position.distance(keyframe.position) < 1e-5, "{:?} at {}", position, keyframe.time);
+            }
+        }
+        assert_eq!(CameraPath { keyframes: Vec::new(), looped: false }.sample(1.0), None);
+    }
+
+    #[test]
+    fn clamped_path_holds_its_ends() {
+        let path = line_path(false);
+        assert_eq!(path.sample(-2.0).unwrap().0, Vec3::ZERO);
+        assert_eq!(path.sample(10.0).unwrap().0, Vec3::ZERO);
+        assert_eq!(path.sample(0.0), path.sample(-1.0));
+    }
+
+    #[test]
+    fn looped_path_wraps_time() {
+        let path = CameraPath::circle(Vec3::ZERO, 5.0, 1.0, 8.0, 8);
+        for time in [0.5, 3.0, 7.9] {
+            let (position, target) = path.sample(time).unwrap();
+            let (wrapped, _) = path.sample(time + 8.0).unwrap();
+            let (before, _) = path.sample(time - 8.0).unwrap();
+            assert!(position.distance(wrapped) < 1e-4 && position.distance(before) < 1e-4);
+            assert_eq!(target, Vec3::ZERO);
+            // Catmull-Rom stays close to the circle between keyframes
+            assert!((Vec2::new(position.x, position.z).length() - 5.0).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn orbit_pitch_is_clamped() {
+        let mut orbit = OrbitController::default();
+        let mut camera = camera();
+        let drag = |y: f32| CameraInput { rotate: Vec2::new(0.0, y), ..Default::default() };
+        orbit.update(&mut camera, &drag(1e4), 0.0);
+        assert_eq!(orbit.pitch, MAX_PITCH);
+        // Never straight overhead, where the view's up vector degenerates
+        assert!(Vec2::new(camera.position.x, camera.position.z).length() > 0.05);
+        orbit.update(&mut camera, &drag(-1e5), 0.0);
+        assert_eq!(orbit.pitch, -MAX_PITCH);
+    }
+
+    #[test]
+    fn beat_cuts_wait_for_beats_and_interval() {
+        let mut cuts = BeatCuts {
+            beats_per_cut: 2,
+            min_interval: 0.5,
+            ..BeatCuts::around(Vec3::ZERO, 5.0, 4)
+        };
+        let mut camera = camera();
+        let beat = AudioFeatures { beat: true, ..Default::default() };
+        cuts.update(&mut camera, 0.1, &beat);
+        assert_eq!(cuts.current_shot(), 0);
+        // Enough beats, but too soon after the start
+        cuts.update(&mut camera, 0.2, &beat);
+        assert_eq!(cuts.current_shot(), 0);
+        cuts.update(&mut camera, 0.6, &AudioFeatures::default());
+        assert_eq!(cuts.current_shot(), 0);
+        cuts.update(&mut camera, 0.7, &beat);
+        assert_ne!(cuts.current_shot(), 0);
+    }
+
+    #[test]
+    fn beat_cuts_are_seeded_and_never_repeat_a_shot() {
+        let sequence = |seed: u32| {
+            let mut cuts = BeatCuts {
+                beats_per_cut: 1,
+                min_interval: 0.0,
+                seed,
+                ..BeatCuts::around(Vec3::ZERO, 5.0, 5)
+            };
+            let mut camera = camera();
+            let beat = AudioFeatures { beat: true, ..Default::default() };
+            (0..32)
+                .map(|i| {
+                    cuts.update(&mut camera, i as f32, &beat);
+                    cuts.current_shot()
+                })
+                .collect::<Vec<_>>()
+        };
+        let shots = sequence(7);
+        assert_eq!(shots, sequence(7));
+        assert_ne!(shots, sequence(8));
+        assert!(shots.windows(2).all(|pair| pair[0] != pair[1]));
+        assert!(shots.iter().all(|&shot| shot < 5));
+    }
+
+    #[test]
+    fn beat_cuts_drift_stops_halfway() {
+        let mut cuts = BeatCuts { drift: 1.0, ..BeatCuts::around(Vec3::ZERO, 4.0, 2) };
+        let shot = cuts.shots[0];
+        let mut camera = camera();
+        cuts.update(&mut camera, 1.0, &AudioFeatures::default());
+        assert!((camera.position.distance(shot.position) - 1.0).abs() < 1e-5);
+        cuts.update(&mut camera, 1000.0, &AudioFeatures::default());
+        let distance = shot.position.distance(shot.target);
+        assert!((camera.position.distance(shot.target) - distance * MAX_DRIFT).abs() < 1e-4);
+    }
+}
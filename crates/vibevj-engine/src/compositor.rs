@@ -0,0 +1,602 @@
+use serde::{Deserialize, Serialize};
+use crate::animated_texture::Playback;
+use crate::pipeline::BindGroupLayoutBuilder;
+use crate::render_target::RenderTarget;
+use crate::video::VideoPlayback;
+
+/// How a layer combines with the layers below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Add,
+    Screen,
+    Multiply,
+    Overlay,
+    Difference,
+    /// Normal blend with dark parts keyed out (see `Layer::key_threshold`)
+    LumaKey,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 7] = [
+        BlendMode::Normal,
+        BlendMode::Add,
+        BlendMode::Screen,
+        BlendMode::Multiply,
+        BlendMode::Overlay,
+        BlendMode::Difference,
+        BlendMode::LumaKey,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BlendMode::Normal => "Normal",
+            BlendMode::Add => "Add",
+            BlendMode::Screen => "Screen",
+            BlendMode::Multiply => "Multiply",
+            BlendMode::Overlay => "Overlay",
+            BlendMode::Difference => "Difference",
+            BlendMode::LumaKey => "Luma Key",
+        }
+    }
+
+    fn shader_index(&self) -> u32 {
+        match self {
+            BlendMode::Normal => 0,
+            BlendMode::Add => 1,
+            BlendMode::Screen => 2,
+            BlendMode::Multiply => 3,
+            BlendMode::Overlay => 4,
+            BlendMode::Difference => 5,
+            BlendMode::LumaKey => 6,
+        }
+    }
+}
+
+/// Shader mode for the deck crossfade pass
+const CROSSFADE_MODE: u32 = 7;
+
+/// Where a layer's image comes from
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LayerSource {
+    /// The tone-mapped 3D scene
+    Scene,
+    /// Fullscreen WGSL file defining `shade(uv)` (see `shader_layer.wgsl`)
+    Shader { path: String },
+    /// Image, GIF, APNG or `#`-numbered image sequence
+    Image {
+        path: String,
+        #[serde(default)]
+        playback: Playback,
+    },
+    /// Y4M or MJPEG AVI video file
+    Video {
+        path: String,
+        #[serde(default)]
+        playback: VideoPlayback,
+    },
+}
+
+impl LayerSource {
+    pub fn name(&self) -> &str {
+        match self {
+            LayerSource::Scene => "Scene",
+            LayerSource::Shader { path } | LayerSource::Image { path, .. } | LayerSource::Video { path, .. } => path,
+        }
+    }
+}
+
+/// One layer in a deck
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Layer {
+    pub name: String,
+    pub source: LayerSource,
+    /// 0 = invisible, 1 = fully blended
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+    /// Luma below which `LumaKey` layers are transparent
+    pub key_threshold: f32,
+    /// Luma range over which the key fades in
+    pub key_softness: f32,
+    pub mute: bool,
+    /// While any layer in a deck is soloed, only soloed layers are shown
+    pub solo: bool,
+}
+
+impl Layer {
+    pub fn new(name: impl Into<String>, source: LayerSource) -> Self {
+        Self {
+            name: name.into(),
+            source,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+}
+
+impl Default for Layer {
+    fn default() -> Self {
+        Self {
+            name: "Layer".to_string(),
+            source: LayerSource::Scene,
+            opacity: 1.0,
+            blend_mode: BlendMode::Normal,
+            key_threshold: 0.1,
+            key_softness: 0.1,
+            mute: false,
+            solo: false,
+        }
+    }
+}
+
+/// Stack of layers composited bottom (first) to top (last)
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Deck {
+    pub layers: Vec<Layer>,
+}
+
+impl Deck {
+    pub fn new(layers: Vec<Layer>) -> Self {
+        Self { layers }
+    }
+
+    /// Layers that contribute to the output, honouring solo and mute
+    pub fn visible_layers(&self) -> impl Iterator<Item = &Layer> {
+        let any_solo = self.layers.iter().any(|layer| layer.solo);
+        self.layers
+            .iter()
+            .filter(move |layer| if any_solo { layer.solo } else { !layer.mute })
+            .filter(|layer| layer.opacity > 0.0)
+    }
+}
+
+/// Crossfader response from deck A (position 0) to deck B (position 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CrossfadeCurve {
+    /// Weights sum to one; dips in brightness halfway
+    #[default]
+    Linear,
+    /// Constant power (sine/cosine), no dip for uncorrelated sources
+    EqualPower,
+    /// Linear with eased ends
+    Smooth,
+    /// Hard switch at the centre
+    Cut,
+}
+
+impl CrossfadeCurve {
+    pub const ALL: [CrossfadeCurve; 4] = [
+        CrossfadeCurve::Linear,
+        CrossfadeCurve::EqualPower,
+        CrossfadeCurve::Smooth,
+        CrossfadeCurve::Cut,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CrossfadeCurve::Linear => "Linear",
+            CrossfadeCurve::EqualPower => "Equal Power",
+            CrossfadeCurve::Smooth => "Smooth",
+            CrossfadeCurve::Cut => "Cut",
+        }
+    }
+
+    /// Weights of deck A and deck B at a fader position in 0..1
+    pub fn weights(&self, position: f32) -> (f32, f32) {
+        let x = position.clamp(0.0, 1.0);
+        match self {
+            CrossfadeCurve::Linear => (1.0 - x, x),
+            CrossfadeCurve::EqualPower => {
+                let angle = x * std::f32::consts::FRAC_PI_2;
+                (angle.cos(), angle.sin())
+            }
+            CrossfadeCurve::Smooth => {
+                let s = x * x * (3.0 - 2.0 * x);
+                (1.0 - s, s)
+            }
+            CrossfadeCurve::Cut => {
+                if x < 0.5 {
+                    (1.0, 0.0)
+                } else {
+                    (0.0, 1.0)
+                }
+            }
+        }
+    }
+}
+
+/// A/B crossfader between two decks
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Crossfader {
+    /// 0 = deck A only, 1 = deck B only
+    pub position: f32,
+    pub curve: CrossfadeCurve,
+}
+
+impl Crossfader {
+    pub fn weights(&self) -> (f32, f32) {
+        self.curve.weights(self.position)
+    }
+}
+
+/// Serializable mixer state: two decks and the crossfader between them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MixerSettings {
+    pub deck_a: Deck,
+    pub deck_b: Deck,
+    pub crossfader: Crossfader,
+}
+
+impl Default for MixerSettings {
+    fn default() -> Self {
+        Self {
+            deck_a: Deck::new(vec![Layer::new("Scene", LayerSource::Scene)]),
+            deck_b: Deck::default(),
+            crossfader: Crossfader::default(),
+        }
+    }
+}
+
+impl MixerSettings {
+    /// Sources of the visible layers in decks the crossfader lets through
+    pub fn sources(&self) -> impl Iterator<Item = &LayerSource> {
+        let (weight_a, weight_b) = self.crossfader.weights();
+        let deck_a = self.deck_a.visible_layers().filter(move |_| weight_a > 0.0);
+        let deck_b = self.deck_b.visible_layers().filter(move |_| weight_b > 0.0);
+        deck_a.chain(deck_b).map(|layer| &layer.source)
+    }
+
+    /// Whether the 3D scene is visible in the mix
+    pub fn uses_scene(&self) -> bool {
+        self.sources().any(|source| *source == LayerSource::Scene)
+    }
+}
+
+/// Compositing pass parameters
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CompositeUniform {
+    pub mode: u32,
+    pub opacity: f32,
+    pub key_threshold: f32,
+    pub key_softness: f32,
+    pub weight_a: f32,
+    pub weight_b: f32,
+    pub _padding: [f32; 2],
+}
+
+impl CompositeUniform {
+    fn layer(layer: &Layer) -> Self {
+        Self {
+            mode: layer.blend_mode.shader_index(),
+            opacity: layer.opacity.clamp(0.0, 1.0),
+            key_threshold: layer.key_threshold,
+            key_softness: layer.key_softness,
+            weight_a: 0.0,
+            weight_b: 0.0,
+            _padding: [0.0; 2],
+        }
+    }
+
+    fn crossfade(crossfader: &Crossfader) -> Self {
+        let (weight_a, weight_b) = crossfader.weights();
+        Self {
+            mode: CROSSFADE_MODE,
+            opacity: 1.0,
+            key_threshold: 0.0,
+            key_softness: 0.0,
+            weight_a,
+            weight_b,
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+/// One fullscreen pass: `base` and `layer` in, `target` out
+struct CompositePass<'a> {
+    base: &'a wgpu::TextureView,
+    layer: &'a wgpu::TextureView,
+    target: &'a wgpu::TextureView,
+    uniform: CompositeUniform,
+}
+
+/// Layers decks of textures with blend modes and crossfades them into an output
+///
+/// Each deck is accumulated in HDR (ping-ponging between two targets), so
+/// additive layers can exceed 1.0 until the output format clamps them.
+/// Sources are sampled through their views; sRGB textures are blended in
+/// linear light.
+pub struct Compositor {
+    accumulate_pipeline: wgpu::RenderPipeline,
+    output_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+    uniform_stride: u64,
+    uniform_capacity: u64,
+    /// Ping-pong targets for deck A and deck B
+    targets: [RenderTarget; 4],
+    output_format: wgpu::TextureFormat,
+}
+
+impl Compositor {
+    /// Format decks are accumulated in
+    pub const ACCUMULATION_FORMAT: wgpu::TextureFormat = RenderTarget::HDR_FORMAT;
+
+    /// Create a compositor for `width` x `height` layers, writing to `output_format`
+    pub fn new(device: &wgpu::Device, output_format: wgpu::TextureFormat, width: u32, height: u32) -> Self {
+        let bind_group_layout = BindGroupLayoutBuilder::new(wgpu::ShaderStages::FRAGMENT)
+            .texture(wgpu::TextureViewDimension::D2)
+            .texture(wgpu::TextureViewDimension::D2)
+            .sampler()
+            .entry(wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: true,
+                min_binding_size: wgpu::BufferSize::new(std::mem::size_of::<CompositeUniform>() as u64),
+            })
+            .build(device, Some("Compositor Bind Group Layout"));
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Compositor Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Compositor Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../../assets/shaders/compositor.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Compositor Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |format: wgpu::TextureFormat, label: &str| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+        let accumulate_pipeline = create_pipeline(Self::ACCUMULATION_FORMAT, "Compositor Accumulate Pipeline");
+        let output_pipeline = create_pipeline(output_format, "Compositor Output Pipeline");
+
+        let uniform_stride = (std::mem::size_of::<CompositeUniform>() as u64)
+            .next_multiple_of(device.limits().min_uniform_buffer_offset_alignment as u64);
+        let uniform_capacity = 16;
+        let uniform_buffer = Self::create_uniform_buffer(device, uniform_stride * uniform_capacity);
+
+        Self {
+            accumulate_pipeline,
+            output_pipeline,
+            bind_group_layout,
+            sampler,
+            uniform_buffer,
+            uniform_stride,
+            uniform_capacity,
+            targets: Self::create_targets(device, width, height),
+            output_format,
+        }
+    }
+
+    fn create_uniform_buffer(device: &wgpu::Device, size: u64) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Compositor Uniform Buffer"),
+            size,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    fn create_targets(device: &wgpu::Device, width: u32, height: u32) -> [RenderTarget; 4] {
+        ["Deck A", "Deck A", "Deck B", "Deck B"].map(|label| {
+            RenderTarget::new(device, width, height, Self::ACCUMULATION_FORMAT, Some(label))
+        })
+    }
+
+    /// Get the output format the final pass was built for
+    pub fn output_format(&self) -> wgpu::TextureFormat {
+        self.output_format
+    }
+
+    /// Resize the accumulation targets
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        if (self.targets[0].width, self.targets[0].height) != (width, height) {
+            self.targets = Self::create_targets(device, width, height);
+        }
+    }
+
+    /// Composite both decks and crossfade them into `output`
+    ///
+    /// `sources` maps a layer source to its current texture; layers whose
+    /// source has no texture (not loaded, failed) are skipped. A deck with no
+    /// weight on the crossfader is not rendered.
+    pub fn render<'s>(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        mixer: &MixerSettings,
+        sources: impl Fn(&LayerSource) -> Option<&'s wgpu::TextureView>,
+        output: &wgpu::TextureView,
+    ) {
+        let resolve = |deck: &Deck| -> Vec<(CompositeUniform, &'s wgpu::TextureView)> {
+            deck.visible_layers()
+                .filter_map(|layer| Some((CompositeUniform::layer(layer), sources(&layer.source)?)))
+                .collect()
+        };
+        let (weight_a, weight_b) = mixer.crossfader.weights();
+        let layers_a = if weight_a > 0.0 { resolve(&mixer.deck_a) } else { Vec::new() };
+        let layers_b = if weight_b > 0.0 { resolve(&mixer.deck_b) } else { Vec::new() };
+        self.reserve_uniforms(device, (layers_a.len() + layers_b.len() + 1) as u64);
+
+        // The first pass of each deck reads a cleared target
+        let [a0, a1, b0, b1] = &self.targets;
+        Self::clear(encoder, &a0.view);
+        Self::clear(encoder, &b0.view);
+
+        let mut passes = Vec::new();
+        let result_a = Self::deck_passes(&mut passes, &layers_a, &a0.view, &a1.view);
+        let result_b = Self::deck_passes(&mut passes, &layers_b, &b0.view, &b1.view);
+        passes.push(CompositePass {
+            base: result_a,
+            layer: result_b,
+            target: output,
+            uniform: CompositeUniform::crossfade(&mixer.crossfader),
+        });
+
+        let mut uniforms = vec![0u8; (passes.len() as u64 * self.uniform_stride) as usize];
+        for (i, pass) in passes.iter().enumerate() {
+            let offset = i * self.uniform_stride as usize;
+            uniforms[offset..offset + std::mem::size_of::<CompositeUniform>()].copy_from_slice(bytemuck::bytes_of(&pass.uniform));
+        }
+        queue.write_buffer(&self.uniform_buffer, 0, &uniforms);
+
+        for (i, pass) in passes.iter().enumerate() {
+            let pipeline = if i + 1 == passes.len() { &self.output_pipeline } else { &self.accumulate_pipeline };
+            self.record_pass(device, encoder, pass, pipeline, i as u64 * self.uniform_stride);
+        }
+    }
+
+    /// Passes blending `layers` in order, ping-ponging between two targets
+    /// Returns the view holding the deck result
+    fn deck_passes<'a>(
+        passes: &mut Vec<CompositePass<'a>>,
+        layers: &[(CompositeUniform, &'a wgpu::TextureView)],
+        ping: &'a wgpu::TextureView,
+        pong: &'a wgpu::TextureView,
+    ) -> &'a wgpu::TextureView {
+        let (mut base, mut target) = (ping, pong);
+        for &(uniform, layer) in layers {
+            passes.push(CompositePass { base, layer, target, uniform });
+            std::mem::swap(&mut base, &mut target);
+        }
+        base
+    }
+
+    /// Grow the uniform buffer to hold at least `count` passes
+    fn reserve_uniforms(&mut self, device: &wgpu::Device, count: u64) {
+        if count > self.uniform_capacity {
+            self.uniform_capacity = count.next_power_of_two();
+            self.uniform_buffer = Self::create_uniform_buffer(device, self.uniform_stride * self.uniform_capacity);
+        }
+    }
+
+    fn record_pass(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        pass: &CompositePass,
+        pipeline: &wgpu::RenderPipeline,
+        uniform_offset: u64,
+    ) {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Compositor Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(pass.base),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(pass.layer),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: &self.uniform_buffer,
+                        offset: 0,
+                        size: wgpu::BufferSize::new(std::mem::size_of::<CompositeUniform>() as u64),
+                    }),
+                },
+            ],
+        });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Compositor Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: pass.target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[uniform_offset as u32]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// Clear a target to transparent black
+    fn clear(encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Compositor Clear Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+    }
+}
@@ -0,0 +1,164 @@
+use vibevj_common::{Result, VibeVJError};
+
+/// Compute pipeline wrapper
+pub struct ComputePipeline {
+    pub compute_pipeline: wgpu::ComputePipeline,
+    /// Bind group layouts in group order
+    pub bind_group_layouts: Vec<wgpu::BindGroupLayout>,
+    /// Workgroup size declared by the shader's `@workgroup_size`
+    pub workgroup_size: [u32; 3],
+}
+
+impl ComputePipeline {
+    /// Create a new compute pipeline builder
+    pub fn builder() -> ComputePipelineBuilder {
+        ComputePipelineBuilder::new()
+    }
+
+    /// Get the bind group layout for a group index
+    pub fn bind_group_layout(&self, group: usize) -> Option<&wgpu::BindGroupLayout> {
+        self.bind_group_layouts.get(group)
+    }
+
+    /// Create a bind group for one of this pipeline's groups
+    pub fn create_bind_group(
+        &self,
+        device: &wgpu::Device,
+        group: usize,
+        entries: &[wgpu::BindGroupEntry],
+        label: Option<&str>,
+    ) -> Result<wgpu::BindGroup> {
+        let layout = self.bind_group_layout(group).ok_or_else(|| {
+            VibeVJError::RenderError(format!("Compute pipeline has no bind group {}", group))
+        })?;
+
+        Ok(device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label,
+            layout,
+            entries,
+        }))
+    }
+
+    /// Record a dispatch of an explicit number of workgroups
+    pub fn dispatch(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        bind_groups: &[&wgpu::BindGroup],
+        workgroups: [u32; 3],
+        label: Option<&str>,
+    ) {
+        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label,
+            timestamp_writes: None,
+        });
+
+        compute_pass.set_pipeline(&self.compute_pipeline);
+        for (index, bind_group) in bind_groups.iter().enumerate() {
+            compute_pass.set_bind_group(index as u32, *bind_group, &[]);
+        }
+        compute_pass.dispatch_workgroups(workgroups[0], workgroups[1], workgroups[2]);
+    }
+
+    /// Record a dispatch covering at least `invocations` threads per axis
+    /// The shader is expected to bounds-check the overshoot
+    pub fn dispatch_invocations(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        bind_groups: &[&wgpu::BindGroup],
+        invocations: [u32; 3],
+        label: Option<&str>,
+    ) {
+        let workgroups = Self::workgroup_count(invocations, self.workgroup_size);
+        self.dispatch(encoder, bind_groups, workgroups, label);
+    }
+
+    /// Number of workgroups needed to cover `invocations` with a given workgroup size
+    pub fn workgroup_count(invocations: [u32; 3], workgroup_size: [u32; 3]) -> [u32; 3] {
+        [
+            invocations[0].div_ceil(workgroup_size[0].max(1)),
+            invocations[1].div_ceil(workgroup_size[1].max(1)),
+            invocations[2].div_ceil(workgroup_size[2].max(1)),
+        ]
+    }
+}
+
+/// Builder for creating compute pipelines
+pub struct ComputePipelineBuilder {
+    shader: Option<wgpu::ShaderModule>,
+    entry_point: String,
+    bind_group_layouts: Vec<wgpu::BindGroupLayout>,
+    workgroup_size: [u32; 3],
+}
+
+impl ComputePipelineBuilder {
+    pub fn new() -> Self {
+        Self {
+            shader: None,
+            entry_point: "cs_main".to_string(),
+            bind_group_layouts: Vec::new(),
+            workgroup_size: [64, 1, 1],
+        }
+    }
+
+    pub fn shader(mut self, shader: wgpu::ShaderModule) -> Self {
+        self.shader = Some(shader);
+        self
+    }
+
+    /// Set the entry point name (defaults to `cs_main`)
+    pub fn entry_point(mut self, entry_point: &str) -> Self {
+        self.entry_point = entry_point.to_string();
+        self
+    }
+
+    /// Add a bind group layout (group index matches call order)
+    pub fn bind_group_layout(mut self, layout: wgpu::BindGroupLayout) -> Self {
+        self.bind_group_layouts.push(layout);
+        self
+    }
+
+    /// Replace all bind group layouts
+    pub fn bind_group_layouts(mut self, layouts: Vec<wgpu::BindGroupLayout>) -> Self {
+        self.bind_group_layouts = layouts;
+        self
+    }
+
+    /// Declare the shader's workgroup size, used by `dispatch_invocations`
+    pub fn workgroup_size(mut self, workgroup_size: [u32; 3]) -> Self {
+        self.workgroup_size = workgroup_size;
+        self
+    }
+
+    pub fn build(self, device: &wgpu::Device, label: Option<&str>) -> Result<ComputePipeline> {
+        let shader = self.shader
+            .ok_or_else(|| VibeVJError::RenderError("Compute shader not set".to_string()))?;
+
+        let layout_refs: Vec<&wgpu::BindGroupLayout> = self.bind_group_layouts.iter().collect();
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Compute Pipeline Layout"),
+            bind_group_layouts: &layout_refs,
+            push_constant_ranges: &[],
+        });
+
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label,
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some(&self.entry_point),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        Ok(ComputePipeline {
+            compute_pipeline,
+            bind_group_layouts: self.bind_group_layouts,
+            workgroup_size: self.workgroup_size,
+        })
+    }
+}
+
+impl Default for ComputePipelineBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,330 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use vibevj_common::{Result, VibeVJError};
+use crate::camera_controller::CameraRig;
+
+/// Output container for offline export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    /// Numbered PNG files in a directory
+    PngSequence,
+    /// Uncompressed YUV4MPEG2 stream (4:4:4)
+    Y4m,
+}
+
+impl ExportFormat {
+    /// Guess the format from an output path (`.y4m` or a directory)
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("y4m") => ExportFormat::Y4m,
+            _ => ExportFormat::PngSequence,
+        }
+    }
+}
+
+/// Settings for a deterministic offline render
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportSettings {
+    /// Directory for PNG sequences, file path for Y4M
+    pub output: PathBuf,
+    pub format: ExportFormat,
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    /// Length of the render in seconds
+    pub duration: f64,
+    /// First frame written (earlier frames are still simulated)
+    pub start_frame: u64,
+    /// Frame after the last one written, defaults to the end of `duration`
+    pub end_frame: Option<u64>,
+    /// Keep the alpha channel (transparent background)
+    pub alpha: bool,
+    /// WAV file driving audio-reactive features
+    pub audio: Option<PathBuf>,
+    /// Scene JSON whose settings, sprites and videos are rendered
+    #[serde(default)]
+    pub scene: Option<PathBuf>,
+    /// Camera movement replacing the scene's, updated with the same fixed
+    /// time step as the scene
+    #[serde(default)]
+    pub camera: Option<CameraRig>,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            output: PathBuf::from("export"),
+            format: ExportFormat::PngSequence,
+            width: 1920,
+            height: 1080,
+            fps: 60,
+            duration: 10.0,
+            start_frame: 0,
+            end_frame: None,
+            alpha: false,
+            audio: None,
+            scene: None,
+            camera: None,
+        }
+    }
+}
+
+impl ExportSettings {
+    /// Total number of frames covered by `duration`
+    pub fn frame_count(&self) -> u64 {
+        (self.duration * self.fps as f64).round() as u64
+    }
+
+    /// Frames that are written to the output
+    pub fn frame_range(&self) -> Range<u64> {
+        let end = self.end_frame.unwrap_or(self.frame_count()).min(self.frame_count());
+        self.start_frame.min(end)..end
+    }
+
+    /// Create the writer for this output format
+    pub fn create_writer(&self) -> Result<Box<dyn FrameWriter>> {
+        Ok(match self.format {
+            ExportFormat::PngSequence => Box::new(PngSequenceWriter::new(
+                &self.output,
+                self.width,
+                self.height,
+                self.alpha,
+                self.frame_range().start,
+            )?),
+            ExportFormat::Y4m => Box::new(Y4mWriter::new(
+                &self.output,
+                self.width,
+                self.height,
+                self.fps,
+                self.alpha,
+            )?),
+        })
+    }
+}
+
+/// Destination for rendered frames
+pub trait FrameWriter {
+    /// Write one frame of tightly packed RGBA8 pixels (sRGB encoded)
+    fn write_frame(&mut self, rgba: &[u8]) -> Result<()>;
+
+    /// Flush and close the output
+    fn finish(&mut self) -> Result<()>;
+}
+
+fn check_frame_size(rgba: &[u8], width: u32, height: u32) -> Result<()> {
+    let expected = width as usize * height as usize * 4;
+    if rgba.len() != expected {
+        return Err(VibeVJError::InvalidOperation(format!(
+            "Frame has {} bytes, expected {} for {}x{} RGBA",
+            rgba.len(),
+            expected,
+            width,
+            height
+        )));
+    }
+    Ok(())
+}
+
+/// Writes `frame_000000.png`, `frame_000001.png`, ... into a directory
+pub struct PngSequenceWriter {
+    directory: PathBuf,
+    width: u32,
+    height: u32,
+    alpha: bool,
+    next_frame: u64,
+}
+
+impl PngSequenceWriter {
+    /// Create the output directory; files are numbered from `first_frame`
+    pub fn new(directory: &Path, width: u32, height: u32, alpha: bool, first_frame: u64) -> Result<Self> {
+        std::fs::create_dir_all(directory)?;
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            width,
+            height,
+            alpha,
+            next_frame: first_frame,
+        })
+    }
+
+    /// Path of a numbered frame
+    pub fn frame_path(&self, frame: u64) -> PathBuf {
+        self.directory.join(format!("frame_{:06}.png", frame))
+    }
+}
+
+impl FrameWriter for PngSequenceWriter {
+    fn write_frame(&mut self, rgba: &[u8]) -> Result<()> {
+        check_frame_size(rgba, self.width, self.height)?;
+        let path = self.frame_path(self.next_frame);
+
+        let result = if self.alpha {
+            image::save_buffer(&path, rgba, self.width, self.height, image::ColorType::Rgba8)
+        } else {
+            let rgb: Vec<u8> = rgba.chunks_exact(4).flat_map(|p| [p[0], p[1], p[2]]).collect();
+            image::save_buffer(&path, &rgb, self.width, self.height, image::ColorType::Rgb8)
+        };
+        result.map_err(|e| VibeVJError::RenderError(format!("Failed to write {}: {}", path.display(), e)))?;
+
+        self.next_frame += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes an uncompressed YUV4MPEG2 stream
+///
+/// Frames are converted to 4:4:4 BT.709 limited range. With alpha the
+/// `C444alpha` colorspace is used, which ffmpeg reads as `yuva444p`.
+pub struct Y4mWriter {
+    writer: BufWriter<File>,
+    width: u32,
+    height: u32,
+    alpha: bool,
+    planes: Vec<u8>,
+}
+
+impl Y4mWriter {
+    pub fn new(path: &Path, width: u32, height: u32, fps: u32, alpha: bool) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        let colorspace = if alpha { "C444alpha" } else { "C444" };
+        writeln!(writer, "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 {} XCOLORRANGE=LIMITED", width, height, fps, colorspace)?;
+
+        Ok(Self {
+            writer,
+            width,
+            height,
+            alpha,
+            planes: Vec::new(),
+        })
+    }
+
+    /// Convert sRGB-encoded RGBA8 to planar Y, Cb, Cr (and A)
+    fn convert(&mut self, rgba: &[u8]) {
+        let pixels = (self.width * self.height) as usize;
+        let plane_count = if self.alpha { 4 } else { 3 };
+        self.planes.resize(pixels * plane_count, 0);
+
+        let (y_plane, rest) = self.planes.split_at_mut(pixels);
+        let (u_plane, rest) = rest.split_at_mut(pixels);
+        let (v_plane, a_plane) = rest.split_at_mut(pixels);
+
+        for (i, pixel) in rgba.chunks_exact(4).enumerate() {
+            let r = pixel[0] as f32;
+            let g = pixel[1] as f32;
+            let b = pixel[2] as f32;
+
+            // BT.709 on gamma-encoded values, scaled to 16-235 / 16-240
+            let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+            let cb = (b - y) / 1.8556;
+            let cr = (r - y) / 1.5748;
+
+            y_plane[i] = (16.0 + y * (219.0 / 255.0)).round().clamp(0.0, 255.0) as u8;
+            u_plane[i] = (128.0 + cb * (224.0 / 255.0)).round().clamp(0.0, 255.0) as u8;
+            v_plane[i] = (128.0 + cr * (224.0 / 255.0)).round().clamp(0.0, 255.0) as u8;
+            if self.alpha {
+                a_plane[i] = pixel[3];
+            }
+        }
+    }
+}
+
+impl FrameWriter for Y4mWriter {
+    fn write_frame(&mut self, rgba: &[u8]) -> Result<()> {
+        check_frame_size(rgba, self.width, self.height)?;
+        self.convert(rgba);
+        self.writer.write_all(b"FRAME\n")?;
+        self.writer.write_all(&self.planes)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("vibevj-{}-{}.y4m", std::process::id(), name))
+    }
+
+    /// Bytes of a Y4M file holding a white and a half-transparent black pixel, twice
+    fn write_y4m(name: &str, alpha: bool) -> Vec<u8> {
+        let path = temp_path(name);
+        let mut writer = Y4mWriter::new(&path, 2, 1, 30, alpha).unwrap();
+        let rgba = [255, 255, 255, 255, 0, 0, 0, 128];
+        writer.write_frame(&rgba).unwrap();
+        writer.write_frame(&rgba).unwrap();
+        assert!(writer.write_frame(&rgba[..4]).is_err());
+        writer.finish().unwrap();
+        drop(writer);
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn y4m_writes_limited_range_444_planes() {
+        let mut expected = b"YUV4MPEG2 W2 H1 F30:1 Ip A1:1 C444 XCOLORRANGE=LIMITED\n".to_vec();
+        for _ in 0..2 {
+            expected.extend(b"FRAME\n");
+            expected.extend([235, 16, 128, 128, 128, 128]);
+        }
+        assert_eq!(write_y4m("opaque", false), expected);
+    }
+
+    #[test]
+    fn y4m_appends_an_alpha_plane() {
+        let mut expected = b"YUV4MPEG2 W2 H1 F30:1 Ip A1:1 C444alpha XCOLORRANGE=LIMITED\n".to_vec();
+        for _ in 0..2 {
+            expected.extend(b"FRAME\n");
+            expected.extend([235, 16, 128, 128, 128, 128, 255, 128]);
+        }
+        assert_eq!(write_y4m("alpha", true), expected);
+    }
+
+    #[test]
+    fn frame_range_stops_at_the_duration() {
+        let settings = ExportSettings {
+            fps: 30,
+            duration: 2.0,
+            ..Default::default()
+        };
+        assert_eq!(settings.frame_range(), 0..60);
+
+        let past_end = ExportSettings {
+            start_frame: 10,
+            end_frame: Some(90),
+            ..settings.clone()
+        };
+        assert_eq!(past_end.frame_range(), 10..60);
+
+        let start_past_end = ExportSettings {
+            start_frame: 75,
+            end_frame: Some(90),
+            ..settings.clone()
+        };
+        assert!(start_past_end.frame_range().is_empty());
+
+        let inside = ExportSettings {
+            start_frame: 5,
+            end_frame: Some(20),
+            ..settings
+        };
+        assert_eq!(inside.frame_range(), 5..20);
+    }
+}
@@ -0,0 +1,44 @@
+use vibevj_common::{AudioFeatures, TimeInfo};
+
+/// Per-frame time and audio data for GPU
+///
+/// Bound next to the camera (group 0, binding 1) so vertex and fragment
+/// shaders can animate without per-object updates.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FrameUniform {
+    /// Elapsed time in seconds
+    pub time: f32,
+    pub delta: f32,
+    /// 1.0 on beat frames, 0.0 otherwise
+    pub beat: f32,
+    pub _padding: f32,
+    /// Render target size in pixels, for screen-space line and point sizes
+    pub resolution: [f32; 2],
+    pub _padding2: [f32; 2],
+    /// Band levels in `AudioBand` order, energy last
+    pub bands: [f32; 8],
+}
+
+impl FrameUniform {
+    pub fn new() -> Self {
+        Self {
+            resolution: [1280.0, 720.0],
+            ..bytemuck::Zeroable::zeroed()
+        }
+    }
+
+    pub fn update(&mut self, time: &TimeInfo, audio: &AudioFeatures) {
+        self.time = time.elapsed as f32;
+        self.delta = time.delta;
+        self.beat = if audio.beat { 1.0 } else { 0.0 };
+        self.bands[..7].copy_from_slice(&audio.bands);
+        self.bands[7] = audio.band(vibevj_common::AudioBand::Energy);
+    }
+}
+
+impl Default for FrameUniform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
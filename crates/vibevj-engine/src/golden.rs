@@ -0,0 +1,225 @@
+use std::path::{Path, PathBuf};
+use vibevj_common::{Result, VibeVJError};
+
+/// Environment variable that switches golden checks to bless mode
+pub const BLESS_ENV_VAR: &str = "VIBEVJ_BLESS";
+
+/// How far a rendered image may drift from its reference
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoldenTolerance {
+    /// Per-pixel perceptual difference (0-1) below which pixels count as equal
+    pub pixel_threshold: f32,
+    /// Fraction of pixels allowed to exceed `pixel_threshold`
+    pub max_differing_fraction: f32,
+}
+
+impl Default for GoldenTolerance {
+    fn default() -> Self {
+        Self {
+            pixel_threshold: 0.1,
+            max_differing_fraction: 0.002,
+        }
+    }
+}
+
+/// Result of comparing two RGBA8 images
+#[derive(Debug, Clone)]
+pub struct ImageComparison {
+    pub width: u32,
+    pub height: u32,
+    pub differing_pixels: usize,
+    /// Largest per-pixel perceptual difference (0-1)
+    pub max_difference: f32,
+    /// Visualization: faded grayscale of the reference, differing pixels in red
+    pub diff_image: Vec<u8>,
+}
+
+impl ImageComparison {
+    /// Fraction of pixels above the tolerance's threshold
+    pub fn differing_fraction(&self) -> f32 {
+        self.differing_pixels as f32 / (self.width as f32 * self.height as f32).max(1.0)
+    }
+
+    pub fn passes(&self, tolerance: &GoldenTolerance) -> bool {
+        self.differing_fraction() <= tolerance.max_differing_fraction
+    }
+}
+
+/// Perceptual difference between two sRGB pixels, in 0-1
+///
+/// Colors are blended over white by alpha and compared in YIQ space, weighted
+/// so that luma changes count more than chroma changes (as in pixelmatch).
+pub fn pixel_difference(a: [u8; 4], b: [u8; 4]) -> f32 {
+    fn yiq(pixel: [u8; 4]) -> [f32; 3] {
+        let alpha = pixel[3] as f32 / 255.0;
+        let blend = |c: u8| 255.0 + (c as f32 - 255.0) * alpha;
+        let (r, g, b) = (blend(pixel[0]), blend(pixel[1]), blend(pixel[2]));
+        [
+            0.298_895 * r + 0.586_622 * g + 0.114_482 * b,
+            0.595_978 * r - 0.274_176 * g - 0.321_802 * b,
+            0.211_470 * r - 0.522_617 * g + 0.311_147 * b,
+        ]
+    }
+
+    // Largest possible weighted delta, between black and white
+    const MAX_DELTA: f32 = 35215.0;
+
+    let [y1, i1, q1] = yiq(a);
+    let [y2, i2, q2] = yiq(b);
+    let (dy, di, dq) = (y1 - y2, i1 - i2, q1 - q2);
+    let delta = 0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq;
+    (delta / MAX_DELTA).sqrt().min(1.0)
+}
+
+/// Compare two tightly packed RGBA8 images of the same size
+pub fn compare_rgba(
+    actual: &[u8],
+    expected: &[u8],
+    width: u32,
+    height: u32,
+    tolerance: &GoldenTolerance,
+) -> Result<ImageComparison> {
+    let expected_len = width as usize * height as usize * 4;
+    if actual.len() != expected_len || expected.len() != expected_len {
+        return Err(VibeVJError::InvalidOperation(format!(
+            "Image sizes differ from {}x{} RGBA ({} and {} bytes)",
+            width,
+            height,
+            actual.len(),
+            expected.len()
+        )));
+    }
+
+    let mut differing_pixels = 0;
+    let mut max_difference: f32 = 0.0;
+    let mut diff_image = Vec::with_capacity(expected_len);
+
+    for (a, e) in actual.chunks_exact(4).zip(expected.chunks_exact(4)) {
+        let a = [a[0], a[1], a[2], a[3]];
+        let e = [e[0], e[1], e[2], e[3]];
+        let difference = pixel_difference(a, e);
+        max_difference = max_difference.max(difference);
+
+        if difference > tolerance.pixel_threshold {
+            differing_pixels += 1;
+            diff_image.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let luma = 0.299 * e[0] as f32 + 0.587 * e[1] as f32 + 0.114 * e[2] as f32;
+            let faded = (255.0 - (255.0 - luma) * 0.1) as u8;
+            diff_image.extend_from_slice(&[faded, faded, faded, 255]);
+        }
+    }
+
+    Ok(ImageComparison {
+        width,
+        height,
+        differing_pixels,
+        max_difference,
+        diff_image,
+    })
+}
+
+/// Golden-image store for render regression tests
+///
+/// References live as `<name>.png` in `reference_dir`. On a mismatch the
+/// rendered image and a diff are written to `output_dir` as
+/// `<name>.actual.png` and `<name>.diff.png`. With bless mode enabled
+/// (`VIBEVJ_BLESS=1`) references are overwritten with the rendered image.
+pub struct GoldenImages {
+    reference_dir: PathBuf,
+    output_dir: PathBuf,
+    bless: bool,
+}
+
+impl GoldenImages {
+    /// Create a store, reading bless mode from `VIBEVJ_BLESS`
+    pub fn new(reference_dir: impl Into<PathBuf>, output_dir: impl Into<PathBuf>) -> Self {
+        let bless = std::env::var(BLESS_ENV_VAR).is_ok_and(|v| !v.is_empty() && v != "0");
+        Self {
+            reference_dir: reference_dir.into(),
+            output_dir: output_dir.into(),
+            bless,
+        }
+    }
+
+    /// Force bless mode on or off
+    pub fn with_bless(mut self, bless: bool) -> Self {
+        self.bless = bless;
+        self
+    }
+
+    pub fn is_blessing(&self) -> bool {
+        self.bless
+    }
+
+    pub fn reference_path(&self, name: &str) -> PathBuf {
+        self.reference_dir.join(format!("{}.png", name))
+    }
+
+    /// Check a rendered RGBA8 image against its reference
+    /// Returns an error describing the mismatch and where the diff was written
+    pub fn check(&self, name: &str, rgba: &[u8], width: u32, height: u32, tolerance: &GoldenTolerance) -> Result<()> {
+        let reference_path = self.reference_path(name);
+
+        if self.bless {
+            std::fs::create_dir_all(&self.reference_dir)?;
+            save_rgba(&reference_path, rgba, width, height)?;
+            log::info!("Blessed golden image {}", reference_path.display());
+            return Ok(());
+        }
+
+        if !reference_path.exists() {
+            save_rgba(&self.output_path(name, "actual")?, rgba, width, height)?;
+            return Err(VibeVJError::RenderError(format!(
+                "Missing golden image {}; run with {}=1 to create it",
+                reference_path.display(),
+                BLESS_ENV_VAR
+            )));
+        }
+
+        let reference = image::open(&reference_path)
+            .map_err(|e| VibeVJError::RenderError(format!("Failed to load {}: {}", reference_path.display(), e)))?
+            .to_rgba8();
+        if reference.dimensions() != (width, height) {
+            save_rgba(&self.output_path(name, "actual")?, rgba, width, height)?;
+            return Err(VibeVJError::RenderError(format!(
+                "Golden image {} is {}x{}, rendered image is {}x{}",
+                reference_path.display(),
+                reference.width(),
+                reference.height(),
+                width,
+                height
+            )));
+        }
+
+        let comparison = compare_rgba(rgba, reference.as_raw(), width, height, tolerance)?;
+        if comparison.passes(tolerance) {
+            return Ok(());
+        }
+
+        let actual_path = self.output_path(name, "actual")?;
+        let diff_path = self.output_path(name, "diff")?;
+        save_rgba(&actual_path, rgba, width, height)?;
+        save_rgba(&diff_path, &comparison.diff_image, width, height)?;
+
+        Err(VibeVJError::RenderError(format!(
+            "Golden image mismatch for '{}': {} pixels ({:.3}%) differ, max difference {:.3}; see {} and {}",
+            name,
+            comparison.differing_pixels,
+            comparison.differing_fraction() * 100.0,
+            comparison.max_difference,
+            actual_path.display(),
+            diff_path.display()
+        )))
+    }
+
+    fn output_path(&self, name: &str, suffix: &str) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.output_dir)?;
+        Ok(self.output_dir.join(format!("{}.{}.png", name, suffix)))
+    }
+}
+
+fn save_rgba(path: &Path, rgba: &[u8], width: u32, height: u32) -> Result<()> {
+    image::save_buffer(path, rgba, width, height, image::ColorType::Rgba8)
+        .map_err(|e| VibeVJError::RenderError(format!("Failed to write {}: {}", path.display(), e)))
+}
@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use vibevj_common::{Result, VibeVJError};
+
+/// Colour grading applied after tone mapping, on display-encoded colour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorGrading {
+    /// Offset for the shadows per channel (0 = unchanged)
+    pub lift: [f32; 3],
+    /// Midtone power per channel (1 = unchanged, higher = brighter)
+    pub gamma: [f32; 3],
+    /// Highlight multiplier per channel (1 = unchanged)
+    pub gain: [f32; 3],
+    /// 0 = greyscale, 1 = unchanged
+    pub saturation: f32,
+    /// Hue rotation in degrees
+    pub hue: f32,
+    /// Contrast around mid grey (1 = unchanged)
+    pub contrast: f32,
+    /// `.cube` 3D LUT file applied after the other controls
+    pub lut: Option<String>,
+    /// Blend from the graded colour (0) to the LUT output (1)
+    pub lut_intensity: f32,
+}
+
+impl Default for ColorGrading {
+    fn default() -> Self {
+        Self {
+            lift: [0.0; 3],
+            gamma: [1.0; 3],
+            gain: [1.0; 3],
+            saturation: 1.0,
+            hue: 0.0,
+            contrast: 1.0,
+            lut: None,
+            lut_intensity: 1.0,
+        }
+    }
+}
+
+/// 3D colour lookup table from an Adobe/Resolve `.cube` file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubeLut {
+    pub title: Option<String>,
+    /// Entries per axis
+    pub size: u32,
+    /// Input values mapped to the first and last entry
+    pub domain_min: [f32; 3],
+    pub domain_max: [f32; 3],
+    /// `size³` output colours, red changing fastest, then green, then blue
+    pub data: Vec<[f32; 3]>,
+}
+
+impl CubeLut {
+    /// Largest `LUT_3D_SIZE` the format allows
+    pub const MAX_SIZE: u32 = 256;
+
+    /// LUT that leaves colours unchanged
+    pub fn identity(size: u32) -> Self {
+        let size = size.clamp(2, Self::MAX_SIZE);
+        let scale = 1.0 / (size - 1) as f32;
+        let mut data = Vec::with_capacity((size * size * size) as usize);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    data.push([r as f32 * scale, g as f32 * scale, b as f32 * scale]);
+                }
+            }
+        }
+        Self {
+            title: None,
+            size,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            data,
+        }
+    }
+
+    /// Load a `.cube` file from disk
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| VibeVJError::ResourceNotFound(format!("Failed to load LUT {}: {}", path.display(), e)))?;
+        Self::parse(&text).map_err(|e| match e {
+            VibeVJError::SerializationError(message) => {
+                VibeVJError::SerializationError(format!("{}:{}", path.display(), message))
+            }
+            e => e,
+        })
+    }
+
+    /// Parse the text of a `.cube` file
+    ///
+    /// Only 3D tables are supported; `LUT_1D_SIZE` files are rejected.
+    pub fn parse(text: &str) -> Result<Self> {
+        let error = |line: usize, message: &str| VibeVJError::SerializationError(format!("{}: {}", line + 1, message));
+        let number = |line: usize, value: &str| {
+            value.parse::<f32>().map_err(|_| error(line, &format!("Invalid number '{}'", value)))
+        };
+        let floats = |line: usize, values: &[&str]| -> Result<[f32; 3]> {
+            match values {
+                [r, g, b] => Ok([number(line, r)?, number(line, g)?, number(line, b)?]),
+                _ => Err(error(line, "Expected three values")),
+            }
+        };
+
+        let mut lut = Self {
+            title: None,
+            size: 0,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            data: Vec::new(),
+        };
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let keyword = words.next().unwrap_or_default();
+            let values: Vec<&str> = words.collect();
+            match keyword {
+                "TITLE" => lut.title = Some(line["TITLE".len()..].trim().trim_matches('"').to_string()),
+                "LUT_3D_SIZE" => {
+                    let size = values.first().and_then(|size| size.parse::<u32>().ok());
+                    lut.size = match size {
+                        Some(size @ 2..=Self::MAX_SIZE) => size,
+                        _ => return Err(error(line_number, "LUT_3D_SIZE must be between 2 and 256")),
+                    };
+                    lut.data.reserve((lut.size * lut.size * lut.size) as usize);
+                }
+                "LUT_1D_SIZE" => return Err(error(line_number, "1D LUTs are not supported")),
+                "DOMAIN_MIN" => lut.domain_min = floats(line_number, &values)?,
+                "DOMAIN_MAX" => lut.domain_max = floats(line_number, &values)?,
+                // Resolve's form of the domain, the same for all channels
+                "LUT_3D_INPUT_RANGE" => match values[..] {
+                    [min, max] => {
+                        lut.domain_min = [number(line_number, min)?; 3];
+                        lut.domain_max = [number(line_number, max)?; 3];
+                    }
+                    _ => return Err(error(line_number, "Expected two values")),
+                },
+                _ if keyword.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                    log::warn!("Ignoring unknown .cube keyword '{}'", keyword);
+                }
+                _ => {
+                    if lut.size == 0 {
+                        return Err(error(line_number, "Table data before LUT_3D_SIZE"));
+                    }
+                    let mut row = vec![keyword];
+                    row.extend(values);
+                    lut.data.push(floats(line_number, &row)?);
+                }
+            }
+        }
+
+        let expected = (lut.size * lut.size * lut.size) as usize;
+        if lut.size == 0 {
+            return Err(VibeVJError::SerializationError("Missing LUT_3D_SIZE".to_string()));
+        }
+        if lut.data.len() != expected {
+            return Err(VibeVJError::SerializationError(format!(
+                "Expected {} table entries, found {}",
+                expected,
+                lut.data.len()
+            )));
+        }
+        if (0..3).any(|i| lut.domain_max[i] <= lut.domain_min[i]) {
+            return Err(VibeVJError::SerializationError("DOMAIN_MAX must be above DOMAIN_MIN".to_string()));
+        }
+        Ok(lut)
+    }
+
+    /// Upload as an `Rgba32Float` 3D texture, red along x, green along y, blue along z
+    pub fn create_texture(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> wgpu::Texture {
+        let size = wgpu::Extent3d {
+            width: self.size,
+            height: self.size,
+            depth_or_array_layers: self.size,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(self.title.as_deref().unwrap_or("Colour LUT")),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D3,
+            format: wgpu::TextureFormat::Rgba32Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        let texels: Vec<[f32; 4]> = self.data.iter().map(|&[r, g, b]| [r, g, b, 1.0]).collect();
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            bytemuck::cast_slice(&texels),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(self.size * 16),
+                rows_per_image: Some(self.size),
+            },
+            size,
+        );
+        texture
+    }
+}
+
+/// Colour grading uniform, following the tone mapping uniform
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GradingUniform {
+    // RGB in xyz, w unused
+    pub lift: [f32; 4],
+    pub gamma: [f32; 4],
+    pub gain: [f32; 4],
+    pub domain_min: [f32; 4],
+    pub domain_max: [f32; 4],
+    pub saturation: f32,
+    /// Hue rotation in radians
+    pub hue: f32,
+    pub contrast: f32,
+    /// 0 when no LUT is loaded
+    pub lut_intensity: f32,
+}
+
+impl GradingUniform {
+    pub fn new(grading: &ColorGrading, lut: Option<&CubeLut>) -> Self {
+        let vec4 = |[x, y, z]: [f32; 3]| [x, y, z, 0.0];
+        Self {
+            lift: vec4(grading.lift),
+            gamma: vec4(grading.gamma.map(|gamma| gamma.max(0.01))),
+            gain: vec4(grading.gain),
+            domain_min: vec4(lut.map_or([0.0; 3], |lut| lut.domain_min)),
+            domain_max: vec4(lut.map_or([1.0; 3], |lut| lut.domain_max)),
+            saturation: grading.saturation,
+            hue: grading.hue.to_radians(),
+            contrast: grading.contrast,
+            lut_intensity: lut.map_or(0.0, |_| grading.lut_intensity.clamp(0.0, 1.0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVERT: &str = r#"
+# Inverts RGB
+TITLE "Invert"
+LUT_3D_SIZE 2
+LUT_3D_INPUT_RANGE 0.0 2.0
+
+1.0 1.0 1.0
+0.0 1.0 1.0
+1.0 0.0 1.0
+0.0 0.0 1.0
+1.0 1.0 0.0
+0.0 1.0 0.0
+1.0 0.0 0.0
+0.0 0.0 0.0
+"#;
+
+    #[test]
+    fn parses_a_3d_table() {
+        let lut = CubeLut::parse(INVERT).unwrap();
+        assert_eq!(lut.title.as_deref(), Some("Invert"));
+        assert_eq!((lut.size, lut.data.len()), (2, 8));
+        assert_eq!((lut.domain_min, lut.domain_max), ([0.0; 3], [2.0; 3]));
+        assert_eq!(lut.data[1], [0.0, 1.0, 1.0]);
+
+        let text = format!("DOMAIN_MIN 0 0.1 0.2\nDOMAIN_MAX 1 2 3\nLUT_3D_SIZE 2\n{}", "0 0 0\n".repeat(8));
+        let lut = CubeLut::parse(&text).unwrap();
+        assert_eq!((lut.domain_min, lut.domain_max), ([0.0, 0.1, 0.2], [1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn rejects_malformed_tables() {
+        for text in [
+            "LUT_1D_SIZE 4",
+            "LUT_3D_SIZE 1",
+            "LUT_3D_SIZE 257",
+            "LUT_3D_SIZE 2\n0 0 0",
+            "0 0 0\nLUT_3D_SIZE 2",
+            "TITLE \"Empty\"",
+            "LUT_3D_SIZE 2\n0 0",
+        ] {
+            assert!(CubeLut::parse(text).is_err(), "{:?}", text);
+        }
+        let inverted_domain = format!("DOMAIN_MIN 1 1 1\nDOMAIN_MAX 0 0 0\nLUT_3D_SIZE 2\n{}", "0 0 0\n".repeat(8));
+        assert!(CubeLut::parse(&inverted_domain).is_err());
+    }
+
+    #[test]
+    fn identity_maps_each_entry_to_its_coordinates() {
+        let lut = CubeLut::identity(3);
+        assert_eq!((lut.size, lut.data.len()), (3, 27));
+        assert_eq!(lut.data[0], [0.0, 0.0, 0.0]);
+        // Red varies fastest, then green, then blue
+        assert_eq!(lut.data[1], [0.5, 0.0, 0.0]);
+        assert_eq!(lut.data[3], [0.0, 0.5, 0.0]);
+        assert_eq!(lut.data[9], [0.0, 0.0, 0.5]);
+        assert_eq!(lut.data[26], [1.0, 1.0, 1.0]);
+        assert_eq!(CubeLut::identity(0).size, 2);
+    }
+}
@@ -0,0 +1,147 @@
+use vibevj_common::{Result, VibeVJError};
+use crate::render_target::RenderTarget;
+
+/// Options for creating a headless renderer
+#[derive(Debug, Clone)]
+pub struct HeadlessOptions {
+    pub backends: wgpu::Backends,
+    pub power_preference: wgpu::PowerPreference,
+    /// Use the software/fallback adapter (e.g. llvmpipe, WARP)
+    pub force_fallback_adapter: bool,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            backends: wgpu::Backends::all(),
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter: false,
+        }
+    }
+}
+
+impl HeadlessOptions {
+    /// Options selecting the software adapter, for deterministic output in tests
+    pub fn software() -> Self {
+        Self {
+            power_preference: wgpu::PowerPreference::LowPower,
+            force_fallback_adapter: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Renderer without a window or surface
+///
+/// Renders into `RenderTarget`s and reads frames back as byte buffers, for
+/// tests, render farms and offline export.
+pub struct HeadlessRenderer {
+    pub instance: wgpu::Instance,
+    pub adapter: wgpu::Adapter,
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+}
+
+impl HeadlessRenderer {
+    /// Create a headless renderer on the default adapter
+    /// Falls back to the software adapter if no hardware adapter is found
+    pub async fn new() -> Result<Self> {
+        match Self::with_options(HeadlessOptions::default()).await {
+            Ok(renderer) => Ok(renderer),
+            Err(e) => {
+                log::warn!("{}, trying the fallback adapter", e);
+                Self::with_options(HeadlessOptions::software()).await
+            }
+        }
+    }
+
+    /// Create a headless renderer with explicit adapter options
+    pub async fn with_options(options: HeadlessOptions) -> Result<Self> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: options.backends,
+            ..Default::default()
+        });
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: options.power_preference,
+                compatible_surface: None,
+                force_fallback_adapter: options.force_fallback_adapter,
+            })
+            .await
+            .map_err(|e| VibeVJError::RenderError(format!("Failed to find suitable adapter: {}", e)))?;
+
+        let info = adapter.get_info();
+        log::info!("Headless renderer using {} ({:?}, {:?})", info.name, info.device_type, info.backend);
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("VibeVJ Headless Device"),
+                    // Needed for MSAA sample counts other than 1 and 4
+                    required_features: adapter.features() & wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+                    // Software and GL adapters may not reach the default limits
+                    required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+                    memory_hints: wgpu::MemoryHints::default(),
+                    experimental_features: wgpu::ExperimentalFeatures::default(),
+                    trace: wgpu::Trace::Off,
+                },
+            )
+            .await
+            .map_err(|e| VibeVJError::RenderError(format!("Failed to create device: {}", e)))?;
+
+        Ok(Self {
+            instance,
+            adapter,
+            device,
+            queue,
+        })
+    }
+
+    /// Blocking version of `new`
+    pub fn new_blocking() -> Result<Self> {
+        pollster::block_on(Self::new())
+    }
+
+    /// Get information about the adapter in use
+    pub fn adapter_info(&self) -> wgpu::AdapterInfo {
+        self.adapter.get_info()
+    }
+
+    /// MSAA sample counts supported for a render target format
+    pub fn supported_sample_counts(&self, format: wgpu::TextureFormat) -> Vec<u32> {
+        RenderTarget::supported_sample_counts(&self.adapter, &self.device, format)
+    }
+
+    /// Create a render target on this renderer's device
+    pub fn create_render_target(&self, width: u32, height: u32, format: wgpu::TextureFormat) -> RenderTarget {
+        RenderTarget::new(&self.device, width, height, format, Some("Headless Render Target"))
+    }
+
+    /// Submit `encoder` and read `target` back as tightly packed bytes
+    pub fn finish_frame(&self, encoder: wgpu::CommandEncoder, target: &RenderTarget) -> Result<Vec<u8>> {
+        self.queue.submit(Some(encoder.finish()));
+        target.read_pixels(&self.device, &self.queue)
+    }
+
+    /// Read `target` back as RGBA8 bytes
+    /// The target must use an 8-bit RGBA format
+    pub fn read_rgba(&self, target: &RenderTarget) -> Result<Vec<u8>> {
+        match target.format {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => {
+                target.read_pixels(&self.device, &self.queue)
+            }
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => {
+                let mut pixels = target.read_pixels(&self.device, &self.queue)?;
+                for pixel in pixels.chunks_exact_mut(4) {
+                    pixel.swap(0, 2);
+                }
+                Ok(pixels)
+            }
+            format => Err(VibeVJError::RenderError(format!(
+                "Cannot read {:?} render target as RGBA8",
+                format
+            ))),
+        }
+    }
+}
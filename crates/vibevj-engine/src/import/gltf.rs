@@ -0,0 +1,779 @@
+use std::collections::HashMap;
+use std::path::Path;
+use glam::{Mat4, Quat, Vec3};
+use serde::Deserialize;
+use vibevj_common::{Color, Result, VibeVJError};
+use crate::material::{Material, ShaderType};
+use crate::mesh::{Mesh, Vertex};
+use crate::mesh_gen::{generate_normals, generate_tangents};
+use super::{resolve_relative, ImportedMaterial, ImportedMesh, ImportedModel, ImportedNode};
+
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+/// Elements an accessor without a bufferView may hold, as those allocate without backing data
+const MAX_ZERO_ELEMENTS: usize = 1 << 24;
+
+/// Load a glTF 2.0 file (`.gltf` or binary `.glb`)
+///
+/// Each glTF mesh primitive becomes one `ImportedMesh`; nodes keep their
+/// hierarchy and local transforms. Buffers may be external files, base64
+/// data URIs or the GLB binary chunk.
+pub fn load(path: &Path) -> Result<ImportedModel> {
+    let bytes = std::fs::read(path)?;
+    parse(&bytes, path)
+}
+
+/// Parse glTF JSON or GLB data; `path` resolves external files and names embedded images
+pub fn parse(bytes: &[u8], path: &Path) -> Result<ImportedModel> {
+    let (json, binary_chunk) = if bytes.starts_with(GLB_MAGIC) {
+        split_glb(bytes)?
+    } else {
+        (bytes, None)
+    };
+
+    let document: Document = serde_json::from_slice(json)
+        .map_err(|e| VibeVJError::SerializationError(format!("{}: {}", path.display(), e)))?;
+
+    let buffers = document
+        .buffers
+        .iter()
+        .map(|buffer| match &buffer.uri {
+            Some(uri) => load_uri(uri, path),
+            None => binary_chunk
+                .map(|chunk| chunk.to_vec())
+                .ok_or_else(|| error(path, "buffer without uri outside of a GLB file")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let reader = Reader {
+        document: &document,
+        buffers: &buffers,
+        path,
+    };
+
+    let mut model = ImportedModel::default();
+
+    // Images are referenced by cache key: the file path, or a key derived from the model path
+    let mut image_keys = Vec::with_capacity(document.images.len());
+    for (index, image) in document.images.iter().enumerate() {
+        let key = match (&image.uri, image.buffer_view) {
+            (Some(uri), _) if !uri.starts_with("data:") => resolve_relative(path, uri),
+            (Some(uri), _) => {
+                let key = format!("{}#image{}", path.display(), index);
+                model.embedded_textures.insert(key.clone(), decode_data_uri(uri, path)?);
+                key
+            }
+            (None, Some(view)) => {
+                let key = format!("{}#image{}", path.display(), index);
+                model.embedded_textures.insert(key.clone(), reader.buffer_view(view)?.to_vec());
+                key
+            }
+            (None, None) => return Err(error(path, "image without uri or bufferView")),
+        };
+        image_keys.push(key);
+    }
+
+    let texture_key = |info: &Option<TextureInfo>| -> Option<String> {
+        let texture = document.textures.get(info.as_ref()?.index)?;
+        image_keys.get(texture.source?).cloned()
+    };
+
+    for (index, material) in document.materials.iter().enumerate() {
+        let pbr = &material.pbr_metallic_roughness;
+        let [r, g, b, a] = pbr.base_color_factor;
+        let mut converted = Material::pbr(Color::new(r, g, b, a), pbr.metallic_factor, pbr.roughness_factor);
+
+        let strength = material
+            .extensions
+            .get("KHR_materials_emissive_strength")
+            .and_then(|ext| ext.get("emissiveStrength"))
+            .and_then(|value| value.as_f64())
+            .unwrap_or(1.0) as f32;
+        let [er, eg, eb] = material.emissive_factor;
+        converted.emissive = Color::new(er * strength, eg * strength, eb * strength, 1.0);
+
+        if material.extensions.contains_key("KHR_materials_unlit") {
+            converted.shader_type = ShaderType::Unlit;
+        }
+
+        converted.textures.albedo = texture_key(&pbr.base_color_texture);
+        converted.textures.metallic_roughness = texture_key(&pbr.metallic_roughness_texture);
+        converted.textures.normal = texture_key(&material.normal_texture);
+        converted.textures.emissive = texture_key(&material.emissive_texture);
+        // Emissive textures are multiplied with the factor, which defaults to black
+        if converted.textures.emissive.is_some() && material.emissive_factor == [0.0; 3] {
+            converted.emissive = Color::WHITE;
+        }
+
+        model.materials.push(ImportedMaterial {
+            name: material.name.clone().unwrap_or_else(|| format!("material{}", index)),
+            material: converted,
+        });
+    }
+
+    // Primitives of each glTF mesh, as indices into `model.meshes`
+    let mut mesh_primitives = Vec::with_capacity(document.meshes.len());
+    for (mesh_index, mesh) in document.meshes.iter().enumerate() {
+        let base_name = mesh.name.clone().unwrap_or_else(|| format!("mesh{}", mesh_index));
+        let mut primitives = Vec::new();
+
+        for (primitive_index, primitive) in mesh.primitives.iter().enumerate() {
+            let Some(converted) = reader.primitive(primitive)? else {
+                log::warn!(
+                    "{}: skipping primitive {} of '{}' with unsupported mode {}",
+                    path.display(),
+                    primitive_index,
+                    base_name,
+                    primitive.mode
+                );
+                continue;
+            };
+
+            let name = if mesh.primitives.len() > 1 {
+                format!("{}.{}", base_name, primitive_index)
+            } else {
+                base_name.clone()
+            };
+            primitives.push(model.meshes.len());
+            model.meshes.push(ImportedMesh {
+                name,
+                mesh: converted,
+                material: primitive.material,
+            });
+        }
+        mesh_primitives.push(primitives);
+    }
+
+    for (index, node) in document.nodes.iter().enumerate() {
+        model.nodes.push(ImportedNode {
+            name: node.name.clone().unwrap_or_else(|| format!("node{}", index)),
+            transform: node.local_transform(),
+            meshes: node
+                .mesh
+                .and_then(|mesh| mesh_primitives.get(mesh).cloned())
+                .unwrap_or_default(),
+            children: node.children.clone(),
+        });
+    }
+
+    let scene = document.scene.or(if document.scenes.is_empty() { None } else { Some(0) });
+    model.roots = match scene.and_then(|scene| document.scenes.get(scene)) {
+        Some(scene) => scene.nodes.clone(),
+        None => {
+            // No scene: every node that is nobody's child is a root
+            let children: Vec<usize> = document.nodes.iter().flat_map(|n| n.children.iter().copied()).collect();
+            (0..document.nodes.len()).filter(|i| !children.contains(i)).collect()
+        }
+    };
+
+    Ok(model)
+}
+
+fn error(path: &Path, message: &str) -> VibeVJError {
+    VibeVJError::SerializationError(format!("{}: {}", path.display(), message))
+}
+
+/// Split a GLB container into its JSON and optional binary chunk
+fn split_glb(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>)> {
+    let read_u32 = |offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+    };
+    let invalid = || VibeVJError::SerializationError("Invalid GLB container".to_string());
+
+    if read_u32(4) != Some(2) {
+        return Err(VibeVJError::SerializationError("Only GLB version 2 is supported".to_string()));
+    }
+    let length = (read_u32(8).ok_or_else(invalid)? as usize).min(bytes.len());
+
+    let mut json = None;
+    let mut binary = None;
+    let mut offset = 12;
+    while offset + 8 <= length {
+        let chunk_length = read_u32(offset).ok_or_else(invalid)? as usize;
+        let chunk_type = read_u32(offset + 4).ok_or_else(invalid)?;
+        let end = (offset + 8).checked_add(chunk_length).ok_or_else(invalid)?;
+        let data = bytes.get(offset + 8..end).ok_or_else(invalid)?;
+        match chunk_type {
+            GLB_CHUNK_JSON => json = Some(data),
+            GLB_CHUNK_BIN => binary = Some(data),
+            _ => {}
+        }
+        // Chunks are 4-byte aligned
+        offset = (offset + 8).checked_add(chunk_length.next_multiple_of(4)).ok_or_else(invalid)?;
+    }
+
+    Ok((json.ok_or_else(invalid)?, binary))
+}
+
+/// Read a buffer or image URI: a base64 data URI or a file next to the model
+fn load_uri(uri: &str, path: &Path) -> Result<Vec<u8>> {
+    if uri.starts_with("data:") {
+        decode_data_uri(uri, path)
+    } else {
+        Ok(std::fs::read(resolve_relative(path, &percent_decode(uri)))?)
+    }
+}
+
+fn decode_data_uri(uri: &str, path: &Path) -> Result<Vec<u8>> {
+    let (header, data) = uri.split_once(',').ok_or_else(|| error(path, "malformed data URI"))?;
+    if !header.ends_with(";base64") {
+        return Err(error(path, "only base64 data URIs are supported"));
+    }
+    decode_base64(data).ok_or_else(|| error(path, "invalid base64 in data URI"))
+}
+
+fn decode_base64(data: &str) -> Option<Vec<u8>> {
+    let value = |c: u8| -> Option<u32> {
+        Some(match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        } as u32)
+    };
+
+    let symbols: Vec<u8> = data.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=').collect();
+    let mut output = Vec::with_capacity(symbols.len() * 3 / 4);
+    for chunk in symbols.chunks(4) {
+        let mut bits = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            bits |= value(c)? << (18 - 6 * i);
+        }
+        let bytes = bits.to_be_bytes();
+        output.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(output)
+}
+
+/// Decode `%20`-style escapes in relative URIs
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(byte) = uri.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                output.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        output.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+/// Reads accessor data out of the loaded buffers
+struct Reader<'a> {
+    document: &'a Document,
+    buffers: &'a [Vec<u8>],
+    path: &'a Path,
+}
+
+impl Reader<'_> {
+    fn buffer_view(&self, index: usize) -> Result<&[u8]> {
+        let view = self
+            .document
+            .buffer_views
+            .get(index)
+            .ok_or_else(|| error(self.path, "bufferView index out of range"))?;
+        let end = view.byte_offset.checked_add(view.byte_length);
+        self.buffers
+            .get(view.buffer)
+            .zip(end)
+            .and_then(|(buffer, end)| buffer.get(view.byte_offset..end))
+            .ok_or_else(|| error(self.path, "bufferView outside of its buffer"))
+    }
+
+    /// Read an accessor as floats, `N` components per element
+    /// Normalized integer components are mapped to 0..1 or -1..1
+    fn read_floats<const N: usize>(&self, index: usize) -> Result<Vec<[f32; N]>> {
+        let accessor = self
+            .document
+            .accessors
+            .get(index)
+            .ok_or_else(|| error(self.path, "accessor index out of range"))?;
+        let components = accessor.component_count().ok_or_else(|| error(self.path, "unknown accessor type"))?;
+        if components < N {
+            return Err(error(self.path, &format!("accessor {} has {} components, expected {}", index, components, N)));
+        }
+
+        let Some(view_index) = accessor.buffer_view else {
+            // Accessors without a buffer view are all zeros
+            if accessor.count > MAX_ZERO_ELEMENTS {
+                return Err(error(self.path, &format!("accessor {} has too many elements", index)));
+            }
+            return Ok(vec![[0.0; N]; accessor.count]);
+        };
+        let data = self.buffer_view(view_index)?;
+        let component_size = component_size(accessor.component_type)
+            .ok_or_else(|| error(self.path, "unknown accessor component type"))?;
+        let element_size = component_size * components;
+        let stride = self.document.buffer_views[view_index].byte_stride.unwrap_or(element_size);
+
+        // Check the last element fits before allocating for `count` of them
+        let outside = || error(self.path, "accessor outside of its bufferView");
+        let element_start = |element: usize| {
+            element.checked_mul(stride).and_then(|offset| offset.checked_add(accessor.byte_offset))
+        };
+        if let Some(last) = accessor.count.checked_sub(1) {
+            let end = element_start(last).and_then(|start| start.checked_add(element_size)).ok_or_else(outside)?;
+            if end > data.len() {
+                return Err(outside());
+            }
+        }
+
+        let mut values = Vec::with_capacity(accessor.count);
+        for element in 0..accessor.count {
+            let start = element_start(element).ok_or_else(outside)?;
+            let mut value = [0.0; N];
+            for (c, slot) in value.iter_mut().enumerate() {
+                let offset = start + c * component_size;
+                let bytes = data.get(offset..offset + component_size).ok_or_else(outside)?;
+                *slot = read_component(bytes, accessor.component_type, accessor.normalized);
+            }
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    fn read_indices(&self, index: usize) -> Result<Vec<u32>> {
+        Ok(self.read_floats::<1>(index)?.into_iter().map(|[i]| i as u32).collect())
+    }
+
+    /// Convert a primitive to a triangle mesh
+    /// Returns `None` for point and line primitives
+    fn primitive(&self, primitive: &Primitive) -> Result<Option<Mesh>> {
+        let position_accessor = *primitive
+            .attributes
+            .get("POSITION")
+            .ok_or_else(|| error(self.path, "primitive without POSITION"))?;
+        let positions = self.read_floats::<3>(position_accessor)?;
+        let count = positions.len();
+
+        let normals = primitive.attributes.get("NORMAL").map(|&a| self.read_floats::<3>(a)).transpose()?;
+        let uvs = primitive.attributes.get("TEXCOORD_0").map(|&a| self.read_floats::<2>(a)).transpose()?;
+        let tangents = primitive.attributes.get("TANGENT").map(|&a| self.read_floats::<4>(a)).transpose()?;
+        let colors = primitive.attributes.get("COLOR_0").map(|&a| self.read_floats::<3>(a)).transpose()?;
+
+        let mut vertices: Vec<Vertex> = (0..count)
+            .map(|i| {
+                let mut vertex = Vertex::new(
+                    positions[i],
+                    normals.as_ref().and_then(|n| n.get(i)).copied().unwrap_or([0.0, 1.0, 0.0]),
+                    uvs.as_ref().and_then(|t| t.get(i)).copied().unwrap_or([0.0, 0.0]),
+                    colors.as_ref().and_then(|c| c.get(i)).copied().unwrap_or([1.0; 3]),
+                );
+                if let Some(tangent) = tangents.as_ref().and_then(|t| t.get(i)) {
+                    vertex.tangent = *tangent;
+                }
+                vertex
+            })
+            .collect();
+
+        let mut indices = match primitive.indices {
+            Some(accessor) => self.read_indices(accessor)?,
+            None => (0..count as u32).collect(),
+        };
+        if indices.iter().any(|&i| i as usize >= count) {
+            return Err(error(self.path, "index out of range"));
+        }
+
+        indices = match primitive.mode {
+            MODE_TRIANGLES => indices,
+            MODE_TRIANGLE_STRIP => (0..indices.len().saturating_sub(2))
+                .flat_map(|i| {
+                    // Every other triangle flips its winding
+                    if i % 2 == 0 {
+                        [indices[i], indices[i + 1], indices[i + 2]]
+                    } else {
+                        [indices[i + 1], indices[i], indices[i + 2]]
+                    }
+                })
+                .collect(),
+            MODE_TRIANGLE_FAN => (1..indices.len().saturating_sub(1))
+                .flat_map(|i| [indices[0], indices[i], indices[i + 1]])
+                .collect(),
+            _ => return Ok(None),
+        };
+
+        if normals.is_none() {
+            generate_normals(&mut vertices, &indices);
+        }
+        if tangents.is_none() {
+            generate_tangents(&mut vertices, &indices);
+        }
+
+        Ok(Some(Mesh::new(vertices, indices)))
+    }
+}
+
+fn component_size(component_type: u32) -> Option<usize> {
+    match component_type {
+        5120 | 5121 => Some(1),
+        5122 | 5123 => Some(2),
+        5125 | 5126 => Some(4),
+        _ => None,
+    }
+}
+
+fn read_component(bytes: &[u8], component_type: u32, normalized: bool) -> f32 {
+    match component_type {
+        5120 => {
+            let v = bytes[0] as i8 as f32;
+            if normalized { (v / 127.0).max(-1.0) } else { v }
+        }
+        5121 => {
+            let v = bytes[0] as f32;
+            if normalized { v / 255.0 } else { v }
+        }
+        5122 => {
+            let v = i16::from_le_bytes([bytes[0], bytes[1]]) as f32;
+            if normalized { (v / 32767.0).max(-1.0) } else { v }
+        }
+        5123 => {
+            let v = u16::from_le_bytes([bytes[0], bytes[1]]) as f32;
+            if normalized { v / 65535.0 } else { v }
+        }
+        5125 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+        _ => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+    }
+}
+
+const MODE_TRIANGLES: u32 = 4;
+const MODE_TRIANGLE_STRIP: u32 = 5;
+const MODE_TRIANGLE_FAN: u32 = 6;
+
+// Subset of the glTF 2.0 schema used by the importer
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct Document {
+    scene: Option<usize>,
+    scenes: Vec<SceneDef>,
+    nodes: Vec<Node>,
+    meshes: Vec<MeshDef>,
+    accessors: Vec<Accessor>,
+    buffer_views: Vec<BufferView>,
+    buffers: Vec<Buffer>,
+    materials: Vec<MaterialDef>,
+    textures: Vec<TextureDef>,
+    images: Vec<Image>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct SceneDef {
+    nodes: Vec<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Node {
+    name: Option<String>,
+    children: Vec<usize>,
+    mesh: Option<usize>,
+    matrix: Option<[f32; 16]>,
+    translation: Option<[f32; 3]>,
+    rotation: Option<[f32; 4]>,
+    scale: Option<[f32; 3]>,
+}
+
+impl Node {
+    fn local_transform(&self) -> Mat4 {
+        if let Some(matrix) = self.matrix {
+            return Mat4::from_cols_array(&matrix);
+        }
+        Mat4::from_scale_rotation_translation(
+            self.scale.map(Vec3::from).unwrap_or(Vec3::ONE),
+            self.rotation.map(Quat::from_array).unwrap_or(Quat::IDENTITY),
+            self.translation.map(Vec3::from).unwrap_or(Vec3::ZERO),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct MeshDef {
+    name: Option<String>,
+    primitives: Vec<Primitive>,
+}
+
+#[derive(Deserialize)]
+struct Primitive {
+    attributes: HashMap<String, usize>,
+    indices: Option<usize>,
+    material: Option<usize>,
+    #[serde(default = "default_mode")]
+    mode: u32,
+}
+
+fn default_mode() -> u32 {
+    MODE_TRIANGLES
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Accessor {
+    buffer_view: Option<usize>,
+    #[serde(default)]
+    byte_offset: usize,
+    component_type: u32,
+    #[serde(default)]
+    normalized: bool,
+    count: usize,
+    #[serde(rename = "type")]
+    element_type: String,
+}
+
+impl Accessor {
+    fn component_count(&self) -> Option<usize> {
+        Some(match self.element_type.as_str() {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" | "MAT2" => 4,
+            "MAT3" => 9,
+            "MAT4" => 16,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferView {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct Buffer {
+    uri: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct MaterialDef {
+    name: Option<String>,
+    pbr_metallic_roughness: PbrMetallicRoughness,
+    normal_texture: Option<TextureInfo>,
+    emissive_texture: Option<TextureInfo>,
+    emissive_factor: [f32; 3],
+    extensions: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct PbrMetallicRoughness {
+    base_color_factor: [f32; 4],
+    base_color_texture: Option<TextureInfo>,
+    metallic_factor: f32,
+    roughness_factor: f32,
+    metallic_roughness_texture: Option<TextureInfo>,
+}
+
+impl Default for PbrMetallicRoughness {
+    fn default() -> Self {
+        Self {
+            base_color_factor: [1.0; 4],
+            base_color_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TextureInfo {
+    index: usize,
+}
+
+#[derive(Deserialize)]
+struct TextureDef {
+    source: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Image {
+    uri: Option<String>,
+    buffer_view: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three interleaved position + normal vertices, then three u16 indices
+    fn fixture_buffer() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for position in [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] {
+            for value in position.into_iter().chain([0.0, 0.0, 1.0]) {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        for index in [0u16, 1, 2] {
+            bytes.extend_from_slice(&index.to_le_bytes());
+        }
+        bytes.resize(80, 0);
+        bytes
+    }
+
+    fn fixture_json(buffer: &str) -> String {
+        format!(
+            r#"{{
+                "asset": {{ "version": "2.0" }},
+                "scene": 0,
+                "scenes": [{{ "nodes": [0] }}],
+                "nodes": [
+                    {{ "name": "Parent", "translation": [1, 0, 0], "children": [1] }},
+                    {{ "name": "Child", "mesh": 0, "scale": [2, 2, 2] }}
+                ],
+                "meshes": [{{
+                    "name": "Tri",
+                    "primitives": [
+                        {{ "attributes": {{ "POSITION": 0, "NORMAL": 1 }}, "indices": 2, "material": 0 }},
+                        {{ "attributes": {{ "POSITION": 0 }}, "material": 1 }}
+                    ]
+                }}],
+                "accessors": [
+                    {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }},
+                    {{ "bufferView": 0, "byteOffset": 12, "componentType": 5126, "count": 3, "type": "VEC3" }},
+                    {{ "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }}
+                ],
+                "bufferViews": [
+                    {{ "buffer": 0, "byteLength": 72, "byteStride": 24 }},
+                    {{ "buffer": 0, "byteOffset": 72, "byteLength": 6 }}
+                ],
+                "buffers": [{{ "byteLength": 80 {} }}],
+                "materials": [
+                    {{
+                        "name": "Glow",
+                        "pbrMetallicRoughness": {{ "baseColorFactor": [0.2, 0.4, 0.6, 1.0], "metallicFactor": 0.25, "roughnessFactor": 0.75 }},
+                        "emissiveFactor": [1.0, 0.5, 0.0],
+                        "extensions": {{ "KHR_materials_emissive_strength": {{ "emissiveStrength": 4.0 }} }}
+                    }},
+                    {{ "extensions": {{ "KHR_materials_unlit": {{}} }} }}
+                ]
+            }}"#,
+            buffer
+        )
+    }
+
+    fn base64(bytes: &[u8]) -> String {
+        const SYMBOLS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut output = String::new();
+        for chunk in bytes.chunks(3) {
+            let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, &byte)| bits | (byte as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                output.push(if i <= chunk.len() { SYMBOLS[(bits >> (18 - 6 * i) & 63) as usize] as char } else { '=' });
+            }
+        }
+        output
+    }
+
+    fn glb(json: &str, binary: &[u8]) -> Vec<u8> {
+        let mut json = json.as_bytes().to_vec();
+        json.resize(json.len().next_multiple_of(4), b' ');
+        let length = 12 + 8 + json.len() + 8 + binary.len();
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(GLB_MAGIC);
+        for value in [2, length as u32, json.len() as u32, GLB_CHUNK_JSON] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&json);
+        for value in [binary.len() as u32, GLB_CHUNK_BIN] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(binary);
+        bytes
+    }
+
+    fn check_fixture(model: &ImportedModel) {
+        // One mesh per primitive, both on the child node
+        let names: Vec<&str> = model.meshes.iter().map(|mesh| mesh.name.as_str()).collect();
+        assert_eq!(names, ["Tri.0", "Tri.1"]);
+        for mesh in &model.meshes {
+            assert_eq!((mesh.mesh.vertices.len(), mesh.mesh.indices.len()), (3, 3));
+        }
+        // Positions and normals share a strided view
+        let positions: Vec<[f32; 3]> = model.meshes[0].mesh.vertices.iter().map(|vertex| vertex.position).collect();
+        assert_eq!(positions, [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+        assert!(model.meshes[0].mesh.vertices.iter().all(|vertex| vertex.normal == [0.0, 0.0, 1.0]));
+
+        assert_eq!(model.roots, [0]);
+        assert_eq!(model.nodes[0].children, [1]);
+        assert_eq!(model.nodes[1].meshes, [0, 1]);
+        let world = model.world_transforms().expect("tree hierarchy");
+        assert_eq!(world[1].transform_point3(Vec3::X), Vec3::new(3.0, 0.0, 0.0));
+
+        let glow = &model.materials[0].material;
+        assert_eq!(model.materials[0].name, "Glow");
+        assert_eq!(glow.color, Color::new(0.2, 0.4, 0.6, 1.0));
+        assert_eq!((glow.metallic, glow.roughness), (0.25, 0.75));
+        assert_eq!(glow.emissive, Color::new(4.0, 2.0, 0.0, 1.0));
+        assert_eq!(glow.shader_type, ShaderType::PBR);
+        assert_eq!(model.materials[1].material.shader_type, ShaderType::Unlit);
+        assert_eq!(model.meshes[1].material, Some(1));
+    }
+
+    #[test]
+    fn glb_binary_chunk() {
+        let bytes = glb(&fixture_json(""), &fixture_buffer());
+        check_fixture(&parse(&bytes, Path::new("test.glb")).expect("parse glb"));
+    }
+
+    #[test]
+    fn gltf_data_uri() {
+        let uri = format!(r#", "uri": "data:application/octet-stream;base64,{}""#, base64(&fixture_buffer()));
+        check_fixture(&parse(fixture_json(&uri).as_bytes(), Path::new("test.gltf")).expect("parse gltf"));
+    }
+
+    #[test]
+    fn glb_split_finds_both_chunks() {
+        let bytes = glb("{}", &[1, 2, 3, 4]);
+        let (json, binary) = split_glb(&bytes).expect("split glb");
+        assert_eq!(json, b"{}  ");
+        assert_eq!(binary, Some(&[1u8, 2, 3, 4][..]));
+
+        // A chunk length past the end of the file
+        let mut truncated = glb("{}", &[1, 2, 3, 4]);
+        truncated[24..28].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(split_glb(&truncated).is_err());
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for length in [0, 1, 2, 3, 100, 256] {
+            assert_eq!(decode_base64(&base64(&bytes[..length])).as_deref(), Some(&bytes[..length]));
+        }
+        assert!(decode_base64("ab*d").is_none());
+    }
+
+    #[test]
+    fn hostile_sizes_are_errors() {
+        let json = fixture_json("");
+        let parse_with = |from: &str, to: &str| {
+            let bytes = glb(&json.replacen(from, to, 1), &fixture_buffer());
+            parse(&bytes, Path::new("test.glb"))
+        };
+        // Counts past the data, offsets that overflow and huge accessors without data
+        assert!(parse_with(r#""count": 3, "type": "VEC3""#, r#""count": 4000000000, "type": "VEC3""#).is_err());
+        assert!(parse_with(r#""byteOffset": 72"#, r#""byteOffset": 18446744073709551615"#).is_err());
+        assert!(parse_with(r#""bufferView": 1, "componentType""#, r#""componentType""#).is_ok());
+        assert!(parse_with(
+            r#""bufferView": 1, "componentType": 5123, "count": 3"#,
+            r#""componentType": 5123, "count": 18446744073709551615"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! Model importers
+//!
+//! Loads artist-authored models into engine meshes and materials:
+//! - Wavefront OBJ with MTL material libraries
+//! - glTF 2.0 (`.gltf` with external or embedded buffers, and binary `.glb`)
+
+pub mod obj;
+pub mod gltf;
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use glam::Mat4;
+use vibevj_common::{Result, VibeVJError};
+use crate::material::Material;
+use crate::mesh::Mesh;
+use crate::render_object::RenderObject;
+use crate::texture_cache::TextureCache;
+
+/// A mesh read from a model file
+#[derive(Debug, Clone)]
+pub struct ImportedMesh {
+    pub name: String,
+    pub mesh: Mesh,
+    /// Index into `ImportedModel::materials`
+    pub material: Option<usize>,
+}
+
+/// A material read from a model file
+#[derive(Debug, Clone)]
+pub struct ImportedMaterial {
+    pub name: String,
+    pub material: Material,
+}
+
+/// A node of the model's hierarchy
+#[derive(Debug, Clone)]
+pub struct ImportedNode {
+    pub name: String,
+    /// Transform relative to the parent node
+    pub transform: Mat4,
+    /// Indices into `ImportedModel::meshes`
+    pub meshes: Vec<usize>,
+    /// Indices into `ImportedModel::nodes`
+    pub children: Vec<usize>,
+}
+
+/// Meshes, materials and node hierarchy loaded from a model file
+#[derive(Debug, Clone, Default)]
+pub struct ImportedModel {
+    pub meshes: Vec<ImportedMesh>,
+    pub materials: Vec<ImportedMaterial>,
+    pub nodes: Vec<ImportedNode>,
+    /// Top-level nodes, indices into `nodes`
+    pub roots: Vec<usize>,
+    /// Encoded images stored inside the file, keyed by the name materials use
+    pub embedded_textures: HashMap<String, Vec<u8>>,
+}
+
+impl ImportedModel {
+    /// Load a model, choosing the importer from the file extension
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "obj" => obj::load(path),
+            "gltf" | "glb" => gltf::load(path),
+            _ => Err(VibeVJError::InvalidOperation(format!(
+                "No importer for model '{}' (expected .obj, .gltf or .glb)",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Material for a mesh, or the default material if it has none
+    pub fn mesh_material(&self, mesh: &ImportedMesh) -> Material {
+        mesh.material
+            .and_then(|index| self.materials.get(index))
+            .map(|m| m.material.clone())
+            .unwrap_or_default()
+    }
+
+    /// Model-space transform of every node, indexed like `nodes`
+    ///
+    /// Fails if a node is reached twice, as the hierarchy then has a cycle.
+    pub fn world_transforms(&self) -> Result<Vec<Mat4>> {
+        let mut transforms = vec![Mat4::IDENTITY; self.nodes.len()];
+        let mut stack: Vec<(usize, Mat4)> = self.roots.iter().map(|&root| (root, Mat4::IDENTITY)).collect();
+        let mut visited = HashSet::new();
+
+        while let Some((index, parent)) = stack.pop() {
+            let Some(node) = self.nodes.get(index) else { continue };
+            if !visited.insert(index) {
+                return Err(VibeVJError::SerializationError(format!(
+                    "Model node {} is reached twice; the node hierarchy has a cycle",
+                    index
+                )));
+            }
+            let world = parent * node.transform;
+            transforms[index] = world;
+            stack.extend(node.children.iter().map(|&child| (child, world)));
+        }
+
+        Ok(transforms)
+    }
+
+    /// Flatten the hierarchy into render objects, one per mesh instance
+    pub fn create_objects(&self) -> Result<Vec<RenderObject>> {
+        let transforms = self.world_transforms()?;
+        let mut objects = Vec::new();
+
+        for (node, transform) in self.nodes.iter().zip(transforms) {
+            for &mesh_index in &node.meshes {
+                if let Some(mesh) = self.meshes.get(mesh_index) {
+                    objects.push(RenderObject::new(mesh.mesh.clone(), self.mesh_material(mesh), transform));
+                }
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// Load every texture referenced by the model's materials into the cache
+    ///
+    /// Embedded images are decoded from memory, others are read from disk.
+    /// Textures that fail to load are logged and left to the fallback.
+    pub fn load_textures(&self, device: &wgpu::Device, queue: &wgpu::Queue, cache: &mut TextureCache) {
+        for imported in &self.materials {
+            for (slot, name) in imported.material.textures.iter() {
+                let result = match self.embedded_textures.get(name) {
+                    Some(bytes) => cache.load_bytes(device, queue, name, bytes, slot.is_srgb()).map(|_| ()),
+                    None => cache.load_file(device, queue, name, slot.is_srgb()).map(|_| ()),
+                };
+                if let Err(e) = result {
+                    log::warn!("Failed to load {:?} texture '{}': {}", slot, name, e);
+                }
+            }
+        }
+    }
+}
+
+/// Path of a file referenced by a model, relative to the model's directory
+fn resolve_relative(model_path: &Path, reference: &str) -> String {
+    let reference = reference.replace('\\', "/");
+    match model_path.parent() {
+        Some(dir) if !Path::new(&reference).is_absolute() => dir.join(reference).to_string_lossy().into_owned(),
+        _ => reference,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, children: Vec<usize>) -> ImportedNode {
+        ImportedNode {
+            name: name.to_string(),
+            transform: Mat4::from_translation(glam::Vec3::X),
+            meshes: Vec::new(),
+            children,
+        }
+    }
+
+    #[test]
+    fn world_transforms_follow_the_hierarchy() {
+        let model = ImportedModel {
+            nodes: vec![node("root", vec![1]), node("child", vec![2]), node("grandchild", vec![])],
+            roots: vec![0],
+            ..Default::default()
+        };
+        let transforms = model.world_transforms().expect("tree hierarchy");
+        let offsets: Vec<f32> = transforms.iter().map(|transform| transform.w_axis.x).collect();
+        assert_eq!(offsets, [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn world_transforms_reject_cycles() {
+        let model = ImportedModel {
+            nodes: vec![node("a", vec![1]), node("b", vec![0])],
+            roots: vec![0],
+            ..Default::default()
+        };
+        assert!(model.world_transforms().is_err());
+        assert!(model.create_objects().is_err());
+    }
+}
@@ -0,0 +1,460 @@
+use std::collections::HashMap;
+use std::path::Path;
+use glam::Mat4;
+use vibevj_common::{Color, Result, VibeVJError};
+use crate::material::{Material, ShaderType};
+use crate::mesh::{Mesh, Vertex};
+use crate::mesh_gen::{generate_normals, generate_tangents};
+use super::{resolve_relative, ImportedMaterial, ImportedMesh, ImportedModel, ImportedNode};
+
+/// Load a Wavefront OBJ file and the MTL libraries it references
+///
+/// Every `o`/`g` object becomes a node, split into one mesh per material.
+/// Faces are fan-triangulated; corners without `vn` get smooth generated normals.
+pub fn load(path: &Path) -> Result<ImportedModel> {
+    let source = std::fs::read_to_string(path)?;
+    parse(&source, path, |library| {
+        let library_path = resolve_relative(path, library);
+        match std::fs::read_to_string(&library_path) {
+            Ok(text) => Some(parse_mtl(&text, Path::new(&library_path))),
+            Err(e) => {
+                log::warn!("Failed to read material library '{}': {}", library_path, e);
+                None
+            }
+        }
+    })
+}
+
+/// Parse OBJ source; `load_library` reads an `mtllib` by name
+pub fn parse(
+    source: &str,
+    path: &Path,
+    mut load_library: impl FnMut(&str) -> Option<Vec<ImportedMaterial>>,
+) -> Result<ImportedModel> {
+    let mut model = ImportedModel::default();
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut colors: Vec<[f32; 3]> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+
+    let default_name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "object".to_string());
+    let mut object = ObjObject::new(default_name);
+    let mut material: Option<usize> = None;
+
+    for (line_number, line) in source.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else { continue };
+        let error = |message: &str| {
+            VibeVJError::SerializationError(format!("{}:{}: {}", path.display(), line_number + 1, message))
+        };
+
+        match keyword {
+            "v" => {
+                let values = parse_floats(tokens).ok_or_else(|| error("invalid vertex"))?;
+                if values.len() < 3 {
+                    return Err(error("vertex needs three coordinates"));
+                }
+                positions.push([values[0], values[1], values[2]]);
+                // Common extension: `v x y z r g b`
+                colors.push(if values.len() >= 6 { [values[3], values[4], values[5]] } else { [1.0; 3] });
+            }
+            "vt" => {
+                let values = parse_floats(tokens).ok_or_else(|| error("invalid texture coordinate"))?;
+                let u = values.first().copied().unwrap_or(0.0);
+                let v = values.get(1).copied().unwrap_or(0.0);
+                // OBJ puts v = 0 at the bottom of the image
+                uvs.push([u, 1.0 - v]);
+            }
+            "vn" => {
+                let values = parse_floats(tokens).ok_or_else(|| error("invalid normal"))?;
+                if values.len() < 3 {
+                    return Err(error("normal needs three components"));
+                }
+                normals.push([values[0], values[1], values[2]]);
+            }
+            "f" => {
+                let corners = tokens
+                    .map(|token| parse_corner(token, positions.len(), uvs.len(), normals.len()))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| error("invalid face index"))?;
+                if corners.len() < 3 {
+                    return Err(error("face needs at least three vertices"));
+                }
+
+                let builder = object.builder(material);
+                let indices: Vec<u32> = corners
+                    .iter()
+                    .map(|&corner| builder.vertex(corner, &positions, &colors, &uvs, &normals))
+                    .collect();
+                for i in 1..indices.len() - 1 {
+                    builder.indices.extend_from_slice(&[indices[0], indices[i], indices[i + 1]]);
+                }
+            }
+            "o" | "g" => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                if object.is_empty() {
+                    if !name.is_empty() {
+                        object.name = name;
+                    }
+                } else {
+                    object.finish(&mut model);
+                    object = ObjObject::new(if name.is_empty() { format!("object{}", model.nodes.len()) } else { name });
+                }
+            }
+            "usemtl" => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                material = model.materials.iter().position(|m| m.name == name);
+                if material.is_none() {
+                    log::warn!("{}: unknown material '{}'", path.display(), name);
+                }
+            }
+            "mtllib" => {
+                for library in tokens {
+                    if let Some(materials) = load_library(library) {
+                        model.materials.extend(materials);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    object.finish(&mut model);
+    model.roots = (0..model.nodes.len()).collect();
+    Ok(model)
+}
+
+/// Parse an MTL material library
+///
+/// Phong parameters are converted to the PBR material: `Ns` maps to
+/// roughness, and the `Pr`/`Pm` PBR extension overrides it when present.
+pub fn parse_mtl(source: &str, path: &Path) -> Vec<ImportedMaterial> {
+    let mut materials: Vec<ImportedMaterial> = Vec::new();
+
+    for line in source.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else { continue };
+        let rest: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            materials.push(ImportedMaterial {
+                name: rest.join(" "),
+                material: Material::pbr(Color::WHITE, 0.0, 0.5),
+            });
+            continue;
+        }
+
+        let Some(current) = materials.last_mut() else { continue };
+        let material = &mut current.material;
+        let floats: Vec<f32> = rest.iter().filter_map(|t| t.parse().ok()).collect();
+        let scalar = floats.first().copied();
+        // Texture maps may have options before the file name
+        let texture = rest.last().map(|file| resolve_relative(path, file));
+
+        match keyword {
+            "Kd" if floats.len() >= 3 => {
+                material.color = Color::new(floats[0], floats[1], floats[2], material.color.a);
+            }
+            "Ke" if floats.len() >= 3 => {
+                material.emissive = Color::new(floats[0], floats[1], floats[2], 1.0);
+            }
+            "d" => {
+                if let Some(alpha) = scalar {
+                    material.color.a = alpha;
+                }
+            }
+            "Tr" => {
+                if let Some(transparency) = scalar {
+                    material.color.a = 1.0 - transparency;
+                }
+            }
+            "Ns" => {
+                if let Some(exponent) = scalar {
+                    // Blinn-Phong exponent to GGX roughness
+                    material.roughness = (2.0 / (exponent.max(0.0) + 2.0)).sqrt().clamp(0.04, 1.0);
+                }
+            }
+            "Pr" => {
+                if let Some(roughness) = scalar {
+                    material.roughness = roughness;
+                }
+            }
+            "Pm" => {
+                if let Some(metallic) = scalar {
+                    material.metallic = metallic;
+                }
+            }
+            // Illumination model 0 is flat color without lighting
+            "illum" if scalar == Some(0.0) => material.shader_type = ShaderType::Unlit,
+            "map_Kd" => material.textures.albedo = texture,
+            "map_Bump" | "map_bump" | "bump" | "norm" => material.textures.normal = texture,
+            "map_Ke" => material.textures.emissive = texture,
+            "map_d" => material.textures.opacity = texture,
+            _ => {}
+        }
+    }
+
+    materials
+}
+
+/// Position, texture coordinate and normal indices of a face corner
+type Corner = (usize, Option<usize>, Option<usize>);
+
+/// Parse `v`, `v/vt`, `v//vn` or `v/vt/vn`, resolving negative indices
+fn parse_corner(token: &str, positions: usize, uvs: usize, normals: usize) -> Option<Corner> {
+    fn resolve(index: &str, len: usize) -> Option<usize> {
+        let index: i64 = index.parse().ok()?;
+        let resolved = if index < 0 { len as i64 + index } else { index - 1 };
+        (0..len as i64).contains(&resolved).then_some(resolved as usize)
+    }
+
+    let mut parts = token.split('/');
+    let position = resolve(parts.next()?, positions)?;
+    let uv = match parts.next() {
+        Some(s) if !s.is_empty() => Some(resolve(s, uvs)?),
+        _ => None,
+    };
+    let normal = match parts.next() {
+        Some(s) if !s.is_empty() => Some(resolve(s, normals)?),
+        _ => None,
+    };
+    Some((position, uv, normal))
+}
+
+fn parse_floats<'a>(tokens: impl Iterator<Item = &'a str>) -> Option<Vec<f32>> {
+    tokens.map(|t| t.parse().ok()).collect()
+}
+
+/// Geometry of one OBJ object, split by material
+struct ObjObject {
+    name: String,
+    builders: Vec<(Option<usize>, MeshBuilder)>,
+}
+
+impl ObjObject {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            builders: Vec::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.builders.iter().all(|(_, builder)| builder.indices.is_empty())
+    }
+
+    fn builder(&mut self, material: Option<usize>) -> &mut MeshBuilder {
+        let index = match self.builders.iter().position(|(m, _)| *m == material) {
+            Some(index) => index,
+            None => {
+                self.builders.push((material, MeshBuilder::default()));
+                self.builders.len() - 1
+            }
+        };
+        &mut self.builders[index].1
+    }
+
+    /// Move the finished meshes into the model as a new node
+    fn finish(self, model: &mut ImportedModel) {
+        if self.is_empty() {
+            return;
+        }
+
+        let split = self.builders.len() > 1;
+        let mut meshes = Vec::new();
+        for (material, builder) in self.builders {
+            if builder.indices.is_empty() {
+                continue;
+            }
+            let name = match material.and_then(|m| model.materials.get(m)) {
+                Some(m) if split => format!("{} ({})", self.name, m.name),
+                _ => self.name.clone(),
+            };
+            meshes.push(model.meshes.len());
+            model.meshes.push(ImportedMesh {
+                name,
+                mesh: builder.build(),
+                material,
+            });
+        }
+
+        model.nodes.push(ImportedNode {
+            name: self.name,
+            transform: Mat4::IDENTITY,
+            meshes,
+            children: Vec::new(),
+        });
+    }
+}
+
+/// Deduplicates face corners into indexed vertices
+#[derive(Default)]
+struct MeshBuilder {
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    lookup: HashMap<Corner, u32>,
+    /// Whether each vertex's normal came from the file
+    has_normal: Vec<bool>,
+}
+
+impl MeshBuilder {
+    fn vertex(
+        &mut self,
+        corner: Corner,
+        positions: &[[f32; 3]],
+        colors: &[[f32; 3]],
+        uvs: &[[f32; 2]],
+        normals: &[[f32; 3]],
+    ) -> u32 {
+        if let Some(&index) = self.lookup.get(&corner) {
+            return index;
+        }
+
+        let (position, uv, normal) = corner;
+        self.has_normal.push(normal.is_some());
+        let index = self.vertices.len() as u32;
+        self.vertices.push(Vertex::new(
+            positions[position],
+            normal.map(|n| normals[n]).unwrap_or([0.0, 1.0, 0.0]),
+            uv.map(|t| uvs[t]).unwrap_or([0.0, 0.0]),
+            colors[position],
+        ));
+        self.lookup.insert(corner, index);
+        index
+    }
+
+    fn build(mut self) -> Mesh {
+        if self.has_normal.contains(&false) {
+            let mut generated = self.vertices.clone();
+            generate_normals(&mut generated, &self.indices);
+            for ((vertex, generated), has_normal) in self.vertices.iter_mut().zip(generated).zip(&self.has_normal) {
+                if !has_normal {
+                    vertex.normal = generated.normal;
+                }
+            }
+        }
+        generate_tangents(&mut self.vertices, &self.indices);
+        Mesh::new(self.vertices, self.indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MTL: &str = "
+newmtl red
+Kd 1 0 0
+Ns 0
+d 0.5
+
+newmtl shiny
+Kd 0 1 0
+Ns 998
+Ke 0.5 0.25 0
+map_Kd -bm 1 tex/albedo.png
+illum 0
+";
+
+    const OBJ: &str = "
+mtllib test.mtl
+o Quad
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+vn 1 0 0
+usemtl red
+f 1/1/1 2/2/1 3/3/1 4/4/1
+o Tri
+usemtl shiny
+f -4 -3 -2
+usemtl red
+f 1//2 2 3
+";
+
+    fn parse_fixture() -> ImportedModel {
+        parse(OBJ, Path::new("models/test.obj"), |library| {
+            assert_eq!(library, "test.mtl");
+            Some(parse_mtl(MTL, Path::new("models/test.mtl")))
+        })
+        .expect("parse obj")
+    }
+
+    #[test]
+    fn objects_and_materials_become_nodes_and_meshes() {
+        let model = parse_fixture();
+        let names: Vec<&str> = model.nodes.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(names, ["Quad", "Tri"]);
+        assert_eq!(model.roots, [0, 1]);
+        assert_eq!(model.nodes[0].meshes, [0]);
+        assert_eq!(model.nodes[1].meshes, [1, 2]);
+
+        // The quad is fan-triangulated over four shared corners
+        let quad = &model.meshes[0];
+        assert_eq!((quad.mesh.vertices.len(), quad.mesh.indices.len()), (4, 6));
+        assert_eq!(quad.mesh.indices, [0, 1, 2, 0, 2, 3]);
+        assert_eq!(quad.material, Some(0));
+        // OBJ texture coordinates start at the bottom of the image
+        assert_eq!(quad.mesh.vertices[0].uv, [0.0, 1.0]);
+
+        let names: Vec<&str> = model.meshes[1..].iter().map(|mesh| mesh.name.as_str()).collect();
+        assert_eq!(names, ["Tri (shiny)", "Tri (red)"]);
+    }
+
+    #[test]
+    fn negative_indices_count_back_from_the_last_vertex() {
+        let model = parse_fixture();
+        let shiny = &model.meshes[1].mesh;
+        assert_eq!((shiny.vertices.len(), shiny.indices.len()), (3, 3));
+        let positions: Vec<[f32; 3]> = shiny.vertices.iter().map(|vertex| vertex.position).collect();
+        assert_eq!(positions, [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]]);
+
+        assert!(parse_corner("-5", 4, 0, 0).is_none());
+        assert!(parse_corner("0", 4, 0, 0).is_none());
+        assert_eq!(parse_corner("-1/-1/-1", 4, 2, 3), Some((3, Some(1), Some(2))));
+    }
+
+    #[test]
+    fn only_missing_normals_are_generated() {
+        let model = parse_fixture();
+        // The first corner keeps the file's normal, the others face the triangle
+        let normals: Vec<[f32; 3]> = model.meshes[2].mesh.vertices.iter().map(|vertex| vertex.normal).collect();
+        assert_eq!(normals, [[1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0, 1.0]]);
+    }
+
+    #[test]
+    fn mtl_maps_to_pbr_materials() {
+        let materials = parse_mtl(MTL, Path::new("models/test.mtl"));
+        assert_eq!(materials.len(), 2);
+
+        let red = &materials[0].material;
+        assert_eq!(red.color, Color::new(1.0, 0.0, 0.0, 0.5));
+        assert_eq!(red.roughness, 1.0);
+        assert_eq!(red.shader_type, ShaderType::PBR);
+
+        let shiny = &materials[1].material;
+        assert_eq!(shiny.color, Color::new(0.0, 1.0, 0.0, 1.0));
+        assert!((shiny.roughness - (2.0f32 / 1000.0).sqrt()).abs() < 1e-6);
+        assert_eq!(shiny.emissive, Color::new(0.5, 0.25, 0.0, 1.0));
+        assert_eq!(shiny.shader_type, ShaderType::Unlit);
+        // Texture options are skipped and the file resolves next to the library
+        assert_eq!(shiny.textures.albedo.as_deref(), Some("models/tex/albedo.png"));
+    }
+
+    #[test]
+    fn invalid_faces_are_errors() {
+        let result = parse("v 0 0 0\nf 1 2 3\n", Path::new("bad.obj"), |_| None);
+        assert!(result.is_err());
+    }
+}
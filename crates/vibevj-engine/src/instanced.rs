@@ -0,0 +1,228 @@
+use glam::{Mat4, Vec4};
+use crate::{Mesh, Material, ModelUniform, TextureCache};
+use crate::render_object::create_material_bind_group;
+
+/// Per-instance data for GPU instancing
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct InstanceData {
+    /// Instance transform, applied before the object's base transform
+    pub model: [[f32; 4]; 4],
+    /// Instance color, multiplied with the material color
+    pub color: [f32; 4],
+    /// Free parameters for custom shaders (e.g. phase, seed, audio band)
+    pub custom: [f32; 4],
+}
+
+impl InstanceData {
+    pub fn new(model: Mat4, color: Vec4, custom: Vec4) -> Self {
+        Self {
+            model: model.to_cols_array_2d(),
+            color: color.to_array(),
+            custom: custom.to_array(),
+        }
+    }
+
+    /// Create instance data with only a transform
+    pub fn from_transform(model: Mat4) -> Self {
+        Self::new(model, Vec4::ONE, Vec4::ZERO)
+    }
+
+    /// Instance buffer layout, starting after the `Vertex` locations (0-4)
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![
+            // Model matrix columns
+            5 => Float32x4,
+            6 => Float32x4,
+            7 => Float32x4,
+            8 => Float32x4,
+            // Color
+            9 => Float32x4,
+            // Custom
+            10 => Float32x4,
+        ];
+
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<InstanceData>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+impl Default for InstanceData {
+    fn default() -> Self {
+        Self::from_transform(Mat4::IDENTITY)
+    }
+}
+
+/// A mesh drawn many times with a single instanced draw call
+///
+/// All instances share the mesh, material and base transform; each instance
+/// adds its own transform, color and custom vec4.
+#[derive(Debug)]
+pub struct InstancedRenderObject {
+    pub mesh: Mesh,
+    pub material: Material,
+    pub transform: Mat4,
+    pub instances: Vec<InstanceData>,
+    pub vertex_buffer: Option<wgpu::Buffer>,
+    pub index_buffer: Option<wgpu::Buffer>,
+    pub instance_buffer: Option<wgpu::Buffer>,
+    pub material_buffer: Option<wgpu::Buffer>,
+    pub model_buffer: Option<wgpu::Buffer>,
+    pub material_bind_group: Option<wgpu::BindGroup>,
+    pub model_bind_group: Option<wgpu::BindGroup>,
+    instance_capacity: usize,
+}
+
+impl InstancedRenderObject {
+    /// Create a new instanced render object
+    pub fn new(mesh: Mesh, material: Material, instances: Vec<InstanceData>) -> Self {
+        Self {
+            mesh,
+            material,
+            transform: Mat4::IDENTITY,
+            instances,
+            vertex_buffer: None,
+            index_buffer: None,
+            instance_buffer: None,
+            material_buffer: None,
+            model_buffer: None,
+            material_bind_group: None,
+            model_bind_group: None,
+            instance_capacity: 0,
+        }
+    }
+
+    /// Number of instances drawn
+    pub fn instance_count(&self) -> u32 {
+        self.instances.len() as u32
+    }
+
+    /// Upload mesh, instance and material data to GPU
+    /// Uses the same bind group layouts as `RenderObject::upload`
+    pub fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        material_layout: &wgpu::BindGroupLayout,
+        model_layout: &wgpu::BindGroupLayout,
+        textures: &TextureCache,
+    ) {
+        use wgpu::util::DeviceExt;
+
+        // Upload shared mesh data
+        self.vertex_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Instanced Vertex Buffer"),
+            contents: bytemuck::cast_slice(&self.mesh.vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        }));
+
+        self.index_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Instanced Index Buffer"),
+            contents: bytemuck::cast_slice(&self.mesh.indices),
+            usage: wgpu::BufferUsages::INDEX,
+        }));
+
+        // Upload instances, leaving headroom up to the next power of two
+        let capacity = self.instances.len().max(1).next_power_of_two();
+        let mut instance_data = vec![InstanceData::default(); capacity];
+        instance_data[..self.instances.len()].copy_from_slice(&self.instances);
+        self.instance_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Instance Buffer"),
+            contents: bytemuck::cast_slice(&instance_data),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        }));
+        self.instance_capacity = capacity;
+
+        // Create material uniform
+        let material_uniform: crate::MaterialUniform = (&self.material).into();
+        self.material_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Instanced Material Buffer"),
+            contents: bytemuck::cast_slice(&[material_uniform]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        }));
+
+        // Create base model uniform
+        let model_uniform = ModelUniform {
+            model: self.transform.to_cols_array_2d(),
+        };
+        self.model_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Instanced Model Buffer"),
+            contents: bytemuck::cast_slice(&[model_uniform]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        }));
+
+        // Create bind groups
+        if let Some(ref material_buffer) = self.material_buffer {
+            self.material_bind_group = Some(create_material_bind_group(
+                device,
+                material_layout,
+                material_buffer,
+                &self.material,
+                textures,
+            ));
+        }
+
+        if let Some(ref model_buffer) = self.model_buffer {
+            self.model_bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Instanced Model Bind Group"),
+                layout: model_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: model_buffer.as_entire_binding(),
+                }],
+            }));
+        }
+    }
+
+    /// Replace all instances and write them to the GPU
+    ///
+    /// The instance buffer is only reallocated when it has to grow, so
+    /// updating a stable number of instances every frame is a single
+    /// `write_buffer` call.
+    pub fn update_instances(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, instances: &[InstanceData]) {
+        self.instances.clear();
+        self.instances.extend_from_slice(instances);
+        self.flush_instances(device, queue);
+    }
+
+    /// Write the current `instances` to the GPU after editing them in place
+    pub fn flush_instances(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.ensure_instance_capacity(device, self.instances.len());
+
+        if let Some(ref instance_buffer) = self.instance_buffer {
+            if !self.instances.is_empty() {
+                queue.write_buffer(instance_buffer, 0, bytemuck::cast_slice(&self.instances));
+            }
+        }
+    }
+
+    /// Update the base transform applied to all instances
+    pub fn update_transform(&mut self, queue: &wgpu::Queue, transform: Mat4) {
+        self.transform = transform;
+
+        if let Some(ref model_buffer) = self.model_buffer {
+            let model_uniform = ModelUniform {
+                model: self.transform.to_cols_array_2d(),
+            };
+            queue.write_buffer(model_buffer, 0, bytemuck::cast_slice(&[model_uniform]));
+        }
+    }
+
+    /// Grow the instance buffer (to the next power of two) if it cannot hold `count` instances
+    fn ensure_instance_capacity(&mut self, device: &wgpu::Device, count: usize) {
+        if self.instance_buffer.is_some() && count <= self.instance_capacity {
+            return;
+        }
+
+        let capacity = count.max(1).next_power_of_two();
+        self.instance_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Instance Buffer"),
+            size: (capacity * std::mem::size_of::<InstanceData>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+        self.instance_capacity = capacity;
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use vibevj_common::{AudioFeatures, Result, TimeInfo, VibeVJError};
+use wgpu::util::DeviceExt;
+use crate::animated_texture::AnimatedTexture;
+use crate::compositor::LayerSource;
+use crate::frame::FrameUniform;
+use crate::pipeline::BindGroupLayoutBuilder;
+use crate::render_target::RenderTarget;
+use crate::video::{VideoPlayer, VideoTexture};
+
+/// Fullscreen generator layer rendered from a WGSL `shade(uv)` function
+pub struct ShaderLayer {
+    pipeline: wgpu::RenderPipeline,
+    frame_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    pub target: RenderTarget,
+}
+
+impl ShaderLayer {
+    /// Compile a layer from the body of a shader layer file
+    /// Returns an error instead of panicking when the WGSL does not validate
+    pub fn new(device: &wgpu::Device, source: &str, width: u32, height: u32, label: &str) -> Result<Self> {
+        let source = format!("{}\n{}", include_str!("../../../assets/shaders/shader_layer.wgsl"), source);
+
+        let bind_group_layout = BindGroupLayoutBuilder::new(wgpu::ShaderStages::FRAGMENT)
+            .uniform_buffer()
+            .build(device, Some("Shader Layer Bind Group Layout"));
+        let frame_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Shader Layer Frame Buffer"),
+            contents: bytemuck::cast_slice(&[FrameUniform::new()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Shader Layer Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: frame_buffer.as_entire_binding(),
+            }],
+        });
+
+        // User shaders are compiled at runtime, so catch validation errors
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Shader Layer Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: RenderTarget::HDR_FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            return Err(VibeVJError::RenderError(format!("Shader layer '{}' failed to compile: {}", label, error)));
+        }
+
+        Ok(Self {
+            pipeline,
+            frame_buffer,
+            bind_group,
+            target: RenderTarget::new(device, width, height, RenderTarget::HDR_FORMAT, Some(label)),
+        })
+    }
+
+    /// Load and compile a shader layer file
+    pub fn load(device: &wgpu::Device, path: &str, width: u32, height: u32) -> Result<Self> {
+        let source = std::fs::read_to_string(path)?;
+        Self::new(device, &source, width, height, path)
+    }
+
+    /// Record the layer for this frame, with time, audio and resolution from `frame`
+    pub fn render(&self, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder, frame: &FrameUniform) {
+        let frame = FrameUniform {
+            resolution: [self.target.width as f32, self.target.height as f32],
+            ..*frame
+        };
+        queue.write_buffer(&self.frame_buffer, 0, bytemuck::cast_slice(&[frame]));
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Shader Layer Pass"),
+            color_attachments: &[Some(self.target.color_attachment(wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)))],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// Video decoding and the texture showing it
+struct VideoLayer {
+    player: VideoPlayer,
+    texture: VideoTexture,
+}
+
+/// Textures for shader, image and video layers, loaded on first use
+///
+/// Sources that fail to load are logged once and then skipped, so a bad
+/// file does not stall every frame. The scene source is owned by the caller.
+/// Image sources are keyed with their playback, so layers sharing a file can
+/// play it differently. Videos are keyed by path so changing playback keeps
+/// the playhead.
+pub struct LayerSources {
+    shaders: HashMap<String, ShaderLayer>,
+    images: HashMap<LayerSource, AnimatedTexture>,
+    videos: HashMap<String, VideoLayer>,
+    failed: HashSet<LayerSource>,
+    width: u32,
+    height: u32,
+    video_wait: Option<Duration>,
+}
+
+impl LayerSources {
+    /// Shader layers render at `width` x `height`
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            shaders: HashMap::new(),
+            images: HashMap::new(),
+            videos: HashMap::new(),
+            failed: HashSet::new(),
+            width,
+            height,
+            video_wait: None,
+        }
+    }
+
+    /// Block up to `timeout` for each video frame rather than keep showing
+    /// the previous one, e.g. for offline rendering
+    pub fn with_video_wait(mut self, timeout: Duration) -> Self {
+        self.video_wait = Some(timeout);
+        self
+    }
+
+    /// Load any new sources, render the shader layers among them and
+    /// advance animated images and videos to `time`
+    #[allow(clippy::too_many_arguments)]
+    pub fn prepare<'a>(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        sources: impl IntoIterator<Item = &'a LayerSource>,
+        frame: &FrameUniform,
+        time: &TimeInfo,
+        audio: &AudioFeatures,
+    ) {
+        for source in sources {
+            if self.failed.contains(source) {
+                continue;
+            }
+            let result: Result<()> = match source {
+                LayerSource::Shader { path } => {
+                    if !self.shaders.contains_key(path) {
+                        match ShaderLayer::load(device, path, self.width, self.height) {
+                            Ok(layer) => {
+                                self.shaders.insert(path.clone(), layer);
+                            }
+                            Err(e) => {
+                                log::error!("{}", e);
+                                self.failed.insert(source.clone());
+                                continue;
+                            }
+                        }
+                    }
+                    self.shaders[path].render(queue, encoder, frame);
+                    Ok(())
+                }
+                LayerSource::Image { path, playback } => {
+                    let loaded = match self.images.contains_key(source) {
+                        true => Ok(()),
+                        false => AnimatedTexture::load(device, queue, path).map(|texture| {
+                            self.images.insert(source.clone(), texture);
+                        }),
+                    };
+                    if let Some(image) = self.images.get_mut(source) {
+                        image.update(queue, playback, time, audio);
+                    }
+                    loaded
+                }
+                LayerSource::Video { path, playback } => {
+                    let loaded = match self.videos.contains_key(path) {
+                        true => Ok(()),
+                        false => VideoPlayer::open(path).map(|player| {
+                            let texture = VideoTexture::new(device, player.info().width, player.info().height, Some(path));
+                            self.videos.insert(path.clone(), VideoLayer { player, texture });
+                        }),
+                    };
+                    if let Some(video) = self.videos.get_mut(path) {
+                        let mut frame = video.player.update(playback, time);
+                        if let Some(timeout) = self.video_wait {
+                            frame = frame.or_else(|| video.player.wait(playback, timeout));
+                        }
+                        if let Some(frame) = frame {
+                            video.texture.upload(device, queue, encoder, &frame);
+                        }
+                    }
+                    loaded
+                }
+                LayerSource::Scene => Ok(()),
+            };
+            if let Err(e) = result {
+                log::error!("Failed to load layer source '{}': {}", source.name(), e);
+                self.failed.insert(source.clone());
+            }
+        }
+    }
+
+    /// Texture for a prepared shader, image or video source
+    pub fn view(&self, source: &LayerSource) -> Option<&wgpu::TextureView> {
+        match source {
+            LayerSource::Shader { path } => self.shaders.get(path).map(|layer| &layer.target.view),
+            LayerSource::Image { .. } => self.images.get(source).map(|image| &image.texture.view),
+            LayerSource::Video { path, .. } => self.videos.get(path).map(|video| &video.texture.target.view),
+            LayerSource::Scene => None,
+        }
+    }
+
+    /// Size in pixels of a prepared shader, image or video source
+    pub fn size(&self, source: &LayerSource) -> Option<(u32, u32)> {
+        match source {
+            LayerSource::Shader { path } => self.shaders.get(path).map(|layer| (layer.target.width, layer.target.height)),
+            LayerSource::Image { .. } => self.images.get(source).map(|image| (image.frames.width, image.frames.height)),
+            LayerSource::Video { path, .. } => {
+                self.videos.get(path).map(|video| (video.texture.target.width, video.texture.target.height))
+            }
+            LayerSource::Scene => None,
+        }
+    }
+
+    /// Player of a loaded video, for seeking
+    pub fn video_mut(&mut self, path: &str) -> Option<&mut VideoPlayer> {
+        self.videos.get_mut(path).map(|video| &mut video.player)
+    }
+
+    /// Forget failed sources so they are retried, e.g. after editing a shader file
+    pub fn retry_failed(&mut self) {
+        self.failed.clear();
+    }
+
+    /// Resize shader layer targets
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        for layer in self.shaders.values_mut() {
+            layer.target.resize(device, width, height);
+        }
+    }
+}
@@ -2,28 +2,77 @@
 /// 
 /// This module provides the core rendering capabilities including:
 /// - WGPU-based renderer
-/// - Pipeline management
+/// - Pipeline management (render and compute)
 /// - Shader compilation and management
 /// - Render passes
 /// - Texture and buffer management
+/// - OBJ and glTF model import
+/// - Headless rendering, export and golden-image comparison
 
 pub mod renderer;
+pub mod headless;
 pub mod pipeline;
+pub mod compute;
 pub mod shader;
 pub mod camera;
+pub mod camera_controller;
+pub mod frame;
 pub mod mesh;
 pub mod mesh_gen;
+pub mod import;
 pub mod material;
 pub mod render_object;
+pub mod instanced;
+pub mod lines;
+pub mod sprites;
+pub mod particles;
 pub mod render_target;
+pub mod render_graph;
+pub mod render_target_pool;
+pub mod output;
+pub mod blit;
+pub mod warp;
 pub mod texture;
+pub mod animated_texture;
+pub mod video;
+pub mod texture_cache;
+pub mod tonemap;
+pub mod grading;
+pub mod compositor;
+pub mod layer_sources;
+pub mod export;
+pub mod golden;
 
 pub use renderer::Renderer;
-pub use pipeline::{Pipeline, PipelineBuilder};
+pub use headless::{HeadlessRenderer, HeadlessOptions};
+pub use pipeline::{Pipeline, PipelineBuilder, BindGroupLayoutBuilder};
+pub use compute::{ComputePipeline, ComputePipelineBuilder};
 pub use shader::{Shader, ShaderManager};
-pub use camera::{Camera, CameraUniform};
+pub use camera::{Camera, CameraUniform, Projection};
+pub use camera_controller::{BeatCuts, BeatOrbit, CameraController, CameraControllerKind, CameraInput, CameraKeyframe, CameraPath, CameraRig, CameraShake, CameraShot, FlyController, OrbitController};
+pub use frame::FrameUniform;
 pub use mesh::{Mesh, Vertex};
-pub use material::{Material, MaterialUniform, ShaderType};
+pub use mesh_gen::{Heightmap, PlatonicSolid, Superformula};
+pub use material::{Displacement, Material, MaterialTextures, MaterialUniform, RenderMode, ShaderType, SpectrumMapping, TextureSlot};
+pub use import::{ImportedModel, ImportedMesh, ImportedMaterial, ImportedNode};
 pub use render_object::{RenderObject, RenderObjectDescriptor, MeshType, ModelUniform};
+pub use instanced::{InstancedRenderObject, InstanceData};
+pub use lines::{LineBatch, LineInstance, Polyline};
+pub use sprites::{Sprite, SpriteBatch, SpriteCamera, SpriteDraw, SpriteRenderer, SpriteVertex};
+pub use particles::{ParticleSystem, ParticleSettings, ParticleForces, EmitterShape, BurstTrigger, Attractor, ColorKey, SizeKey};
 pub use render_target::RenderTarget;
+pub use render_graph::{PassBuilder, PassContext, RenderGraph, ResourceId};
+pub use render_target_pool::{RenderTargetPool, TargetDesc};
+pub use output::{AspectFit, AspectMode, CropRect, OutputSettings, OutputSource, OutputWindowSettings, Resolution};
+pub use blit::Blitter;
+pub use warp::{EdgeBlend, PolygonMask, WarpHandles, WarpMesh, WarpSettings, Warper};
 pub use texture::Texture;
+pub use animated_texture::{AnimatedTexture, AnimationFrames, Playback, PlaybackMode};
+pub use texture_cache::TextureCache;
+pub use video::{VideoDecoder, VideoFrame, VideoInfo, VideoPlayback, VideoPlayer, VideoTexture, YuvMatrix};
+pub use export::{ExportFormat, ExportSettings, FrameWriter, PngSequenceWriter, Y4mWriter};
+pub use golden::{GoldenImages, GoldenTolerance, ImageComparison};
+pub use tonemap::{ToneMapper, ToneMapSettings, ToneMapCurve, ToneMapUniform};
+pub use grading::{ColorGrading, CubeLut, GradingUniform};
+pub use compositor::{BlendMode, Compositor, CompositeUniform, Crossfader, CrossfadeCurve, Deck, Layer, LayerSource, MixerSettings};
+pub use layer_sources::{LayerSources, ShaderLayer};
@@ -0,0 +1,195 @@
+use bytemuck::{Pod, Zeroable};
+use glam::Vec3;
+use vibevj_common::Color;
+use crate::mesh::Mesh;
+
+/// One thick line segment, drawn as a screen-space quad
+///
+/// Width is in pixels; segments get square caps of half the width so joins
+/// between consecutive segments have no gaps.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct LineInstance {
+    pub start: [f32; 3],
+    pub width: f32,
+    pub end: [f32; 3],
+    pub color: [f32; 4],
+    /// Surface normals at the endpoints, used by vertex displacement on mesh edges
+    pub start_normal: [f32; 3],
+    pub end_normal: [f32; 3],
+}
+
+impl LineInstance {
+    pub fn new(start: Vec3, end: Vec3, width: f32, color: Color) -> Self {
+        Self {
+            start: start.into(),
+            width,
+            end: end.into(),
+            color: [color.r, color.g, color.b, color.a],
+            start_normal: [0.0; 3],
+            end_normal: [0.0; 3],
+        }
+    }
+
+    /// Segments along the feature edges of a mesh (see `Mesh::feature_edges`)
+    /// Colored by the vertex colors; the material's color and width apply on top
+    pub fn from_mesh_edges(mesh: &Mesh, crease_angle: f32) -> Vec<Self> {
+        mesh.feature_edges(crease_angle)
+            .into_iter()
+            .map(|[a, b]| {
+                let (a, b) = (&mesh.vertices[a as usize], &mesh.vertices[b as usize]);
+                let color = [(a.color[0] + b.color[0]) * 0.5, (a.color[1] + b.color[1]) * 0.5, (a.color[2] + b.color[2]) * 0.5, 1.0];
+                Self {
+                    start: a.position,
+                    width: 1.0,
+                    end: b.position,
+                    color,
+                    start_normal: a.normal,
+                    end_normal: b.normal,
+                }
+            })
+            .collect()
+    }
+
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<LineInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                // Start + width
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                // End
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                // Color
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 7]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                // Start normal
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 11]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                // End normal
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 14]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+            ],
+        }
+    }
+}
+
+/// A world-space polyline with constant width and color
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Vec3>,
+    pub color: Color,
+    /// Width in pixels
+    pub width: f32,
+    /// Connect the last point back to the first
+    pub closed: bool,
+}
+
+impl Polyline {
+    pub fn new(points: Vec<Vec3>, color: Color, width: f32) -> Self {
+        Self {
+            points,
+            color,
+            width,
+            closed: false,
+        }
+    }
+
+    pub fn closed(mut self) -> Self {
+        self.closed = true;
+        self
+    }
+
+    /// Segments between consecutive points
+    pub fn segments(&self) -> impl Iterator<Item = LineInstance> + '_ {
+        let count = match self.points.len() {
+            0 | 1 => 0,
+            2 => 1,
+            n if self.closed => n,
+            n => n - 1,
+        };
+        (0..count).map(move |i| {
+            let end = self.points[(i + 1) % self.points.len()];
+            LineInstance::new(self.points[i], end, self.width, self.color)
+        })
+    }
+}
+
+/// GPU buffer of polylines, drawn with `SceneRenderer::render_lines`
+pub struct LineBatch {
+    buffer: Option<wgpu::Buffer>,
+    capacity: usize,
+    count: u32,
+}
+
+impl LineBatch {
+    pub fn new() -> Self {
+        Self {
+            buffer: None,
+            capacity: 0,
+            count: 0,
+        }
+    }
+
+    /// Replace the batch contents, growing the buffer when needed
+    pub fn set_polylines(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, polylines: &[Polyline]) {
+        let segments: Vec<LineInstance> = polylines.iter().flat_map(|p| p.segments()).collect();
+        self.set_segments(device, queue, &segments);
+    }
+
+    /// Replace the batch contents with raw segments
+    pub fn set_segments(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, segments: &[LineInstance]) {
+        self.count = segments.len() as u32;
+        if segments.is_empty() {
+            return;
+        }
+
+        if self.buffer.is_none() || segments.len() > self.capacity {
+            self.capacity = segments.len().next_power_of_two();
+            self.buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Line Instance Buffer"),
+                size: (self.capacity * std::mem::size_of::<LineInstance>()) as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+        if let Some(buffer) = &self.buffer {
+            queue.write_buffer(buffer, 0, bytemuck::cast_slice(segments));
+        }
+    }
+
+    pub fn segment_count(&self) -> u32 {
+        self.count
+    }
+
+    /// Draw the segments with a line pipeline already set on the pass
+    pub fn draw(&self, render_pass: &mut wgpu::RenderPass) {
+        if let (Some(buffer), true) = (&self.buffer, self.count > 0) {
+            render_pass.set_vertex_buffer(0, buffer.slice(..));
+            render_pass.draw(0..6, 0..self.count);
+        }
+    }
+}
+
+impl Default for LineBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use vibevj_common::Color;
+use vibevj_common::{AudioBand, Color};
 
 /// Material properties for rendering
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +18,205 @@ pub struct Material {
     
     /// Shader type to use
     pub shader_type: ShaderType,
+    
+    /// Optional texture maps, resolved through the texture cache
+    #[serde(default)]
+    pub textures: MaterialTextures,
+    
+    /// Optional vertex displacement along normals
+    #[serde(default)]
+    pub displacement: Option<Displacement>,
+    
+    /// How the mesh is drawn
+    #[serde(default)]
+    pub render_mode: RenderMode,
+}
+
+/// Texture map references for a material
+///
+/// Each entry is a texture cache key, usually the file path it was loaded from.
+/// Missing entries fall back to a neutral 1x1 texture.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaterialTextures {
+    /// Base color map (sRGB), multiplied with `Material::color`
+    pub albedo: Option<String>,
+    
+    /// Tangent-space normal map (linear)
+    pub normal: Option<String>,
+    
+    /// Metallic (blue) and roughness (green) map (linear), glTF channel layout
+    pub metallic_roughness: Option<String>,
+    
+    /// Emissive map (sRGB), multiplied with `Material::emissive`
+    pub emissive: Option<String>,
+    
+    /// Opacity map (linear, red channel), multiplied with the final alpha
+    pub opacity: Option<String>,
+}
+
+impl MaterialTextures {
+    /// Iterate over all referenced textures with their slot
+    pub fn iter(&self) -> impl Iterator<Item = (TextureSlot, &str)> {
+        [
+            (TextureSlot::Albedo, &self.albedo),
+            (TextureSlot::Normal, &self.normal),
+            (TextureSlot::MetallicRoughness, &self.metallic_roughness),
+            (TextureSlot::Emissive, &self.emissive),
+            (TextureSlot::Opacity, &self.opacity),
+        ]
+        .into_iter()
+        .filter_map(|(slot, name)| name.as_deref().map(|name| (slot, name)))
+    }
+    
+    /// Get the texture reference for a slot
+    pub fn get(&self, slot: TextureSlot) -> Option<&str> {
+        match slot {
+            TextureSlot::Albedo => self.albedo.as_deref(),
+            TextureSlot::Normal => self.normal.as_deref(),
+            TextureSlot::MetallicRoughness => self.metallic_roughness.as_deref(),
+            TextureSlot::Emissive => self.emissive.as_deref(),
+            TextureSlot::Opacity => self.opacity.as_deref(),
+        }
+    }
+}
+
+/// Texture slots available on a material
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureSlot {
+    Albedo,
+    Normal,
+    MetallicRoughness,
+    Emissive,
+    Opacity,
+}
+
+impl TextureSlot {
+    /// All slots in bind group order
+    pub const ALL: [TextureSlot; 5] = [
+        TextureSlot::Albedo,
+        TextureSlot::Normal,
+        TextureSlot::MetallicRoughness,
+        TextureSlot::Emissive,
+        TextureSlot::Opacity,
+    ];
+    
+    /// Whether the slot holds color data that should be decoded as sRGB
+    pub fn is_srgb(&self) -> bool {
+        matches!(self, TextureSlot::Albedo | TextureSlot::Emissive)
+    }
+}
+
+/// Vertex displacement along normals, applied in the vertex shader
+///
+/// The offset is the sum of simplex noise, the audio spectrum and a sine
+/// wave, all in object space. Normals are recomputed from the displaced
+/// surface. Terms with zero amplitude are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Displacement {
+    /// Noise offset at full strength, in object units
+    pub noise_amplitude: f32,
+    /// Noise features per object unit
+    pub noise_frequency: f32,
+    /// How fast the noise field drifts through the object
+    pub noise_speed: f32,
+    /// Band that pumps the noise amplitude
+    pub noise_band: Option<AudioBand>,
+    /// Noise amplitude gain per unit of band level
+    pub noise_band_gain: f32,
+    
+    /// Offset per unit of spectrum level
+    pub spectrum_amplitude: f32,
+    /// Which coordinate selects the spectrum position
+    pub spectrum_mapping: SpectrumMapping,
+    
+    /// Sine wave travelling up the object's Y axis
+    pub wave_amplitude: f32,
+    /// Wave cycles per object unit, in radians
+    pub wave_frequency: f32,
+    /// Wave phase speed in radians per second
+    pub wave_speed: f32,
+    /// Band that pumps the wave amplitude
+    pub wave_band: Option<AudioBand>,
+    /// Wave amplitude gain per unit of band level
+    pub wave_band_gain: f32,
+}
+
+impl Default for Displacement {
+    fn default() -> Self {
+        Self {
+            noise_amplitude: 0.0,
+            noise_frequency: 1.0,
+            noise_speed: 0.0,
+            noise_band: None,
+            noise_band_gain: 1.0,
+            spectrum_amplitude: 0.0,
+            spectrum_mapping: SpectrumMapping::U,
+            wave_amplitude: 0.0,
+            wave_frequency: 1.0,
+            wave_speed: 0.0,
+            wave_band: None,
+            wave_band_gain: 1.0,
+        }
+    }
+}
+
+impl Displacement {
+    /// Noise-deformed surface pulsing with a band
+    pub fn pulse(amplitude: f32, frequency: f32, band: AudioBand) -> Self {
+        Self {
+            noise_amplitude: amplitude,
+            noise_frequency: frequency,
+            noise_speed: 0.5,
+            noise_band: Some(band),
+            noise_band_gain: 4.0,
+            ..Default::default()
+        }
+    }
+}
+
+/// How spectrum displacement picks a position in the spectrum (0 = lowest band)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SpectrumMapping {
+    /// Texture U coordinate
+    U,
+    /// Texture V coordinate
+    V,
+    /// Object-space Y between `min` and `max`
+    Height { min: f32, max: f32 },
+}
+
+/// How a mesh is drawn; sizes are in pixels
+///
+/// Line and point modes are unlit and do not need the `POLYGON_MODE_LINE`
+/// or `POLYGON_MODE_POINT` device features.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum RenderMode {
+    /// Filled triangles
+    #[default]
+    Solid,
+    
+    /// Every triangle edge, drawn with a barycentric shader
+    Wireframe { thickness: f32 },
+    
+    /// A round point at every vertex
+    Points { size: f32 },
+    
+    /// Only crease and boundary edges, as thick lines
+    /// `crease_angle` is the angle in degrees between face normals above which an edge is drawn
+    Edges { thickness: f32, crease_angle: f32 },
+}
+
+impl RenderMode {
+    /// Line thickness or point size, 0 for solid
+    pub fn size(&self) -> f32 {
+        match *self {
+            RenderMode::Solid => 0.0,
+            RenderMode::Wireframe { thickness } => thickness,
+            RenderMode::Points { size } => size,
+            RenderMode::Edges { thickness, .. } => thickness,
+        }
+    }
 }
 
 /// Types of shaders available
@@ -45,6 +244,9 @@ impl Material {
             roughness: 0.5,
             emissive: Color::BLACK,
             shader_type: ShaderType::BasicLit,
+            textures: MaterialTextures::default(),
+            displacement: None,
+            render_mode: RenderMode::Solid,
         }
     }
     
@@ -56,6 +258,23 @@ impl Material {
             roughness: 1.0,
             emissive: Color::BLACK,
             shader_type: ShaderType::Unlit,
+            textures: MaterialTextures::default(),
+            displacement: None,
+            render_mode: RenderMode::Solid,
+        }
+    }
+    
+    /// Create a physically-based material with metallic/roughness parameters
+    pub fn pbr(color: Color, metallic: f32, roughness: f32) -> Self {
+        Self {
+            color,
+            metallic,
+            roughness,
+            emissive: Color::BLACK,
+            shader_type: ShaderType::PBR,
+            textures: MaterialTextures::default(),
+            displacement: None,
+            render_mode: RenderMode::Solid,
         }
     }
     
@@ -72,8 +291,36 @@ impl Material {
                 a: color.a,
             },
             shader_type: ShaderType::Unlit,
+            textures: MaterialTextures::default(),
+            displacement: None,
+            render_mode: RenderMode::Solid,
         }
     }
+    
+    /// Assign a texture map to a slot
+    pub fn with_texture(mut self, slot: TextureSlot, name: impl Into<String>) -> Self {
+        let name = Some(name.into());
+        match slot {
+            TextureSlot::Albedo => self.textures.albedo = name,
+            TextureSlot::Normal => self.textures.normal = name,
+            TextureSlot::MetallicRoughness => self.textures.metallic_roughness = name,
+            TextureSlot::Emissive => self.textures.emissive = name,
+            TextureSlot::Opacity => self.textures.opacity = name,
+        }
+        self
+    }
+    
+    /// Draw as wireframe, points or edges instead of solid triangles
+    pub fn with_render_mode(mut self, render_mode: RenderMode) -> Self {
+        self.render_mode = render_mode;
+        self
+    }
+    
+    /// Enable vertex displacement
+    pub fn with_displacement(mut self, displacement: Displacement) -> Self {
+        self.displacement = Some(displacement);
+        self
+    }
 }
 
 impl Default for Material {
@@ -90,7 +337,17 @@ pub struct MaterialUniform {
     pub emissive: [f32; 4],
     pub metallic: f32,
     pub roughness: f32,
-    pub _padding: [f32; 2],
+    /// Line thickness or point size in pixels (see `RenderMode::size`)
+    pub render_size: f32,
+    pub _padding: f32,
+    /// Amplitude, frequency, speed, band gain
+    pub displacement_noise: [f32; 4],
+    /// Amplitude, frequency, speed, band gain
+    pub displacement_wave: [f32; 4],
+    /// Amplitude, mapping (0 U, 1 V, 2 height), height min, height max
+    pub displacement_spectrum: [f32; 4],
+    /// Noise band, wave band (band index or -1), enabled flag, unused
+    pub displacement_bands: [f32; 4],
 }
 
 impl From<&Material> for MaterialUniform {
@@ -100,7 +357,33 @@ impl From<&Material> for MaterialUniform {
             emissive: [material.emissive.r, material.emissive.g, material.emissive.b, material.emissive.a],
             metallic: material.metallic,
             roughness: material.roughness,
-            _padding: [0.0; 2],
+            render_size: material.render_mode.size(),
+            _padding: 0.0,
+            ..Self::displacement(material.displacement.as_ref())
+        }
+    }
+}
+
+impl MaterialUniform {
+    /// Displacement fields; everything else is zeroed
+    fn displacement(displacement: Option<&Displacement>) -> Self {
+        let Some(d) = displacement else {
+            return bytemuck::Zeroable::zeroed();
+        };
+        
+        let (mapping, min, max) = match d.spectrum_mapping {
+            SpectrumMapping::U => (0.0, 0.0, 1.0),
+            SpectrumMapping::V => (1.0, 0.0, 1.0),
+            SpectrumMapping::Height { min, max } => (2.0, min, max),
+        };
+        let band = |band: Option<AudioBand>| band.map_or(-1.0, |b| b.index() as f32);
+        
+        Self {
+            displacement_noise: [d.noise_amplitude, d.noise_frequency, d.noise_speed, d.noise_band_gain],
+            displacement_wave: [d.wave_amplitude, d.wave_frequency, d.wave_speed, d.wave_band_gain],
+            displacement_spectrum: [d.spectrum_amplitude, mapping, min, max],
+            displacement_bands: [band(d.noise_band), band(d.wave_band), 1.0, 0.0],
+            ..bytemuck::Zeroable::zeroed()
         }
     }
 }
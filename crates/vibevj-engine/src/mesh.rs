@@ -8,6 +8,8 @@ pub struct Vertex {
     pub normal: [f32; 3],
     pub uv: [f32; 2],
     pub color: [f32; 3],
+    /// Tangent in xyz, bitangent sign in w (see `mesh_gen::generate_tangents`)
+    pub tangent: [f32; 4],
 }
 
 impl Vertex {
@@ -17,6 +19,7 @@ impl Vertex {
             normal,
             uv,
             color,
+            tangent: [1.0, 0.0, 0.0, 1.0],
         }
     }
 
@@ -49,9 +52,23 @@ impl Vertex {
                     shader_location: 3,
                     format: wgpu::VertexFormat::Float32x3,
                 },
+                // Tangent
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 11]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
             ],
         }
     }
+
+    /// Layout stepping once per instance, for drawing a shape at every vertex
+    pub fn instance_desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            step_mode: wgpu::VertexStepMode::Instance,
+            ..Self::desc()
+        }
+    }
 }
 
 /// 3D Mesh with vertices and indices
@@ -86,6 +103,65 @@ impl Mesh {
         }
     }
 
+    /// Vertices of every triangle in index order, three per triangle
+    /// Used by the wireframe shader, which derives barycentrics from the vertex index
+    pub fn triangle_vertices(&self) -> Vec<Vertex> {
+        self.indices.iter().map(|&i| self.vertices[i as usize]).collect()
+    }
+
+    /// Edges where the surface creases by more than `crease_angle` degrees,
+    /// plus open boundary edges
+    ///
+    /// Vertices at the same position are treated as one, so hard edges of
+    /// meshes with split normals (like a cube) are found. Each edge is
+    /// returned once as a pair of vertex indices.
+    pub fn feature_edges(&self, crease_angle: f32) -> Vec<[u32; 2]> {
+        use std::collections::HashMap;
+        use glam::Vec3;
+
+        // Weld vertices by quantized position
+        let mut welded: HashMap<[i32; 3], u32> = HashMap::new();
+        let canonical: Vec<u32> = self
+            .vertices
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let key = v.position.map(|c| (c * 1e4).round() as i32);
+                *welded.entry(key).or_insert(i as u32)
+            })
+            .collect();
+
+        // Face normals meeting at each welded edge, with the original indices
+        let mut edges: HashMap<(u32, u32), ([u32; 2], Vec<Vec3>)> = HashMap::new();
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+            let p = |i: u32| Vec3::from(self.vertices[i as usize].position);
+            let normal = (p(b) - p(a)).cross(p(c) - p(a)).normalize_or_zero();
+            if normal == Vec3::ZERO {
+                continue;
+            }
+
+            for (from, to) in [(a, b), (b, c), (c, a)] {
+                let (ca, cb) = (canonical[from as usize], canonical[to as usize]);
+                let key = (ca.min(cb), ca.max(cb));
+                edges.entry(key).or_insert_with(|| ([from, to], Vec::new())).1.push(normal);
+            }
+        }
+
+        let cos_threshold = crease_angle.to_radians().cos();
+        let mut result: Vec<[u32; 2]> = edges
+            .into_values()
+            .filter(|(_, normals)| {
+                normals.len() == 1
+                    || normals.iter().enumerate().any(|(i, n)| normals[i + 1..].iter().any(|m| n.dot(*m) < cos_threshold))
+            })
+            .map(|(edge, _)| edge)
+            .collect();
+        // Stable order so uploads are deterministic
+        result.sort_unstable();
+        result
+    }
+
     /// Upload mesh data to GPU
     pub fn upload(&mut self, device: &wgpu::Device) {
         use wgpu::util::DeviceExt;
@@ -1,11 +1,15 @@
 use super::mesh::{Mesh, Vertex};
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::f32::consts::PI;
+use vibevj_common::{Result, VibeVJError};
 
 /// Generate a cube mesh with specified size
 pub fn create_cube(size: f32) -> Mesh {
     let s = size / 2.0;
     
-    let vertices = vec![
+    let mut vertices = vec![
         // Front face (Z+)
         Vertex::new([-s, -s, s], [0.0, 0.0, 1.0], [0.0, 0.0], [1.0, 0.0, 0.0]),
         Vertex::new([s, -s, s], [0.0, 0.0, 1.0], [1.0, 0.0], [1.0, 0.0, 0.0]),
@@ -52,39 +56,39 @@ pub fn create_cube(size: f32) -> Mesh {
         20, 21, 22, 22, 23, 20, // Bottom
     ];
     
+    generate_tangents(&mut vertices, &indices);
     Mesh::new(vertices, indices)
 }
 
 /// Generate a UV sphere mesh
 pub fn create_sphere(radius: f32, segments: u32, rings: u32) -> Mesh {
     let mut vertices = Vec::new();
-    let mut indices = Vec::new();
-    
+
     // Generate vertices
     for ring in 0..=rings {
         let phi = PI * ring as f32 / rings as f32;
         let sin_phi = phi.sin();
         let cos_phi = phi.cos();
-        
+
         for segment in 0..=segments {
             let theta = 2.0 * PI * segment as f32 / segments as f32;
             let sin_theta = theta.sin();
             let cos_theta = theta.cos();
-            
+
             let x = sin_phi * cos_theta;
             let y = cos_phi;
             let z = sin_phi * sin_theta;
-            
+
             let u = segment as f32 / segments as f32;
             let v = ring as f32 / rings as f32;
-            
+
             // Color based on position
             let color = [
                 (x + 1.0) * 0.5,
                 (y + 1.0) * 0.5,
                 (z + 1.0) * 0.5,
             ];
-            
+
             vertices.push(Vertex::new(
                 [x * radius, y * radius, z * radius],
                 [x, y, z], // Normal is same as normalized position for sphere
@@ -93,90 +97,58 @@ pub fn create_sphere(radius: f32, segments: u32, rings: u32) -> Mesh {
             ));
         }
     }
-    
-    // Generate indices
-    for ring in 0..rings {
-        for segment in 0..segments {
-            let current = ring * (segments + 1) + segment;
-            let next = current + segments + 1;
-            
-            indices.push(current);
-            indices.push(next);
-            indices.push(current + 1);
-            
-            indices.push(current + 1);
-            indices.push(next);
-            indices.push(next + 1);
-        }
-    }
-    
-    Mesh::new(vertices, indices)
+
+    let indices = grid_indices(segments, rings);
+    finish(vertices, indices)
 }
 
-/// Generate a plane mesh
+/// Generate a plane mesh in the XY plane, facing Z+
 pub fn create_plane(width: f32, height: f32, subdivisions_x: u32, subdivisions_y: u32) -> Mesh {
     let mut vertices = Vec::new();
-    let mut indices = Vec::new();
-    
+
     let half_width = width / 2.0;
     let half_height = height / 2.0;
-    
+
     // Generate vertices
     for y in 0..=subdivisions_y {
         let v = y as f32 / subdivisions_y as f32;
         let py = -half_height + height * v;
-        
+
         for x in 0..=subdivisions_x {
             let u = x as f32 / subdivisions_x as f32;
             let px = -half_width + width * u;
-            
+
             vertices.push(Vertex::new(
                 [px, py, 0.0],
                 [0.0, 0.0, 1.0], // Normal pointing up (Z+)
-                [u, v],
+                [u, 1.0 - v],
                 [1.0, 1.0, 1.0], // White color
             ));
         }
     }
-    
-    // Generate indices
-    for y in 0..subdivisions_y {
-        for x in 0..subdivisions_x {
-            let base = y * (subdivisions_x + 1) + x;
-            let next_row = base + subdivisions_x + 1;
-            
-            indices.push(base);
-            indices.push(next_row);
-            indices.push(base + 1);
-            
-            indices.push(base + 1);
-            indices.push(next_row);
-            indices.push(next_row + 1);
-        }
-    }
-    
-    Mesh::new(vertices, indices)
+
+    let indices = grid_indices(subdivisions_x, subdivisions_y);
+    finish(vertices, indices)
 }
 
-/// Generate a cylinder mesh
+/// Generate a capped cylinder mesh along Y
 pub fn create_cylinder(radius: f32, height: f32, segments: u32) -> Mesh {
     let mut vertices = Vec::new();
-    let mut indices = Vec::new();
-    
+
     let half_height = height / 2.0;
-    
-    // Generate side vertices
+
+    // Generate side vertices, top ring first
     for ring in 0..=1 {
-        let y = if ring == 0 { -half_height } else { half_height };
+        let y = if ring == 0 { half_height } else { -half_height };
         let v = ring as f32;
-        
+
         for segment in 0..=segments {
             let theta = 2.0 * PI * segment as f32 / segments as f32;
             let x = theta.cos();
             let z = theta.sin();
-            
+
             let u = segment as f32 / segments as f32;
-            
+
             vertices.push(Vertex::new(
                 [x * radius, y, z * radius],
                 [x, 0.0, z], // Normal points outward
@@ -185,39 +157,864 @@ pub fn create_cylinder(radius: f32, height: f32, segments: u32) -> Mesh {
             ));
         }
     }
-    
-    // Generate side indices
+
+    let mut indices = grid_indices(segments, 1);
+    add_cap(&mut vertices, &mut indices, half_height, radius, segments, true);
+    add_cap(&mut vertices, &mut indices, -half_height, radius, segments, false);
+
+    finish(vertices, indices)
+}
+
+/// Generate a capped cone along Y with its apex at the top
+pub fn create_cone(radius: f32, height: f32, segments: u32) -> Mesh {
+    let mut vertices = Vec::new();
+    let half_height = height / 2.0;
+
+    // Apex row (one vertex per segment so each gets its own normal) and base rim
+    for ring in 0..=1 {
+        for segment in 0..=segments {
+            let theta = 2.0 * PI * segment as f32 / segments as f32;
+            let (sin_theta, cos_theta) = theta.sin_cos();
+            let normal = Vec3::new(cos_theta * height, radius, sin_theta * height).normalize_or_zero();
+            let position = if ring == 0 {
+                [0.0, half_height, 0.0]
+            } else {
+                [cos_theta * radius, -half_height, sin_theta * radius]
+            };
+
+            vertices.push(Vertex::new(
+                position,
+                normal.into(),
+                [segment as f32 / segments as f32, ring as f32],
+                [1.0, 1.0, 1.0],
+            ));
+        }
+    }
+
+    let mut indices = grid_indices(segments, 1);
+    add_cap(&mut vertices, &mut indices, -half_height, radius, segments, false);
+
+    finish(vertices, indices)
+}
+
+/// Generate a capsule along Y: a cylinder of `height` with hemispherical ends
+///
+/// `rings` is the number of latitude steps in each hemisphere.
+pub fn create_capsule(radius: f32, height: f32, segments: u32, rings: u32) -> Mesh {
+    let mut vertices = Vec::new();
+    let half_height = height / 2.0;
+    let rings = rings.max(1);
+
+    // Rows run from the top pole to the bottom pole; the equator is doubled
+    // so the band between the two copies forms the cylinder section
+    let profile_length = PI * radius + height;
+    let mut rows = Vec::new();
+    for ring in 0..=rings {
+        rows.push((PI / 2.0 * ring as f32 / rings as f32, half_height));
+    }
+    for ring in 0..=rings {
+        rows.push((PI / 2.0 + PI / 2.0 * ring as f32 / rings as f32, -half_height));
+    }
+
+    for (row, &(phi, offset)) in rows.iter().enumerate() {
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        // Distance along the profile, for UVs without stretching
+        let arc = if row <= rings as usize {
+            phi * radius
+        } else {
+            phi * radius + height
+        };
+
+        for segment in 0..=segments {
+            let theta = 2.0 * PI * segment as f32 / segments as f32;
+            let (sin_theta, cos_theta) = theta.sin_cos();
+            let normal = [sin_phi * cos_theta, cos_phi, sin_phi * sin_theta];
+
+            vertices.push(Vertex::new(
+                [normal[0] * radius, normal[1] * radius + offset, normal[2] * radius],
+                normal,
+                [segment as f32 / segments as f32, arc / profile_length],
+                [1.0, 1.0, 1.0],
+            ));
+        }
+    }
+
+    let indices = grid_indices(segments, rows.len() as u32 - 1);
+    finish(vertices, indices)
+}
+
+/// Generate a torus around the Y axis
+pub fn create_torus(major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32) -> Mesh {
+    let mut vertices = Vec::new();
+
+    // Rows walk around the tube starting at the outer equator, heading down
+    for ring in 0..=minor_segments {
+        let phi = 2.0 * PI * ring as f32 / minor_segments as f32;
+        let (sin_phi, cos_phi) = phi.sin_cos();
+
+        for segment in 0..=major_segments {
+            let theta = 2.0 * PI * segment as f32 / major_segments as f32;
+            let (sin_theta, cos_theta) = theta.sin_cos();
+            let normal = [cos_phi * cos_theta, -sin_phi, cos_phi * sin_theta];
+            let distance = major_radius + minor_radius * cos_phi;
+
+            vertices.push(Vertex::new(
+                [distance * cos_theta, -minor_radius * sin_phi, distance * sin_theta],
+                normal,
+                [segment as f32 / major_segments as f32, ring as f32 / minor_segments as f32],
+                [1.0, 1.0, 1.0],
+            ));
+        }
+    }
+
+    let indices = grid_indices(major_segments, minor_segments);
+    finish(vertices, indices)
+}
+
+/// Generate a (p, q) torus knot tube
+///
+/// The curve winds `p` times around the Y axis and `q` times through the
+/// hole; `radius` is roughly the outer size, `tube_radius` the tube thickness.
+pub fn create_torus_knot(
+    radius: f32,
+    tube_radius: f32,
+    p: u32,
+    q: u32,
+    tubular_segments: u32,
+    radial_segments: u32,
+) -> Mesh {
+    let p = p.max(1) as f32;
+    let q = q as f32;
+    let curve = |t: f32| {
+        let angle = t * p * 2.0 * PI;
+        let twist = q / p * angle;
+        let r = radius * (2.0 + twist.cos()) / 3.0;
+        Vec3::new(r * angle.cos(), radius * twist.sin() / 3.0, r * angle.sin())
+    };
+
+    let mut vertices = Vec::new();
+    for ring in 0..=radial_segments {
+        let phi = 2.0 * PI * ring as f32 / radial_segments as f32;
+        let (sin_phi, cos_phi) = phi.sin_cos();
+
+        for segment in 0..=tubular_segments {
+            let t = segment as f32 / tubular_segments as f32;
+            let center = curve(t);
+
+            // Frame from the tangent and the direction away from the knot's center
+            let tangent = (curve(t + 1e-3) - curve(t - 1e-3)).normalize_or_zero();
+            let binormal = tangent.cross(center).normalize_or_zero();
+            let frame_normal = binormal.cross(tangent);
+            let normal = frame_normal * cos_phi - binormal * sin_phi;
+
+            vertices.push(Vertex::new(
+                (center + normal * tube_radius).into(),
+                normal.into(),
+                [t, ring as f32 / radial_segments as f32],
+                [1.0, 1.0, 1.0],
+            ));
+        }
+    }
+
+    let indices = grid_indices(tubular_segments, radial_segments);
+    finish(vertices, indices)
+}
+
+/// Generate a sphere by subdividing an icosahedron
+///
+/// Each subdivision level splits every triangle into four, giving evenly
+/// sized triangles without the pole pinching of `create_sphere`.
+pub fn create_icosphere(radius: f32, subdivisions: u32) -> Mesh {
+    let (mut positions, mut faces) = icosahedron();
+
+    for _ in 0..subdivisions {
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut midpoint = |a: usize, b: usize, positions: &mut Vec<Vec3>| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                positions.push((positions[a] + positions[b]).normalize());
+                positions.len() - 1
+            })
+        };
+
+        let mut subdivided = Vec::with_capacity(faces.len() * 4);
+        for [a, b, c] in faces {
+            let ab = midpoint(a, b, &mut positions);
+            let bc = midpoint(b, c, &mut positions);
+            let ca = midpoint(c, a, &mut positions);
+            subdivided.extend_from_slice(&[[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]);
+        }
+        faces = subdivided;
+    }
+
+    let uv = |p: Vec3| Vec2::new(0.5 - p.z.atan2(p.x) / (2.0 * PI), p.y.clamp(-1.0, 1.0).acos() / PI);
+    let mut vertices: Vec<Vertex> = positions
+        .iter()
+        .map(|&p| Vertex::new((p * radius).into(), p.into(), uv(p).into(), [1.0, 1.0, 1.0]))
+        .collect();
+
+    // Triangles crossing the texture seam get their own copies of the vertices
+    // on the far side, and pole vertices take the U of their triangle
+    let mut indices = Vec::with_capacity(faces.len() * 3);
+    let mut seam_copies: HashMap<usize, u32> = HashMap::new();
+    for face in faces {
+        let us = face.map(|i| vertices[i].uv[0]);
+        let wraps = us.iter().copied().fold(f32::MIN, f32::max) - us.iter().copied().fold(f32::MAX, f32::min) > 0.5;
+
+        for (corner, &i) in face.iter().enumerate() {
+            let position = positions[i];
+            let is_pole = position.x.abs() < 1e-6 && position.z.abs() < 1e-6;
+
+            let index = if is_pole {
+                let others: Vec<f32> = (0..3).filter(|&c| c != corner).map(|c| us[c]).collect();
+                let mut u = (others[0] + others[1]) / 2.0;
+                if (others[0] - others[1]).abs() > 0.5 {
+                    u = (u + 0.5).fract();
+                }
+                vertices.push(Vertex::new(
+                    (position * radius).into(),
+                    position.into(),
+                    [u, vertices[i].uv[1]],
+                    [1.0, 1.0, 1.0],
+                ));
+                vertices.len() as u32 - 1
+            } else if wraps && us[corner] < 0.5 {
+                *seam_copies.entry(i).or_insert_with(|| {
+                    let mut copy = vertices[i];
+                    copy.uv[0] += 1.0;
+                    vertices.push(copy);
+                    vertices.len() as u32 - 1
+                })
+            } else {
+                i as u32
+            };
+            indices.push(index);
+        }
+    }
+
+    finish(vertices, indices)
+}
+
+/// The five Platonic solids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlatonicSolid {
+    Tetrahedron,
+    Cube,
+    Octahedron,
+    Dodecahedron,
+    Icosahedron,
+}
+
+impl PlatonicSolid {
+    pub const ALL: [PlatonicSolid; 5] = [
+        PlatonicSolid::Tetrahedron,
+        PlatonicSolid::Cube,
+        PlatonicSolid::Octahedron,
+        PlatonicSolid::Dodecahedron,
+        PlatonicSolid::Icosahedron,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlatonicSolid::Tetrahedron => "Tetrahedron",
+            PlatonicSolid::Cube => "Cube",
+            PlatonicSolid::Octahedron => "Octahedron",
+            PlatonicSolid::Dodecahedron => "Dodecahedron",
+            PlatonicSolid::Icosahedron => "Icosahedron",
+        }
+    }
+}
+
+/// Generate a flat-shaded Platonic solid with all vertices at `radius`
+///
+/// Each face gets its own vertices and a planar UV mapping.
+pub fn create_platonic_solid(solid: PlatonicSolid, radius: f32) -> Mesh {
+    let (positions, faces): (Vec<Vec3>, Vec<Vec<usize>>) = match solid {
+        PlatonicSolid::Tetrahedron => (
+            vec![
+                Vec3::new(1.0, 1.0, 1.0),
+                Vec3::new(1.0, -1.0, -1.0),
+                Vec3::new(-1.0, 1.0, -1.0),
+                Vec3::new(-1.0, -1.0, 1.0),
+            ],
+            vec![vec![0, 1, 2], vec![0, 3, 1], vec![0, 2, 3], vec![1, 3, 2]],
+        ),
+        PlatonicSolid::Cube => (
+            (0..8)
+                .map(|i| Vec3::new(
+                    if i & 1 == 0 { -1.0 } else { 1.0 },
+                    if i & 2 == 0 { -1.0 } else { 1.0 },
+                    if i & 4 == 0 { -1.0 } else { 1.0 },
+                ))
+                .collect(),
+            vec![
+                vec![0, 1, 3, 2],
+                vec![4, 6, 7, 5],
+                vec![0, 4, 5, 1],
+                vec![2, 3, 7, 6],
+                vec![0, 2, 6, 4],
+                vec![1, 5, 7, 3],
+            ],
+        ),
+        PlatonicSolid::Octahedron => (
+            vec![Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z],
+            vec![
+                vec![0, 2, 4],
+                vec![0, 4, 3],
+                vec![0, 3, 5],
+                vec![0, 5, 2],
+                vec![1, 4, 2],
+                vec![1, 3, 4],
+                vec![1, 5, 3],
+                vec![1, 2, 5],
+            ],
+        ),
+        PlatonicSolid::Icosahedron => {
+            let (positions, faces) = icosahedron();
+            (positions, faces.into_iter().map(|f| f.to_vec()).collect())
+        }
+        PlatonicSolid::Dodecahedron => dodecahedron(),
+    };
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+
+    for face in faces {
+        let mut corners: Vec<Vec3> = face.iter().map(|&i| positions[i].normalize() * radius).collect();
+        let center = corners.iter().copied().sum::<Vec3>() / corners.len() as f32;
+        let mut normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]).normalize();
+        // Make sure the face winds counter-clockwise seen from outside
+        if normal.dot(center) < 0.0 {
+            corners.reverse();
+            normal = -normal;
+        }
+
+        // Planar UVs in the face's own basis, scaled to fit 0..1
+        let axis_u = (corners[0] - center).normalize();
+        let axis_v = normal.cross(axis_u);
+        let extent = corners.iter().map(|c| (*c - center).length()).fold(0.0, f32::max);
+
+        let base = vertices.len() as u32;
+        for corner in &corners {
+            let offset = *corner - center;
+            let uv = [
+                0.5 + offset.dot(axis_u) / (2.0 * extent),
+                0.5 - offset.dot(axis_v) / (2.0 * extent),
+            ];
+            vertices.push(Vertex::new((*corner).into(), normal.into(), uv, [1.0, 1.0, 1.0]));
+        }
+        for i in 1..corners.len() as u32 - 1 {
+            indices.extend_from_slice(&[base, base + i, base + i + 1]);
+        }
+    }
+
+    finish(vertices, indices)
+}
+
+/// Parameters of Gielis' superformula
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Superformula {
+    /// Rotational symmetry
+    pub m: f32,
+    pub n1: f32,
+    pub n2: f32,
+    pub n3: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl Default for Superformula {
+    /// Parameters producing a circle
+    fn default() -> Self {
+        Self {
+            m: 0.0,
+            n1: 1.0,
+            n2: 1.0,
+            n3: 1.0,
+            a: 1.0,
+            b: 1.0,
+        }
+    }
+}
+
+impl Superformula {
+    /// Radius at angle `angle`
+    pub fn radius(&self, angle: f32) -> f32 {
+        let t = self.m * angle / 4.0;
+        let term = (t.cos() / self.a).abs().powf(self.n2) + (t.sin() / self.b).abs().powf(self.n3);
+        if term <= f32::EPSILON {
+            return 0.0;
+        }
+        term.powf(-1.0 / self.n1)
+    }
+}
+
+/// Generate a supershape: the spherical product of two superformulas
+///
+/// `longitude` shapes the horizontal cross section and `latitude` the
+/// vertical profile. The result is scaled so its furthest point is at `radius`.
+pub fn create_supershape(
+    radius: f32,
+    longitude: Superformula,
+    latitude: Superformula,
+    segments: u32,
+    rings: u32,
+) -> Mesh {
+    let surface = |u: f32, v: f32| {
+        let theta = PI - u * 2.0 * PI;
+        let phi = PI / 2.0 - v * PI;
+        let r1 = longitude.radius(theta);
+        let r2 = latitude.radius(phi);
+        Vec3::new(r1 * theta.cos() * r2 * phi.cos(), r2 * phi.sin(), r1 * theta.sin() * r2 * phi.cos())
+    };
+
+    let mut mesh = create_parametric(segments, rings, surface);
+    let extent = mesh
+        .vertices
+        .iter()
+        .map(|v| Vec3::from(v.position).length())
+        .fold(0.0, f32::max);
+    if extent > 0.0 {
+        let scale = radius / extent;
+        for vertex in &mut mesh.vertices {
+            vertex.position = (Vec3::from(vertex.position) * scale).into();
+        }
+    }
+    mesh
+}
+
+/// Generate a surface from a function of `(u, v)` in `0..=1`
+///
+/// The grid has `u_segments` x `v_segments` quads with UVs equal to the
+/// parameters. Normals point along `dP/du x dP/dv` (estimated numerically);
+/// swap the parameters to flip the surface.
+pub fn create_parametric(u_segments: u32, v_segments: u32, surface: impl Fn(f32, f32) -> Vec3) -> Mesh {
+    const EPSILON: f32 = 1e-4;
+    let mut vertices = Vec::new();
+
+    for row in 0..=v_segments {
+        let v = row as f32 / v_segments as f32;
+        for column in 0..=u_segments {
+            let u = column as f32 / u_segments as f32;
+
+            let normal_at = |u: f32, v: f32| {
+                let du = surface((u + EPSILON).min(1.0), v) - surface((u - EPSILON).max(0.0), v);
+                let dv = surface(u, (v + EPSILON).min(1.0)) - surface(u, (v - EPSILON).max(0.0));
+                du.cross(dv).normalize_or_zero()
+            };
+            // Poles and other singular points: take the normal from just inside
+            let mut normal = normal_at(u, v);
+            if normal == Vec3::ZERO {
+                let inward = if v < 0.5 { 0.01 } else { -0.01 };
+                normal = normal_at(u, v + inward);
+            }
+
+            vertices.push(Vertex::new(surface(u, v).into(), normal.into(), [u, v], [1.0, 1.0, 1.0]));
+        }
+    }
+
+    let indices = grid_indices(u_segments, v_segments);
+    finish(vertices, indices)
+}
+
+/// Grid of height samples in `0..=1`
+#[derive(Debug, Clone)]
+pub struct Heightmap {
+    pub width: u32,
+    pub depth: u32,
+    /// Row-major heights, `width` samples per row
+    pub heights: Vec<f32>,
+}
+
+impl Heightmap {
+    /// Heights from an image's luminance
+    pub fn from_image(image: &image::DynamicImage) -> Self {
+        let luma = image.to_luma16();
+        Self {
+            width: luma.width(),
+            depth: luma.height(),
+            heights: luma.pixels().map(|p| p.0[0] as f32 / 65535.0).collect(),
+        }
+    }
+
+    /// Load a heightmap image from disk
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let image = image::open(path.as_ref()).map_err(|e| {
+            VibeVJError::ResourceNotFound(format!("Failed to load heightmap {}: {}", path.as_ref().display(), e))
+        })?;
+        Ok(Self::from_image(&image))
+    }
+
+    /// Fractal value noise, deterministic for a given seed
+    ///
+    /// `frequency` is the number of noise cells across the map for the first
+    /// octave; each further octave doubles it at half the amplitude.
+    pub fn from_noise(width: u32, depth: u32, seed: u32, frequency: f32, octaves: u32) -> Self {
+        let mut heights = Vec::with_capacity((width * depth) as usize);
+        let max_amplitude: f32 = (0..octaves.max(1)).map(|o| 0.5f32.powi(o as i32)).sum();
+
+        for z in 0..depth {
+            for x in 0..width {
+                let mut value = 0.0;
+                for octave in 0..octaves.max(1) {
+                    let scale = frequency * 2f32.powi(octave as i32);
+                    let px = x as f32 / width.max(1) as f32 * scale;
+                    let pz = z as f32 / depth.max(1) as f32 * scale;
+                    value += value_noise(px, pz, seed.wrapping_add(octave)) * 0.5f32.powi(octave as i32);
+                }
+                heights.push(value / max_amplitude);
+            }
+        }
+
+        Self { width, depth, heights }
+    }
+
+    /// Height at integer sample coordinates, clamped to the edges
+    pub fn get(&self, x: i64, z: i64) -> f32 {
+        let x = x.clamp(0, self.width as i64 - 1) as usize;
+        let z = z.clamp(0, self.depth as i64 - 1) as usize;
+        self.heights[z * self.width as usize + x]
+    }
+}
+
+/// Generate terrain from a heightmap, centered on the origin
+///
+/// One vertex is created per sample, spread over `size_x` by `size_z` with
+/// heights scaled to `0..=height`.
+pub fn create_terrain(heightmap: &Heightmap, size_x: f32, size_z: f32, height: f32) -> Mesh {
+    let columns = heightmap.width.max(2) - 1;
+    let rows = heightmap.depth.max(2) - 1;
+    let step_x = size_x / columns as f32;
+    let step_z = size_z / rows as f32;
+
+    let mut vertices = Vec::new();
+    for row in 0..=rows {
+        for column in 0..=columns {
+            let (x, z) = (column as i64, row as i64);
+            let y = heightmap.get(x, z) * height;
+
+            // Central differences across neighbouring samples
+            let dx = (heightmap.get(x + 1, z) - heightmap.get(x - 1, z)) * height / (2.0 * step_x);
+            let dz = (heightmap.get(x, z + 1) - heightmap.get(x, z - 1)) * height / (2.0 * step_z);
+            let normal = Vec3::new(-dx, 1.0, -dz).normalize();
+
+            let u = column as f32 / columns as f32;
+            let v = row as f32 / rows as f32;
+            vertices.push(Vertex::new(
+                [(u - 0.5) * size_x, y, (v - 0.5) * size_z],
+                normal.into(),
+                [u, v],
+                [1.0, 1.0, 1.0],
+            ));
+        }
+    }
+
+    // Rows advance along +Z, so the grid is wound to face +Y
+    let indices = grid_indices(rows, columns)
+        .chunks_exact(3)
+        .flat_map(|t| [transpose(t[0], rows, columns), transpose(t[1], rows, columns), transpose(t[2], rows, columns)])
+        .collect();
+    finish(vertices, indices)
+}
+
+/// Map an index of a `rows x columns` grid onto the transposed grid
+fn transpose(index: u32, rows: u32, columns: u32) -> u32 {
+    let (column, row) = (index % (rows + 1), index / (rows + 1));
+    column * (columns + 1) + row
+}
+
+/// Indices for a `(columns + 1) x (rows + 1)` vertex grid stored row by row
+///
+/// Triangles face along `d/dcolumn x d/drow`.
+fn grid_indices(columns: u32, rows: u32) -> Vec<u32> {
+    let mut indices = Vec::with_capacity((columns * rows * 6) as usize);
+    for row in 0..rows {
+        for column in 0..columns {
+            let current = row * (columns + 1) + column;
+            let below = current + columns + 1;
+            indices.extend_from_slice(&[current, current + 1, below, current + 1, below + 1, below]);
+        }
+    }
+    indices
+}
+
+/// Append a flat disc cap at height `y` facing up or down
+fn add_cap(vertices: &mut Vec<Vertex>, indices: &mut Vec<u32>, y: f32, radius: f32, segments: u32, up: bool) {
+    let normal = if up { [0.0, 1.0, 0.0] } else { [0.0, -1.0, 0.0] };
+    let center = vertices.len() as u32;
+    vertices.push(Vertex::new([0.0, y, 0.0], normal, [0.5, 0.5], [1.0, 1.0, 1.0]));
+
+    for segment in 0..=segments {
+        let theta = 2.0 * PI * segment as f32 / segments as f32;
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let v = if up { 0.5 + 0.5 * sin_theta } else { 0.5 - 0.5 * sin_theta };
+        vertices.push(Vertex::new(
+            [cos_theta * radius, y, sin_theta * radius],
+            normal,
+            [0.5 + 0.5 * cos_theta, v],
+            [1.0, 1.0, 1.0],
+        ));
+    }
+
     for segment in 0..segments {
-        let base = segment;
-        let next = segment + 1;
-        let top_base = base + segments + 1;
-        let top_next = next + segments + 1;
-        
-        indices.push(base);
-        indices.push(top_base);
-        indices.push(next);
+        let rim = center + 1 + segment;
+        if up {
+            indices.extend_from_slice(&[center, rim + 1, rim]);
+        } else {
+            indices.extend_from_slice(&[center, rim, rim + 1]);
+        }
+    }
+}
+
+/// Unit icosahedron vertices and outward-facing triangles
+fn icosahedron() -> (Vec<Vec3>, Vec<[usize; 3]>) {
+    let t = (1.0 + 5f32.sqrt()) / 2.0;
+    let positions = [
+        [-1.0, t, 0.0], [1.0, t, 0.0], [-1.0, -t, 0.0], [1.0, -t, 0.0],
+        [0.0, -1.0, t], [0.0, 1.0, t], [0.0, -1.0, -t], [0.0, 1.0, -t],
+        [t, 0.0, -1.0], [t, 0.0, 1.0], [-t, 0.0, -1.0], [-t, 0.0, 1.0],
+    ]
+    .iter()
+    .map(|&p| Vec3::from(p).normalize())
+    .collect();
+
+    let faces = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+
+    (positions, faces)
+}
+
+/// Dodecahedron as the dual of the icosahedron
+/// Vertices are icosahedron face centers; each icosahedron vertex becomes a pentagon
+fn dodecahedron() -> (Vec<Vec3>, Vec<Vec<usize>>) {
+    let (ico_positions, ico_faces) = icosahedron();
+    let positions: Vec<Vec3> = ico_faces
+        .iter()
+        .map(|f| ((ico_positions[f[0]] + ico_positions[f[1]] + ico_positions[f[2]]) / 3.0).normalize())
+        .collect();
+
+    let faces = ico_positions
+        .iter()
+        .enumerate()
+        .map(|(vertex, &axis)| {
+            let mut ring: Vec<usize> = (0..ico_faces.len()).filter(|&f| ico_faces[f].contains(&vertex)).collect();
+            // Order the surrounding face centers counter-clockwise around the axis
+            let reference = (positions[ring[0]] - axis * positions[ring[0]].dot(axis)).normalize();
+            let side = axis.cross(reference);
+            ring.sort_by(|&a, &b| {
+                let angle = |i: usize| positions[i].dot(side).atan2(positions[i].dot(reference));
+                angle(a).total_cmp(&angle(b))
+            });
+            ring
+        })
+        .collect();
+
+    (positions, faces)
+}
+
+/// Smooth 2D value noise in `0..=1`
+fn value_noise(x: f32, z: f32, seed: u32) -> f32 {
+    fn hash(x: i32, z: i32, seed: u32) -> f32 {
+        let mut h = (x as u32).wrapping_mul(0x27d4_eb2d) ^ (z as u32).wrapping_mul(0x1656_67b1) ^ seed.wrapping_mul(0x9e37_79b9);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x85eb_ca6b);
+        h ^= h >> 13;
+        h = h.wrapping_mul(0xc2b2_ae35);
+        h ^= h >> 16;
+        h as f32 / u32::MAX as f32
+    }
+
+    let (x0, z0) = (x.floor(), z.floor());
+    let (fx, fz) = (x - x0, z - z0);
+    // Smoothstep fade between lattice values
+    let (sx, sz) = (fx * fx * (3.0 - 2.0 * fx), fz * fz * (3.0 - 2.0 * fz));
+    let (ix, iz) = (x0 as i32, z0 as i32);
+
+    let top = hash(ix, iz, seed) + (hash(ix + 1, iz, seed) - hash(ix, iz, seed)) * sx;
+    let bottom = hash(ix, iz + 1, seed) + (hash(ix + 1, iz + 1, seed) - hash(ix, iz + 1, seed)) * sx;
+    top + (bottom - top) * sz
+}
+
+/// Generate tangents and build the mesh
+fn finish(mut vertices: Vec<Vertex>, indices: Vec<u32>) -> Mesh {
+    generate_tangents(&mut vertices, &indices);
+    Mesh::new(vertices, indices)
+}
+
+/// Generate smooth per-vertex normals from positions
+///
+/// Face normals are accumulated unnormalized, so larger triangles weigh more.
+/// Vertices must be shared between triangles for the result to be smooth.
+pub fn generate_normals(vertices: &mut [Vertex], indices: &[u32]) {
+    let mut normals = vec![Vec3::ZERO; vertices.len()];
+    
+    for triangle in indices.chunks_exact(3) {
+        let [i0, i1, i2] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+        let p0 = Vec3::from(vertices[i0].position);
+        let p1 = Vec3::from(vertices[i1].position);
+        let p2 = Vec3::from(vertices[i2].position);
+        let face_normal = (p1 - p0).cross(p2 - p0);
         
-        indices.push(next);
-        indices.push(top_base);
-        indices.push(top_next);
+        for &i in &[i0, i1, i2] {
+            normals[i] += face_normal;
+        }
     }
     
-    // Add caps (simplified)
-    let cap_center_bottom = vertices.len() as u32;
-    vertices.push(Vertex::new(
-        [0.0, -half_height, 0.0],
-        [0.0, -1.0, 0.0],
-        [0.5, 0.5],
-        [1.0, 1.0, 1.0],
-    ));
+    for (vertex, normal) in vertices.iter_mut().zip(normals) {
+        vertex.normal = normal.try_normalize().unwrap_or(Vec3::Y).into();
+    }
+}
+
+/// Generate per-vertex tangents from positions, normals and UVs
+///
+/// Tangents are accumulated per triangle, orthogonalized against the vertex
+/// normal (Gram-Schmidt) and store the bitangent handedness in `w`.
+pub fn generate_tangents(vertices: &mut [Vertex], indices: &[u32]) {
+    let mut tangents = vec![Vec3::ZERO; vertices.len()];
+    let mut bitangents = vec![Vec3::ZERO; vertices.len()];
     
-    let cap_center_top = vertices.len() as u32;
-    vertices.push(Vertex::new(
-        [0.0, half_height, 0.0],
-        [0.0, 1.0, 0.0],
-        [0.5, 0.5],
-        [1.0, 1.0, 1.0],
-    ));
+    for triangle in indices.chunks_exact(3) {
+        let [i0, i1, i2] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+        
+        let p0 = Vec3::from(vertices[i0].position);
+        let p1 = Vec3::from(vertices[i1].position);
+        let p2 = Vec3::from(vertices[i2].position);
+        let uv0 = Vec2::from(vertices[i0].uv);
+        let uv1 = Vec2::from(vertices[i1].uv);
+        let uv2 = Vec2::from(vertices[i2].uv);
+        
+        let edge1 = p1 - p0;
+        let edge2 = p2 - p0;
+        let duv1 = uv1 - uv0;
+        let duv2 = uv2 - uv0;
+        
+        let det = duv1.x * duv2.y - duv2.x * duv1.y;
+        if det.abs() < f32::EPSILON {
+            // Degenerate UV mapping, leave this triangle out
+            continue;
+        }
+        let r = 1.0 / det;
+        let tangent = (edge1 * duv2.y - edge2 * duv1.y) * r;
+        let bitangent = (edge2 * duv1.x - edge1 * duv2.x) * r;
+        
+        for &i in &[i0, i1, i2] {
+            tangents[i] += tangent;
+            bitangents[i] += bitangent;
+        }
+    }
     
-    Mesh::new(vertices, indices)
+    for (i, vertex) in vertices.iter_mut().enumerate() {
+        let n = Vec3::from(vertex.normal);
+        let t = tangents[i];
+        
+        // Gram-Schmidt orthogonalize
+        let mut ortho = (t - n * n.dot(t)).normalize_or_zero();
+        if ortho == Vec3::ZERO {
+            // No usable UV gradient, pick any vector perpendicular to the normal
+            ortho = n.any_orthonormal_vector();
+        }
+        
+        let handedness = if n.cross(ortho).dot(bitangents[i]) < 0.0 { -1.0 } else { 1.0 };
+        vertex.tangent = [ortho.x, ortho.y, ortho.z, handedness];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assert every non-degenerate triangle winds counter-clockwise seen from
+    /// the side its vertex normals point to, so back-face culling keeps it
+    fn assert_faces_outward(name: &str, mesh: &Mesh) {
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| &mesh.vertices[triangle[i] as usize]);
+            let [pa, pb, pc] = [a, b, c].map(|v| Vec3::from(v.position));
+            let face = (pb - pa).cross(pc - pa);
+            if face.length() < 1e-6 {
+                continue;
+            }
+            let normal = Vec3::from(a.normal) + Vec3::from(b.normal) + Vec3::from(c.normal);
+            assert!(face.dot(normal) > 0.0, "{} has an inward triangle {:?}", name, triangle);
+        }
+    }
+
+    fn all_generators() -> Vec<(String, Mesh)> {
+        let mut meshes = vec![
+            ("cube".to_string(), create_cube(1.0)),
+            ("sphere".to_string(), create_sphere(1.0, 8, 6)),
+            ("plane".to_string(), create_plane(2.0, 1.0, 3, 2)),
+            ("cylinder".to_string(), create_cylinder(0.5, 2.0, 8)),
+            ("cone".to_string(), create_cone(0.5, 1.0, 8)),
+            ("capsule".to_string(), create_capsule(0.5, 1.0, 8, 4)),
+            ("torus".to_string(), create_torus(1.0, 0.3, 12, 8)),
+            ("torus knot".to_string(), create_torus_knot(1.0, 0.2, 2, 3, 32, 6)),
+            ("icosphere".to_string(), create_icosphere(1.0, 2)),
+            (
+                "supershape".to_string(),
+                create_supershape(1.0, Superformula { m: 5.0, ..Default::default() }, Superformula::default(), 16, 8),
+            ),
+            ("parametric".to_string(), create_parametric(6, 4, |u, v| Vec3::new(u, (u * v * PI).sin(), v))),
+            ("terrain".to_string(), create_terrain(&Heightmap::from_noise(9, 7, 1, 2.0, 3), 4.0, 3.0, 1.0)),
+        ];
+        meshes.extend(PlatonicSolid::ALL.map(|solid| (solid.name().to_string(), create_platonic_solid(solid, 1.0))));
+        meshes
+    }
+
+    #[test]
+    fn generators_emit_valid_vertices_and_indices() {
+        for (name, mesh) in all_generators() {
+            assert!(!mesh.indices.is_empty() && mesh.indices.len() % 3 == 0, "{} has a partial triangle", name);
+            assert!(
+                mesh.indices.iter().all(|&i| (i as usize) < mesh.vertices.len()),
+                "{} indexes past its vertices",
+                name
+            );
+            // Icosphere seam copies continue past U = 1, relying on the repeating material sampler
+            let max_uv = if name == "icosphere" { Vec2::new(1.5, 1.0) } else { Vec2::ONE };
+            for vertex in &mesh.vertices {
+                let normal = Vec3::from(vertex.normal);
+                assert!((normal.length() - 1.0).abs() < 1e-4, "{} has normal {:?}", name, normal);
+                let uv = Vec2::from(vertex.uv);
+                assert!(uv.cmpge(Vec2::ZERO).all() && uv.cmple(max_uv).all(), "{} has UV {:?}", name, uv);
+                let tangent = Vec3::from_slice(&vertex.tangent[..3]);
+                assert!(tangent.is_finite() && vertex.tangent[3].abs() == 1.0, "{} has tangent {:?}", name, vertex.tangent);
+            }
+        }
+    }
+
+    #[test]
+    fn generators_face_outward() {
+        for (name, mesh) in all_generators() {
+            assert_faces_outward(&name, &mesh);
+        }
+    }
+
+    #[test]
+    fn textures_are_upright() {
+        // Texture row 0 is the top of the image, so V grows downwards
+        let top_v = |mesh: &Mesh| {
+            let top = mesh.vertices.iter().map(|v| v.position[1]).fold(f32::MIN, f32::max);
+            mesh.vertices.iter().filter(|v| v.position[1] == top).map(|v| v.uv[1]).fold(f32::MIN, f32::max)
+        };
+        assert_eq!(top_v(&create_plane(2.0, 1.0, 3, 2)), 0.0);
+        assert_eq!(top_v(&create_sphere(1.0, 8, 6)), 0.0);
+
+        let cylinder = create_cylinder(0.5, 2.0, 8);
+        let side_top = cylinder.vertices.iter().find(|v| v.position[1] == 1.0 && v.normal[1] == 0.0).unwrap();
+        assert_eq!(side_top.uv[1], 0.0);
+    }
+
+    #[test]
+    fn cylinder_caps_are_closed() {
+        let cylinder = create_cylinder(0.5, 2.0, 8);
+        let cap_triangles = cylinder
+            .indices
+            .chunks_exact(3)
+            .filter(|triangle| triangle.iter().all(|&i| cylinder.vertices[i as usize].normal[1] != 0.0))
+            .count();
+        assert_eq!(cap_triangles, 16);
+    }
 }
@@ -0,0 +1,331 @@
+use serde::{Deserialize, Serialize};
+use crate::compositor::LayerSource;
+use crate::warp::WarpSettings;
+
+/// Output resolution, from a preset or a custom size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Resolution {
+    #[default]
+    Hd720,
+    Hd1080,
+    Qhd1440,
+    Uhd4k,
+    /// 1080 x 1080, for LED panels and social media
+    Square1080,
+    /// 1080 x 1920 portrait screens
+    Portrait1080,
+    Custom { width: u32, height: u32 },
+}
+
+impl Resolution {
+    /// Presets shown in menus (without `Custom`)
+    pub const PRESETS: [Resolution; 6] = [
+        Resolution::Hd720,
+        Resolution::Hd1080,
+        Resolution::Qhd1440,
+        Resolution::Uhd4k,
+        Resolution::Square1080,
+        Resolution::Portrait1080,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Resolution::Hd720 => "720p (1280 x 720)",
+            Resolution::Hd1080 => "1080p (1920 x 1080)",
+            Resolution::Qhd1440 => "1440p (2560 x 1440)",
+            Resolution::Uhd4k => "4K UHD (3840 x 2160)",
+            Resolution::Square1080 => "Square (1080 x 1080)",
+            Resolution::Portrait1080 => "Portrait (1080 x 1920)",
+            Resolution::Custom { .. } => "Custom",
+        }
+    }
+
+    /// Width and height in pixels, at least 1 x 1
+    pub fn size(&self) -> (u32, u32) {
+        let (width, height) = match *self {
+            Resolution::Hd720 => (1280, 720),
+            Resolution::Hd1080 => (1920, 1080),
+            Resolution::Qhd1440 => (2560, 1440),
+            Resolution::Uhd4k => (3840, 2160),
+            Resolution::Square1080 => (1080, 1080),
+            Resolution::Portrait1080 => (1080, 1920),
+            Resolution::Custom { width, height } => (width, height),
+        };
+        (width.max(1), height.max(1))
+    }
+}
+
+/// How an output is shown in an area of a different aspect ratio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AspectMode {
+    /// Fit inside the area with bars on two sides
+    #[default]
+    Letterbox,
+    /// Fill the area, cutting off the overhanging edges
+    Crop,
+    /// Fill the area, distorting the image
+    Stretch,
+}
+
+impl AspectMode {
+    pub const ALL: [AspectMode; 3] = [AspectMode::Letterbox, AspectMode::Crop, AspectMode::Stretch];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AspectMode::Letterbox => "Letterbox",
+            AspectMode::Crop => "Crop",
+            AspectMode::Stretch => "Stretch",
+        }
+    }
+
+    /// Place `content` (width, height) in an `area` of another size
+    pub fn fit(&self, content: (f32, f32), area: (f32, f32)) -> AspectFit {
+        let content_aspect = content.0.max(1.0) / content.1.max(1.0);
+        let area_aspect = area.0.max(1.0) / area.1.max(1.0);
+        let full = AspectFit {
+            offset: [0.0, 0.0],
+            size: [area.0, area.1],
+            uv_min: [0.0, 0.0],
+            uv_max: [1.0, 1.0],
+        };
+
+        match self {
+            AspectMode::Stretch => full,
+            AspectMode::Letterbox => {
+                let size = if content_aspect > area_aspect {
+                    [area.0, area.0 / content_aspect]
+                } else {
+                    [area.1 * content_aspect, area.1]
+                };
+                AspectFit {
+                    offset: [(area.0 - size[0]) * 0.5, (area.1 - size[1]) * 0.5],
+                    size,
+                    ..full
+                }
+            }
+            AspectMode::Crop => {
+                // Visible fraction of the content along the overhanging axis
+                let (u, v) = if content_aspect > area_aspect {
+                    (area_aspect / content_aspect, 1.0)
+                } else {
+                    (1.0, content_aspect / area_aspect)
+                };
+                AspectFit {
+                    uv_min: [(1.0 - u) * 0.5, (1.0 - v) * 0.5],
+                    uv_max: [(1.0 + u) * 0.5, (1.0 + v) * 0.5],
+                    ..full
+                }
+            }
+        }
+    }
+}
+
+/// Where content lands in an area: a rectangle in the area and the
+/// part of the content shown in it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AspectFit {
+    /// Top-left corner relative to the area
+    pub offset: [f32; 2],
+    pub size: [f32; 2],
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+}
+
+/// Resolution the show is rendered and output at
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputSettings {
+    pub resolution: Resolution,
+    /// Internal render size relative to the output (0.5 renders at half size
+    /// and upscales, 2.0 supersamples)
+    pub render_scale: f32,
+    /// How the output fits previews and windows of another aspect ratio
+    pub aspect_mode: AspectMode,
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        Self {
+            resolution: Resolution::default(),
+            render_scale: 1.0,
+            aspect_mode: AspectMode::Letterbox,
+        }
+    }
+}
+
+impl OutputSettings {
+    /// Render scales offered in menus
+    pub const RENDER_SCALES: [f32; 5] = [0.25, 0.5, 0.75, 1.0, 2.0];
+
+    /// Output size in pixels
+    pub fn output_size(&self) -> (u32, u32) {
+        self.resolution.size()
+    }
+
+    /// Size of the internal render targets, the output size times `render_scale`
+    pub fn render_size(&self) -> (u32, u32) {
+        let (width, height) = self.output_size();
+        let scale = self.render_scale.clamp(0.1, 4.0);
+        (
+            ((width as f32 * scale).round() as u32).max(1),
+            ((height as f32 * scale).round() as u32).max(1),
+        )
+    }
+
+    /// `render_size` shrunk to keep its aspect with neither side over
+    /// `max_dimension`, e.g. the device's `max_texture_dimension_2d`
+    pub fn render_size_within(&self, max_dimension: u32) -> (u32, u32) {
+        let (width, height) = self.render_size();
+        let largest = width.max(height);
+        if largest <= max_dimension {
+            return (width, height);
+        }
+        let fit = |side: u32| ((side as u64 * max_dimension as u64 / largest as u64) as u32).max(1);
+        (fit(width), fit(height))
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        let (width, height) = self.output_size();
+        width as f32 / height as f32
+    }
+}
+
+/// What an output window shows
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum OutputSource {
+    /// Final mix of both decks
+    #[default]
+    Mix,
+    /// A single layer source, before mixing
+    Layer(LayerSource),
+}
+
+impl OutputSource {
+    pub fn name(&self) -> &str {
+        match self {
+            OutputSource::Mix => "Mix",
+            OutputSource::Layer(source) => source.name(),
+        }
+    }
+}
+
+/// Part of the output canvas shown by a window, in 0..1 canvas coordinates
+///
+/// Spanning a show across projectors gives each one a slice of the canvas.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CropRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for CropRect {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl CropRect {
+    /// The whole canvas
+    pub const FULL: CropRect = CropRect {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    };
+
+    /// Smallest width or height of a crop
+    pub const MIN_SIZE: f32 = 0.01;
+
+    /// Crop kept inside the canvas
+    pub fn clamped(&self) -> Self {
+        let x = self.x.clamp(0.0, 1.0 - Self::MIN_SIZE);
+        let y = self.y.clamp(0.0, 1.0 - Self::MIN_SIZE);
+        Self {
+            x,
+            y,
+            width: self.width.clamp(Self::MIN_SIZE, 1.0 - x),
+            height: self.height.clamp(Self::MIN_SIZE, 1.0 - y),
+        }
+    }
+
+    /// Size in pixels of the cropped part of a canvas
+    pub fn size_in(&self, canvas: (f32, f32)) -> (f32, f32) {
+        let crop = self.clamped();
+        (canvas.0 * crop.width, canvas.1 * crop.height)
+    }
+
+    /// Turn a fit of the cropped part into one of the whole canvas
+    pub fn apply(&self, fit: AspectFit) -> AspectFit {
+        let crop = self.clamped();
+        let map = |uv: [f32; 2]| [crop.x + uv[0] * crop.width, crop.y + uv[1] * crop.height];
+        AspectFit {
+            uv_min: map(fit.uv_min),
+            uv_max: map(fit.uv_max),
+            ..fit
+        }
+    }
+}
+
+/// One output window: where it opens and what it shows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputWindowSettings {
+    pub name: String,
+    /// Closed outputs keep their settings
+    pub enabled: bool,
+    /// Name of the monitor to open on, `None` for a floating window
+    pub monitor: Option<String>,
+    /// Borderless fullscreen on the monitor
+    pub fullscreen: bool,
+    pub source: OutputSource,
+    pub crop: CropRect,
+    /// Projection mapping of the image onto the window
+    pub warp: WarpSettings,
+}
+
+impl Default for OutputWindowSettings {
+    fn default() -> Self {
+        Self::new("Output")
+    }
+}
+
+impl OutputWindowSettings {
+    /// Floating window showing the whole mix
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            enabled: true,
+            monitor: None,
+            fullscreen: false,
+            source: OutputSource::Mix,
+            crop: CropRect::FULL,
+            warp: WarpSettings::default(),
+        }
+    }
+
+    /// Where `content` (the source size in pixels) lands in a window of `window` size
+    pub fn fit(&self, aspect_mode: AspectMode, content: (f32, f32), window: (f32, f32)) -> AspectFit {
+        self.crop.apply(aspect_mode.fit(self.crop.size_in(content), window))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_size_fits_the_texture_limit() {
+        let custom = |width, height, render_scale| OutputSettings {
+            resolution: Resolution::Custom { width, height },
+            render_scale,
+            ..Default::default()
+        };
+        assert_eq!(custom(1920, 1080, 1.0).render_size_within(8192), (1920, 1080));
+        assert_eq!(custom(7680, 4320, 2.0).render_size(), (15360, 8640));
+        assert_eq!(custom(7680, 4320, 2.0).render_size_within(8192), (8192, 4608));
+        assert_eq!(custom(100_000, 2, 1.0).render_size_within(4096), (4096, 1));
+    }
+}
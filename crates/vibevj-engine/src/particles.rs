@@ -0,0 +1,696 @@
+use bytemuck::Zeroable;
+use glam::{Mat4, Vec3};
+use serde::{Deserialize, Serialize};
+use vibevj_common::{AudioBand, AudioFeatures, Color};
+use wgpu::util::DeviceExt;
+use crate::camera::Camera;
+use crate::compute::{ComputePipeline, ComputePipelineBuilder};
+use crate::mesh::Mesh;
+use crate::pipeline::BindGroupLayoutBuilder;
+
+/// Maximum number of attractors per particle system
+pub const MAX_ATTRACTORS: usize = 4;
+
+/// Maximum number of keys in color and size curves
+pub const MAX_CURVE_KEYS: usize = 4;
+
+/// Where new particles are spawned
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EmitterShape {
+    /// All particles start at the emitter origin
+    Point,
+    /// Uniformly inside a sphere
+    Sphere { radius: f32 },
+    /// On the surface of a mesh, weighted by triangle area
+    /// The caller resolves the name and passes the mesh to `ParticleSystem::new`
+    MeshSurface { mesh: String },
+}
+
+/// Point that pulls (positive strength) or pushes (negative) particles
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Attractor {
+    pub position: [f32; 3],
+    pub strength: f32,
+}
+
+/// Forces applied to every particle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticleForces {
+    pub gravity: [f32; 3],
+    /// Linear velocity damping per second
+    pub drag: f32,
+    pub curl_strength: f32,
+    pub curl_frequency: f32,
+    /// Speed at which the curl noise field scrolls over time
+    pub curl_speed: f32,
+    pub attractors: Vec<Attractor>,
+}
+
+impl Default for ParticleForces {
+    fn default() -> Self {
+        Self {
+            gravity: [0.0, -0.5, 0.0],
+            drag: 0.1,
+            curl_strength: 0.0,
+            curl_frequency: 1.0,
+            curl_speed: 0.2,
+            attractors: Vec::new(),
+        }
+    }
+}
+
+/// Color at a normalized age (0 = birth, 1 = death)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ColorKey {
+    pub t: f32,
+    pub color: Color,
+}
+
+/// Billboard size at a normalized age (0 = birth, 1 = death)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SizeKey {
+    pub t: f32,
+    pub size: f32,
+}
+
+/// What triggers a burst of particles
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BurstTrigger {
+    /// Bursts only happen through `ParticleSystem::burst`
+    Manual,
+    /// Burst on every detected beat
+    Beat,
+    /// Burst when a band rises above a threshold
+    BandThreshold { band: AudioBand, threshold: f32 },
+}
+
+/// Serializable particle system configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticleSettings {
+    pub max_particles: u32,
+    pub emitter: EmitterShape,
+    /// Particles spawned per second
+    pub emission_rate: f32,
+    /// Band that scales the emission rate: `rate * (1 + gain * level)`
+    pub rate_band: Option<AudioBand>,
+    pub rate_audio_gain: f32,
+    pub burst_trigger: BurstTrigger,
+    pub burst_count: u32,
+    /// Lifetime range in seconds
+    pub lifetime: [f32; 2],
+    pub initial_speed: f32,
+    pub speed_variance: f32,
+    pub forces: ParticleForces,
+    pub color_over_life: Vec<ColorKey>,
+    pub size_over_life: Vec<SizeKey>,
+}
+
+impl Default for ParticleSettings {
+    fn default() -> Self {
+        Self {
+            max_particles: 10_000,
+            emitter: EmitterShape::Point,
+            emission_rate: 500.0,
+            rate_band: None,
+            rate_audio_gain: 1.0,
+            burst_trigger: BurstTrigger::Manual,
+            burst_count: 500,
+            lifetime: [1.0, 2.0],
+            initial_speed: 1.0,
+            speed_variance: 0.3,
+            forces: ParticleForces::default(),
+            color_over_life: vec![
+                ColorKey { t: 0.0, color: Color::new(1.0, 0.8, 0.4, 1.0) },
+                ColorKey { t: 1.0, color: Color::new(1.0, 0.2, 0.1, 0.0) },
+            ],
+            size_over_life: vec![
+                SizeKey { t: 0.0, size: 0.05 },
+                SizeKey { t: 1.0, size: 0.01 },
+            ],
+        }
+    }
+}
+
+/// Uniform shared by the simulation and render shaders
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ParticleUniform {
+    pub emitter_model: [[f32; 4]; 4],
+    pub emitter: [f32; 4],
+    pub lifetime: [f32; 4],
+    pub gravity: [f32; 4],
+    pub curl: [f32; 4],
+    pub attractors: [[f32; 4]; MAX_ATTRACTORS],
+    pub color_keys: [[f32; 4]; MAX_CURVE_KEYS],
+    pub color_times: [f32; MAX_CURVE_KEYS],
+    pub size_keys: [f32; MAX_CURVE_KEYS],
+    pub size_times: [f32; MAX_CURVE_KEYS],
+    pub camera_right: [f32; 4],
+    pub camera_up: [f32; 4],
+    pub counts: [u32; 4],
+    pub curve_counts: [u32; 4],
+    pub timing: [f32; 4],
+}
+
+/// GPU particle layout (48 bytes)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct GpuParticle {
+    position: [f32; 3],
+    age: f32,
+    velocity: [f32; 3],
+    lifetime: f32,
+    seed: f32,
+    size_scale: f32,
+    _padding: [f32; 2],
+}
+
+/// Emission triangle for mesh surface emitters
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SurfaceTriangle {
+    a: [f32; 4],
+    b: [f32; 4],
+    c: [f32; 4],
+    normal: [f32; 4],
+}
+
+/// Emission state carried between frames
+#[derive(Debug, Clone, Default)]
+struct Emission {
+    /// Fraction of a particle left over from continuous emission
+    accumulator: f32,
+    pending_burst: u32,
+    band_was_above: bool,
+}
+
+impl Emission {
+    /// Number of particles to spawn over `delta` seconds, including
+    /// triggered and queued bursts
+    fn advance(&mut self, settings: &ParticleSettings, delta: f32, audio: &AudioFeatures) -> u32 {
+        // Continuous emission, optionally scaled by an audio band
+        let mut rate = settings.emission_rate;
+        if let Some(band) = settings.rate_band {
+            rate *= 1.0 + settings.rate_audio_gain * audio.band(band);
+        }
+        self.accumulator += rate.max(0.0) * delta;
+        let spawn_count = self.accumulator.floor() as u32;
+        self.accumulator -= spawn_count as f32;
+
+        // Bursts
+        let triggered = match settings.burst_trigger {
+            BurstTrigger::Manual => false,
+            BurstTrigger::Beat => audio.beat,
+            BurstTrigger::BandThreshold { band, threshold } => {
+                // Rising edge only, so a sustained level bursts once
+                let above = audio.band(band) > threshold;
+                let rising = above && !self.band_was_above;
+                self.band_was_above = above;
+                rising
+            }
+        };
+        if triggered {
+            self.pending_burst = self.pending_burst.saturating_add(settings.burst_count);
+        }
+        spawn_count.saturating_add(std::mem::take(&mut self.pending_burst))
+    }
+}
+
+/// GPU particle system simulated in a compute shader and drawn as billboards
+///
+/// Buffers hold `settings.max_particles` as it was at creation; create a
+/// new system to change it.
+pub struct ParticleSystem {
+    pub settings: ParticleSettings,
+    pub transform: Mat4,
+    uniform: ParticleUniform,
+    uniform_buffer: wgpu::Buffer,
+    counter_buffer: wgpu::Buffer,
+    simulate: ComputePipeline,
+    simulate_bind_group: wgpu::BindGroup,
+    render_pipeline: wgpu::RenderPipeline,
+    render_pipeline_layout: wgpu::PipelineLayout,
+    render_shader: wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    render_bind_group: wgpu::BindGroup,
+    triangle_count: u32,
+    /// Particles the buffers hold
+    capacity: u32,
+    emission: Emission,
+    elapsed: f32,
+    frame: u32,
+}
+
+impl ParticleSystem {
+    /// Create a particle system
+    ///
+    /// `camera_layout` is the scene camera bind group layout (group 0 of the
+    /// render pipeline) and `sample_count` must match the render target.
+    /// `surface_mesh` is only used by `EmitterShape::MeshSurface`.
+    pub fn new(
+        device: &wgpu::Device,
+        camera_layout: &wgpu::BindGroupLayout,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+        settings: ParticleSettings,
+        surface_mesh: Option<&Mesh>,
+    ) -> Self {
+        let max_particles = settings.max_particles.max(1);
+
+        let particle_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Buffer"),
+            size: (max_particles as usize * std::mem::size_of::<GpuParticle>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::STORAGE,
+            // Zeroed particles have age >= lifetime, i.e. start out dead
+            mapped_at_creation: false,
+        });
+
+        let counter_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Counter Buffer"),
+            size: 8,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let (triangles, cdf) = match (&settings.emitter, surface_mesh) {
+            (EmitterShape::MeshSurface { .. }, Some(mesh)) => Self::surface_triangles(mesh),
+            (EmitterShape::MeshSurface { mesh }, None) => {
+                log::warn!("Particle emitter mesh '{}' not provided, emitting from a point", mesh);
+                (Vec::new(), Vec::new())
+            }
+            _ => (Vec::new(), Vec::new()),
+        };
+        let triangle_count = triangles.len() as u32;
+
+        // Storage bindings cannot be empty, keep one placeholder element
+        let placeholder_triangle = SurfaceTriangle::zeroed();
+        let triangle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Particle Surface Triangle Buffer"),
+            contents: if triangles.is_empty() {
+                bytemuck::bytes_of(&placeholder_triangle)
+            } else {
+                bytemuck::cast_slice(&triangles)
+            },
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let cdf_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Particle Surface CDF Buffer"),
+            contents: if cdf.is_empty() {
+                bytemuck::bytes_of(&1.0f32)
+            } else {
+                bytemuck::cast_slice(&cdf)
+            },
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let uniform = ParticleUniform::zeroed();
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Particle Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[uniform]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Simulation pipeline
+        let simulate_layout = BindGroupLayoutBuilder::new(wgpu::ShaderStages::COMPUTE)
+            .uniform_buffer()
+            .storage_buffer(false)
+            .storage_buffer(false)
+            .storage_buffer(true)
+            .storage_buffer(true)
+            .build(device, Some("Particle Simulation Bind Group Layout"));
+
+        let simulate_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Particle Simulation Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../../assets/shaders/particles_sim.wgsl").into()),
+        });
+
+        let simulate = ComputePipelineBuilder::new()
+            .shader(simulate_shader)
+            .bind_group_layout(simulate_layout)
+            .workgroup_size([64, 1, 1])
+            .build(device, Some("Particle Simulation Pipeline"))
+            .expect("particle simulation shader is set");
+
+        let simulate_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Particle Simulation Bind Group"),
+            layout: &simulate.bind_group_layouts[0],
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: particle_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: counter_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: triangle_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 4, resource: cdf_buffer.as_entire_binding() },
+            ],
+        });
+
+        // Billboard render pipeline
+        let render_layout = BindGroupLayoutBuilder::new(wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT)
+            .uniform_buffer()
+            .visibility(wgpu::ShaderStages::VERTEX)
+            .storage_buffer(true)
+            .build(device, Some("Particle Render Bind Group Layout"));
+
+        let render_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Particle Render Bind Group"),
+            layout: &render_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: particle_buffer.as_entire_binding() },
+            ],
+        });
+
+        let render_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Particle Render Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../../assets/shaders/particles_render.wgsl").into()),
+        });
+
+        let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Particle Render Pipeline Layout"),
+            bind_group_layouts: &[camera_layout, &render_layout],
+            push_constant_ranges: &[],
+        });
+
+        let render_pipeline = Self::create_render_pipeline(
+            device,
+            &render_pipeline_layout,
+            &render_shader,
+            format,
+            sample_count,
+        );
+
+        Self {
+            settings,
+            transform: Mat4::IDENTITY,
+            uniform,
+            uniform_buffer,
+            counter_buffer,
+            simulate,
+            simulate_bind_group,
+            render_pipeline,
+            render_pipeline_layout,
+            render_shader,
+            format,
+            sample_count,
+            render_bind_group,
+            triangle_count,
+            capacity: max_particles,
+            emission: Emission::default(),
+            elapsed: 0.0,
+            frame: 0,
+        }
+    }
+
+    /// Rebuild the render pipeline for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        if self.sample_count == sample_count {
+            return;
+        }
+
+        self.sample_count = sample_count;
+        self.render_pipeline = Self::create_render_pipeline(
+            device,
+            &self.render_pipeline_layout,
+            &self.render_shader,
+            self.format,
+            sample_count,
+        );
+    }
+
+    /// Particles the buffers hold
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Triangles particles are emitted from, 0 unless emitting from a mesh surface
+    pub fn surface_triangle_count(&self) -> u32 {
+        self.triangle_count
+    }
+
+    /// Queue a burst of particles for the next update
+    pub fn burst(&mut self, count: u32) {
+        self.emission.pending_burst = self.emission.pending_burst.saturating_add(count);
+    }
+
+    /// Advance emission state and write this frame's uniforms
+    ///
+    /// Call once per frame before `simulate`. Audio features drive the
+    /// emission rate binding and burst triggers.
+    pub fn update(&mut self, queue: &wgpu::Queue, delta: f32, camera: &Camera, audio: &AudioFeatures) {
+        self.elapsed += delta;
+        self.frame = self.frame.wrapping_add(1);
+
+        let spawn_count = self.emission.advance(&self.settings, delta, audio);
+        self.uniform = self.build_uniform(delta, spawn_count, camera);
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[self.uniform]));
+        queue.write_buffer(&self.counter_buffer, 0, bytemuck::cast_slice(&[0u32, 0u32]));
+    }
+
+    /// Record the simulation compute pass
+    pub fn simulate(&self, encoder: &mut wgpu::CommandEncoder) {
+        self.simulate.dispatch_invocations(
+            encoder,
+            &[&self.simulate_bind_group],
+            [self.capacity, 1, 1],
+            Some("Particle Simulation Pass"),
+        );
+    }
+
+    /// Draw particles as billboards
+    /// The camera bind group must already be set at group 0
+    pub fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(1, &self.render_bind_group, &[]);
+        render_pass.draw(0..6, 0..self.capacity);
+    }
+
+    /// Create the billboard pipeline for a color format and MSAA sample count
+    fn create_render_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Particle Render Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    // Additive blending, order independent
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            dst_factor: wgpu::BlendFactor::One,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                        alpha: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::Zero,
+                            dst_factor: wgpu::BlendFactor::One,
+                            operation: wgpu::BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            // Test against scene depth but do not occlude other particles
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    fn build_uniform(&self, delta: f32, spawn_count: u32, camera: &Camera) -> ParticleUniform {
+        let settings = &self.settings;
+        let mut uniform = ParticleUniform::zeroed();
+
+        uniform.emitter_model = self.transform.to_cols_array_2d();
+        let (shape, radius) = match settings.emitter {
+            EmitterShape::Point => (0.0, 0.0),
+            EmitterShape::Sphere { radius } => (1.0, radius),
+            EmitterShape::MeshSurface { .. } if self.triangle_count > 0 => (2.0, 0.0),
+            EmitterShape::MeshSurface { .. } => (0.0, 0.0),
+        };
+        uniform.emitter = [shape, radius, settings.initial_speed, settings.speed_variance];
+        uniform.lifetime = [settings.lifetime[0], settings.lifetime[1], settings.forces.drag, 0.0];
+        uniform.gravity = Vec3::from(settings.forces.gravity).extend(0.0).to_array();
+        uniform.curl = [
+            settings.forces.curl_strength,
+            settings.forces.curl_frequency,
+            self.elapsed * settings.forces.curl_speed,
+            0.0,
+        ];
+
+        if settings.forces.attractors.len() > MAX_ATTRACTORS {
+            log::warn!("Particle system supports {} attractors, ignoring the rest", MAX_ATTRACTORS);
+        }
+        let attractors = &settings.forces.attractors[..settings.forces.attractors.len().min(MAX_ATTRACTORS)];
+        for (slot, attractor) in uniform.attractors.iter_mut().zip(attractors) {
+            *slot = Vec3::from(attractor.position).extend(attractor.strength).to_array();
+        }
+
+        if settings.color_over_life.len() > MAX_CURVE_KEYS || settings.size_over_life.len() > MAX_CURVE_KEYS {
+            log::warn!("Particle curves support {} keys, ignoring the rest", MAX_CURVE_KEYS);
+        }
+        let color_keys = &settings.color_over_life[..settings.color_over_life.len().min(MAX_CURVE_KEYS)];
+        for (i, key) in color_keys.iter().enumerate() {
+            uniform.color_keys[i] = key.color.to_array();
+            uniform.color_times[i] = key.t;
+        }
+        let size_keys = &settings.size_over_life[..settings.size_over_life.len().min(MAX_CURVE_KEYS)];
+        for (i, key) in size_keys.iter().enumerate() {
+            uniform.size_keys[i] = key.size;
+            uniform.size_times[i] = key.t;
+        }
+
+        // Billboard axes from the camera basis
+        let forward = (camera.target - camera.position).normalize_or_zero();
+        let right = forward.cross(camera.up).normalize_or_zero();
+        let up = right.cross(forward);
+        uniform.camera_right = right.extend(0.0).to_array();
+        uniform.camera_up = up.extend(0.0).to_array();
+
+        uniform.counts = [spawn_count, self.frame, attractors.len() as u32, self.triangle_count];
+        uniform.curve_counts = [
+            color_keys.len() as u32,
+            size_keys.len() as u32,
+            self.capacity,
+            0,
+        ];
+        uniform.timing = [delta, self.elapsed, 0.0, 0.0];
+
+        uniform
+    }
+
+    /// Build emission triangles and their normalized cumulative areas
+    fn surface_triangles(mesh: &Mesh) -> (Vec<SurfaceTriangle>, Vec<f32>) {
+        let mut triangles = Vec::with_capacity(mesh.indices.len() / 3);
+        let mut cdf = Vec::with_capacity(mesh.indices.len() / 3);
+        let mut total_area = 0.0;
+
+        for tri in mesh.indices.chunks_exact(3) {
+            let a = Vec3::from(mesh.vertices[tri[0] as usize].position);
+            let b = Vec3::from(mesh.vertices[tri[1] as usize].position);
+            let c = Vec3::from(mesh.vertices[tri[2] as usize].position);
+            let cross = (b - a).cross(c - a);
+            let area = cross.length() * 0.5;
+            if area <= f32::EPSILON {
+                continue;
+            }
+
+            total_area += area;
+            cdf.push(total_area);
+            triangles.push(SurfaceTriangle {
+                a: a.extend(1.0).to_array(),
+                b: b.extend(1.0).to_array(),
+                c: c.extend(1.0).to_array(),
+                normal: cross.normalize().extend(0.0).to_array(),
+            });
+        }
+
+        for value in &mut cdf {
+            *value /= total_area;
+        }
+
+        (triangles, cdf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet() -> ParticleSettings {
+        ParticleSettings {
+            emission_rate: 0.0,
+            burst_count: 50,
+            ..Default::default()
+        }
+    }
+
+    fn bass(level: f32) -> AudioFeatures {
+        let mut audio = AudioFeatures::default();
+        audio.bands[1] = level;
+        audio
+    }
+
+    #[test]
+    fn fractional_emission_carries_over() {
+        let settings = ParticleSettings { emission_rate: 5.0, ..quiet() };
+        let mut emission = Emission::default();
+        let counts: Vec<u32> = (0..4).map(|_| emission.advance(&settings, 0.1, &AudioFeatures::default())).collect();
+        assert_eq!(counts, [0, 1, 0, 1]);
+        let settings = ParticleSettings { emission_rate: -10.0, ..settings };
+        assert_eq!(emission.advance(&settings, 1.0, &AudioFeatures::default()), 0);
+    }
+
+    #[test]
+    fn rate_band_scales_emission() {
+        let settings = ParticleSettings {
+            emission_rate: 100.0,
+            rate_band: Some(AudioBand::Bass),
+            rate_audio_gain: 2.0,
+            ..quiet()
+        };
+        let mut emission = Emission::default();
+        assert_eq!(emission.advance(&settings, 0.1, &bass(0.0)), 10);
+        assert_eq!(emission.advance(&settings, 0.1, &bass(0.5)), 20);
+    }
+
+    #[test]
+    fn beats_and_queued_bursts_spawn_once() {
+        let settings = ParticleSettings { burst_trigger: BurstTrigger::Beat, ..quiet() };
+        let mut emission = Emission::default();
+        let beat = AudioFeatures { beat: true, ..Default::default() };
+        assert_eq!(emission.advance(&settings, 0.1, &beat), 50);
+        assert_eq!(emission.advance(&settings, 0.1, &AudioFeatures::default()), 0);
+
+        emission.pending_burst = 7;
+        assert_eq!(emission.advance(&settings, 0.1, &beat), 57);
+        assert_eq!(emission.pending_burst, 0);
+    }
+
+    #[test]
+    fn band_threshold_bursts_on_rising_edge() {
+        let settings = ParticleSettings {
+            burst_trigger: BurstTrigger::BandThreshold { band: AudioBand::Bass, threshold: 0.5 },
+            ..quiet()
+        };
+        let mut emission = Emission::default();
+        let counts: Vec<u32> = [0.2, 0.8, 0.9, 0.3, 0.7]
+            .iter()
+            .map(|&level| emission.advance(&settings, 0.1, &bass(level)))
+            .collect();
+        assert_eq!(counts, [0, 50, 0, 0, 50]);
+    }
+}
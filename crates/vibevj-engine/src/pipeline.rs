@@ -1,10 +1,10 @@
 use vibevj_common::{Result, VibeVJError};
-use crate::shader::Shader;
 
 /// Render pipeline wrapper
 pub struct Pipeline {
     pub render_pipeline: wgpu::RenderPipeline,
-    pub bind_group_layout: wgpu::BindGroupLayout,
+    /// Bind group layouts in group order
+    pub bind_group_layouts: Vec<wgpu::BindGroupLayout>,
 }
 
 impl Pipeline {
@@ -12,6 +12,17 @@ impl Pipeline {
     pub fn builder() -> PipelineBuilder {
         PipelineBuilder::default()
     }
+
+    /// Get the layout of group 0, which every built pipeline has
+    /// Replaces the former `bind_group_layout` field
+    pub fn bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.bind_group_layouts[0]
+    }
+
+    /// Get the bind group layout for a group index
+    pub fn bind_group_layout_at(&self, group: usize) -> Option<&wgpu::BindGroupLayout> {
+        self.bind_group_layouts.get(group)
+    }
 }
 
 /// Builder for creating render pipelines
@@ -23,7 +34,9 @@ pub struct PipelineBuilder {
     bind_group_layouts: Vec<wgpu::BindGroupLayout>,
     topology: wgpu::PrimitiveTopology,
     cull_mode: Option<wgpu::Face>,
+    polygon_mode: wgpu::PolygonMode,
     depth_stencil: Option<wgpu::DepthStencilState>,
+    sample_count: u32,
 }
 
 impl PipelineBuilder {
@@ -31,6 +44,7 @@ impl PipelineBuilder {
         Self {
             topology: wgpu::PrimitiveTopology::TriangleList,
             cull_mode: Some(wgpu::Face::Back),
+            sample_count: 1,
             ..Default::default()
         }
     }
@@ -45,6 +59,31 @@ impl PipelineBuilder {
         self
     }
 
+    /// Add a vertex buffer layout (slot order matches call order)
+    pub fn vertex_buffer(mut self, layout: wgpu::VertexBufferLayout<'static>) -> Self {
+        self.vertex_buffers.push(layout);
+        self
+    }
+
+    /// Replace all vertex buffer layouts
+    pub fn vertex_buffers(mut self, layouts: Vec<wgpu::VertexBufferLayout<'static>>) -> Self {
+        self.vertex_buffers = layouts;
+        self
+    }
+
+    /// Add a bind group layout (group index matches call order)
+    /// If none are added, a single uniform buffer layout is created at group 0
+    pub fn bind_group_layout(mut self, layout: wgpu::BindGroupLayout) -> Self {
+        self.bind_group_layouts.push(layout);
+        self
+    }
+
+    /// Replace all bind group layouts
+    pub fn bind_group_layouts(mut self, layouts: Vec<wgpu::BindGroupLayout>) -> Self {
+        self.bind_group_layouts = layouts;
+        self
+    }
+
     pub fn topology(mut self, topology: wgpu::PrimitiveTopology) -> Self {
         self.topology = topology;
         self
@@ -55,11 +94,24 @@ impl PipelineBuilder {
         self
     }
 
+    /// `Line` and `Point` need the matching `wgpu::Features::POLYGON_MODE_*`;
+    /// `RenderMode` draws lines and points without them
+    pub fn polygon_mode(mut self, polygon_mode: wgpu::PolygonMode) -> Self {
+        self.polygon_mode = polygon_mode;
+        self
+    }
+
     pub fn depth_stencil(mut self, depth_stencil: wgpu::DepthStencilState) -> Self {
         self.depth_stencil = Some(depth_stencil);
         self
     }
 
+    /// Set the MSAA sample count, must match the render target
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
     pub fn build(
         self,
         device: &wgpu::Device,
@@ -72,26 +124,19 @@ impl PipelineBuilder {
         let fragment_shader = self.fragment_shader
             .ok_or_else(|| VibeVJError::RenderError("Fragment shader not set".to_string()))?;
 
-        // Create bind group layout
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("Pipeline Bind Group Layout"),
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                },
-            ],
-        });
+        // Fall back to a single uniform buffer at group 0
+        let bind_group_layouts = if self.bind_group_layouts.is_empty() {
+            vec![BindGroupLayoutBuilder::new(wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT)
+                .uniform_buffer()
+                .build(device, Some("Pipeline Bind Group Layout"))]
+        } else {
+            self.bind_group_layouts
+        };
+        let layout_refs: Vec<&wgpu::BindGroupLayout> = bind_group_layouts.iter().collect();
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Pipeline Layout"),
-            bind_group_layouts: &[&bind_group_layout],
+            bind_group_layouts: &layout_refs,
             push_constant_ranges: &[],
         });
 
@@ -119,13 +164,13 @@ impl PipelineBuilder {
                 strip_index_format: None,
                 front_face: wgpu::FrontFace::Ccw,
                 cull_mode: self.cull_mode,
-                polygon_mode: wgpu::PolygonMode::Fill,
+                polygon_mode: self.polygon_mode,
                 unclipped_depth: false,
                 conservative: false,
             },
             depth_stencil: self.depth_stencil,
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: self.sample_count.max(1),
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
@@ -135,7 +180,96 @@ impl PipelineBuilder {
 
         Ok(Pipeline {
             render_pipeline,
-            bind_group_layout,
+            bind_group_layouts,
+        })
+    }
+}
+
+/// Builder for bind group layouts
+///
+/// Bindings are numbered in the order entries are added, starting at 0.
+pub struct BindGroupLayoutBuilder {
+    visibility: wgpu::ShaderStages,
+    entries: Vec<wgpu::BindGroupLayoutEntry>,
+}
+
+impl BindGroupLayoutBuilder {
+    /// Create a builder whose entries are visible to the given stages
+    pub fn new(visibility: wgpu::ShaderStages) -> Self {
+        Self {
+            visibility,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Change the visibility used for entries added after this call
+    pub fn visibility(mut self, visibility: wgpu::ShaderStages) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    /// Add a raw binding type at the next binding index
+    pub fn entry(mut self, ty: wgpu::BindingType) -> Self {
+        self.entries.push(wgpu::BindGroupLayoutEntry {
+            binding: self.entries.len() as u32,
+            visibility: self.visibility,
+            ty,
+            count: None,
+        });
+        self
+    }
+
+    /// Add a uniform buffer binding
+    pub fn uniform_buffer(self) -> Self {
+        self.entry(wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        })
+    }
+
+    /// Add a storage buffer binding
+    pub fn storage_buffer(self, read_only: bool) -> Self {
+        self.entry(wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        })
+    }
+
+    /// Add a storage texture binding
+    pub fn storage_texture(
+        self,
+        format: wgpu::TextureFormat,
+        access: wgpu::StorageTextureAccess,
+        view_dimension: wgpu::TextureViewDimension,
+    ) -> Self {
+        self.entry(wgpu::BindingType::StorageTexture {
+            access,
+            format,
+            view_dimension,
+        })
+    }
+
+    /// Add a filterable float texture binding
+    pub fn texture(self, view_dimension: wgpu::TextureViewDimension) -> Self {
+        self.entry(wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension,
+            multisampled: false,
+        })
+    }
+
+    /// Add a filtering sampler binding
+    pub fn sampler(self) -> Self {
+        self.entry(wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering))
+    }
+
+    /// Create the bind group layout
+    pub fn build(self, device: &wgpu::Device, label: Option<&str>) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label,
+            entries: &self.entries,
         })
     }
 }
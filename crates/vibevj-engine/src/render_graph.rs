@@ -0,0 +1,366 @@
+use vibevj_common::{Result, VibeVJError};
+use crate::render_target::RenderTarget;
+use crate::render_target_pool::{RenderTargetPool, TargetDesc};
+
+/// Handle to a texture declared in a `RenderGraph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResourceId(usize);
+
+enum Resource<'a> {
+    /// Allocated from the pool for the duration of its use
+    Transient(TargetDesc),
+    /// Owned outside the graph, e.g. the egui texture or an export target
+    ImportedTarget(&'a RenderTarget),
+    /// A bare view such as a surface texture
+    ImportedView(&'a wgpu::TextureView),
+}
+
+type PassFn<'a> = Box<dyn FnOnce(&mut PassContext) + 'a>;
+
+struct Pass<'a> {
+    name: String,
+    reads: Vec<ResourceId>,
+    writes: Vec<ResourceId>,
+    side_effect: bool,
+    execute: Option<PassFn<'a>>,
+}
+
+/// What a pass can use while it records commands
+pub struct PassContext<'r> {
+    pub device: &'r wgpu::Device,
+    pub queue: &'r wgpu::Queue,
+    pub encoder: &'r mut wgpu::CommandEncoder,
+    bindings: &'r [Binding<'r>],
+}
+
+#[derive(Clone, Copy)]
+enum Binding<'r> {
+    Target(&'r RenderTarget),
+    View(&'r wgpu::TextureView),
+    Unbound,
+}
+
+impl<'r> PassContext<'r> {
+    /// Render target behind a declared resource
+    ///
+    /// Panics for imported bare views and for resources the pass did not declare.
+    pub fn target(&self, id: ResourceId) -> &'r RenderTarget {
+        match self.bindings[id.0] {
+            Binding::Target(target) => target,
+            Binding::View(_) => panic!("render graph resource {:?} is a bare view, not a render target", id),
+            Binding::Unbound => panic!("render graph resource {:?} was not declared by this pass", id),
+        }
+    }
+
+    /// Texture view of a declared resource
+    pub fn view(&self, id: ResourceId) -> &'r wgpu::TextureView {
+        match self.bindings[id.0] {
+            Binding::Target(target) => &target.view,
+            Binding::View(view) => view,
+            Binding::Unbound => panic!("render graph resource {:?} was not declared by this pass", id),
+        }
+    }
+}
+
+/// Declares the reads and writes of a pass before its commands are given
+pub struct PassBuilder<'g, 'a> {
+    graph: &'g mut RenderGraph<'a>,
+    pass: Pass<'a>,
+}
+
+impl<'a> PassBuilder<'_, 'a> {
+    pub fn read(mut self, id: ResourceId) -> Self {
+        self.pass.reads.push(id);
+        self
+    }
+
+    pub fn write(mut self, id: ResourceId) -> Self {
+        self.pass.writes.push(id);
+        self
+    }
+
+    /// Keep the pass even when nothing reads its outputs (compute, readback)
+    pub fn side_effect(mut self) -> Self {
+        self.pass.side_effect = true;
+        self
+    }
+
+    /// Set the pass commands and add it to the graph
+    pub fn execute(mut self, execute: impl FnOnce(&mut PassContext) + 'a) {
+        self.pass.execute = Some(Box::new(execute));
+        self.graph.passes.push(self.pass);
+    }
+}
+
+/// Per-frame graph of render passes
+///
+/// Passes declare the textures they read and write and may be added in any
+/// order. Writes to one texture run in the order they were added, and every
+/// read-only pass runs after all writes to what it reads. Passes that do not
+/// contribute to an imported texture (or have no side effect) are culled.
+/// Transient targets are taken from a `RenderTargetPool` when first used and
+/// handed to later transients with the same description once their last
+/// reader has run.
+pub struct RenderGraph<'a> {
+    resources: Vec<(String, Resource<'a>)>,
+    passes: Vec<Pass<'a>>,
+}
+
+impl<'a> Default for RenderGraph<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> RenderGraph<'a> {
+    pub fn new() -> Self {
+        Self {
+            resources: Vec::new(),
+            passes: Vec::new(),
+        }
+    }
+
+    /// Declare a target that only lives while the graph executes
+    pub fn create_target(&mut self, name: &str, desc: TargetDesc) -> ResourceId {
+        self.add_resource(name, Resource::Transient(desc))
+    }
+
+    /// Use a target owned outside the graph; passes writing it are never culled
+    pub fn import_target(&mut self, name: &str, target: &'a RenderTarget) -> ResourceId {
+        self.add_resource(name, Resource::ImportedTarget(target))
+    }
+
+    /// Use a bare view owned outside the graph, e.g. a surface texture
+    pub fn import_view(&mut self, name: &str, view: &'a wgpu::TextureView) -> ResourceId {
+        self.add_resource(name, Resource::ImportedView(view))
+    }
+
+    /// Start declaring a pass
+    pub fn add_pass(&mut self, name: &str) -> PassBuilder<'_, 'a> {
+        PassBuilder {
+            graph: self,
+            pass: Pass {
+                name: name.to_string(),
+                reads: Vec::new(),
+                writes: Vec::new(),
+                side_effect: false,
+                execute: None,
+            },
+        }
+    }
+
+    /// Description of a transient target, `None` for imported resources
+    pub fn desc(&self, id: ResourceId) -> Option<TargetDesc> {
+        match self.resources[id.0].1 {
+            Resource::Transient(desc) => Some(desc),
+            Resource::ImportedTarget(_) | Resource::ImportedView(_) => None,
+        }
+    }
+
+    fn add_resource(&mut self, name: &str, resource: Resource<'a>) -> ResourceId {
+        self.resources.push((name.to_string(), resource));
+        ResourceId(self.resources.len() - 1)
+    }
+
+    fn is_imported(&self, id: ResourceId) -> bool {
+        !matches!(self.resources[id.0].1, Resource::Transient(_))
+    }
+
+    /// Passes each pass depends on
+    fn dependencies(&self) -> Result<Vec<Vec<usize>>> {
+        let mut writers: Vec<Vec<usize>> = vec![Vec::new(); self.resources.len()];
+        for (index, pass) in self.passes.iter().enumerate() {
+            for id in &pass.writes {
+                writers[id.0].push(index);
+            }
+        }
+
+        let mut dependencies = vec![Vec::new(); self.passes.len()];
+        for (index, pass) in self.passes.iter().enumerate() {
+            for id in &pass.writes {
+                // Writes to one resource keep their declaration order
+                let position = writers[id.0].iter().position(|&w| w == index).unwrap_or(0);
+                if position > 0 {
+                    dependencies[index].push(writers[id.0][position - 1]);
+                }
+            }
+            for id in pass.reads.iter().filter(|id| !pass.writes.contains(id)) {
+                match writers[id.0].last() {
+                    Some(&writer) => dependencies[index].push(writer),
+                    None if self.is_imported(*id) => {}
+                    None => {
+                        return Err(VibeVJError::RenderError(format!(
+                            "Render pass '{}' reads '{}', which no pass writes",
+                            pass.name, self.resources[id.0].0
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(dependencies)
+    }
+
+    /// Passes that reach an imported resource or a side effect, in execution order
+    fn compile(&self) -> Result<Vec<usize>> {
+        let dependencies = self.dependencies()?;
+
+        // Cull everything the roots do not depend on
+        let mut live = vec![false; self.passes.len()];
+        let mut stack: Vec<usize> = self
+            .passes
+            .iter()
+            .enumerate()
+            .filter(|(_, pass)| pass.side_effect || pass.writes.iter().any(|&id| self.is_imported(id)))
+            .map(|(index, _)| index)
+            .collect();
+        while let Some(index) = stack.pop() {
+            if !std::mem::replace(&mut live[index], true) {
+                stack.extend(&dependencies[index]);
+            }
+        }
+
+        // Kahn's algorithm, preferring declaration order among ready passes
+        let mut remaining: Vec<usize> = (0..self.passes.len())
+            .map(|index| dependencies[index].iter().filter(|&&d| live[d]).count())
+            .collect();
+        let mut order = Vec::new();
+        let mut done = vec![false; self.passes.len()];
+        while let Some(next) = (0..self.passes.len()).find(|&i| live[i] && !done[i] && remaining[i] == 0) {
+            done[next] = true;
+            order.push(next);
+            for (index, deps) in dependencies.iter().enumerate() {
+                remaining[index] -= deps.iter().filter(|&&d| d == next).count();
+            }
+        }
+
+        if order.len() != live.iter().filter(|&&l| l).count() {
+            let stuck: Vec<&str> = (0..self.passes.len())
+                .filter(|&i| live[i] && !done[i])
+                .map(|i| self.passes[i].name.as_str())
+                .collect();
+            return Err(VibeVJError::RenderError(format!("Render graph has a cycle between {:?}", stuck)));
+        }
+        Ok(order)
+    }
+
+    /// Names of the passes that would run, in order
+    pub fn execution_order(&self) -> Result<Vec<&str>> {
+        Ok(self.compile()?.into_iter().map(|index| self.passes[index].name.as_str()).collect())
+    }
+
+    /// Sort, cull, allocate transients and record every live pass into `encoder`
+    pub fn execute(
+        mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        pool: &mut RenderTargetPool,
+    ) -> Result<()> {
+        let order = self.compile()?;
+
+        // Step of the last pass using each resource
+        let mut last_use = vec![0; self.resources.len()];
+        for (step, &index) in order.iter().enumerate() {
+            let pass = &self.passes[index];
+            for id in pass.reads.iter().chain(&pass.writes) {
+                last_use[id.0] = step;
+            }
+        }
+
+        let mut physical: Vec<RenderTarget> = Vec::new();
+        let mut slots: Vec<Option<usize>> = vec![None; self.resources.len()];
+        let mut free: Vec<usize> = Vec::new();
+
+        for (step, &index) in order.iter().enumerate() {
+            let pass = &mut self.passes[index];
+            for id in pass.writes.iter().chain(&pass.reads) {
+                if let (Resource::Transient(desc), None) = (&self.resources[id.0].1, slots[id.0]) {
+                    // Alias a target released earlier this frame, or take one from the pool
+                    let slot = match free.iter().position(|&slot| TargetDesc::of(&physical[slot]) == *desc) {
+                        Some(position) => free.swap_remove(position),
+                        None => {
+                            physical.push(pool.acquire(device, *desc, &self.resources[id.0].0));
+                            physical.len() - 1
+                        }
+                    };
+                    slots[id.0] = Some(slot);
+                }
+            }
+
+            let mut bindings = vec![Binding::Unbound; self.resources.len()];
+            for id in pass.reads.iter().chain(&pass.writes) {
+                bindings[id.0] = match &self.resources[id.0].1 {
+                    Resource::Transient(_) => Binding::Target(&physical[slots[id.0].unwrap_or_default()]),
+                    Resource::ImportedTarget(target) => Binding::Target(target),
+                    Resource::ImportedView(view) => Binding::View(view),
+                };
+            }
+            if let Some(execute) = pass.execute.take() {
+                let mut context = PassContext {
+                    device,
+                    queue,
+                    encoder: &mut *encoder,
+                    bindings: &bindings,
+                };
+                execute(&mut context);
+            }
+
+            for id in pass.reads.iter().chain(&pass.writes) {
+                if last_use[id.0] == step {
+                    if let Some(slot) = slots[id.0] {
+                        if !free.contains(&slot) {
+                            free.push(slot);
+                        }
+                    }
+                }
+            }
+        }
+
+        for target in physical {
+            pool.release(target);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(graph: &mut RenderGraph, name: &str) -> ResourceId {
+        graph.create_target(name, TargetDesc::new(4, 4, RenderTarget::HDR_FORMAT))
+    }
+
+    #[test]
+    fn orders_passes_by_dependency_and_culls_unread_ones() {
+        let mut graph = RenderGraph::new();
+        let hdr = target(&mut graph, "HDR");
+        let layer = target(&mut graph, "Layer");
+        let output = target(&mut graph, "Output");
+        let unused = target(&mut graph, "Unused");
+
+        // The mix is declared before the passes producing what it reads
+        graph.add_pass("Mix").read(layer).write(output).side_effect().execute(|_| {});
+        graph.add_pass("Unused").write(unused).execute(|_| {});
+        graph.add_pass("Scene").write(hdr).execute(|_| {});
+        graph.add_pass("Overlay").read(hdr).write(hdr).execute(|_| {});
+        graph.add_pass("Tone Mapping").read(hdr).write(layer).execute(|_| {});
+
+        assert_eq!(graph.execution_order().unwrap(), ["Scene", "Overlay", "Tone Mapping", "Mix"]);
+    }
+
+    #[test]
+    fn rejects_unwritten_reads_and_cycles() {
+        let mut graph = RenderGraph::new();
+        let never_written = target(&mut graph, "Never Written");
+        graph.add_pass("Reader").read(never_written).side_effect().execute(|_| {});
+        assert!(graph.execution_order().is_err());
+
+        let mut graph = RenderGraph::new();
+        let a = target(&mut graph, "A");
+        let b = target(&mut graph, "B");
+        graph.add_pass("First").read(b).write(a).side_effect().execute(|_| {});
+        graph.add_pass("Second").read(a).write(b).execute(|_| {});
+        assert!(graph.execution_order().is_err());
+    }
+}
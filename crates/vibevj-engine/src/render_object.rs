@@ -1,6 +1,9 @@
 use glam::Mat4;
 use serde::{Deserialize, Serialize};
-use crate::{Mesh, Material};
+use crate::{Mesh, Material, TextureCache};
+use crate::material::{RenderMode, TextureSlot};
+use crate::lines::LineInstance;
+use crate::mesh_gen::{PlatonicSolid, Superformula};
 
 /// A renderable 3D object combining mesh, material, and transform
 #[derive(Debug)]
@@ -14,6 +17,11 @@ pub struct RenderObject {
     pub model_buffer: Option<wgpu::Buffer>,
     pub material_bind_group: Option<wgpu::BindGroup>,
     pub model_bind_group: Option<wgpu::BindGroup>,
+    /// Expanded triangle vertices for `RenderMode::Wireframe`
+    pub wireframe_buffer: Option<wgpu::Buffer>,
+    /// `LineInstance`s along feature edges for `RenderMode::Edges`
+    pub edge_buffer: Option<wgpu::Buffer>,
+    pub edge_count: u32,
 }
 
 impl RenderObject {
@@ -29,11 +37,22 @@ impl RenderObject {
             model_buffer: None,
             material_bind_group: None,
             model_bind_group: None,
+            wireframe_buffer: None,
+            edge_buffer: None,
+            edge_count: 0,
         }
     }
     
     /// Upload mesh and material data to GPU
-    pub fn upload(&mut self, device: &wgpu::Device, material_layout: &wgpu::BindGroupLayout, model_layout: &wgpu::BindGroupLayout) {
+    /// Material textures are resolved through `textures`, which should already
+    /// hold everything the material references (see `TextureCache::load_material_textures`)
+    pub fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        material_layout: &wgpu::BindGroupLayout,
+        model_layout: &wgpu::BindGroupLayout,
+        textures: &TextureCache,
+    ) {
         use wgpu::util::DeviceExt;
         
         // Upload mesh data
@@ -49,6 +68,8 @@ impl RenderObject {
             usage: wgpu::BufferUsages::INDEX,
         }));
         
+        self.upload_render_mode(device);
+        
         // Create material uniform
         let material_uniform: crate::MaterialUniform = (&self.material).into();
         self.material_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -69,14 +90,13 @@ impl RenderObject {
         
         // Create bind groups
         if let Some(ref material_buffer) = self.material_buffer {
-            self.material_bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Material Bind Group"),
-                layout: material_layout,
-                entries: &[wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: material_buffer.as_entire_binding(),
-                }],
-            }));
+            self.material_bind_group = Some(create_material_bind_group(
+                device,
+                material_layout,
+                material_buffer,
+                &self.material,
+                textures,
+            ));
         }
         
         if let Some(ref model_buffer) = self.model_buffer {
@@ -91,6 +111,47 @@ impl RenderObject {
         }
     }
     
+    /// Create the extra geometry the material's render mode draws from
+    /// Call again (or `upload`) after switching to wireframe or edges
+    pub fn upload_render_mode(&mut self, device: &wgpu::Device) {
+        use wgpu::util::DeviceExt;
+        
+        self.wireframe_buffer = None;
+        self.edge_buffer = None;
+        self.edge_count = 0;
+        
+        match self.material.render_mode {
+            RenderMode::Wireframe { .. } => {
+                self.wireframe_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Wireframe Vertex Buffer"),
+                    contents: bytemuck::cast_slice(&self.mesh.triangle_vertices()),
+                    usage: wgpu::BufferUsages::VERTEX,
+                }));
+            }
+            RenderMode::Edges { crease_angle, .. } => {
+                let edges = LineInstance::from_mesh_edges(&self.mesh, crease_angle);
+                self.edge_count = edges.len() as u32;
+                if !edges.is_empty() {
+                    self.edge_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                        label: Some("Edge Instance Buffer"),
+                        contents: bytemuck::cast_slice(&edges),
+                        usage: wgpu::BufferUsages::VERTEX,
+                    }));
+                }
+            }
+            RenderMode::Solid | RenderMode::Points { .. } => {}
+        }
+    }
+    
+    /// Re-upload the material uniform after changing its parameters
+    /// Texture changes need a new bind group, see `upload`
+    pub fn update_material(&self, queue: &wgpu::Queue) {
+        if let Some(ref material_buffer) = self.material_buffer {
+            let material_uniform: crate::MaterialUniform = (&self.material).into();
+            queue.write_buffer(material_buffer, 0, bytemuck::cast_slice(&[material_uniform]));
+        }
+    }
+    
     /// Update the transform matrix
     pub fn update_transform(&mut self, queue: &wgpu::Queue, transform: Mat4) {
         self.transform = transform;
@@ -104,6 +165,39 @@ impl RenderObject {
     }
 }
 
+/// Create the material bind group shared by all mesh pipelines
+/// Binding 0: uniform, 1: sampler, 2..: texture maps in `TextureSlot::ALL` order
+pub(crate) fn create_material_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    material_buffer: &wgpu::Buffer,
+    material: &Material,
+    textures: &TextureCache,
+) -> wgpu::BindGroup {
+    let mut entries = vec![
+        wgpu::BindGroupEntry {
+            binding: 0,
+            resource: material_buffer.as_entire_binding(),
+        },
+        wgpu::BindGroupEntry {
+            binding: 1,
+            resource: wgpu::BindingResource::Sampler(textures.sampler()),
+        },
+    ];
+    for (i, slot) in TextureSlot::ALL.iter().enumerate() {
+        entries.push(wgpu::BindGroupEntry {
+            binding: 2 + i as u32,
+            resource: wgpu::BindingResource::TextureView(&textures.resolve(material, *slot).view),
+        });
+    }
+    
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Material Bind Group"),
+        layout,
+        entries: &entries,
+    })
+}
+
 /// Model uniform data for GPU
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
@@ -113,7 +207,10 @@ pub struct ModelUniform {
 
 /// Descriptor for creating a render object from serialized data
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "RawDescriptor")]
 pub struct RenderObjectDescriptor {
+    /// Format version, always `RenderObjectDescriptor::VERSION` once loaded
+    pub version: u32,
     pub mesh_type: MeshType,
     pub material: Material,
     pub position: [f32; 3],
@@ -122,32 +219,190 @@ pub struct RenderObjectDescriptor {
 }
 
 /// Types of procedural meshes
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Dimensions are in world units, whether written as integers or floats.
+/// Descriptors without a `version` predate float dimensions: their integer
+/// cube, sphere, plane and cylinder dimensions are read as hundredths.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum MeshType {
-    Cube { size: u32 }, // size in fixed point (divide by 100)
+    Cube {
+        size: f32,
+    },
+    Sphere {
+        radius: f32,
+        segments: u32,
+        rings: u32,
+    },
+    Plane {
+        width: f32,
+        height: f32,
+        subdivisions_x: u32,
+        subdivisions_y: u32,
+    },
+    Cylinder {
+        radius: f32,
+        height: f32,
+        segments: u32,
+    },
+    Cone {
+        radius: f32,
+        height: f32,
+        segments: u32,
+    },
+    Capsule {
+        radius: f32,
+        height: f32,
+        segments: u32,
+        rings: u32,
+    },
+    Torus {
+        major_radius: f32,
+        minor_radius: f32,
+        major_segments: u32,
+        minor_segments: u32,
+    },
+    TorusKnot {
+        radius: f32,
+        tube_radius: f32,
+        p: u32,
+        q: u32,
+        tubular_segments: u32,
+        radial_segments: u32,
+    },
+    Icosphere {
+        radius: f32,
+        subdivisions: u32,
+    },
+    Platonic {
+        solid: PlatonicSolid,
+        radius: f32,
+    },
+    Supershape {
+        radius: f32,
+        longitude: Superformula,
+        latitude: Superformula,
+        segments: u32,
+        rings: u32,
+    },
+    /// Terrain from seeded fractal noise
+    Terrain {
+        size: f32,
+        height: f32,
+        resolution: u32,
+        seed: u32,
+        frequency: f32,
+        octaves: u32,
+    },
+}
+
+impl MeshType {
+    /// Generate the mesh this type describes
+    pub fn create_mesh(&self) -> Mesh {
+        use crate::mesh_gen::*;
+
+        match *self {
+            MeshType::Cube { size } => create_cube(size),
+            MeshType::Sphere { radius, segments, rings } => create_sphere(radius, segments, rings),
+            MeshType::Plane { width, height, subdivisions_x, subdivisions_y } => {
+                create_plane(width, height, subdivisions_x, subdivisions_y)
+            }
+            MeshType::Cylinder { radius, height, segments } => create_cylinder(radius, height, segments),
+            MeshType::Cone { radius, height, segments } => create_cone(radius, height, segments),
+            MeshType::Capsule { radius, height, segments, rings } => create_capsule(radius, height, segments, rings),
+            MeshType::Torus { major_radius, minor_radius, major_segments, minor_segments } => {
+                create_torus(major_radius, minor_radius, major_segments, minor_segments)
+            }
+            MeshType::TorusKnot { radius, tube_radius, p, q, tubular_segments, radial_segments } => {
+                create_torus_knot(radius, tube_radius, p, q, tubular_segments, radial_segments)
+            }
+            MeshType::Icosphere { radius, subdivisions } => create_icosphere(radius, subdivisions),
+            MeshType::Platonic { solid, radius } => create_platonic_solid(solid, radius),
+            MeshType::Supershape { radius, longitude, latitude, segments, rings } => {
+                create_supershape(radius, longitude, latitude, segments, rings)
+            }
+            MeshType::Terrain { size, height, resolution, seed, frequency, octaves } => {
+                let heightmap = Heightmap::from_noise(resolution, resolution, seed, frequency, octaves);
+                create_terrain(&heightmap, size, size, height)
+            }
+        }
+    }
+}
+
+/// Mesh types as written before dimensions were floats
+#[derive(Deserialize)]
+enum LegacyMeshType {
+    Cube { size: u32 },
     Sphere { radius: u32, segments: u32, rings: u32 },
     Plane { width: u32, height: u32, subdivisions_x: u32, subdivisions_y: u32 },
     Cylinder { radius: u32, height: u32, segments: u32 },
 }
 
-impl RenderObjectDescriptor {
-    /// Create a render object from this descriptor
-    pub fn create_object(&self) -> RenderObject {
-        use crate::mesh_gen::*;
-        
-        let mesh = match self.mesh_type {
-            MeshType::Cube { size } => create_cube(size as f32 / 100.0),
-            MeshType::Sphere { radius, segments, rings } => {
-                create_sphere(radius as f32 / 100.0, segments, rings)
-            }
-            MeshType::Plane { width, height, subdivisions_x, subdivisions_y } => {
-                create_plane(width as f32 / 100.0, height as f32 / 100.0, subdivisions_x, subdivisions_y)
+impl LegacyMeshType {
+    /// Convert with `unit` world units per stored integer
+    fn into_mesh_type(self, unit: f32) -> MeshType {
+        match self {
+            LegacyMeshType::Cube { size } => MeshType::Cube { size: size as f32 * unit },
+            LegacyMeshType::Sphere { radius, segments, rings } => {
+                MeshType::Sphere { radius: radius as f32 * unit, segments, rings }
             }
-            MeshType::Cylinder { radius, height, segments } => {
-                create_cylinder(radius as f32 / 100.0, height as f32 / 100.0, segments)
+            LegacyMeshType::Plane { width, height, subdivisions_x, subdivisions_y } => MeshType::Plane {
+                width: width as f32 * unit,
+                height: height as f32 * unit,
+                subdivisions_x,
+                subdivisions_y,
+            },
+            LegacyMeshType::Cylinder { radius, height, segments } => {
+                MeshType::Cylinder { radius: radius as f32 * unit, height: height as f32 * unit, segments }
             }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawMeshType {
+    Legacy(LegacyMeshType),
+    Current(MeshType),
+}
+
+/// Descriptor as read from disk, before legacy dimensions are converted
+#[derive(Deserialize)]
+struct RawDescriptor {
+    version: Option<u32>,
+    mesh_type: RawMeshType,
+    material: Material,
+    position: [f32; 3],
+    rotation: [f32; 3],
+    scale: [f32; 3],
+}
+
+impl From<RawDescriptor> for RenderObjectDescriptor {
+    fn from(raw: RawDescriptor) -> Self {
+        let mesh_type = match raw.mesh_type {
+            // Only unversioned files stored hundredths
+            RawMeshType::Legacy(legacy) if raw.version.is_none() => legacy.into_mesh_type(0.01),
+            RawMeshType::Legacy(legacy) => legacy.into_mesh_type(1.0),
+            RawMeshType::Current(mesh_type) => mesh_type,
         };
-        
+        Self {
+            version: Self::VERSION,
+            mesh_type,
+            material: raw.material,
+            position: raw.position,
+            rotation: raw.rotation,
+            scale: raw.scale,
+        }
+    }
+}
+
+impl RenderObjectDescriptor {
+    /// Current descriptor format version
+    pub const VERSION: u32 = 1;
+
+    /// Create a render object from this descriptor
+    pub fn create_object(&self) -> RenderObject {
+        let mesh = self.mesh_type.create_mesh();
+
         // Build transform matrix
         let translation = Mat4::from_translation(self.position.into());
         let rotation = Mat4::from_euler(
@@ -162,3 +417,73 @@ impl RenderObjectDescriptor {
         RenderObject::new(mesh, self.material.clone(), transform)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Descriptor JSON with `mesh_type` and an optional `version`
+    fn descriptor_json(mesh_type: &str, version: Option<u32>) -> String {
+        let mut value = serde_json::to_value(RenderObjectDescriptor {
+            version: RenderObjectDescriptor::VERSION,
+            mesh_type: MeshType::Cube { size: 1.0 },
+            material: Material::default(),
+            position: [0.0; 3],
+            rotation: [0.0; 3],
+            scale: [1.0; 3],
+        })
+        .unwrap();
+        value["mesh_type"] = serde_json::from_str(mesh_type).unwrap();
+        match version {
+            Some(version) => value["version"] = version.into(),
+            None => drop(value.as_object_mut().unwrap().remove("version")),
+        }
+        value.to_string()
+    }
+
+    fn load(mesh_type: &str, version: Option<u32>) -> MeshType {
+        let json = descriptor_json(mesh_type, version);
+        serde_json::from_str::<RenderObjectDescriptor>(&json).unwrap().mesh_type
+    }
+
+    #[test]
+    fn unversioned_integer_dimensions_are_legacy_hundredths() {
+        let cylinder = load(r#"{"Cylinder":{"radius":50,"height":200,"segments":16}}"#, None);
+        assert_eq!(cylinder, MeshType::Cylinder { radius: 0.5, height: 2.0, segments: 16 });
+        assert_eq!(load(r#"{"Cube":{"size":100}}"#, None), MeshType::Cube { size: 1.0 });
+
+        let sphere = load(r#"{"Sphere":{"radius":1.5,"segments":32,"rings":16}}"#, None);
+        assert_eq!(sphere, MeshType::Sphere { radius: 1.5, segments: 32, rings: 16 });
+        assert_eq!(load(r#"{"Cone":{"radius":2,"height":3,"segments":8}}"#, None), MeshType::Cone {
+            radius: 2.0,
+            height: 3.0,
+            segments: 8,
+        });
+    }
+
+    #[test]
+    fn versioned_integer_dimensions_are_world_units() {
+        let version = Some(RenderObjectDescriptor::VERSION);
+        assert_eq!(load(r#"{"Cube":{"size":2}}"#, version), MeshType::Cube { size: 2.0 });
+        let cylinder = load(r#"{"Cylinder":{"radius":1,"height":3,"segments":16}}"#, version);
+        assert_eq!(cylinder, MeshType::Cylinder { radius: 1.0, height: 3.0, segments: 16 });
+
+        let cube: MeshType = serde_json::from_str(r#"{"Cube":{"size":2}}"#).unwrap();
+        assert_eq!(cube, MeshType::Cube { size: 2.0 });
+        assert!(serde_json::from_str::<MeshType>(r#"{"Cube":{"size":"large"}}"#).is_err());
+    }
+
+    #[test]
+    fn float_dimensions_round_trip() {
+        let plane = MeshType::Plane { width: 3.0, height: 0.25, subdivisions_x: 4, subdivisions_y: 2 };
+        let json = serde_json::to_string(&plane).unwrap();
+        assert_eq!(serde_json::from_str::<MeshType>(&json).unwrap(), plane);
+
+        let json = descriptor_json(&json, Some(RenderObjectDescriptor::VERSION));
+        let descriptor: RenderObjectDescriptor = serde_json::from_str(&json).unwrap();
+        assert_eq!(descriptor.mesh_type, plane);
+        let saved = serde_json::to_string(&descriptor).unwrap();
+        assert!(saved.contains(r#""version":1"#));
+        assert_eq!(serde_json::from_str::<RenderObjectDescriptor>(&saved).unwrap().mesh_type, plane);
+    }
+}
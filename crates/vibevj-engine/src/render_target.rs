@@ -1,22 +1,79 @@
+use vibevj_common::{Result, VibeVJError};
+
 /// Render target for rendering to a texture
+///
+/// With multisampling enabled, passes render into `msaa_view` and resolve
+/// into `view`. `texture` is always single-sampled, so it can be sampled,
+/// copied and shown in the GUI regardless of the sample count.
 pub struct RenderTarget {
     pub texture: wgpu::Texture,
     pub view: wgpu::TextureView,
+    pub msaa_texture: Option<wgpu::Texture>,
+    pub msaa_view: Option<wgpu::TextureView>,
     pub depth_texture: wgpu::Texture,
     pub depth_view: wgpu::TextureView,
     pub width: u32,
     pub height: u32,
     pub format: wgpu::TextureFormat,
+    pub sample_count: u32,
 }
 
 impl RenderTarget {
-    /// Create a new render target
+    /// Sample counts that can be requested
+    pub const SAMPLE_COUNTS: [u32; 4] = [1, 2, 4, 8];
+
+    /// Format for HDR scene rendering, tone mapped to the output format afterwards
+    pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+    /// Sample counts supported by an adapter for a color format (with a Depth32Float depth buffer)
+    pub fn supported_sample_counts(
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+    ) -> Vec<u32> {
+        // Without adapter-specific format features only 1 and 4 are guaranteed
+        if !device.features().contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+            return vec![1, 4];
+        }
+
+        let color_flags = adapter.get_texture_format_features(format).flags;
+        let depth_flags = adapter.get_texture_format_features(wgpu::TextureFormat::Depth32Float).flags;
+        Self::SAMPLE_COUNTS
+            .into_iter()
+            .filter(|&count| color_flags.sample_count_supported(count) && depth_flags.sample_count_supported(count))
+            .collect()
+    }
+
+    /// Highest supported sample count not above `requested`
+    pub fn clamp_sample_count(requested: u32, supported: &[u32]) -> u32 {
+        supported
+            .iter()
+            .copied()
+            .filter(|&count| count <= requested)
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Create a new single-sampled render target
     pub fn new(
         device: &wgpu::Device,
         width: u32,
         height: u32,
         format: wgpu::TextureFormat,
         label: Option<&str>,
+    ) -> Self {
+        Self::with_sample_count(device, width, height, format, 1, label)
+    }
+
+    /// Create a new render target with multisampling
+    /// `sample_count` must be one of `SAMPLE_COUNTS` and supported by the adapter
+    pub fn with_sample_count(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+        label: Option<&str>,
     ) -> Self {
         let size = wgpu::Extent3d {
             width,
@@ -24,7 +81,7 @@ impl RenderTarget {
             depth_or_array_layers: 1,
         };
 
-        // Create color texture
+        // Create color (resolve) texture
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label,
             size,
@@ -40,12 +97,53 @@ impl RenderTarget {
 
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        // Create depth texture
+        let (msaa_texture, msaa_view, depth_texture, depth_view) =
+            Self::create_sample_textures(device, size, format, sample_count);
+
+        Self {
+            texture,
+            view,
+            msaa_texture,
+            msaa_view,
+            depth_texture,
+            depth_view,
+            width,
+            height,
+            format,
+            sample_count,
+        }
+    }
+
+    /// Create the multisampled color texture (if any) and the depth texture
+    fn create_sample_textures(
+        device: &wgpu::Device,
+        size: wgpu::Extent3d,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> (Option<wgpu::Texture>, Option<wgpu::TextureView>, wgpu::Texture, wgpu::TextureView) {
+        let (msaa_texture, msaa_view) = if sample_count > 1 {
+            let msaa_texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Render Target MSAA Texture"),
+                size,
+                mip_level_count: 1,
+                sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+            let msaa_view = msaa_texture.create_view(&wgpu::TextureViewDescriptor::default());
+            (Some(msaa_texture), Some(msaa_view))
+        } else {
+            (None, None)
+        };
+
+        // Create depth texture (must match the color sample count)
         let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Render Target Depth Texture"),
             size,
             mip_level_count: 1,
-            sample_count: 1,
+            sample_count,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Depth32Float,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -54,14 +152,62 @@ impl RenderTarget {
 
         let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        Self {
-            texture,
-            view,
-            depth_texture,
-            depth_view,
-            width,
-            height,
-            format,
+        (msaa_texture, msaa_view, depth_texture, depth_view)
+    }
+
+    /// Change the sample count
+    ///
+    /// Only the multisampled and depth textures are recreated; `texture` and
+    /// `view` are kept, so anything registered with them (e.g. egui) stays valid.
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        if self.sample_count == sample_count {
+            return;
+        }
+
+        let size = wgpu::Extent3d {
+            width: self.width,
+            height: self.height,
+            depth_or_array_layers: 1,
+        };
+        let (msaa_texture, msaa_view, depth_texture, depth_view) =
+            Self::create_sample_textures(device, size, self.format, sample_count);
+
+        self.msaa_texture = msaa_texture;
+        self.msaa_view = msaa_view;
+        self.depth_texture = depth_texture;
+        self.depth_view = depth_view;
+        self.sample_count = sample_count;
+    }
+
+    /// View to use as the color attachment of a render pass
+    pub fn color_view(&self) -> &wgpu::TextureView {
+        self.msaa_view.as_ref().unwrap_or(&self.view)
+    }
+
+    /// Resolve target for the color attachment, `None` without multisampling
+    pub fn resolve_target(&self) -> Option<&wgpu::TextureView> {
+        self.msaa_view.as_ref().map(|_| &self.view)
+    }
+
+    /// Color attachment that clears or loads, and resolves when multisampled
+    pub fn color_attachment(&self, load: wgpu::LoadOp<wgpu::Color>) -> wgpu::RenderPassColorAttachment<'_> {
+        wgpu::RenderPassColorAttachment {
+            view: self.color_view(),
+            resolve_target: self.resolve_target(),
+            ops: wgpu::Operations {
+                load,
+                store: wgpu::StoreOp::Store,
+            },
+            depth_slice: None,
+        }
+    }
+
+    /// Multisample state for pipelines drawing into this target
+    pub fn multisample_state(&self) -> wgpu::MultisampleState {
+        wgpu::MultisampleState {
+            count: self.sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
         }
     }
 
@@ -71,7 +217,7 @@ impl RenderTarget {
             return;
         }
 
-        *self = Self::new(device, width, height, self.format, Some("Render Target"));
+        *self = Self::with_sample_count(device, width, height, self.format, self.sample_count, Some("Render Target"));
     }
 
     /// Get the aspect ratio
@@ -86,7 +232,7 @@ impl RenderTarget {
         device: &wgpu::Device,
         queue: &wgpu::Queue,
     ) -> (wgpu::Buffer, u32, u32) {
-        let bytes_per_pixel = 4; // RGBA8
+        let bytes_per_pixel = self.format.block_copy_size(None).unwrap_or(4);
         let unpadded_bytes_per_row = self.width * bytes_per_pixel;
         let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
         let padded_bytes_per_row = (unpadded_bytes_per_row + align - 1) / align * align;
@@ -129,4 +275,39 @@ impl RenderTarget {
 
         (buffer, padded_bytes_per_row, unpadded_bytes_per_row)
     }
+
+    /// Read the resolved color texture back to the CPU
+    ///
+    /// Blocks until the GPU has finished. Returns tightly packed rows
+    /// (`width * bytes_per_pixel` each, row padding removed) in the
+    /// target's format, e.g. RGBA8 for `Rgba8Unorm`/`Rgba8UnormSrgb`.
+    pub fn read_pixels(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Result<Vec<u8>> {
+        let (buffer, padded_bytes_per_row, unpadded_bytes_per_row) = self.copy_to_buffer(device, queue);
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+
+        device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|e| VibeVJError::RenderError(format!("Failed to wait for GPU: {}", e)))?;
+        receiver
+            .recv()
+            .map_err(|e| VibeVJError::RenderError(format!("Buffer map callback dropped: {}", e)))?
+            .map_err(|e| VibeVJError::RenderError(format!("Failed to map readback buffer: {}", e)))?;
+
+        // Strip the per-row alignment padding
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * self.height) as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(padded_bytes_per_row as usize).take(self.height as usize) {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+        buffer.unmap();
+
+        Ok(pixels)
+    }
 }
@@ -0,0 +1,106 @@
+use crate::render_target::RenderTarget;
+
+/// Size, format and sample count of a pooled target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TargetDesc {
+    pub width: u32,
+    pub height: u32,
+    pub format: wgpu::TextureFormat,
+    pub sample_count: u32,
+}
+
+impl TargetDesc {
+    /// Single-sampled target
+    pub fn new(width: u32, height: u32, format: wgpu::TextureFormat) -> Self {
+        Self {
+            width: width.max(1),
+            height: height.max(1),
+            format,
+            sample_count: 1,
+        }
+    }
+
+    pub fn with_sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    /// Description matching an existing target
+    pub fn of(target: &RenderTarget) -> Self {
+        Self {
+            width: target.width,
+            height: target.height,
+            format: target.format,
+            sample_count: target.sample_count,
+        }
+    }
+}
+
+struct PooledTarget {
+    target: RenderTarget,
+    /// Frame the target was released in
+    released: u64,
+}
+
+/// Hands out render targets by size and format and recycles released ones
+///
+/// Released targets are kept for `MAX_IDLE_FRAMES` frames, so switching
+/// back and forth between sizes or resizing a window reuses textures
+/// instead of reallocating every target.
+#[derive(Default)]
+pub struct RenderTargetPool {
+    free: Vec<PooledTarget>,
+    frame: u64,
+}
+
+impl RenderTargetPool {
+    /// Frames a released target is kept before it is dropped
+    pub const MAX_IDLE_FRAMES: u64 = 60;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take a matching free target or create a new one
+    pub fn acquire(&mut self, device: &wgpu::Device, desc: TargetDesc, label: &str) -> RenderTarget {
+        match self.free.iter().position(|pooled| TargetDesc::of(&pooled.target) == desc) {
+            Some(index) => self.free.swap_remove(index).target,
+            None => RenderTarget::with_sample_count(device, desc.width, desc.height, desc.format, desc.sample_count, Some(label)),
+        }
+    }
+
+    /// Return a target for reuse
+    pub fn release(&mut self, target: RenderTarget) {
+        self.free.push(PooledTarget {
+            target,
+            released: self.frame,
+        });
+    }
+
+    /// Replace `target` with one of a new description if it differs
+    /// Returns true if the target changed
+    pub fn resize(&mut self, device: &wgpu::Device, target: &mut RenderTarget, desc: TargetDesc, label: &str) -> bool {
+        if TargetDesc::of(target) == desc {
+            return false;
+        }
+        let old = std::mem::replace(target, self.acquire(device, desc, label));
+        self.release(old);
+        true
+    }
+
+    /// Advance the frame counter and drop targets idle for too long
+    pub fn end_frame(&mut self) {
+        self.frame += 1;
+        let frame = self.frame;
+        self.free.retain(|pooled| frame - pooled.released <= Self::MAX_IDLE_FRAMES);
+    }
+
+    /// Number of free targets held
+    pub fn len(&self) -> usize {
+        self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.free.is_empty()
+    }
+}
@@ -4,7 +4,9 @@ use vibevj_common::{Result, VibeVJError};
 
 /// Main renderer managing the WGPU device, queue, and surface
 pub struct Renderer {
+    pub instance: wgpu::Instance,
     pub surface: wgpu::Surface<'static>,
+    pub adapter: wgpu::Adapter,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
     pub config: wgpu::SurfaceConfiguration,
@@ -41,7 +43,8 @@ impl Renderer {
             .request_device(
                 &wgpu::DeviceDescriptor {
                     label: Some("VibeVJ Device"),
-                    required_features: wgpu::Features::empty(),
+                    // Needed for MSAA sample counts other than 1 and 4
+                    required_features: adapter.features() & wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
                     required_limits: wgpu::Limits::default(),
                     memory_hints: wgpu::MemoryHints::default(),
                     experimental_features: wgpu::ExperimentalFeatures::default(),
@@ -52,7 +55,36 @@ impl Renderer {
             .map_err(|e| VibeVJError::RenderError(format!("Failed to create device: {}", e)))?;
 
         // Configure surface
-        let surface_caps = surface.get_capabilities(&adapter);
+        let config = Self::surface_config(&surface, &adapter, size)?;
+        surface.configure(&device, &config);
+
+        Ok(Self {
+            instance,
+            surface,
+            adapter,
+            device,
+            queue,
+            config,
+            size,
+        })
+    }
+
+    /// Pick an sRGB format and FIFO presentation for a window surface
+    ///
+    /// Fails if the adapter cannot present to the surface, e.g. a window on
+    /// a display driven by another GPU.
+    fn surface_config(
+        surface: &wgpu::Surface,
+        adapter: &wgpu::Adapter,
+        size: winit::dpi::PhysicalSize<u32>,
+    ) -> Result<wgpu::SurfaceConfiguration> {
+        let surface_caps = surface.get_capabilities(adapter);
+        if !adapter.is_surface_supported(surface) || surface_caps.formats.is_empty() || surface_caps.alpha_modes.is_empty() {
+            return Err(VibeVJError::RenderError(format!(
+                "Adapter '{}' cannot present to this surface",
+                adapter.get_info().name
+            )));
+        }
         let surface_format = surface_caps
             .formats
             .iter()
@@ -60,28 +92,31 @@ impl Renderer {
             .find(|f| f.is_srgb())
             .unwrap_or(surface_caps.formats[0]);
 
-        let config = wgpu::SurfaceConfiguration {
+        Ok(wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
-            width: size.width,
-            height: size.height,
+            width: size.width.max(1),
+            height: size.height.max(1),
             present_mode: wgpu::PresentMode::Fifo,
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
-        };
-
-        surface.configure(&device, &config);
-
-        Ok(Self {
-            surface,
-            device,
-            queue,
-            config,
-            size,
         })
     }
 
+    /// Create and configure a surface for another window, presented with this device
+    pub fn create_window_surface(
+        &self,
+        window: std::sync::Arc<Window>,
+    ) -> Result<(wgpu::Surface<'static>, wgpu::SurfaceConfiguration)> {
+        let size = window.inner_size();
+        let surface = self.instance.create_surface(window)
+            .map_err(|e| VibeVJError::RenderError(format!("Failed to create surface: {}", e)))?;
+        let config = Self::surface_config(&surface, &self.adapter, size)?;
+        surface.configure(&self.device, &config);
+        Ok((surface, config))
+    }
+
     /// Resize the surface
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
@@ -104,6 +139,11 @@ impl Renderer {
         self.config.format
     }
 
+    /// MSAA sample counts supported for a render target format
+    pub fn supported_sample_counts(&self, format: wgpu::TextureFormat) -> Vec<u32> {
+        crate::RenderTarget::supported_sample_counts(&self.adapter, &self.device, format)
+    }
+
     /// Get aspect ratio
     pub fn aspect_ratio(&self) -> f32 {
         self.size.width as f32 / self.size.height as f32
@@ -0,0 +1,538 @@
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec2};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use vibevj_common::Color;
+use wgpu::util::DeviceExt;
+use crate::material::TextureSlot;
+use crate::pipeline::BindGroupLayoutBuilder;
+use crate::render_target::RenderTarget;
+use crate::texture_cache::TextureCache;
+
+/// One textured quad in the 2D layer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprite {
+    /// Name in the `TextureCache`, usually a file path
+    pub texture: String,
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Sprites with a greater depth are drawn behind
+    pub depth: f32,
+    /// Counter-clockwise rotation in radians around the pivot
+    pub rotation: f32,
+    /// Point of the sprite placed at `position`, (0, 0) top-left to (1, 1) bottom-right
+    pub pivot: Vec2,
+    /// Part of the texture shown, as UV min and max (x, y, x, y)
+    pub region: [f32; 4],
+    /// Multiplied with the texture
+    pub color: Color,
+}
+
+impl Sprite {
+    /// Whole texture, centred on `position`
+    pub fn new(texture: impl Into<String>, position: Vec2, size: Vec2) -> Self {
+        Self {
+            texture: texture.into(),
+            position,
+            size,
+            depth: 0.0,
+            rotation: 0.0,
+            pivot: Vec2::splat(0.5),
+            region: [0.0, 0.0, 1.0, 1.0],
+            color: Color::WHITE,
+        }
+    }
+
+    pub fn with_depth(mut self, depth: f32) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_pivot(mut self, pivot: Vec2) -> Self {
+        self.pivot = pivot;
+        self
+    }
+
+    pub fn with_region(mut self, region: [f32; 4]) -> Self {
+        self.region = region;
+        self
+    }
+
+    /// Show cell `index` of an atlas split into `columns` x `rows`, row by row from the top-left
+    pub fn with_grid_cell(self, columns: u32, rows: u32, index: u32) -> Self {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let (column, row) = ((index % columns) as f32, ((index / columns) % rows) as f32);
+        let (width, height) = (1.0 / columns as f32, 1.0 / rows as f32);
+        self.with_region([column * width, row * height, (column + 1.0) * width, (row + 1.0) * height])
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// World-space corners: top-left, top-right, bottom-right, bottom-left
+    pub fn corners(&self) -> [Vec2; 4] {
+        let left = -self.pivot.x * self.size.x;
+        let right = left + self.size.x;
+        // World y points up while the pivot, like UVs, runs down
+        let top = self.pivot.y * self.size.y;
+        let bottom = top - self.size.y;
+        let rotation = Vec2::from_angle(self.rotation);
+        [Vec2::new(left, top), Vec2::new(right, top), Vec2::new(right, bottom), Vec2::new(left, bottom)]
+            .map(|corner| self.position + rotation.rotate(corner))
+    }
+}
+
+/// Orthographic camera of the 2D sprite layer
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpriteCamera {
+    /// World position at the centre of the view
+    pub center: Vec2,
+    /// World units visible from bottom to top
+    pub height: f32,
+    /// Counter-clockwise view rotation in radians
+    pub rotation: f32,
+}
+
+impl Default for SpriteCamera {
+    fn default() -> Self {
+        Self {
+            center: Vec2::ZERO,
+            height: 2.0,
+            rotation: 0.0,
+        }
+    }
+}
+
+impl SpriteCamera {
+    /// One world unit per pixel, with the origin at the bottom-left corner
+    pub fn pixels(width: u32, height: u32) -> Self {
+        Self {
+            center: Vec2::new(width as f32, height as f32) * 0.5,
+            height: height as f32,
+            rotation: 0.0,
+        }
+    }
+
+    /// World to clip space for a view of `aspect` width / height
+    pub fn view_projection(&self, aspect: f32) -> Mat4 {
+        let half_height = self.height * 0.5;
+        let half_width = half_height * aspect;
+        Mat4::orthographic_rh(-half_width, half_width, -half_height, half_height, -1.0, 1.0)
+            * Mat4::from_rotation_z(-self.rotation)
+            * Mat4::from_translation(-self.center.extend(0.0))
+    }
+}
+
+/// Sprite corner in world space
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct SpriteVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl SpriteVertex {
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
+            wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x4];
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<SpriteVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+/// Consecutive sprites sharing a texture, drawn with one call
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteDraw {
+    pub texture: String,
+    pub indices: Range<u32>,
+}
+
+/// Sprites sorted and packed into vertex and index buffers
+///
+/// Sprites are ordered back to front by depth so alpha blends correctly,
+/// then by texture among equal depths; each run of one texture becomes a
+/// single draw. Drawn with `SpriteRenderer::render`.
+pub struct SpriteBatch {
+    vertex_buffer: Option<wgpu::Buffer>,
+    index_buffer: Option<wgpu::Buffer>,
+    /// Sprites the buffers can hold
+    capacity: usize,
+    count: u32,
+    draws: Vec<SpriteDraw>,
+}
+
+impl SpriteBatch {
+    pub fn new() -> Self {
+        Self {
+            vertex_buffer: None,
+            index_buffer: None,
+            capacity: 0,
+            count: 0,
+            draws: Vec::new(),
+        }
+    }
+
+    /// Replace the batch contents, growing the buffers when needed
+    pub fn set_sprites(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, sprites: &[Sprite]) {
+        self.count = sprites.len() as u32;
+        self.draws.clear();
+        if sprites.is_empty() {
+            return;
+        }
+        let (vertices, indices) = self.pack(sprites);
+
+        if self.vertex_buffer.is_none() || sprites.len() > self.capacity {
+            self.capacity = sprites.len().next_power_of_two();
+            self.vertex_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Sprite Vertex Buffer"),
+                size: (self.capacity * 4 * std::mem::size_of::<SpriteVertex>()) as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+            self.index_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Sprite Index Buffer"),
+                size: (self.capacity * 6 * std::mem::size_of::<u32>()) as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+        if let (Some(vertex_buffer), Some(index_buffer)) = (&self.vertex_buffer, &self.index_buffer) {
+            queue.write_buffer(vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+            queue.write_buffer(index_buffer, 0, bytemuck::cast_slice(&indices));
+        }
+    }
+
+    /// Sort `sprites` into vertices and indices, recording a draw per texture run
+    fn pack(&mut self, sprites: &[Sprite]) -> (Vec<SpriteVertex>, Vec<u32>) {
+        let mut order: Vec<&Sprite> = sprites.iter().collect();
+        order.sort_by(|a, b| b.depth.total_cmp(&a.depth).then_with(|| a.texture.cmp(&b.texture)));
+
+        let mut vertices = Vec::with_capacity(sprites.len() * 4);
+        let mut indices = Vec::with_capacity(sprites.len() * 6);
+        for sprite in order {
+            let first = vertices.len() as u32;
+            let [u0, v0, u1, v1] = sprite.region;
+            let uvs = [[u0, v0], [u1, v0], [u1, v1], [u0, v1]];
+            for (corner, uv) in sprite.corners().into_iter().zip(uvs) {
+                vertices.push(SpriteVertex {
+                    position: corner.into(),
+                    uv,
+                    color: sprite.color.to_array(),
+                });
+            }
+            let start = indices.len() as u32;
+            indices.extend([0, 1, 2, 0, 2, 3].map(|i| first + i));
+
+            match self.draws.last_mut() {
+                Some(draw) if draw.texture == sprite.texture => draw.indices.end = start + 6,
+                _ => self.draws.push(SpriteDraw {
+                    texture: sprite.texture.clone(),
+                    indices: start..start + 6,
+                }),
+            }
+        }
+        (vertices, indices)
+    }
+
+    pub fn sprite_count(&self) -> u32 {
+        self.count
+    }
+
+    /// Draw calls in the order they are issued
+    pub fn draws(&self) -> &[SpriteDraw] {
+        &self.draws
+    }
+
+    /// Draw the sprites with the sprite pipeline already set on the pass
+    fn draw<'a>(&self, render_pass: &mut wgpu::RenderPass, bind_group: impl Fn(&str) -> Option<&'a wgpu::BindGroup>) {
+        let (Some(vertex_buffer), Some(index_buffer)) = (&self.vertex_buffer, &self.index_buffer) else {
+            return;
+        };
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        for draw in &self.draws {
+            if let Some(bind_group) = bind_group(&draw.texture) {
+                render_pass.set_bind_group(1, bind_group, &[]);
+                render_pass.draw_indexed(draw.indices.clone(), 0, 0..1);
+            }
+        }
+    }
+}
+
+impl Default for SpriteBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Texture bind group of one sprite texture
+struct SpriteTexture {
+    bind_group: wgpu::BindGroup,
+    /// Bound to the white fallback until the texture loads
+    fallback: bool,
+}
+
+/// Draws sprite batches over a render target with a `SpriteCamera`
+///
+/// Sprites are alpha blended in batch order without depth testing, so the
+/// 2D layer always lies over whatever is already in the target.
+pub struct SpriteRenderer {
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    pipeline: wgpu::RenderPipeline,
+    camera_buffer: wgpu::Buffer,
+    camera_bind_group: wgpu::BindGroup,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    textures: HashMap<String, SpriteTexture>,
+    failed: HashSet<String>,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+}
+
+impl SpriteRenderer {
+    /// Create a renderer for targets of `format` with `sample_count` samples
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, sample_count: u32) -> Self {
+        let camera_bind_group_layout = BindGroupLayoutBuilder::new(wgpu::ShaderStages::VERTEX)
+            .uniform_buffer()
+            .build(device, Some("Sprite Camera Bind Group Layout"));
+        let texture_bind_group_layout = BindGroupLayoutBuilder::new(wgpu::ShaderStages::FRAGMENT)
+            .texture(wgpu::TextureViewDimension::D2)
+            .sampler()
+            .build(device, Some("Sprite Texture Bind Group Layout"));
+
+        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Sprite Camera Buffer"),
+            contents: bytemuck::cast_slice(&SpriteCamera::default().view_projection(1.0).to_cols_array_2d()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Sprite Camera Bind Group"),
+            layout: &camera_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: camera_buffer.as_entire_binding(),
+            }],
+        });
+
+        // Clamped so atlas cells at the texture edge don't bleed into the opposite side
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Sprite Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Sprite Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../../assets/shaders/sprites.wgsl").into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Sprite Pipeline Layout"),
+            bind_group_layouts: &[&camera_bind_group_layout, &texture_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = Self::create_pipeline(device, &shader, &pipeline_layout, format, sample_count);
+
+        Self {
+            shader,
+            pipeline_layout,
+            pipeline,
+            camera_buffer,
+            camera_bind_group,
+            texture_bind_group_layout,
+            sampler,
+            textures: HashMap::new(),
+            failed: HashSet::new(),
+            format,
+            sample_count,
+        }
+    }
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        shader: &wgpu::ShaderModule,
+        layout: &wgpu::PipelineLayout,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Sprite Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[SpriteVertex::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    /// Rebuild the pipeline for a new MSAA sample count
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        if self.sample_count != sample_count {
+            self.sample_count = sample_count;
+            self.pipeline = Self::create_pipeline(device, &self.shader, &self.pipeline_layout, self.format, sample_count);
+        }
+    }
+
+    /// View sprites through `camera` on a target of `aspect` width / height
+    pub fn set_camera(&self, queue: &wgpu::Queue, camera: &SpriteCamera, aspect: f32) {
+        let view_proj = camera.view_projection(aspect).to_cols_array_2d();
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&view_proj));
+    }
+
+    /// Load the textures `batches` use into `textures` and bind them
+    ///
+    /// Textures that fail to load are logged once and drawn white. Names
+    /// bound with `bind_view` are left as they are.
+    pub fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, textures: &mut TextureCache, batches: &[&SpriteBatch]) {
+        for draw in batches.iter().flat_map(|batch| batch.draws()) {
+            let name = &draw.texture;
+            if self.textures.get(name).is_some_and(|texture| !texture.fallback) {
+                continue;
+            }
+            if textures.get(name).is_none() && !self.failed.contains(name) {
+                if let Err(e) = textures.load_file(device, queue, name, true) {
+                    log::warn!("Failed to load sprite texture '{}': {}", name, e);
+                    self.failed.insert(name.clone());
+                }
+            }
+
+            let loaded = textures.get(name);
+            if self.textures.contains_key(name) && loaded.is_none() {
+                continue;
+            }
+            let texture = loaded.unwrap_or_else(|| textures.fallback(TextureSlot::Albedo));
+            let bind_group = self.create_bind_group(device, name, &texture.view);
+            self.textures.insert(name.clone(), SpriteTexture {
+                bind_group,
+                fallback: loaded.is_none(),
+            });
+        }
+    }
+
+    /// Draw sprites using texture `name` with `view`, e.g. a video frame
+    ///
+    /// The view is not tracked, so bind it again when it is recreated.
+    pub fn bind_view(&mut self, device: &wgpu::Device, name: &str, view: &wgpu::TextureView) {
+        let bind_group = self.create_bind_group(device, name, view);
+        self.textures.insert(name.to_string(), SpriteTexture {
+            bind_group,
+            fallback: false,
+        });
+    }
+
+    /// Forget the binding of texture `name`, so it is loaded again if used
+    pub fn unbind(&mut self, name: &str) {
+        self.textures.remove(name);
+        self.failed.remove(name);
+    }
+
+    fn create_bind_group(&self, device: &wgpu::Device, name: &str, view: &wgpu::TextureView) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(name),
+            layout: &self.texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        })
+    }
+
+    /// Draw prepared batches over `target`, keeping its contents
+    pub fn render(&self, encoder: &mut wgpu::CommandEncoder, target: &RenderTarget, batches: &[&SpriteBatch]) {
+        if batches.iter().all(|batch| batch.sprite_count() == 0) {
+            return;
+        }
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Sprite Render Pass"),
+            color_attachments: &[Some(target.color_attachment(wgpu::LoadOp::Load))],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        for batch in batches {
+            batch.draw(&mut render_pass, |name| self.textures.get(name).map(|texture| &texture.bind_group));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pivot_and_grid_cell_place_the_quad() {
+        // Pivot (0, 0) is the top-left corner, so the sprite hangs down from its position
+        let corners = Sprite::new("a", Vec2::new(10.0, 10.0), Vec2::new(4.0, 2.0)).with_pivot(Vec2::ZERO).corners();
+        assert_eq!(corners[0], Vec2::new(10.0, 10.0));
+        assert_eq!(corners[2], Vec2::new(14.0, 8.0));
+        assert_eq!(Sprite::new("a", Vec2::ZERO, Vec2::ONE).with_grid_cell(2, 2, 3).region, [0.5, 0.5, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn sorts_back_to_front_then_by_texture() {
+        let sprites = [
+            Sprite::new("disc", Vec2::ZERO, Vec2::ONE),
+            Sprite::new("atlas", Vec2::ZERO, Vec2::ONE).with_depth(1.0),
+            Sprite::new("atlas", Vec2::ZERO, Vec2::ONE),
+            Sprite::new("missing.png", Vec2::ZERO, Vec2::ONE),
+            Sprite::new("atlas", Vec2::ZERO, Vec2::ONE).with_color(Color::new(1.0, 0.0, 0.0, 1.0)),
+        ];
+        let mut batch = SpriteBatch::new();
+        let (vertices, indices) = batch.pack(&sprites);
+        assert_eq!((vertices.len(), indices.len()), (20, 30));
+        assert_eq!(&indices[6..12], [4, 5, 6, 4, 6, 7]);
+
+        // The depth 1 sprite merges into the run of atlas sprites after it
+        let draw = |texture: &str, indices| SpriteDraw { texture: texture.to_string(), indices };
+        assert_eq!(batch.draws(), [draw("atlas", 0..18), draw("disc", 18..24), draw("missing.png", 24..30)]);
+    }
+}
@@ -21,12 +21,49 @@ impl Texture {
         Self::from_image(device, queue, &img.to_rgba8(), label)
     }
 
+    /// Create a texture from raw bytes without sRGB decoding
+    /// Use this for data maps such as normal or metallic-roughness textures
+    pub fn from_bytes_linear(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        bytes: &[u8],
+        label: Option<&str>,
+    ) -> Result<Self> {
+        let img = image::load_from_memory(bytes)
+            .map_err(|e| VibeVJError::RenderError(format!("Failed to load image: {}", e)))?;
+        
+        Self::from_image_with_format(device, queue, &img.to_rgba8(), wgpu::TextureFormat::Rgba8Unorm, label)
+    }
+
     /// Create a texture from an image
     pub fn from_image(
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         img: &image::RgbaImage,
         label: Option<&str>,
+    ) -> Result<Self> {
+        Self::from_image_with_format(device, queue, img, wgpu::TextureFormat::Rgba8UnormSrgb, label)
+    }
+
+    /// Create a 1x1 texture filled with a single color
+    pub fn from_color(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        color: [u8; 4],
+        format: wgpu::TextureFormat,
+        label: Option<&str>,
+    ) -> Result<Self> {
+        let img = image::RgbaImage::from_pixel(1, 1, image::Rgba(color));
+        Self::from_image_with_format(device, queue, &img, format, label)
+    }
+
+    /// Create a texture from an image using a specific RGBA8 format
+    pub fn from_image_with_format(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        img: &image::RgbaImage,
+        format: wgpu::TextureFormat,
+        label: Option<&str>,
     ) -> Result<Self> {
         let dimensions = img.dimensions();
 
@@ -42,7 +79,7 @@ impl Texture {
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            format,
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         });
@@ -81,6 +118,30 @@ impl Texture {
         })
     }
 
+    /// Replace the contents with an image of the same size, e.g. the next frame of an animation
+    pub fn write(&self, queue: &wgpu::Queue, img: &image::RgbaImage) {
+        let (width, height) = img.dimensions();
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            img,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
     /// Create a depth texture
     pub fn create_depth_texture(
         device: &wgpu::Device,
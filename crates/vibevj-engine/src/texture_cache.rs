@@ -0,0 +1,160 @@
+use std::collections::{HashMap, HashSet};
+use vibevj_common::{Result, VibeVJError};
+use crate::material::{Material, TextureSlot};
+use crate::texture::Texture;
+
+/// Cache of loaded textures shared by materials
+///
+/// Textures are keyed by name (usually their file path). Lookups that miss
+/// resolve to 1x1 fallback textures so a material can always be bound.
+pub struct TextureCache {
+    textures: HashMap<String, Texture>,
+    /// Material textures that failed to load, resolved to the fallback
+    failed: HashSet<String>,
+    white: Texture,
+    flat_normal: Texture,
+    sampler: wgpu::Sampler,
+}
+
+impl TextureCache {
+    /// Create a new texture cache with fallback textures
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Result<Self> {
+        let white = Texture::from_color(
+            device,
+            queue,
+            [255, 255, 255, 255],
+            wgpu::TextureFormat::Rgba8Unorm,
+            Some("Fallback White Texture"),
+        )?;
+
+        // Tangent-space +Z, i.e. an unperturbed normal
+        let flat_normal = Texture::from_color(
+            device,
+            queue,
+            [128, 128, 255, 255],
+            wgpu::TextureFormat::Rgba8Unorm,
+            Some("Fallback Normal Texture"),
+        )?;
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Material Sampler"),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Ok(Self {
+            textures: HashMap::new(),
+            failed: HashSet::new(),
+            white,
+            flat_normal,
+            sampler,
+        })
+    }
+
+    /// Insert an already created texture under a name
+    pub fn insert(&mut self, name: impl Into<String>, texture: Texture) {
+        let name = name.into();
+        self.failed.remove(&name);
+        self.textures.insert(name, texture);
+    }
+
+    /// Load a texture from encoded image bytes and cache it under a name
+    pub fn load_bytes(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        name: &str,
+        bytes: &[u8],
+        srgb: bool,
+    ) -> Result<&Texture> {
+        if !self.textures.contains_key(name) {
+            let texture = if srgb {
+                Texture::from_bytes(device, queue, bytes, Some(name))?
+            } else {
+                Texture::from_bytes_linear(device, queue, bytes, Some(name))?
+            };
+            self.insert(name, texture);
+        }
+
+        self.get(name)
+            .ok_or_else(|| VibeVJError::ResourceNotFound(name.to_string()))
+    }
+
+    /// Load a texture from disk, using the path as its name
+    ///
+    /// The color space is fixed on first load; later calls return the cached texture.
+    pub fn load_file(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        path: &str,
+        srgb: bool,
+    ) -> Result<&Texture> {
+        if !self.textures.contains_key(path) {
+            let bytes = std::fs::read(path)?;
+            return self.load_bytes(device, queue, path, &bytes, srgb);
+        }
+
+        self.get(path)
+            .ok_or_else(|| VibeVJError::ResourceNotFound(path.to_string()))
+    }
+
+    /// Make sure every texture referenced by a material is loaded
+    ///
+    /// Textures that fail to load are logged once and left to the fallback;
+    /// `remove` the name to try again.
+    pub fn load_material_textures(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        material: &Material,
+    ) {
+        for (slot, name) in material.textures.iter() {
+            if self.failed.contains(name) {
+                continue;
+            }
+            if let Err(e) = self.load_file(device, queue, name, slot.is_srgb()) {
+                log::warn!("Failed to load {:?} texture '{}': {}", slot, name, e);
+                self.failed.insert(name.to_string());
+            }
+        }
+    }
+
+    /// Get a cached texture by name
+    pub fn get(&self, name: &str) -> Option<&Texture> {
+        self.textures.get(name)
+    }
+
+    /// Remove a texture from the cache
+    pub fn remove(&mut self, name: &str) -> Option<Texture> {
+        self.failed.remove(name);
+        self.textures.remove(name)
+    }
+
+    /// Get the fallback texture for a slot
+    pub fn fallback(&self, slot: TextureSlot) -> &Texture {
+        match slot {
+            TextureSlot::Normal => &self.flat_normal,
+            _ => &self.white,
+        }
+    }
+
+    /// Resolve the texture bound to a material slot, falling back if missing
+    pub fn resolve(&self, material: &Material, slot: TextureSlot) -> &Texture {
+        material
+            .textures
+            .get(slot)
+            .and_then(|name| self.get(name))
+            .unwrap_or_else(|| self.fallback(slot))
+    }
+
+    /// Get the sampler used for material textures
+    pub fn sampler(&self) -> &wgpu::Sampler {
+        &self.sampler
+    }
+}
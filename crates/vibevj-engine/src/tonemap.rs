@@ -0,0 +1,332 @@
+use serde::{Deserialize, Serialize};
+use wgpu::util::DeviceExt;
+use crate::grading::{ColorGrading, CubeLut, GradingUniform};
+use crate::pipeline::BindGroupLayoutBuilder;
+
+/// Tone mapping curve applied when converting HDR to display range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToneMapCurve {
+    /// Scale by the white point and clamp
+    None,
+    /// Extended Reinhard on luminance
+    Reinhard,
+    /// ACES filmic approximation
+    Aces,
+    /// AgX, a softer filmic curve that desaturates bright highlights
+    AgX,
+}
+
+impl ToneMapCurve {
+    pub const ALL: [ToneMapCurve; 4] = [
+        ToneMapCurve::None,
+        ToneMapCurve::Reinhard,
+        ToneMapCurve::Aces,
+        ToneMapCurve::AgX,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToneMapCurve::None => "None",
+            ToneMapCurve::Reinhard => "Reinhard",
+            ToneMapCurve::Aces => "ACES",
+            ToneMapCurve::AgX => "AgX",
+        }
+    }
+
+    fn shader_index(&self) -> u32 {
+        match self {
+            ToneMapCurve::None => 0,
+            ToneMapCurve::Reinhard => 1,
+            ToneMapCurve::Aces => 2,
+            ToneMapCurve::AgX => 3,
+        }
+    }
+}
+
+/// Serializable tone mapping settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToneMapSettings {
+    pub curve: ToneMapCurve,
+    /// Exposure in stops (0 = unchanged, +1 = twice as bright)
+    pub exposure: f32,
+    /// Linear scene value that maps to display white
+    pub white_point: f32,
+}
+
+impl Default for ToneMapSettings {
+    fn default() -> Self {
+        Self {
+            curve: ToneMapCurve::Aces,
+            exposure: 0.0,
+            white_point: 4.0,
+        }
+    }
+}
+
+/// Tone mapping uniform
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ToneMapUniform {
+    pub curve: u32,
+    pub encode_srgb: u32,
+    pub exposure: f32,
+    pub white_point: f32,
+}
+
+impl ToneMapUniform {
+    pub fn new(settings: &ToneMapSettings, output_format: wgpu::TextureFormat) -> Self {
+        Self {
+            curve: settings.curve.shader_index(),
+            // sRGB formats encode on write, others need it in the shader
+            encode_srgb: (!output_format.is_srgb()) as u32,
+            exposure: settings.exposure.exp2(),
+            white_point: settings.white_point,
+        }
+    }
+}
+
+/// Fullscreen pass converting an HDR texture to an LDR output format
+///
+/// The output format can be the swapchain format or an export format such
+/// as `Rgba8Unorm`; sRGB encoding is applied exactly once either way.
+/// Colour grading and the LUT run in the same pass, after the curve.
+pub struct ToneMapper {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: Option<wgpu::BindGroup>,
+    // HDR texture bound in `bind_group`
+    source: Option<wgpu::TextureView>,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+    grading_buffer: wgpu::Buffer,
+    output_format: wgpu::TextureFormat,
+    pub settings: ToneMapSettings,
+    pub grading: ColorGrading,
+
+    // Loaded LUT, with an identity table bound when there is none
+    lut: Option<CubeLut>,
+    lut_view: wgpu::TextureView,
+    // LUT file last requested by `update_grading`, loaded or not
+    lut_path: Option<String>,
+}
+
+impl ToneMapper {
+    /// Create a tone mapper writing to `output_format`
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, output_format: wgpu::TextureFormat, settings: ToneMapSettings) -> Self {
+        let bind_group_layout = BindGroupLayoutBuilder::new(wgpu::ShaderStages::FRAGMENT)
+            .texture(wgpu::TextureViewDimension::D2)
+            .sampler()
+            .uniform_buffer()
+            // Float32 LUTs are read with textureLoad and interpolated in the shader
+            .entry(wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D3,
+                multisampled: false,
+            })
+            .uniform_buffer()
+            .build(device, Some("Tone Map Bind Group Layout"));
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Tone Map Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Tone Map Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[ToneMapUniform::new(&settings, output_format)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let grading = ColorGrading::default();
+        let grading_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Colour Grading Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[GradingUniform::new(&grading, None)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let lut_view = CubeLut::identity(2)
+            .create_texture(device, queue)
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Tone Map Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../../assets/shaders/tonemap.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Tone Map Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Tone Map Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: output_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            bind_group: None,
+            source: None,
+            sampler,
+            uniform_buffer,
+            grading_buffer,
+            output_format,
+            settings,
+            grading,
+            lut: None,
+            lut_view,
+            lut_path: None,
+        }
+    }
+
+    /// Get the output format the pipeline was built for
+    pub fn output_format(&self) -> wgpu::TextureFormat {
+        self.output_format
+    }
+
+    /// Set the HDR texture to tone map
+    /// Call again whenever the source texture is recreated; the bind group is
+    /// only rebuilt when the view changes
+    pub fn set_source(&mut self, device: &wgpu::Device, source: &wgpu::TextureView) {
+        if self.source.as_ref() != Some(source) {
+            self.source = Some(source.clone());
+            self.rebuild_bind_group(device);
+        }
+    }
+
+    fn rebuild_bind_group(&mut self, device: &wgpu::Device) {
+        let Some(source) = &self.source else {
+            return;
+        };
+        self.bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Tone Map Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(&self.lut_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: self.grading_buffer.as_entire_binding(),
+                },
+            ],
+        }));
+    }
+
+    /// Update settings and write them to the GPU
+    pub fn update(&mut self, queue: &wgpu::Queue, settings: ToneMapSettings) {
+        self.settings = settings;
+        let uniform = ToneMapUniform::new(&self.settings, self.output_format);
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Update colour grading and write it to the GPU
+    /// Loads the LUT file when it changed; a file that fails to load is logged once and skipped
+    pub fn update_grading(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, grading: &ColorGrading) {
+        if grading.lut != self.lut_path {
+            self.lut_path = grading.lut.clone();
+            let lut = self.lut_path.as_ref().and_then(|path| match CubeLut::load(path) {
+                Ok(lut) => {
+                    log::info!("Loaded {}³ LUT '{}'", lut.size, path);
+                    Some(lut)
+                }
+                Err(e) => {
+                    log::error!("Failed to load LUT: {}", e);
+                    None
+                }
+            });
+            self.set_lut(device, queue, lut);
+        }
+        self.grading = grading.clone();
+        self.write_grading(queue);
+    }
+
+    /// Replace the LUT, or remove it with `None`
+    pub fn set_lut(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, lut: Option<CubeLut>) {
+        let texture = match &lut {
+            Some(lut) => lut.create_texture(device, queue),
+            None => CubeLut::identity(2).create_texture(device, queue),
+        };
+        self.lut_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        self.lut = lut;
+        self.rebuild_bind_group(device);
+        self.write_grading(queue);
+    }
+
+    fn write_grading(&self, queue: &wgpu::Queue) {
+        let uniform = GradingUniform::new(&self.grading, self.lut.as_ref());
+        queue.write_buffer(&self.grading_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Record the tone mapping pass into `target`
+    pub fn render(&self, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView) {
+        let Some(bind_group) = &self.bind_group else {
+            log::warn!("Tone mapper has no source texture");
+            return;
+        };
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Tone Map Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
@@ -0,0 +1,338 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use vibevj_common::{Result, VibeVJError};
+use super::{VideoDecoder, VideoFrame, VideoInfo, YuvMatrix};
+
+/// Reader for Motion JPEG video in AVI (including OpenDML files over 1 GB)
+///
+/// Each frame is an independent JPEG, so seeking is free. Audio and other
+/// streams are ignored. Frames without Huffman tables rely on the AVI1
+/// marker MJPEG encoders write to get the standard tables.
+pub struct MjpegAviDecoder {
+    reader: BufReader<File>,
+    info: VideoInfo,
+    /// File offset and size of each frame's JPEG data
+    frames: Vec<(u64, u32)>,
+}
+
+/// Header values collected while walking the RIFF tree
+#[derive(Default)]
+struct Index {
+    width: u32,
+    height: u32,
+    /// From the main header, used when the stream header has no rate
+    frame_micros: u32,
+    fps: Option<f64>,
+    /// Number of the stream whose `strl` is being read
+    streams: u32,
+    video_stream: Option<u32>,
+    compression: Option<[u8; 4]>,
+    frames: Vec<(u64, u32)>,
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_fourcc(reader: &mut impl Read) -> Result<[u8; 4]> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn field(body: &[u8], offset: usize) -> u32 {
+    body.get(offset..offset + 4)
+        .map_or(0, |bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+impl MjpegAviDecoder {
+    /// Open a file and index its video frames
+    pub fn open(path: &Path) -> Result<Self> {
+        let error = |message: &str| VibeVJError::SerializationError(format!("{}: {}", path.display(), message));
+        let mut reader = BufReader::new(File::open(path)?);
+        let length = reader.get_ref().metadata()?.len();
+
+        let mut index = Index::default();
+        // OpenDML continues the file in further `RIFF AVIX` chunks
+        let mut position = 0;
+        while position + 12 <= length {
+            reader.seek(SeekFrom::Start(position))?;
+            let id = read_fourcc(&mut reader)?;
+            let size = read_u32(&mut reader)? as u64;
+            let form = read_fourcc(&mut reader)?;
+            match (&id, &form, position) {
+                (b"RIFF", b"AVI ", 0) | (b"RIFF", b"AVIX", 1..) => {}
+                (_, _, 0) => return Err(error("Not an AVI file")),
+                _ => break,
+            }
+            let end = (position + 8 + size).min(length);
+            Self::walk(&mut reader, position + 12, end, &mut index)?;
+            position = end + (end & 1);
+        }
+
+        match index.compression {
+            Some(fourcc) if fourcc.eq_ignore_ascii_case(b"MJPG") => {}
+            Some(fourcc) => {
+                return Err(error(&format!(
+                    "Unsupported codec '{}', only Motion JPEG is supported",
+                    String::from_utf8_lossy(&fourcc)
+                )))
+            }
+            None => return Err(error("No video stream")),
+        }
+
+        let fps = index
+            .fps
+            .or((index.frame_micros > 0).then(|| 1_000_000.0 / index.frame_micros as f64))
+            .unwrap_or(25.0);
+        Ok(Self {
+            reader,
+            info: VideoInfo {
+                width: index.width,
+                height: index.height,
+                fps,
+                frame_count: index.frames.len() as u64,
+            },
+            frames: index.frames,
+        })
+    }
+
+    /// Visit the chunks between `start` and `end`
+    fn walk(reader: &mut BufReader<File>, start: u64, end: u64, index: &mut Index) -> Result<()> {
+        let mut position = start;
+        while position + 8 <= end {
+            reader.seek(SeekFrom::Start(position))?;
+            let id = read_fourcc(reader)?;
+            let size = read_u32(reader)?;
+            let body = position + 8;
+            let next = (body + size as u64).min(end);
+
+            match &id {
+                b"LIST" if size >= 4 => match &read_fourcc(reader)? {
+                    b"hdrl" | b"movi" | b"rec " => Self::walk(reader, body + 4, next, index)?,
+                    b"strl" => {
+                        Self::walk(reader, body + 4, next, index)?;
+                        index.streams += 1;
+                    }
+                    _ => {}
+                },
+                b"avih" | b"strh" | b"strf" => {
+                    let mut header = vec![0; size.min(1024) as usize];
+                    reader.read_exact(&mut header)?;
+                    Self::read_header(&id, &header, index);
+                }
+                // Stream chunks are named by stream number: `00dc`, `01wb`, ...
+                [d0, d1, b'd', b'c' | b'b'] => {
+                    let stream = std::str::from_utf8(&[*d0, *d1]).ok().and_then(|n| n.parse::<u32>().ok());
+                    if stream.is_some() && stream == index.video_stream {
+                        if size > 0 {
+                            index.frames.push((body, size));
+                        } else if let Some(&previous) = index.frames.last() {
+                            // Empty chunks repeat the previous frame
+                            index.frames.push(previous);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            // Chunks are padded to an even size
+            position = next + (next & 1);
+        }
+        Ok(())
+    }
+
+    fn read_header(id: &[u8; 4], header: &[u8], index: &mut Index) {
+        match id {
+            b"avih" => {
+                index.frame_micros = field(header, 0);
+                index.width = field(header, 32);
+                index.height = field(header, 36);
+            }
+            b"strh" if &header[..4.min(header.len())] == b"vids" && index.video_stream.is_none() => {
+                index.video_stream = Some(index.streams);
+                let (scale, rate) = (field(header, 20), field(header, 24));
+                if scale > 0 && rate > 0 {
+                    index.fps = Some(rate as f64 / scale as f64);
+                }
+            }
+            // BITMAPINFOHEADER of the video stream
+            b"strf" if index.video_stream == Some(index.streams) && header.len() >= 20 => {
+                index.width = field(header, 4);
+                index.height = (field(header, 8) as i32).unsigned_abs();
+                index.compression = Some(header[16..20].try_into().unwrap());
+            }
+            _ => {}
+        }
+    }
+}
+
+impl VideoDecoder for MjpegAviDecoder {
+    fn info(&self) -> &VideoInfo {
+        &self.info
+    }
+
+    fn decode(&mut self, index: u64) -> Result<VideoFrame> {
+        let (offset, size) = *self
+            .frames
+            .get(index as usize)
+            .ok_or_else(|| VibeVJError::InvalidOperation(format!("Frame {} is past the end of the video", index)))?;
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let mut data = vec![0; size as usize];
+        self.reader.read_exact(&mut data)?;
+
+        let error = |e: jpeg_decoder::Error| VibeVJError::RenderError(format!("Failed to decode frame {}: {}", index, e));
+        let mut decoder = jpeg_decoder::Decoder::new(data.as_slice());
+        // Keep YCbCr so conversion happens on the GPU; chroma is still upsampled
+        decoder.set_color_transform(jpeg_decoder::ColorTransform::None);
+        let pixels = decoder.decode().map_err(error)?;
+        let info = decoder
+            .info()
+            .ok_or_else(|| VibeVJError::RenderError(format!("Frame {} has no image header", index)))?;
+        let (width, height) = (info.width as u32, info.height as u32);
+
+        let (y, u, v, chroma_width, chroma_height) = match info.pixel_format {
+            jpeg_decoder::PixelFormat::L8 => (pixels, vec![128], vec![128], 1, 1),
+            jpeg_decoder::PixelFormat::RGB24 => {
+                // Untransformed rows hold each component's run in turn, not interleaved pixels
+                let count = (width * height) as usize;
+                let row = width as usize;
+                let (mut y, mut u, mut v) = (Vec::with_capacity(count), Vec::with_capacity(count), Vec::with_capacity(count));
+                for line in pixels.chunks_exact(3 * row) {
+                    y.extend_from_slice(&line[..row]);
+                    u.extend_from_slice(&line[row..2 * row]);
+                    v.extend_from_slice(&line[2 * row..]);
+                }
+                (y, u, v, width, height)
+            }
+            format => {
+                return Err(VibeVJError::RenderError(format!("Unsupported JPEG pixel format {:?}", format)));
+            }
+        };
+
+        // JFIF is always BT.601 with full-range levels
+        Ok(VideoFrame {
+            index,
+            width,
+            height,
+            chroma_width,
+            chroma_height,
+            y,
+            u,
+            v,
+            matrix: YuvMatrix::Bt601,
+            full_range: true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut bytes = id.to_vec();
+        bytes.extend((body.len() as u32).to_le_bytes());
+        bytes.extend(body);
+        if body.len() % 2 == 1 {
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    fn list(kind: &[u8; 4], children: &[Vec<u8>]) -> Vec<u8> {
+        let mut body = kind.to_vec();
+        body.extend(children.concat());
+        chunk(b"LIST", &body)
+    }
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|value| value.to_le_bytes()).collect()
+    }
+
+    fn jpeg(color: [u8; 3]) -> Vec<u8> {
+        let image = image::RgbImage::from_pixel(16, 12, image::Rgb(color));
+        let mut jpeg = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, 95)
+            .encode(&image, 16, 12, image::ColorType::Rgb8)
+            .expect("encode jpeg");
+        jpeg
+    }
+
+    /// 16x12 AVI holding `jpegs` as stream 1 at `rate / scale` fps, after an
+    /// audio stream, with an empty chunk repeating the last frame
+    fn avi(codec: &[u8; 4], jpegs: &[Vec<u8>], rate: u32, scale: u32) -> Vec<u8> {
+        let (width, height) = (16, 12);
+        let frames = jpegs.len() as u32 + 1;
+        let avih = words(&[1_000_000 * scale / rate, 0, 0, 0, frames, 0, 2, 0, width, height, 0, 0, 0, 0]);
+
+        let mut auds = b"auds\0\0\0\0".to_vec();
+        auds.extend(words(&[0, 0, 0, 1, 44100, 0, 0, 0, 0, 0, 0, 0]));
+        let mut vids = b"vids".to_vec();
+        vids.extend(codec);
+        vids.extend(words(&[0, 0, 0, scale, rate, 0, frames, 0, 0, 0, 0, 0]));
+        let mut strf = words(&[40, width, height]);
+        strf.extend([1, 0, 24, 0]);
+        strf.extend(codec);
+        strf.extend(words(&[0, 0, 0, 0, 0]));
+        let hdrl = list(
+            b"hdrl",
+            &[
+                chunk(b"avih", &avih),
+                list(b"strl", &[chunk(b"strh", &auds), chunk(b"strf", &[0; 16])]),
+                list(b"strl", &[chunk(b"strh", &vids), chunk(b"strf", &strf)]),
+            ],
+        );
+
+        let mut movi = vec![chunk(b"00wb", &[0; 7])];
+        movi.extend(jpegs.iter().map(|jpeg| chunk(b"01dc", jpeg)));
+        movi.push(chunk(b"01dc", &[]));
+        let mut body = b"AVI ".to_vec();
+        body.extend(hdrl);
+        body.extend(list(b"movi", &movi));
+        chunk(b"RIFF", &body)
+    }
+
+    /// Write `bytes` to a temporary file and open it
+    fn open_bytes(name: &str, bytes: &[u8]) -> Result<MjpegAviDecoder> {
+        let path = std::env::temp_dir().join(format!("vibevj-{}-{}.avi", std::process::id(), name));
+        std::fs::write(&path, bytes).expect("write avi");
+        let decoder = MjpegAviDecoder::open(&path);
+        std::fs::remove_file(&path).ok();
+        decoder
+    }
+
+    #[test]
+    fn indexes_the_video_stream() {
+        let decoder = open_bytes("index", &avi(b"MJPG", &[jpeg([128; 3]), jpeg([255, 0, 0])], 25, 2)).expect("open avi");
+        let info = decoder.info();
+        assert_eq!((info.width, info.height, info.fps), (16, 12, 12.5));
+        // Audio chunks are skipped and the empty chunk repeats the last frame
+        assert_eq!(info.frame_count, 3);
+        assert_eq!(decoder.frames[1], decoder.frames[2]);
+        assert_ne!(decoder.frames[0], decoder.frames[1]);
+    }
+
+    #[test]
+    fn decodes_frames_as_full_range_bt601() {
+        let mut decoder = open_bytes("decode", &avi(b"MJPG", &[jpeg([128; 3]), jpeg([255, 0, 0])], 25, 1)).expect("open avi");
+        let grey = decoder.decode(0).expect("decode grey");
+        assert!(grey.y.iter().chain(&grey.u).chain(&grey.v).all(|&value| value.abs_diff(128) <= 2));
+        let red = decoder.decode(2).expect("decode repeated frame");
+        assert_eq!((red.width, red.height, red.matrix, red.full_range), (16, 12, YuvMatrix::Bt601, true));
+        assert_eq!(red.y.len(), 16 * 12);
+        assert!(red.y.iter().all(|&y| y.abs_diff(76) <= 2) && red.v.iter().all(|&v| v >= 250));
+        assert!(decoder.decode(3).is_err());
+    }
+
+    #[test]
+    fn rejects_other_files_and_codecs() {
+        assert!(open_bytes("codec", &avi(b"H264", &[jpeg([0; 3])], 25, 1)).is_err());
+        assert!(open_bytes("wave", &chunk(b"RIFF", b"WAVEfmt ")).is_err());
+        let mut body = b"AVI ".to_vec();
+        body.extend(list(b"movi", &[]));
+        assert!(open_bytes("no-video", &chunk(b"RIFF", &body)).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+//! Video decoding and playback
+//!
+//! Pure-Rust decoders that need no system codecs, for transcoded VJ loops:
+//! - Uncompressed YUV4MPEG2 (`.y4m`)
+//! - Motion JPEG in AVI (`.avi`)
+//!
+//! Frames stay in YUV and are converted to RGB on the GPU by `VideoTexture`.
+
+pub mod y4m;
+pub mod avi;
+pub mod player;
+pub mod texture;
+
+pub use player::{VideoPlayback, VideoPlayer};
+pub use texture::VideoTexture;
+
+use std::path::Path;
+use vibevj_common::{Result, VibeVJError};
+
+/// RGB conversion matrix of a YUV stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YuvMatrix {
+    /// SD video and JPEG
+    Bt601,
+    /// HD video
+    Bt709,
+}
+
+/// Stream properties known after opening a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoInfo {
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+    pub frame_count: u64,
+}
+
+impl VideoInfo {
+    /// Length in seconds
+    pub fn duration(&self) -> f64 {
+        self.frame_count as f64 / self.fps
+    }
+}
+
+/// One decoded frame as planar Y, Cb and Cr
+#[derive(Debug, Clone)]
+pub struct VideoFrame {
+    pub index: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Size of the Cb and Cr planes, smaller than the frame when subsampled
+    pub chroma_width: u32,
+    pub chroma_height: u32,
+    pub y: Vec<u8>,
+    pub u: Vec<u8>,
+    pub v: Vec<u8>,
+    pub matrix: YuvMatrix,
+    /// 0-255 levels instead of 16-235 / 16-240
+    pub full_range: bool,
+}
+
+/// Random-access frame decoder for one video file
+pub trait VideoDecoder: Send {
+    fn info(&self) -> &VideoInfo;
+
+    /// Decode frame `index`, which must be below `info().frame_count`
+    fn decode(&mut self, index: u64) -> Result<VideoFrame>;
+}
+
+/// Open a `.y4m` or MJPEG `.avi` file
+pub fn open<P: AsRef<Path>>(path: P) -> Result<Box<dyn VideoDecoder>> {
+    let path = path.as_ref();
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+
+    let decoder: Box<dyn VideoDecoder> = match extension.as_deref() {
+        Some("y4m") => Box::new(y4m::Y4mDecoder::open(path)?),
+        Some("avi") => Box::new(avi::MjpegAviDecoder::open(path)?),
+        _ => {
+            return Err(VibeVJError::InvalidOperation(format!(
+                "Unsupported video format: {} (expected .y4m or MJPEG .avi)",
+                path.display()
+            )))
+        }
+    };
+    if decoder.info().frame_count == 0 {
+        return Err(VibeVJError::InvalidOperation(format!("Video has no frames: {}", path.display())));
+    }
+    Ok(decoder)
+}
@@ -0,0 +1,415 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender, TryRecvError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use vibevj_common::{Result, TimeInfo};
+use super::{VideoDecoder, VideoFrame, VideoInfo};
+
+/// Transport controls for a video
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VideoPlayback {
+    pub playing: bool,
+    /// Start over at the end instead of holding the last frame
+    pub loop_enabled: bool,
+    pub reverse: bool,
+    /// Playback rate (1 = normal)
+    pub speed: f32,
+}
+
+impl Default for VideoPlayback {
+    fn default() -> Self {
+        Self {
+            playing: true,
+            loop_enabled: true,
+            reverse: false,
+            speed: 1.0,
+        }
+    }
+}
+
+// Speeds are never NaN, so bitwise comparison is enough for map keys
+impl Eq for VideoPlayback {}
+
+impl std::hash::Hash for VideoPlayback {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.playing.hash(state);
+        self.loop_enabled.hash(state);
+        self.reverse.hash(state);
+        self.speed.to_bits().hash(state);
+    }
+}
+
+/// Frames the decode thread runs ahead of playback
+const QUEUE_SIZE: usize = 8;
+
+/// Where the decode thread should read from
+struct Request {
+    /// Frames from older requests are discarded when received
+    generation: u64,
+    start: u64,
+    /// +1 forwards, -1 in reverse
+    step: i64,
+    looping: bool,
+}
+
+/// The run of frames the decode thread is producing
+struct Stream {
+    generation: u64,
+    /// Index of the next frame to arrive
+    next: u64,
+    step: i64,
+    looping: bool,
+}
+
+/// Plays a video with decoding on a background thread
+///
+/// The thread decodes ahead of the playhead into a small queue, so only the
+/// GPU upload happens on the render thread. Seeking, changing direction or
+/// falling too far behind restarts the queue at the wanted frame; until it
+/// arrives the previous frame stays on screen.
+pub struct VideoPlayer {
+    info: VideoInfo,
+    requests: Option<Sender<Request>>,
+    frames: Receiver<(u64, Result<VideoFrame>)>,
+    worker: Option<JoinHandle<()>>,
+    stream: Option<Stream>,
+    generation: u64,
+    queue: VecDeque<VideoFrame>,
+    /// Playhead in seconds
+    position: f64,
+    /// Index of the last frame returned
+    shown: Option<u64>,
+    last_update: Option<u64>,
+}
+
+impl VideoPlayer {
+    /// Start a decode thread for `decoder`
+    pub fn new(decoder: Box<dyn VideoDecoder>) -> Self {
+        let info = decoder.info().clone();
+        let (request_sender, request_receiver) = mpsc::channel();
+        let (frame_sender, frame_receiver) = mpsc::sync_channel(QUEUE_SIZE);
+        let worker = std::thread::Builder::new()
+            .name("Video Decoder".to_string())
+            .spawn(move || Self::decode_loop(decoder, request_receiver, frame_sender))
+            .expect("Failed to spawn video decode thread");
+
+        Self {
+            info,
+            requests: Some(request_sender),
+            frames: frame_receiver,
+            worker: Some(worker),
+            stream: None,
+            generation: 0,
+            queue: VecDeque::with_capacity(QUEUE_SIZE),
+            position: 0.0,
+            shown: None,
+            last_update: None,
+        }
+    }
+
+    /// Open a `.y4m` or MJPEG `.avi` file (see `video::open`)
+    pub fn open(path: &str) -> Result<Self> {
+        Ok(Self::new(super::open(path)?))
+    }
+
+    pub fn info(&self) -> &VideoInfo {
+        &self.info
+    }
+
+    /// Playhead in seconds
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// Move the playhead; the frame is fetched on the next update
+    pub fn seek(&mut self, seconds: f64) {
+        self.position = seconds.clamp(0.0, self.info.duration());
+    }
+
+    /// Index of the frame at the playhead
+    pub fn frame_index(&self) -> u64 {
+        ((self.position * self.info.fps) as u64).min(self.info.frame_count - 1)
+    }
+
+    /// Advance the playhead by `time.delta` and return the frame to show if it changed
+    /// Further calls for the same `time.frame` are ignored
+    pub fn update(&mut self, playback: &VideoPlayback, time: &TimeInfo) -> Option<VideoFrame> {
+        if self.last_update == Some(time.frame) {
+            return None;
+        }
+        self.last_update = Some(time.frame);
+
+        if playback.playing {
+            let direction = if playback.reverse { -1.0 } else { 1.0 };
+            let position = self.position + time.delta as f64 * playback.speed as f64 * direction;
+            let duration = self.info.duration();
+            self.position = match playback.loop_enabled {
+                true => position.rem_euclid(duration),
+                false => position.clamp(0.0, duration),
+            };
+        }
+        self.poll(playback, None)
+    }
+
+    /// Like `update` without moving the playhead, but block up to `timeout`
+    /// for the frame at the playhead, e.g. for offline rendering
+    pub fn wait(&mut self, playback: &VideoPlayback, timeout: Duration) -> Option<VideoFrame> {
+        self.poll(playback, Some(Instant::now() + timeout))
+    }
+
+    /// Request and receive frames until the one at the playhead is available
+    fn poll(&mut self, playback: &VideoPlayback, deadline: Option<Instant>) -> Option<VideoFrame> {
+        let wanted = self.frame_index();
+        if self.shown == Some(wanted) {
+            return None;
+        }
+        // Paused video still decodes ahead in the direction it would play
+        let step = if playback.reverse == (playback.speed < 0.0) { 1 } else { -1 };
+        let looping = playback.loop_enabled;
+
+        let reachable = match &self.stream {
+            Some(stream) if stream.step == step && stream.looping == looping => {
+                self.queue.iter().any(|frame| frame.index == wanted)
+                    || self.distance(stream.next, wanted, step).is_some_and(|distance| distance <= QUEUE_SIZE as u64)
+            }
+            _ => false,
+        };
+        if !reachable {
+            self.restart(wanted, step, looping);
+        }
+
+        loop {
+            self.receive(deadline.is_some());
+            // Drop frames the playhead has passed
+            while let Some(front) = self.queue.front() {
+                if front.index == wanted {
+                    self.shown = Some(wanted);
+                    return self.queue.pop_front();
+                }
+                match self.distance(front.index, wanted, step) {
+                    Some(distance) if distance <= QUEUE_SIZE as u64 * 2 => {
+                        self.queue.pop_front();
+                    }
+                    _ => break,
+                }
+            }
+
+            let timed_out = deadline.is_none_or(|deadline| Instant::now() >= deadline);
+            if timed_out || self.stream.is_none() {
+                return None;
+            }
+            if self.queue.len() >= QUEUE_SIZE {
+                // The queue holds only frames past the playhead
+                self.restart(wanted, step, looping);
+            }
+        }
+    }
+
+    /// Frames from `from` to `to` in play order, None if `to` is behind
+    fn distance(&self, from: u64, to: u64, step: i64) -> Option<u64> {
+        let offset = (to as i64 - from as i64) * step;
+        match self.stream.as_ref().is_some_and(|stream| stream.looping) {
+            true => Some(offset.rem_euclid(self.info.frame_count as i64) as u64),
+            false => u64::try_from(offset).ok(),
+        }
+    }
+
+    /// Discard queued frames and decode from `start`
+    fn restart(&mut self, start: u64, step: i64, looping: bool) {
+        self.generation += 1;
+        self.queue.clear();
+        self.stream = Some(Stream {
+            generation: self.generation,
+            next: start,
+            step,
+            looping,
+        });
+        if let Some(requests) = &self.requests {
+            let _ = requests.send(Request {
+                generation: self.generation,
+                start,
+                step,
+                looping,
+            });
+        }
+    }
+
+    /// Move decoded frames into the queue, waiting briefly for one if `block`
+    fn receive(&mut self, block: bool) {
+        while self.queue.len() < QUEUE_SIZE {
+            let received = match block && self.queue.is_empty() {
+                true => self.frames.recv_timeout(Duration::from_millis(5)).map_err(|e| match e {
+                    RecvTimeoutError::Timeout => TryRecvError::Empty,
+                    RecvTimeoutError::Disconnected => TryRecvError::Disconnected,
+                }),
+                false => self.frames.try_recv(),
+            };
+            let Ok((generation, frame)) = received else {
+                return;
+            };
+            let Some(stream) = self.stream.as_mut().filter(|stream| stream.generation == generation) else {
+                continue;
+            };
+            match frame {
+                Ok(frame) => {
+                    let next = frame.index as i64 + stream.step;
+                    stream.next = next.rem_euclid(self.info.frame_count as i64) as u64;
+                    self.queue.push_back(frame);
+                }
+                Err(e) => {
+                    log::error!("Video decoding stopped: {}", e);
+                    self.stream = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Decode thread: follow the latest request until the file ends or the player is dropped
+    fn decode_loop(
+        mut decoder: Box<dyn VideoDecoder>,
+        requests: Receiver<Request>,
+        frames: SyncSender<(u64, Result<VideoFrame>)>,
+    ) {
+        let count = decoder.info().frame_count as i64;
+        let mut request: Option<Request> = None;
+        let mut next = 0;
+        loop {
+            // Block while idle, otherwise only check for a newer request
+            let incoming = match request {
+                Some(_) => match requests.try_recv() {
+                    Ok(incoming) => Some(incoming),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => return,
+                },
+                None => match requests.recv() {
+                    Ok(incoming) => Some(incoming),
+                    Err(_) => return,
+                },
+            };
+            if let Some(incoming) = incoming {
+                next = incoming.start as i64;
+                request = Some(incoming);
+                continue;
+            }
+            let Some(current) = &request else {
+                continue;
+            };
+
+            if !(0..count).contains(&next) {
+                if !current.looping {
+                    request = None;
+                    continue;
+                }
+                next = next.rem_euclid(count);
+            }
+            let frame = decoder.decode(next as u64);
+            let failed = frame.is_err();
+            // Blocks while the queue is full; the player drains stale frames
+            if frames.send((current.generation, frame)).is_err() {
+                return;
+            }
+            next += current.step;
+            if failed {
+                request = None;
+            }
+        }
+    }
+}
+
+impl Drop for VideoPlayer {
+    fn drop(&mut self) {
+        // Closing both channels wakes the thread wherever it is blocked
+        self.requests = None;
+        let (_, receiver) = mpsc::sync_channel(0);
+        drop(std::mem::replace(&mut self.frames, receiver));
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::video::YuvMatrix;
+
+    /// Decoder of `frame_count` blank 2x2 frames at 10 fps
+    struct Frames {
+        info: VideoInfo,
+    }
+
+    impl VideoDecoder for Frames {
+        fn info(&self) -> &VideoInfo {
+            &self.info
+        }
+
+        fn decode(&mut self, index: u64) -> Result<VideoFrame> {
+            Ok(VideoFrame {
+                index,
+                width: 2,
+                height: 2,
+                chroma_width: 1,
+                chroma_height: 1,
+                y: vec![0; 4],
+                u: vec![128],
+                v: vec![128],
+                matrix: YuvMatrix::Bt709,
+                full_range: false,
+            })
+        }
+    }
+
+    fn player(frame_count: u64) -> VideoPlayer {
+        VideoPlayer::new(Box::new(Frames {
+            info: VideoInfo {
+                width: 2,
+                height: 2,
+                fps: 10.0,
+                frame_count,
+            },
+        }))
+    }
+
+    /// Advance by `delta` seconds and wait for the frame at the playhead
+    fn step(player: &mut VideoPlayer, playback: &VideoPlayback, frame: u64, delta: f32) -> Option<u64> {
+        let time = TimeInfo { elapsed: 0.0, delta, frame };
+        let shown = player.update(playback, &time).or_else(|| player.wait(playback, Duration::from_secs(5)));
+        shown.map(|frame| frame.index)
+    }
+
+    #[test]
+    fn steps_forward_looping_and_reversed() {
+        let mut player = player(6);
+        let looped = VideoPlayback::default();
+        assert_eq!(step(&mut player, &looped, 1, 0.0), Some(0));
+        assert_eq!(step(&mut player, &looped, 2, 0.25), Some(2));
+        assert_eq!(step(&mut player, &looped, 3, 0.25), Some(5));
+        // Past the end wraps to the start
+        assert_eq!(step(&mut player, &looped, 4, 0.25), Some(1));
+        let reverse = VideoPlayback { reverse: true, ..looped };
+        assert_eq!(step(&mut player, &reverse, 5, 0.25), Some(5));
+        assert_eq!(step(&mut player, &reverse, 6, 0.25), Some(2));
+        // Repeated frame numbers don't move the playhead
+        assert_eq!(step(&mut player, &reverse, 6, 0.25), None);
+        assert_eq!(player.frame_index(), 2);
+    }
+
+    #[test]
+    fn clamps_without_looping_and_holds_when_paused() {
+        let mut player = player(6);
+        let once = VideoPlayback { loop_enabled: false, speed: 2.0, ..VideoPlayback::default() };
+        assert_eq!(step(&mut player, &once, 1, 0.0), Some(0));
+        assert_eq!(step(&mut player, &once, 2, 0.25), Some(5));
+        assert_eq!(step(&mut player, &once, 3, 0.25), None);
+
+        let paused = VideoPlayback { playing: false, ..VideoPlayback::default() };
+        player.seek(0.35);
+        assert_eq!(step(&mut player, &paused, 4, 0.25), Some(3));
+        assert_eq!(step(&mut player, &paused, 5, 0.25), None);
+        player.seek(100.0);
+        assert_eq!(player.frame_index(), 5);
+    }
+}
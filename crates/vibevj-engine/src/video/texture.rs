@@ -0,0 +1,240 @@
+use wgpu::util::DeviceExt;
+use crate::pipeline::BindGroupLayoutBuilder;
+use crate::render_target::RenderTarget;
+use super::{VideoFrame, YuvMatrix};
+
+/// YCbCr to RGB conversion for one stream
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct YuvUniform {
+    red: [f32; 4],
+    green: [f32; 4],
+    blue: [f32; 4],
+    offset: [f32; 4],
+}
+
+impl YuvUniform {
+    fn new(matrix: YuvMatrix, full_range: bool) -> Self {
+        let (kr, kb) = match matrix {
+            YuvMatrix::Bt601 => (0.299, 0.114),
+            YuvMatrix::Bt709 => (0.2126, 0.0722),
+        };
+        let kg = 1.0 - kr - kb;
+        // Stretch limited-range levels to 0-1 as part of the matrix
+        let (black, luma_scale, chroma_scale) = match full_range {
+            true => (0.0, 1.0, 1.0),
+            false => (16.0 / 255.0, 255.0 / 219.0, 255.0 / 224.0),
+        };
+        let (y, c) = (luma_scale, chroma_scale);
+        Self {
+            red: [y, 0.0, 2.0 * (1.0 - kr) * c, 0.0],
+            green: [y, -2.0 * kb * (1.0 - kb) / kg * c, -2.0 * kr * (1.0 - kr) / kg * c, 0.0],
+            blue: [y, 2.0 * (1.0 - kb) * c, 0.0, 0.0],
+            offset: [black, 128.0 / 255.0, 128.0 / 255.0, 0.0],
+        }
+    }
+}
+
+/// Plane textures for frames of one size
+struct Planes {
+    textures: [wgpu::Texture; 3],
+    bind_group: wgpu::BindGroup,
+    sizes: [(u32, u32); 3],
+}
+
+/// Shows decoded video frames, converting YUV to RGB on the GPU
+///
+/// Frames are uploaded as three `R8Unorm` planes and converted into `target`,
+/// which is resized to match the video.
+pub struct VideoTexture {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+    uniform: Option<YuvUniform>,
+    planes: Option<Planes>,
+    pub target: RenderTarget,
+}
+
+impl VideoTexture {
+    /// Format of `target`; the shader writes linear colour
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    /// Create a texture for frames of `width` x `height`
+    pub fn new(device: &wgpu::Device, width: u32, height: u32, label: Option<&str>) -> Self {
+        let bind_group_layout = BindGroupLayoutBuilder::new(wgpu::ShaderStages::FRAGMENT)
+            .texture(wgpu::TextureViewDimension::D2)
+            .texture(wgpu::TextureViewDimension::D2)
+            .texture(wgpu::TextureViewDimension::D2)
+            .sampler()
+            .uniform_buffer()
+            .build(device, Some("Video Bind Group Layout"));
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Video Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Video Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[YuvUniform::new(YuvMatrix::Bt709, false)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Video Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../../../assets/shaders/video.wgsl").into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Video Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Video Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: Self::FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            uniform_buffer,
+            uniform: None,
+            planes: None,
+            target: RenderTarget::new(device, width.max(1), height.max(1), Self::FORMAT, label),
+        }
+    }
+
+    /// Upload `frame` and record its conversion into `target`
+    pub fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder, frame: &VideoFrame) {
+        let sizes = [
+            (frame.width, frame.height),
+            (frame.chroma_width, frame.chroma_height),
+            (frame.chroma_width, frame.chroma_height),
+        ];
+        if self.planes.as_ref().is_none_or(|planes| planes.sizes != sizes) {
+            self.planes = Some(self.create_planes(device, sizes));
+        }
+        if (self.target.width, self.target.height) != (frame.width, frame.height) {
+            self.target.resize(device, frame.width, frame.height);
+        }
+        let uniform = YuvUniform::new(frame.matrix, frame.full_range);
+        if self.uniform != Some(uniform) {
+            queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+            self.uniform = Some(uniform);
+        }
+
+        let planes = self.planes.as_ref().unwrap();
+        for ((texture, (width, height)), data) in planes.textures.iter().zip(sizes).zip([&frame.y, &frame.u, &frame.v]) {
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                data,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(width),
+                    rows_per_image: Some(height),
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Video Pass"),
+            color_attachments: &[Some(self.target.color_attachment(wgpu::LoadOp::Clear(wgpu::Color::BLACK)))],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &planes.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    fn create_planes(&self, device: &wgpu::Device, sizes: [(u32, u32); 3]) -> Planes {
+        let labels = ["Video Y Plane", "Video Cb Plane", "Video Cr Plane"];
+        let textures: [wgpu::Texture; 3] = std::array::from_fn(|plane| {
+            let (width, height) = sizes[plane];
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(labels[plane]),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::R8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            })
+        });
+        let views = textures.each_ref().map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Video Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&views[0]),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&views[1]),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&views[2]),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: self.uniform_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        Planes { textures, bind_group, sizes }
+    }
+}
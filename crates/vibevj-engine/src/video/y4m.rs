@@ -0,0 +1,258 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use vibevj_common::{Result, VibeVJError};
+use super::{VideoDecoder, VideoFrame, VideoInfo, YuvMatrix};
+
+/// Reader for uncompressed YUV4MPEG2 streams
+///
+/// Supports 8-bit 4:2:0, 4:2:2, 4:4:4 (with alpha, which is dropped) and
+/// mono. The format has no matrix tag, so BT.709 is assumed, matching
+/// `Y4mWriter`; `XCOLORRANGE=FULL` selects full-range levels.
+pub struct Y4mDecoder {
+    reader: BufReader<File>,
+    info: VideoInfo,
+    chroma_width: u32,
+    chroma_height: u32,
+    /// Bytes of each plane in a frame: Y, Cb, Cr and anything after them
+    plane_sizes: [usize; 4],
+    full_range: bool,
+    /// File offset of each frame's pixel data
+    offsets: Vec<u64>,
+}
+
+impl Y4mDecoder {
+    /// Open a file and index its frames
+    pub fn open(path: &Path) -> Result<Self> {
+        let error = |message: String| VibeVJError::SerializationError(format!("{}: {}", path.display(), message));
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let mut params = header.trim_end().split(' ');
+        if params.next() != Some("YUV4MPEG2") {
+            return Err(error("Not a YUV4MPEG2 file".to_string()));
+        }
+
+        let (mut width, mut height, mut fps) = (0u32, 0u32, 25.0);
+        let mut colorspace = "420jpeg";
+        let mut full_range = false;
+        for param in params.filter(|param| !param.is_empty()) {
+            let mut chars = param.chars();
+            let (Some(tag), value) = (chars.next(), chars.as_str()) else { continue };
+            match tag {
+                'W' => width = value.parse().map_err(|_| error(format!("Invalid width '{}'", value)))?,
+                'H' => height = value.parse().map_err(|_| error(format!("Invalid height '{}'", value)))?,
+                'F' => {
+                    let rate = value
+                        .split_once(':')
+                        .and_then(|(numerator, denominator)| Some((numerator.parse::<f64>().ok()?, denominator.parse::<f64>().ok()?)))
+                        .filter(|&(numerator, denominator)| numerator > 0.0 && denominator > 0.0);
+                    fps = match rate {
+                        Some((numerator, denominator)) => numerator / denominator,
+                        None => return Err(error(format!("Invalid frame rate '{}'", value))),
+                    };
+                }
+                'C' => colorspace = value,
+                'X' if value.eq_ignore_ascii_case("COLORRANGE=FULL") => full_range = true,
+                // Interlacing, aspect ratio and other extensions don't affect decoding
+                _ => {}
+            }
+        }
+        if width == 0 || height == 0 {
+            return Err(error("Missing frame size".to_string()));
+        }
+
+        // Plane sizes in u64 so huge headers are rejected instead of wrapping
+        let file_len = reader.get_ref().metadata()?.len();
+        let luma = width as u64 * height as u64;
+        let (chroma_width, chroma_height, extra) = match colorspace {
+            "420jpeg" | "420paldv" | "420mpeg2" | "420" => (width.div_ceil(2), height.div_ceil(2), 0),
+            "422" => (width.div_ceil(2), height, 0),
+            "444" => (width, height, 0),
+            "444alpha" => (width, height, luma),
+            "mono" => (0, 0, 0),
+            other => return Err(error(format!("Unsupported colorspace C{}", other))),
+        };
+        let chroma = chroma_width as u64 * chroma_height as u64;
+        let frame_size = [luma, chroma, chroma, extra]
+            .iter()
+            .try_fold(0u64, |total, &size| total.checked_add(size))
+            .filter(|&size| size <= file_len)
+            .ok_or_else(|| error(format!("Frame size {}x{} is larger than the file", width, height)))?;
+        // Each plane is no bigger than the file, so it fits in memory-sized indices
+        let plane_sizes = [luma as usize, chroma as usize, chroma as usize, extra as usize];
+
+        // Frame headers may carry parameters, so walk them rather than assume a stride
+        let mut offsets = Vec::new();
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            if !line.starts_with(b"FRAME") {
+                return Err(error(format!("Expected FRAME header for frame {}", offsets.len())));
+            }
+            let offset = reader.stream_position()?;
+            if offset + frame_size > file_len {
+                log::warn!("{}: Ignoring truncated frame {}", path.display(), offsets.len());
+                break;
+            }
+            offsets.push(offset);
+            reader.seek_relative(frame_size as i64)?;
+        }
+
+        Ok(Self {
+            reader,
+            info: VideoInfo {
+                width,
+                height,
+                fps,
+                frame_count: offsets.len() as u64,
+            },
+            chroma_width,
+            chroma_height,
+            plane_sizes,
+            full_range,
+            offsets,
+        })
+    }
+}
+
+impl VideoDecoder for Y4mDecoder {
+    fn info(&self) -> &VideoInfo {
+        &self.info
+    }
+
+    fn decode(&mut self, index: u64) -> Result<VideoFrame> {
+        let offset = *self
+            .offsets
+            .get(index as usize)
+            .ok_or_else(|| VibeVJError::InvalidOperation(format!("Frame {} is past the end of the video", index)))?;
+        self.reader.seek(SeekFrom::Start(offset))?;
+
+        let [luma, chroma, _, _] = self.plane_sizes;
+        let mut y = vec![0; luma];
+        self.reader.read_exact(&mut y)?;
+        let (u, v, chroma_width, chroma_height) = if chroma > 0 {
+            let mut u = vec![0; chroma];
+            let mut v = vec![0; chroma];
+            self.reader.read_exact(&mut u)?;
+            self.reader.read_exact(&mut v)?;
+            (u, v, self.chroma_width, self.chroma_height)
+        } else {
+            // Mono: a neutral 1x1 chroma plane
+            (vec![128], vec![128], 1, 1)
+        };
+
+        Ok(VideoFrame {
+            index,
+            width: self.info.width,
+            height: self.info.height,
+            chroma_width,
+            chroma_height,
+            y,
+            u,
+            v,
+            matrix: YuvMatrix::Bt709,
+            full_range: self.full_range,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `bytes` to a temporary file and open it
+    fn open_bytes(name: &str, bytes: &[u8]) -> Result<Y4mDecoder> {
+        let path = std::env::temp_dir().join(format!("vibevj-{}-{}.y4m", std::process::id(), name));
+        std::fs::write(&path, bytes).expect("write y4m");
+        let decoder = Y4mDecoder::open(&path);
+        std::fs::remove_file(&path).ok();
+        decoder
+    }
+
+    fn stream(header: &str, frames: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = format!("{}\n", header).into_bytes();
+        for frame in frames {
+            bytes.extend(b"FRAME\n");
+            bytes.extend(*frame);
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_header_and_planes() {
+        // 4x2 4:2:0 has a 2x1 chroma plane
+        let first: Vec<u8> = (0..12).collect();
+        let mut bytes = stream("YUV4MPEG2 W4 H2 F30000:1001 Ip A1:1 C420jpeg XCOLORRANGE=FULL", &[&first]);
+        // Frame headers may carry their own parameters
+        bytes.extend(b"FRAME Ixyz\n");
+        bytes.extend([7; 12]);
+        let mut decoder = open_bytes("planes", &bytes).expect("open y4m");
+
+        let info = decoder.info();
+        assert_eq!((info.width, info.height, info.frame_count), (4, 2, 2));
+        assert!((info.fps - 29.97).abs() < 0.01);
+        let frame = decoder.decode(0).expect("decode frame");
+        assert_eq!((frame.chroma_width, frame.chroma_height), (2, 1));
+        assert_eq!(frame.y, (0..8).collect::<Vec<u8>>());
+        assert_eq!((frame.u.as_slice(), frame.v.as_slice()), (&[8, 9][..], &[10, 11][..]));
+        assert_eq!((frame.matrix, frame.full_range), (YuvMatrix::Bt709, true));
+        assert_eq!(decoder.decode(1).expect("decode second frame").v, [7, 7]);
+        assert!(decoder.decode(2).is_err());
+    }
+
+    #[test]
+    fn odd_sizes_round_chroma_up() {
+        let mut decoder = open_bytes("odd", &stream("YUV4MPEG2 W3 H3 C420", &[&[0; 9 + 4 + 4]])).expect("open y4m");
+        let frame = decoder.decode(0).expect("decode frame");
+        assert_eq!((frame.chroma_width, frame.chroma_height, frame.u.len()), (2, 2, 4));
+        assert!(!frame.full_range);
+    }
+
+    #[test]
+    fn mono_and_alpha_layouts() {
+        let mut mono = open_bytes("mono", &stream("YUV4MPEG2 W2 H2 Cmono", &[&[1; 4], &[2; 4]])).expect("open mono");
+        assert_eq!(mono.info().frame_count, 2);
+        let frame = mono.decode(1).expect("decode mono");
+        assert_eq!((frame.y, frame.u, frame.chroma_width), (vec![2; 4], vec![128], 1));
+
+        // The alpha plane is skipped, so the second frame starts after it
+        let alpha = [[1u8; 4], [2; 4], [3; 4], [255; 4]].concat();
+        let second = [[4u8; 4], [5; 4], [6; 4], [255; 4]].concat();
+        let mut decoder = open_bytes("alpha", &stream("YUV4MPEG2 W2 H2 C444alpha", &[&alpha, &second])).expect("open 444alpha");
+        assert_eq!(decoder.info().frame_count, 2);
+        let frame = decoder.decode(1).expect("decode 444alpha");
+        assert_eq!((frame.y, frame.u, frame.v), (vec![4; 4], vec![5; 4], vec![6; 4]));
+    }
+
+    #[test]
+    fn truncated_last_frame_is_dropped() {
+        let mut bytes = stream("YUV4MPEG2 W2 H2 C444", &[&[0; 12]]);
+        bytes.extend(b"FRAME\n");
+        bytes.extend([0; 5]);
+        assert_eq!(open_bytes("truncated", &bytes).expect("open y4m").info().frame_count, 1);
+    }
+
+    #[test]
+    fn malformed_headers_are_errors() {
+        assert!(open_bytes("magic", b"RIFF W2 H2\n").is_err());
+        assert!(open_bytes("size", &stream("YUV4MPEG2 W2", &[])).is_err());
+        assert!(open_bytes("rate", &stream("YUV4MPEG2 W2 H2 F30:0", &[])).is_err());
+        assert!(open_bytes("colorspace", &stream("YUV4MPEG2 W2 H2 C411", &[])).is_err());
+        assert!(open_bytes("frame", b"YUV4MPEG2 W1 H1 Cmono\nJUNK\n").is_err());
+        // Multi-byte tags are ignored rather than split mid-character
+        assert!(open_bytes("utf8", "YUV4MPEG2 é W1 H1 Cmono\n".as_bytes()).is_ok());
+        assert!(open_bytes("utf8-size", "YUV4MPEG2 é".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn frame_sizes_beyond_the_file_are_rejected() {
+        let header = "YUV4MPEG2 W4294967295 H4294967295 C444alpha";
+        assert!(open_bytes("overflow", &stream(header, &[&[0; 16]])).is_err());
+        assert!(open_bytes("large", &stream("YUV4MPEG2 W4096 H4096", &[&[0; 16]])).is_err());
+    }
+}
@@ -0,0 +1,602 @@
+use glam::{Mat3, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use wgpu::util::DeviceExt;
+use crate::blit::Blitter;
+use crate::output::AspectFit;
+use crate::pipeline::BindGroupLayoutBuilder;
+
+/// Grid of control points bending an output image
+///
+/// Points are interpolated with Catmull-Rom splines, so the image passes
+/// through every point and stays smooth between them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WarpMesh {
+    pub columns: u32,
+    pub rows: u32,
+    /// Row-major positions in 0..1 image coordinates
+    pub points: Vec<[f32; 2]>,
+}
+
+impl Default for WarpMesh {
+    fn default() -> Self {
+        Self::grid(2, 2)
+    }
+}
+
+impl WarpMesh {
+    /// Largest number of columns or rows
+    pub const MAX_SIZE: u32 = 8;
+
+    /// Undistorted grid of `columns` x `rows` points
+    pub fn grid(columns: u32, rows: u32) -> Self {
+        let (columns, rows) = (columns.clamp(2, Self::MAX_SIZE), rows.clamp(2, Self::MAX_SIZE));
+        let points = (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| [column as f32 / (columns - 1) as f32, row as f32 / (rows - 1) as f32])
+            })
+            .collect();
+        Self { columns, rows, points }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.columns >= 2 && self.rows >= 2 && self.points.len() == (self.columns * self.rows) as usize
+    }
+
+    /// Control point, extrapolated linearly outside the grid
+    fn point(&self, column: i32, row: i32) -> Vec2 {
+        let (last_column, last_row) = (self.columns as i32 - 1, self.rows as i32 - 1);
+        if column < 0 {
+            return 2.0 * self.point(0, row) - self.point(1, row);
+        }
+        if column > last_column {
+            return 2.0 * self.point(last_column, row) - self.point(last_column - 1, row);
+        }
+        if row < 0 {
+            return 2.0 * self.point(column, 0) - self.point(column, 1);
+        }
+        if row > last_row {
+            return 2.0 * self.point(column, last_row) - self.point(column, last_row - 1);
+        }
+        Vec2::from(self.points[(row * self.columns as i32 + column) as usize])
+    }
+
+    /// Warped position of an image point
+    pub fn eval(&self, uv: Vec2) -> Vec2 {
+        if !self.is_valid() {
+            return uv;
+        }
+        let x = uv.x.clamp(0.0, 1.0) * (self.columns - 1) as f32;
+        let y = uv.y.clamp(0.0, 1.0) * (self.rows - 1) as f32;
+        let column = (x.floor() as i32).min(self.columns as i32 - 2);
+        let row = (y.floor() as i32).min(self.rows as i32 - 2);
+        let (s, t) = (x - column as f32, y - row as f32);
+
+        let rows = [-1, 0, 1, 2].map(|r| {
+            let p = [-1, 0, 1, 2].map(|c| self.point(column + c, row + r));
+            catmull_rom(p, s)
+        });
+        catmull_rom(rows, t)
+    }
+
+    /// Change the grid size, keeping the current warp
+    pub fn resize(&mut self, columns: u32, rows: u32) {
+        let mut mesh = Self::grid(columns, rows);
+        for point in &mut mesh.points {
+            *point = self.eval(Vec2::from(*point)).to_array();
+        }
+        *self = mesh;
+    }
+}
+
+fn catmull_rom(p: [Vec2; 4], t: f32) -> Vec2 {
+    let (t2, t3) = (t * t, t * t * t);
+    0.5 * (2.0 * p[1]
+        + (p[2] - p[0]) * t
+        + (2.0 * p[0] - 5.0 * p[1] + 4.0 * p[2] - p[3]) * t2
+        + (3.0 * p[1] - p[0] - 3.0 * p[2] + p[3]) * t3)
+}
+
+/// Brightness ramps at the image edges, where overlapping projectors meet
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EdgeBlend {
+    /// Ramp widths as a fraction of the image
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+    /// Applied to the ramp to match the projector's response
+    pub gamma: f32,
+}
+
+impl Default for EdgeBlend {
+    fn default() -> Self {
+        Self {
+            left: 0.0,
+            right: 0.0,
+            top: 0.0,
+            bottom: 0.0,
+            gamma: 1.0,
+        }
+    }
+}
+
+/// Polygon in window coordinates (0..1) blacking out part of an output
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolygonMask {
+    pub points: Vec<[f32; 2]>,
+    /// Black out everything outside the polygon instead
+    pub invert: bool,
+}
+
+impl PolygonMask {
+    /// Axis-aligned rectangle mask
+    pub fn rectangle(min: [f32; 2], max: [f32; 2]) -> Self {
+        Self {
+            points: vec![min, [max[0], min[1]], max, [min[0], max[1]]],
+            invert: false,
+        }
+    }
+}
+
+/// Which handles the warp editor shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WarpHandles {
+    #[default]
+    Corners,
+    Mesh,
+    Masks,
+}
+
+impl WarpHandles {
+    pub const ALL: [WarpHandles; 3] = [WarpHandles::Corners, WarpHandles::Mesh, WarpHandles::Masks];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarpHandles::Corners => "Corners",
+            WarpHandles::Mesh => "Mesh",
+            WarpHandles::Masks => "Masks",
+        }
+    }
+
+    /// The next kind, wrapping around
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|kind| kind == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// Geometry correction of an output: corner pin, mesh warp, edge blend and masks
+///
+/// The image is bent by the mesh, then pinned to the corners. Corners are
+/// relative to the rectangle the output's aspect mode fits the image into.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WarpSettings {
+    /// Top-left, top-right, bottom-right and bottom-left image corners
+    pub corners: [[f32; 2]; 4],
+    pub mesh: WarpMesh,
+    pub edge_blend: EdgeBlend,
+    pub masks: Vec<PolygonMask>,
+}
+
+impl Default for WarpSettings {
+    fn default() -> Self {
+        Self {
+            corners: Self::UNIT_CORNERS,
+            mesh: WarpMesh::default(),
+            edge_blend: EdgeBlend::default(),
+            masks: Vec::new(),
+        }
+    }
+}
+
+impl WarpSettings {
+    /// Corners of the undistorted image
+    pub const UNIT_CORNERS: [[f32; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+    /// Masks beyond these limits are ignored
+    pub const MAX_MASKS: usize = 8;
+    pub const MAX_MASK_POINTS: usize = 64;
+
+    /// Perspective transform taking the unit square to the corners
+    pub fn homography(&self) -> Mat3 {
+        let [p0, p1, p2, p3] = self.corners.map(Vec2::from);
+        let (d1, d2, d3) = (p1 - p2, p3 - p2, p0 - p1 + p2 - p3);
+        let det = d1.x * d2.y - d2.x * d1.y;
+        let (g, h) = if d3.length_squared() < 1e-12 || det.abs() < 1e-12 {
+            (0.0, 0.0)
+        } else {
+            ((d3.x * d2.y - d2.x * d3.y) / det, (d1.x * d3.y - d3.x * d1.y) / det)
+        };
+        Mat3::from_cols(
+            Vec3::new(p1.x - p0.x + g * p1.x, p1.y - p0.y + g * p1.y, g),
+            Vec3::new(p3.x - p0.x + h * p3.x, p3.y - p0.y + h * p3.y, h),
+            Vec3::new(p0.x, p0.y, 1.0),
+        )
+    }
+
+    /// Window position (0..1) of a point of the fitted rectangle
+    fn rect_to_window(point: Vec2, fit: &AspectFit, target_size: (u32, u32)) -> Vec2 {
+        (Vec2::from(fit.offset) + point * Vec2::from(fit.size)) / target_vec(target_size)
+    }
+
+    /// Point of the fitted rectangle at a window position (0..1)
+    fn window_to_rect(position: Vec2, fit: &AspectFit, target_size: (u32, u32)) -> Vec2 {
+        (position * target_vec(target_size) - Vec2::from(fit.offset)) / Vec2::from(fit.size).max(Vec2::ONE)
+    }
+
+    /// Window positions (0..1) of the handles of one kind
+    pub fn handles(&self, kind: WarpHandles, fit: &AspectFit, target_size: (u32, u32)) -> Vec<[f32; 2]> {
+        let homography = self.homography();
+        let to_window = |point: Vec2| Self::rect_to_window(point, fit, target_size).to_array();
+        match kind {
+            WarpHandles::Corners => self.corners.iter().map(|&corner| to_window(Vec2::from(corner))).collect(),
+            WarpHandles::Mesh => self
+                .mesh
+                .points
+                .iter()
+                .map(|&point| to_window(project(homography, Vec2::from(point))))
+                .collect(),
+            WarpHandles::Masks => self.masks.iter().flat_map(|mask| mask.points.iter().copied()).collect(),
+        }
+    }
+
+    /// Move a handle to a window position (0..1)
+    pub fn move_handle(&mut self, kind: WarpHandles, index: usize, position: [f32; 2], fit: &AspectFit, target_size: (u32, u32)) {
+        let point = Self::window_to_rect(Vec2::from(position), fit, target_size);
+        match kind {
+            WarpHandles::Corners => {
+                if let Some(corner) = self.corners.get_mut(index) {
+                    *corner = point.to_array();
+                }
+            }
+            WarpHandles::Mesh => {
+                let inverse = self.homography().inverse();
+                if let Some(mesh_point) = self.mesh.points.get_mut(index) {
+                    *mesh_point = project(inverse, point).to_array();
+                }
+            }
+            WarpHandles::Masks => {
+                if let Some(mask_point) = self.masks.iter_mut().flat_map(|mask| mask.points.iter_mut()).nth(index) {
+                    *mask_point = position;
+                }
+            }
+        }
+    }
+
+    /// Triangles covering the warped image, in clip space
+    fn vertices(&self, fit: &AspectFit, target_size: (u32, u32)) -> Vec<WarpVertex> {
+        let homography = self.homography();
+        let target = target_vec(target_size);
+        let (offset, size) = (Vec2::from(fit.offset), Vec2::from(fit.size));
+
+        // Homogeneous clip coordinates keep the corner pin perspective-correct
+        let vertex = |uv: Vec2| {
+            let p = homography * self.mesh.eval(uv).extend(1.0);
+            let x = 2.0 * (offset.x * p.z + p.x * size.x) / target.x - p.z;
+            let y = p.z - 2.0 * (offset.y * p.z + p.y * size.y) / target.y;
+            WarpVertex {
+                position: [x, y, 0.0, p.z],
+                uv: uv.to_array(),
+            }
+        };
+
+        let n = WARP_SUBDIVISIONS;
+        let mut vertices = Vec::with_capacity((n * n * 6) as usize);
+        for row in 0..n {
+            for column in 0..n {
+                let uv = |c: u32, r: u32| Vec2::new(c as f32 / n as f32, r as f32 / n as f32);
+                let (a, b) = (vertex(uv(column, row)), vertex(uv(column + 1, row)));
+                let (c, d) = (vertex(uv(column, row + 1)), vertex(uv(column + 1, row + 1)));
+                vertices.extend([a, c, b, b, c, d]);
+            }
+        }
+        vertices
+    }
+}
+
+/// Apply a perspective transform to a point
+fn project(transform: Mat3, point: Vec2) -> Vec2 {
+    let p = transform * point.extend(1.0);
+    p.truncate() / p.z
+}
+
+fn target_vec(target_size: (u32, u32)) -> Vec2 {
+    Vec2::new(target_size.0.max(1) as f32, target_size.1.max(1) as f32)
+}
+
+/// Quads per side of the tessellated image
+const WARP_SUBDIVISIONS: u32 = 32;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct WarpVertex {
+    position: [f32; 4],
+    uv: [f32; 2],
+}
+
+impl WarpVertex {
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x2];
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<WarpVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct HandleVertex {
+    position: [f32; 2],
+    color: [f32; 4],
+}
+
+impl HandleVertex {
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4];
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<HandleVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+/// Warp shader parameters
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct WarpUniform {
+    uv_min: [f32; 2],
+    uv_max: [f32; 2],
+    /// Left, right, top and bottom ramp widths
+    edge_blend: [f32; 4],
+    target_size: [f32; 2],
+    blend_gamma: f32,
+    mask_count: u32,
+    /// First point, point count and invert flag of each mask
+    mask_ranges: [[u32; 4]; WarpSettings::MAX_MASKS],
+    /// Two points per element
+    mask_points: [[f32; 4]; WarpSettings::MAX_MASK_POINTS / 2],
+}
+
+impl WarpUniform {
+    fn new(settings: &WarpSettings, fit: &AspectFit, target_size: (u32, u32)) -> Self {
+        let blend = settings.edge_blend;
+        let mut uniform = Self {
+            uv_min: fit.uv_min,
+            uv_max: fit.uv_max,
+            edge_blend: [blend.left, blend.right, blend.top, blend.bottom],
+            target_size: target_vec(target_size).to_array(),
+            blend_gamma: blend.gamma.max(0.01),
+            mask_count: 0,
+            mask_ranges: [[0; 4]; WarpSettings::MAX_MASKS],
+            mask_points: [[0.0; 4]; WarpSettings::MAX_MASK_POINTS / 2],
+        };
+
+        let mut start = 0;
+        for mask in settings.masks.iter().take(WarpSettings::MAX_MASKS) {
+            if mask.points.len() < 3 || start + mask.points.len() > WarpSettings::MAX_MASK_POINTS {
+                continue;
+            }
+            uniform.mask_ranges[uniform.mask_count as usize] = [start as u32, mask.points.len() as u32, mask.invert as u32, 0];
+            for point in &mask.points {
+                let element = &mut uniform.mask_points[start / 2];
+                element[(start % 2) * 2] = point[0];
+                element[(start % 2) * 2 + 1] = point[1];
+                start += 1;
+            }
+            uniform.mask_count += 1;
+        }
+        uniform
+    }
+}
+
+/// Draws a texture into an output window with its `WarpSettings`
+pub struct Warper {
+    pipeline: wgpu::RenderPipeline,
+    handle_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+}
+
+impl Warper {
+    /// Color of the window outside the image and of masked areas
+    pub const BAR_COLOR: wgpu::Color = Blitter::BAR_COLOR;
+    /// Edge length of edit handles in pixels
+    pub const HANDLE_SIZE: f32 = 10.0;
+    const HANDLE_COLOR: [f32; 4] = [1.0, 0.6, 0.1, 1.0];
+    const SELECTED_HANDLE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+    /// Create a warper writing to `output_format`
+    pub fn new(device: &wgpu::Device, output_format: wgpu::TextureFormat) -> Self {
+        let bind_group_layout = BindGroupLayoutBuilder::new(wgpu::ShaderStages::VERTEX_FRAGMENT)
+            .texture(wgpu::TextureViewDimension::D2)
+            .sampler()
+            .uniform_buffer()
+            .build(device, Some("Warp Bind Group Layout"));
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Warp Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Warp Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../../../assets/shaders/warp.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Warp Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let handle_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Warp Handle Pipeline Layout"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |label, layout, vertex_entry, fragment_entry, buffer| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some(vertex_entry),
+                    buffers: &[buffer],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some(fragment_entry),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: output_format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+        let pipeline = create_pipeline("Warp Pipeline", &pipeline_layout, "vs_main", "fs_main", WarpVertex::desc());
+        let handle_pipeline = create_pipeline("Warp Handle Pipeline", &handle_layout, "vs_handle", "fs_handle", HandleVertex::desc());
+
+        Self {
+            pipeline,
+            handle_pipeline,
+            bind_group_layout,
+            sampler,
+        }
+    }
+
+    /// Draw `source` into `target` (of `target_size` pixels), fitted by `fit` and
+    /// warped by `settings`, with edit `handles` (window positions, 0..1) on top
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+        target_size: (u32, u32),
+        fit: &AspectFit,
+        settings: &WarpSettings,
+        handles: &[[f32; 2]],
+        selected: Option<usize>,
+    ) {
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Warp Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[WarpUniform::new(settings, fit, target_size)]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let vertices = settings.vertices(fit, target_size);
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Warp Vertex Buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Warp Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        // Handles are squares of a fixed pixel size
+        let half = Vec2::splat(Self::HANDLE_SIZE * 0.5) / target_vec(target_size) * 2.0;
+        let handle_vertices: Vec<HandleVertex> = handles
+            .iter()
+            .enumerate()
+            .flat_map(|(index, &position)| {
+                let center = Vec2::new(position[0] * 2.0 - 1.0, 1.0 - position[1] * 2.0);
+                let color = if selected == Some(index) { Self::SELECTED_HANDLE_COLOR } else { Self::HANDLE_COLOR };
+                let corner = |x: f32, y: f32| HandleVertex {
+                    position: (center + half * Vec2::new(x, y)).to_array(),
+                    color,
+                };
+                [corner(-1.0, -1.0), corner(1.0, -1.0), corner(-1.0, 1.0), corner(-1.0, 1.0), corner(1.0, -1.0), corner(1.0, 1.0)]
+            })
+            .collect();
+        let handle_buffer = (!handle_vertices.is_empty()).then(|| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Warp Handle Buffer"),
+                contents: bytemuck::cast_slice(&handle_vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            })
+        });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Warp Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(Self::BAR_COLOR),
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        render_pass.draw(0..vertices.len() as u32, 0..1);
+
+        if let Some(handle_buffer) = &handle_buffer {
+            render_pass.set_pipeline(&self.handle_pipeline);
+            render_pass.set_vertex_buffer(0, handle_buffer.slice(..));
+            render_pass.draw(0..handle_vertices.len() as u32, 0..1);
+        }
+    }
+
+    /// Fill `target` with `BAR_COLOR`, for outputs with nothing to show
+    pub fn clear(&self, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView) {
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Warp Clear Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(Self::BAR_COLOR),
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+    }
+}
@@ -1,8 +1,29 @@
 use egui::{Context, ViewportId};
 use egui_wgpu::Renderer as EguiRenderer;
 use vibevj_common::TimeInfo;
+use vibevj_engine::{BlendMode, CameraControllerKind, CameraInput, CameraRig, CrossfadeCurve, Deck, Layer, LayerSource, MixerSettings, Playback, PlaybackMode, VideoPlayback};
+use vibevj_engine::{AspectMode, ColorGrading, CropRect, OutputSettings, OutputSource, OutputWindowSettings, PolygonMask, Projection, Resolution, ToneMapCurve, ToneMapSettings, WarpMesh, WarpSettings};
 use crate::panels::{LeftPanel, CenterPanel, RightPanel, PanelContent};
 
+/// Camera options shown in the Render menu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraMenu {
+    pub kind: CameraControllerKind,
+    pub projection: Projection,
+    /// Shake trauma added on each beat
+    pub beat_shake: f32,
+}
+
+impl CameraMenu {
+    pub fn from_rig(rig: &CameraRig) -> Self {
+        Self {
+            kind: rig.controller.kind(),
+            projection: rig.projection,
+            beat_shake: rig.shake.beat_trauma,
+        }
+    }
+}
+
 /// Main GUI application
 pub struct GuiApp {
     context: Context,
@@ -11,10 +32,26 @@ pub struct GuiApp {
     center_panel: CenterPanel,
     right_panel: RightPanel,
     render_texture_id: Option<egui::TextureId>,
-    show_preview_window: bool,
     audio_devices: Vec<String>,
     selected_audio_device_index: usize,
     audio_device_changed: bool,
+    msaa_options: Vec<u32>,
+    msaa_samples: u32,
+    msaa_changed: bool,
+    tone_mapping: ToneMapSettings,
+    tone_mapping_changed: bool,
+    color_grading: ColorGrading,
+    color_grading_changed: bool,
+    camera: CameraMenu,
+    camera_changed: bool,
+    mixer: MixerSettings,
+    mixer_changed: bool,
+    video_restart: Option<String>,
+    output: OutputSettings,
+    output_changed: bool,
+    outputs: Vec<OutputWindowSettings>,
+    outputs_changed: bool,
+    monitors: Vec<String>,
 }
 
 impl GuiApp {
@@ -39,10 +76,26 @@ impl GuiApp {
             center_panel: CenterPanel::new(),
             right_panel: RightPanel::new(),
             render_texture_id: None,
-            show_preview_window: false,
             audio_devices: Vec::new(),
             selected_audio_device_index: 0,
             audio_device_changed: false,
+            msaa_options: vec![1],
+            msaa_samples: 1,
+            msaa_changed: false,
+            tone_mapping: ToneMapSettings::default(),
+            tone_mapping_changed: false,
+            color_grading: ColorGrading::default(),
+            color_grading_changed: false,
+            camera: CameraMenu::from_rig(&CameraRig::default()),
+            camera_changed: false,
+            mixer: MixerSettings::default(),
+            mixer_changed: false,
+            video_restart: None,
+            output: OutputSettings::default(),
+            output_changed: false,
+            outputs: Vec::new(),
+            outputs_changed: false,
+            monitors: Vec::new(),
         }
     }
     
@@ -71,7 +124,111 @@ impl GuiApp {
         }
     }
     
+    /// Set the selectable MSAA sample counts and the current one
+    pub fn set_msaa_options(&mut self, sample_counts: Vec<u32>, current: u32) {
+        self.msaa_options = sample_counts;
+        self.msaa_samples = current;
+    }
+    
+    /// Get the MSAA sample count if the user changed it
+    pub fn take_msaa_change(&mut self) -> Option<u32> {
+        if self.msaa_changed {
+            self.msaa_changed = false;
+            Some(self.msaa_samples)
+        } else {
+            None
+        }
+    }
+    
+    /// Set the tone mapping settings shown in the Render menu
+    pub fn set_tone_mapping(&mut self, settings: ToneMapSettings) {
+        self.tone_mapping = settings;
+    }
+    
+    /// Get the tone mapping settings if the user changed them
+    pub fn take_tone_mapping_change(&mut self) -> Option<ToneMapSettings> {
+        if self.tone_mapping_changed {
+            self.tone_mapping_changed = false;
+            Some(self.tone_mapping)
+        } else {
+            None
+        }
+    }
+    
+    /// Set the colour grading shown in the Render menu
+    pub fn set_color_grading(&mut self, grading: ColorGrading) {
+        self.color_grading = grading;
+    }
+    
+    /// Get the colour grading if the user changed it
+    pub fn take_color_grading_change(&mut self) -> Option<ColorGrading> {
+        if self.color_grading_changed {
+            self.color_grading_changed = false;
+            Some(self.color_grading.clone())
+        } else {
+            None
+        }
+    }
+    
+    /// Set the camera options shown in the Render menu
+    pub fn set_camera(&mut self, camera: CameraMenu) {
+        self.camera = camera;
+    }
+    
+    /// Get the camera options if the user changed them
+    pub fn take_camera_change(&mut self) -> Option<CameraMenu> {
+        if self.camera_changed {
+            self.camera_changed = false;
+            Some(self.camera)
+        } else {
+            None
+        }
+    }
+    
+    /// Set the layer mixer shown in the Mixer menu
+    pub fn set_mixer(&mut self, mixer: MixerSettings) {
+        self.mixer = mixer;
+    }
+    
+    /// Get the mixer settings if the user changed them
+    pub fn take_mixer_change(&mut self) -> Option<MixerSettings> {
+        if self.mixer_changed {
+            self.mixer_changed = false;
+            Some(self.mixer.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Get the path of a video layer the user asked to play from the start
+    pub fn take_video_restart(&mut self) -> Option<String> {
+        self.video_restart.take()
+    }
+    
+    /// Set the output settings shown in the Render menu
+    pub fn set_output(&mut self, output: OutputSettings) {
+        self.output = output;
+    }
+    
+    /// Get the output settings if the user changed them
+    pub fn take_output_change(&mut self) -> Option<OutputSettings> {
+        if self.output_changed {
+            self.output_changed = false;
+            Some(self.output)
+        } else {
+            None
+        }
+    }
+    
+    /// Take the mouse and keyboard camera input gathered over the preview
+    pub fn take_camera_input(&mut self) -> CameraInput {
+        let mut input = self.center_panel.take_camera_input();
+        input.accumulate(&self.left_panel.take_camera_input());
+        input
+    }
+    
     /// Register a render target texture to display in preview
+    /// Calling it again (after the target was resized) points the existing ID at the new texture
     pub fn register_render_texture(
         &mut self,
         device: &wgpu::Device,
@@ -79,11 +236,12 @@ impl GuiApp {
         texture: &wgpu::Texture,
         size: [u32; 2],
     ) -> egui::TextureId {
-        let texture_id = self.renderer.register_native_texture(
-            device,
-            &texture.create_view(&wgpu::TextureViewDescriptor::default()),
-            wgpu::FilterMode::Linear,
-        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        if let Some(texture_id) = self.render_texture_id {
+            self.renderer.update_egui_texture_from_wgpu_texture(device, &view, wgpu::FilterMode::Linear, texture_id);
+            return texture_id;
+        }
+        let texture_id = self.renderer.register_native_texture(device, &view, wgpu::FilterMode::Linear);
         self.render_texture_id = Some(texture_id);
         texture_id
     }
@@ -98,14 +256,24 @@ impl GuiApp {
         &self.context
     }
     
-    /// Check if the preview window should be shown
-    pub fn should_show_preview_window(&self) -> bool {
-        self.show_preview_window
+    /// Set the output windows shown in the Window menu
+    pub fn set_outputs(&mut self, outputs: Vec<OutputWindowSettings>) {
+        self.outputs = outputs;
+    }
+    
+    /// Get the output windows if the user changed them
+    pub fn take_outputs_change(&mut self) -> Option<Vec<OutputWindowSettings>> {
+        if self.outputs_changed {
+            self.outputs_changed = false;
+            Some(self.outputs.clone())
+        } else {
+            None
+        }
     }
     
-    /// Set whether the preview window should be shown
-    pub fn set_show_preview_window(&mut self, show: bool) {
-        self.show_preview_window = show;
+    /// Set the monitor names outputs can be assigned to
+    pub fn set_monitors(&mut self, monitors: Vec<String>) {
+        self.monitors = monitors;
     }
 
     /// Update the GUI
@@ -114,6 +282,8 @@ impl GuiApp {
         self.left_panel.update(time);
         self.center_panel.update(time);
         self.right_panel.update(time);
+        self.left_panel.set_output(self.output);
+        self.center_panel.set_output(self.output);
         
         // Update render texture placement based on current panel content
         if let Some(texture_id) = self.render_texture_id {
@@ -192,10 +362,145 @@ impl GuiApp {
                     });
                 });
                 
+                // Render menu
+                ui.menu_button("Render", |ui| {
+                    ui.menu_button("Anti-aliasing (MSAA)", |ui| {
+                        for &samples in &self.msaa_options {
+                            let label = if samples == 1 { "Off".to_string() } else { format!("{}x", samples) };
+                            if ui.selectable_label(samples == self.msaa_samples, label).clicked() {
+                                self.msaa_samples = samples;
+                                self.msaa_changed = true;
+                                ui.close();
+                            }
+                        }
+                    });
+                    
+                    ui.menu_button("Tone Mapping", |ui| {
+                        let mut changed = false;
+                        for curve in ToneMapCurve::ALL {
+                            if ui.selectable_label(self.tone_mapping.curve == curve, curve.name()).clicked() {
+                                self.tone_mapping.curve = curve;
+                                changed = true;
+                            }
+                        }
+                        ui.separator();
+                        changed |= ui.add(egui::Slider::new(&mut self.tone_mapping.exposure, -5.0..=5.0).text("Exposure (EV)")).changed();
+                        changed |= ui.add(egui::Slider::new(&mut self.tone_mapping.white_point, 1.0..=16.0).text("White Point")).changed();
+                        if changed {
+                            self.tone_mapping_changed = true;
+                        }
+                    });
+                    
+                    ui.menu_button("Colour Grading", |ui| {
+                        if color_grading_ui(ui, &mut self.color_grading) {
+                            self.color_grading_changed = true;
+                        }
+                    });
+                    
+                    ui.menu_button("Output", |ui| {
+                        let mut changed = false;
+                        for resolution in Resolution::PRESETS {
+                            if ui.selectable_label(self.output.resolution == resolution, resolution.name()).clicked() {
+                                self.output.resolution = resolution;
+                                changed = true;
+                            }
+                        }
+                        let (mut width, mut height) = self.output.output_size();
+                        ui.horizontal(|ui| {
+                            let custom = matches!(self.output.resolution, Resolution::Custom { .. });
+                            ui.label(if custom { "Custom:" } else { "Size:" });
+                            let mut resized = ui.add(egui::DragValue::new(&mut width).range(16..=8192)).changed();
+                            ui.label("x");
+                            resized |= ui.add(egui::DragValue::new(&mut height).range(16..=8192)).changed();
+                            if resized {
+                                self.output.resolution = Resolution::Custom { width, height };
+                                changed = true;
+                            }
+                        });
+                        ui.separator();
+                        ui.label("Render Scale");
+                        ui.horizontal(|ui| {
+                            for scale in OutputSettings::RENDER_SCALES {
+                                if ui.selectable_label(self.output.render_scale == scale, format!("{}%", (scale * 100.0) as u32)).clicked() {
+                                    self.output.render_scale = scale;
+                                    changed = true;
+                                }
+                            }
+                        });
+                        ui.separator();
+                        ui.label("Aspect Ratio");
+                        for mode in AspectMode::ALL {
+                            if ui.selectable_label(self.output.aspect_mode == mode, mode.name()).clicked() {
+                                self.output.aspect_mode = mode;
+                                changed = true;
+                            }
+                        }
+                        if changed {
+                            self.output_changed = true;
+                        }
+                    });
+                    
+                    ui.menu_button("Camera", |ui| {
+                        let mut changed = false;
+                        for kind in CameraControllerKind::ALL {
+                            if ui.selectable_label(self.camera.kind == kind, kind.name()).clicked() {
+                                self.camera.kind = kind;
+                                changed = true;
+                            }
+                        }
+                        ui.separator();
+                        let mut orthographic = matches!(self.camera.projection, Projection::Orthographic { .. });
+                        if ui.checkbox(&mut orthographic, "Orthographic").changed() {
+                            self.camera.projection = if orthographic {
+                                Projection::Orthographic { height: 5.0 }
+                            } else {
+                                Projection::Perspective
+                            };
+                            changed = true;
+                        }
+                        if let Projection::Orthographic { height } = &mut self.camera.projection {
+                            changed |= ui.add(egui::Slider::new(height, 0.5..=30.0).text("View Height")).changed();
+                        }
+                        changed |= ui.add(egui::Slider::new(&mut self.camera.beat_shake, 0.0..=1.0).text("Shake on Beat")).changed();
+                        if changed {
+                            self.camera_changed = true;
+                        }
+                    });
+                });
+                
+                // Mixer menu
+                ui.menu_button("Mixer", |ui| {
+                    let mut changed = false;
+                    let crossfader = &mut self.mixer.crossfader;
+                    changed |= ui.add(egui::Slider::new(&mut crossfader.position, 0.0..=1.0).text("A / B")).changed();
+                    ui.horizontal(|ui| {
+                        for curve in CrossfadeCurve::ALL {
+                            if ui.selectable_label(crossfader.curve == curve, curve.name()).clicked() {
+                                crossfader.curve = curve;
+                                changed = true;
+                            }
+                        }
+                    });
+                    ui.separator();
+                    changed |= deck_ui(ui, "Deck A", &mut self.mixer.deck_a, &mut self.video_restart);
+                    changed |= deck_ui(ui, "Deck B", &mut self.mixer.deck_b, &mut self.video_restart);
+                    if changed {
+                        self.mixer_changed = true;
+                    }
+                });
+                
                 // Window menu
                 ui.menu_button("Window", |ui| {
-                    if ui.checkbox(&mut self.show_preview_window, "Show Preview Window").changed() {
-                        // State has changed, will be checked by main app
+                    // Outputs can show the mix or any layer source used in it
+                    let mut sources = vec![OutputSource::Mix, OutputSource::Layer(LayerSource::Scene)];
+                    for layer in self.mixer.deck_a.layers.iter().chain(&self.mixer.deck_b.layers) {
+                        let source = OutputSource::Layer(layer.source.clone());
+                        if !sources.contains(&source) {
+                            sources.push(source);
+                        }
+                    }
+                    if outputs_ui(ui, &mut self.outputs, &sources, &self.monitors) {
+                        self.outputs_changed = true;
                     }
                 });
                 
@@ -246,3 +551,280 @@ impl GuiApp {
         &mut self.renderer
     }
 }
+
+/// Example generator added by the Mixer menu
+const EXAMPLE_SHADER_LAYER: &str = "assets/shaders/layers/plasma.wgsl";
+
+/// Layer list of one deck, bottom layer first
+/// Returns true if anything changed; a restarted video's path goes to `video_restart`
+fn deck_ui(ui: &mut egui::Ui, label: &str, deck: &mut Deck, video_restart: &mut Option<String>) -> bool {
+    let mut changed = false;
+    let mut remove = None;
+    ui.collapsing(label, |ui| {
+        for (index, layer) in deck.layers.iter_mut().enumerate() {
+            ui.push_id(index, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(&layer.name);
+                    changed |= ui.toggle_value(&mut layer.mute, "M").changed();
+                    changed |= ui.toggle_value(&mut layer.solo, "S").changed();
+                    if ui.small_button("x").clicked() {
+                        remove = Some(index);
+                    }
+                });
+                changed |= ui.add(egui::Slider::new(&mut layer.opacity, 0.0..=1.0).text("Opacity")).changed();
+                egui::ComboBox::from_label("Blend")
+                    .selected_text(layer.blend_mode.name())
+                    .show_ui(ui, |ui| {
+                        for mode in BlendMode::ALL {
+                            changed |= ui.selectable_value(&mut layer.blend_mode, mode, mode.name()).changed();
+                        }
+                    });
+                if layer.blend_mode == BlendMode::LumaKey {
+                    changed |= ui.add(egui::Slider::new(&mut layer.key_threshold, 0.0..=1.0).text("Key Threshold")).changed();
+                    changed |= ui.add(egui::Slider::new(&mut layer.key_softness, 0.0..=1.0).text("Key Softness")).changed();
+                }
+                if let LayerSource::Image { path, playback } = &mut layer.source {
+                    ui.horizontal(|ui| {
+                        ui.label("File");
+                        changed |= path_edit(ui, path, "clip.gif or frames/####.png");
+                    });
+                    ui.horizontal(|ui| {
+                        changed |= ui
+                            .add(egui::DragValue::new(&mut playback.fps).range(0..=120).prefix("FPS "))
+                            .on_hover_text("0 plays at the file's own timing")
+                            .changed();
+                        egui::ComboBox::from_id_salt("playback_mode")
+                            .selected_text(playback.mode.name())
+                            .show_ui(ui, |ui| {
+                                for mode in PlaybackMode::ALL {
+                                    changed |= ui.selectable_value(&mut playback.mode, mode, mode.name()).changed();
+                                }
+                            });
+                        changed |= ui.toggle_value(&mut playback.beat_step, "Beat").on_hover_text("Step one frame per beat").changed();
+                    });
+                }
+                if let LayerSource::Video { path, playback } = &mut layer.source {
+                    ui.horizontal(|ui| {
+                        ui.label("File");
+                        changed |= path_edit(ui, path, "clip.y4m or clip.avi (MJPEG)");
+                    });
+                    ui.horizontal(|ui| {
+                        let label = if playback.playing { "Pause" } else { "Play" };
+                        if ui.button(label).clicked() {
+                            playback.playing = !playback.playing;
+                            changed = true;
+                        }
+                        if ui.button("Restart").clicked() {
+                            *video_restart = Some(path.clone());
+                        }
+                        changed |= ui.toggle_value(&mut playback.loop_enabled, "Loop").changed();
+                        changed |= ui.toggle_value(&mut playback.reverse, "Reverse").changed();
+                    });
+                    changed |= ui.add(egui::Slider::new(&mut playback.speed, 0.0..=4.0).text("Speed")).changed();
+                }
+                ui.separator();
+            });
+        }
+        ui.horizontal(|ui| {
+            if ui.button("+ Scene").clicked() {
+                deck.layers.push(Layer::new("Scene", LayerSource::Scene));
+                changed = true;
+            }
+            if ui.button("+ Plasma").clicked() {
+                let source = LayerSource::Shader { path: EXAMPLE_SHADER_LAYER.to_string() };
+                deck.layers.push(Layer::new("Plasma", source).with_blend_mode(BlendMode::Screen));
+                changed = true;
+            }
+            if ui.button("+ Image").clicked() {
+                let source = LayerSource::Image { path: String::new(), playback: Playback::default() };
+                deck.layers.push(Layer::new("Image", source));
+                changed = true;
+            }
+            if ui.button("+ Video").clicked() {
+                let source = LayerSource::Video { path: String::new(), playback: VideoPlayback::default() };
+                deck.layers.push(Layer::new("Video", source));
+                changed = true;
+            }
+        });
+    });
+    if let Some(index) = remove {
+        deck.layers.remove(index);
+        changed = true;
+    }
+    changed
+}
+
+/// Single-line file path field
+/// The typed text is kept until editing finishes, so files load once; returns true then if the path changed
+fn path_edit(ui: &mut egui::Ui, path: &mut String, hint: &str) -> bool {
+    let id = ui.id().with("path_edit");
+    let mut text = ui.data(|data| data.get_temp::<String>(id)).unwrap_or_else(|| path.clone());
+    let response = ui.add(egui::TextEdit::singleline(&mut text).hint_text(hint).desired_width(180.0));
+    if response.changed() {
+        ui.data_mut(|data| data.insert_temp(id, text.clone()));
+    }
+    if response.lost_focus() {
+        ui.data_mut(|data| data.remove::<String>(id));
+        let text = text.trim();
+        if text != path {
+            *path = text.to_string();
+            return true;
+        }
+    }
+    false
+}
+
+/// Lift/gamma/gain, colour controls and LUT of the grade
+/// Returns true if anything changed
+fn color_grading_ui(ui: &mut egui::Ui, grading: &mut ColorGrading) -> bool {
+    let mut changed = false;
+    for (label, values, range) in [
+        ("Lift", &mut grading.lift, -0.5..=0.5),
+        ("Gamma", &mut grading.gamma, 0.2..=3.0),
+        ("Gain", &mut grading.gain, 0.0..=3.0),
+    ] {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            for (value, channel) in values.iter_mut().zip(["R", "G", "B"]) {
+                changed |= ui.add(egui::DragValue::new(value).range(range.clone()).speed(0.005).prefix(format!("{} ", channel))).changed();
+            }
+        });
+    }
+    changed |= ui.add(egui::Slider::new(&mut grading.contrast, 0.0..=2.0).text("Contrast")).changed();
+    changed |= ui.add(egui::Slider::new(&mut grading.saturation, 0.0..=2.0).text("Saturation")).changed();
+    changed |= ui.add(egui::Slider::new(&mut grading.hue, -180.0..=180.0).text("Hue (deg)")).changed();
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label("LUT (.cube)");
+        let mut path = grading.lut.clone().unwrap_or_default();
+        if path_edit(ui, &mut path, "path/to/look.cube") {
+            grading.lut = (!path.is_empty()).then_some(path);
+            changed = true;
+        }
+        if grading.lut.is_some() && ui.small_button("x").clicked() {
+            grading.lut = None;
+            changed = true;
+        }
+    });
+    changed |= ui.add(egui::Slider::new(&mut grading.lut_intensity, 0.0..=1.0).text("LUT Intensity")).changed();
+    if ui.button("Reset").clicked() {
+        *grading = ColorGrading::default();
+        changed = true;
+    }
+    changed
+}
+
+/// Output window list with source, monitor and crop of each
+/// Returns true if anything changed
+fn outputs_ui(ui: &mut egui::Ui, outputs: &mut Vec<OutputWindowSettings>, sources: &[OutputSource], monitors: &[String]) -> bool {
+    let mut changed = false;
+    let mut remove = None;
+    ui.label("Outputs");
+    for (index, output) in outputs.iter_mut().enumerate() {
+        ui.push_id(index, |ui| {
+            ui.horizontal(|ui| {
+                changed |= ui.checkbox(&mut output.enabled, "").changed();
+                changed |= ui.add(egui::TextEdit::singleline(&mut output.name).desired_width(100.0)).changed();
+                if ui.small_button("x").clicked() {
+                    remove = Some(index);
+                }
+            });
+            egui::ComboBox::from_label("Source")
+                .selected_text(output.source.name())
+                .show_ui(ui, |ui| {
+                    for source in sources {
+                        changed |= ui.selectable_value(&mut output.source, source.clone(), source.name()).changed();
+                    }
+                });
+            egui::ComboBox::from_label("Monitor")
+                .selected_text(output.monitor.as_deref().unwrap_or("Floating"))
+                .show_ui(ui, |ui| {
+                    changed |= ui.selectable_value(&mut output.monitor, None, "Floating").changed();
+                    for monitor in monitors {
+                        changed |= ui.selectable_value(&mut output.monitor, Some(monitor.clone()), monitor).changed();
+                    }
+                });
+            changed |= ui.checkbox(&mut output.fullscreen, "Fullscreen").changed();
+            ui.horizontal(|ui| {
+                ui.label("Crop");
+                let crop = &mut output.crop;
+                for (value, label) in [(&mut crop.x, "x"), (&mut crop.y, "y"), (&mut crop.width, "w"), (&mut crop.height, "h")] {
+                    changed |= ui.add(egui::DragValue::new(value).range(0.0..=1.0).speed(0.005).prefix(format!("{} ", label)).max_decimals(3)).changed();
+                }
+                if ui.small_button("Full").clicked() {
+                    *crop = CropRect::FULL;
+                    changed = true;
+                }
+            });
+            ui.collapsing("Warp", |ui| {
+                changed |= warp_ui(ui, &mut output.warp);
+            });
+            ui.separator();
+        });
+    }
+    if ui.button("+ Output").clicked() {
+        outputs.push(OutputWindowSettings::new(format!("Output {}", outputs.len() + 1)));
+        changed = true;
+    }
+    if let Some(index) = remove {
+        outputs.remove(index);
+        changed = true;
+    }
+    changed
+}
+
+/// Projection mapping controls of an output; the handles are dragged in the output window
+/// Returns true if anything changed
+fn warp_ui(ui: &mut egui::Ui, warp: &mut WarpSettings) -> bool {
+    let mut changed = false;
+    ui.label("E in the output window edits handles, Tab switches corners/mesh/masks");
+    if ui.button("Reset Corners").clicked() {
+        warp.corners = WarpSettings::UNIT_CORNERS;
+        changed = true;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Mesh");
+        let (mut columns, mut rows) = (warp.mesh.columns, warp.mesh.rows);
+        let size = 2..=WarpMesh::MAX_SIZE;
+        let resized = ui.add(egui::DragValue::new(&mut columns).range(size.clone()).prefix("cols ")).changed()
+            | ui.add(egui::DragValue::new(&mut rows).range(size).prefix("rows ")).changed();
+        if resized {
+            warp.mesh.resize(columns, rows);
+            changed = true;
+        }
+        if ui.small_button("Reset").clicked() {
+            warp.mesh = WarpMesh::grid(warp.mesh.columns, warp.mesh.rows);
+            changed = true;
+        }
+    });
+
+    ui.label("Edge Blend");
+    let blend = &mut warp.edge_blend;
+    for (value, label) in [(&mut blend.left, "Left"), (&mut blend.right, "Right"), (&mut blend.top, "Top"), (&mut blend.bottom, "Bottom")] {
+        changed |= ui.add(egui::Slider::new(value, 0.0..=0.5).text(label)).changed();
+    }
+    changed |= ui.add(egui::Slider::new(&mut blend.gamma, 0.5..=3.0).text("Gamma")).changed();
+
+    ui.label("Masks");
+    let mut remove = None;
+    for (index, mask) in warp.masks.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!("Mask {} ({} points)", index + 1, mask.points.len()));
+            changed |= ui.checkbox(&mut mask.invert, "Invert").changed();
+            if ui.small_button("x").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = remove {
+        warp.masks.remove(index);
+        changed = true;
+    }
+    if warp.masks.len() < WarpSettings::MAX_MASKS && ui.button("+ Mask").clicked() {
+        warp.masks.push(PolygonMask::rectangle([0.4, 0.4], [0.6, 0.6]));
+        changed = true;
+    }
+    changed
+}
@@ -12,6 +12,6 @@ pub mod panels;
 pub mod widgets;
 pub mod scene_editor;
 
-pub use app::GuiApp;
+pub use app::{CameraMenu, GuiApp};
 pub use panels::{LeftPanel, CenterPanel, RightPanel, PanelContent};
 pub use scene_editor::SceneEditor;
@@ -1,6 +1,7 @@
 use egui::Ui;
 use vibevj_common::TimeInfo;
 use vibevj_engine::texture;
+use vibevj_engine::{CameraInput, OutputSettings};
 use crate::scene_editor::SceneEditor;
 
 /// Content types for the center panel
@@ -11,11 +12,61 @@ pub enum PanelContent {
     Sequencer,
 }
 
+/// Pixels of scroll per zoom step
+const SCROLL_STEP: f32 = 50.0;
+
+/// Show the render texture and read camera input over it
+///
+/// The image is placed in `area` by the output's aspect mode. Left drag
+/// rotates, right or middle drag pans, scroll zooms. While the pointer is
+/// over the image, WASD/QE fly and Shift boosts.
+fn preview_image(ui: &mut Ui, texture_id: egui::TextureId, area: egui::Vec2, output: &OutputSettings, input: &mut CameraInput) {
+    let (rect, response) = ui.allocate_exact_size(area, egui::Sense::click_and_drag());
+    let (width, height) = output.output_size();
+    let fit = output.aspect_mode.fit((width as f32, height as f32), (area.x, area.y));
+    let image_rect = egui::Rect::from_min_size(
+        rect.min + egui::vec2(fit.offset[0], fit.offset[1]),
+        egui::vec2(fit.size[0], fit.size[1]),
+    );
+    let uv = egui::Rect::from_min_max(egui::pos2(fit.uv_min[0], fit.uv_min[1]), egui::pos2(fit.uv_max[0], fit.uv_max[1]));
+    ui.painter().rect_filled(rect, 0.0, egui::Color32::BLACK);
+    ui.painter().image(texture_id, image_rect, uv, egui::Color32::WHITE);
+
+    let drag = response.drag_delta();
+    let drag = glam::Vec2::new(drag.x, drag.y);
+    if response.dragged_by(egui::PointerButton::Primary) {
+        input.rotate += drag;
+    } else if response.dragged_by(egui::PointerButton::Secondary) || response.dragged_by(egui::PointerButton::Middle) {
+        input.pan += drag;
+    }
+
+    if response.hovered() || response.dragged() {
+        ui.input(|i| {
+            input.zoom += i.smooth_scroll_delta.y / SCROLL_STEP;
+            let axis = |positive: egui::Key, negative: egui::Key| {
+                i.key_down(positive) as i32 as f32 - i.key_down(negative) as i32 as f32
+            };
+            let movement = glam::Vec3::new(
+                axis(egui::Key::D, egui::Key::A),
+                axis(egui::Key::E, egui::Key::Q),
+                axis(egui::Key::W, egui::Key::S),
+            );
+            input.accumulate(&CameraInput {
+                movement,
+                boost: i.modifiers.shift,
+                ..Default::default()
+            });
+        });
+    }
+}
+
 /// Left panel - Main render preview and controls
 pub struct LeftPanel {
     fps: f32,
     show_stats: bool,
     render_texture: Option<egui::TextureId>,
+    output: OutputSettings,
+    camera_input: CameraInput,
 }
 
 impl LeftPanel {
@@ -24,6 +75,8 @@ impl LeftPanel {
             fps: 0.0,
             show_stats: true,
             render_texture: None,
+            output: OutputSettings::default(),
+            camera_input: CameraInput::default(),
         }
     }
 
@@ -37,8 +90,18 @@ impl LeftPanel {
     pub fn set_render_texture(&mut self, texture_id: Option<egui::TextureId>) {
         self.render_texture = texture_id;
     }
+    
+    /// Set the output the preview matches in aspect ratio
+    pub fn set_output(&mut self, output: OutputSettings) {
+        self.output = output;
+    }
+
+    /// Take the camera input gathered over the preview since the last call
+    pub fn take_camera_input(&mut self) -> CameraInput {
+        std::mem::take(&mut self.camera_input)
+    }
 
-    pub fn render_preview(&self, ui: &mut Ui, texture_id: egui::TextureId) {
+    pub fn render_preview(&mut self, ui: &mut Ui, texture_id: egui::TextureId) {
         ui.heading("Render Preview");
         ui.separator();
         // Preview area
@@ -46,11 +109,10 @@ impl LeftPanel {
             if let Some(texture_id) = self.render_texture {
                 // Display the 3D render texture
                 let available_size = ui.available_size();
-                let aspect_ratio = 1280.0 / 720.0; // Match render target aspect
-                let height = available_size.x / aspect_ratio;
+                let height = available_size.x / self.output.aspect_ratio();
                 let size = egui::vec2(available_size.x, height.min(available_size.y));
                 
-                ui.image(egui::load::SizedTexture::new(texture_id, size));
+                preview_image(ui, texture_id, size, &self.output, &mut self.camera_input);
             } else {
                 // Fallback if no texture
                 ui.group(|ui| {
@@ -122,6 +184,8 @@ pub struct CenterPanel {
     current_content: PanelContent,
     scene_editor: SceneEditor,
     render_texture: Option<egui::TextureId>,
+    output: OutputSettings,
+    camera_input: CameraInput,
 }
 
 impl CenterPanel {
@@ -130,6 +194,8 @@ impl CenterPanel {
             current_content: PanelContent::Preview,
             scene_editor: SceneEditor::new(),
             render_texture: None,
+            output: OutputSettings::default(),
+            camera_input: CameraInput::default(),
         }
     }
 
@@ -142,6 +208,16 @@ impl CenterPanel {
         self.render_texture = texture_id;
     }
     
+    /// Set the output the preview matches in aspect ratio
+    pub fn set_output(&mut self, output: OutputSettings) {
+        self.output = output;
+    }
+    
+    /// Take the camera input gathered over the preview since the last call
+    pub fn take_camera_input(&mut self) -> CameraInput {
+        std::mem::take(&mut self.camera_input)
+    }
+    
     /// Get the current content type
     pub fn current_content(&self) -> PanelContent {
         self.current_content
@@ -165,18 +241,17 @@ impl CenterPanel {
         }
     }
 
-    fn render_preview(&self, ui: &mut Ui) {
+    fn render_preview(&mut self, ui: &mut Ui) {
         ui.heading("Main Preview");
         
         egui::ScrollArea::both().show(ui, |ui| {
             if let Some(texture_id) = self.render_texture {
                 // Display the 3D render texture
                 let available_size = ui.available_size();
-                let aspect_ratio = 1280.0 / 720.0; // Match render target aspect
-                let height = available_size.x / aspect_ratio;
+                let height = available_size.x / self.output.aspect_ratio();
                 let size = egui::vec2(available_size.x, height.min(available_size.y));
                 
-                ui.image(egui::load::SizedTexture::new(texture_id, size));
+                preview_image(ui, texture_id, size, &self.output, &mut self.camera_input);
             } else {
                 // Fallback if no texture
                 ui.group(|ui| {
@@ -1,5 +1,6 @@
 use vibevj_common::{Color, Transform};
 use serde::{Deserialize, Serialize};
+use vibevj_engine::{ParticleSettings, VideoPlayback};
 
 /// Component types that can be attached to scene nodes
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,16 +38,34 @@ pub enum Component {
         script_name: String,
         enabled: bool,
     },
-    /// 2D sprite renderer
+    /// 2D sprite in the orthographic layer over the scene
+    ///
+    /// The node's x/y position places the sprite, its x/y scale is the size,
+    /// z rotation turns it and a greater z draws it in front.
     SpriteRenderer {
         texture: String,
         color: Color,
+        /// Point placed at the node, (0, 0) top-left to (1, 1) bottom-right
+        #[serde(default = "default_sprite_pivot")]
+        pivot: [f32; 2],
+        /// Texture atlas region as UV min and max
+        #[serde(default = "default_sprite_region")]
+        region: [f32; 4],
     },
-    /// Video player
+    /// Video player for `.y4m` and MJPEG `.avi` files
     VideoPlayer {
         video_path: String,
         playing: bool,
         loop_enabled: bool,
+        #[serde(default)]
+        reverse: bool,
+        #[serde(default = "default_video_speed")]
+        speed: f32,
+    },
+    /// GPU particle emitter
+    ParticleEmitter {
+        settings: ParticleSettings,
+        enabled: bool,
     },
 }
 
@@ -62,10 +81,36 @@ impl Component {
             Component::Script { .. } => "Script",
             Component::SpriteRenderer { .. } => "SpriteRenderer",
             Component::VideoPlayer { .. } => "VideoPlayer",
+            Component::ParticleEmitter { .. } => "ParticleEmitter",
+        }
+    }
+
+    /// Playback settings of a video player component
+    pub fn video_playback(&self) -> Option<VideoPlayback> {
+        match *self {
+            Component::VideoPlayer { playing, loop_enabled, reverse, speed, .. } => Some(VideoPlayback {
+                playing,
+                loop_enabled,
+                reverse,
+                speed,
+            }),
+            _ => None,
         }
     }
 }
 
+fn default_video_speed() -> f32 {
+    1.0
+}
+
+fn default_sprite_pivot() -> [f32; 2] {
+    [0.5, 0.5]
+}
+
+fn default_sprite_region() -> [f32; 4] {
+    [0.0, 0.0, 1.0, 1.0]
+}
+
 /// Types of lights
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum LightType {
@@ -97,4 +142,5 @@ pub enum ComponentType {
     Script,
     SpriteRenderer,
     VideoPlayer,
+    ParticleEmitter,
 }
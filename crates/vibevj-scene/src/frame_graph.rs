@@ -0,0 +1,114 @@
+use vibevj_engine::{Compositor, LayerSource, LayerSources, LineBatch, MixerSettings, ParticleSystem, RenderGraph, RenderObject};
+use vibevj_engine::{RenderTarget, ResourceId, SpriteBatch, TargetDesc, ToneMapper};
+use crate::renderer::SceneRenderer;
+
+/// Everything drawn into the HDR scene target for one frame
+///
+/// The main view, preview window, exporter and golden tests all build
+/// their frames from these passes.
+pub struct SceneFrame<'a> {
+    pub renderer: &'a SceneRenderer,
+    pub objects: &'a [&'a RenderObject],
+    pub particles: &'a [&'a ParticleSystem],
+    pub lines: &'a [&'a LineBatch],
+    /// Orthographic 2D layer drawn over the 3D scene
+    pub sprites: &'a [&'a SpriteBatch],
+    pub clear_color: wgpu::Color,
+    /// Size of the HDR target
+    pub width: u32,
+    pub height: u32,
+}
+
+impl<'a> SceneFrame<'a> {
+    /// Add scene, particle, line and sprite passes into a transient HDR target,
+    /// then tone map it into `output`
+    ///
+    /// Returns the HDR target. Particles must already be simulated.
+    pub fn add_passes(self, graph: &mut RenderGraph<'a>, tone_mapper: &'a mut ToneMapper, output: ResourceId) -> ResourceId {
+        let desc = TargetDesc::new(self.width, self.height, RenderTarget::HDR_FORMAT)
+            .with_sample_count(self.renderer.sample_count());
+        let hdr = graph.create_target("Scene HDR", desc);
+        let renderer = self.renderer;
+
+        let (objects, clear_color) = (self.objects, self.clear_color);
+        graph.add_pass("Scene").write(hdr).execute(move |ctx| {
+            let target = ctx.target(hdr);
+            renderer.render(ctx.encoder, target, objects, clear_color);
+        });
+
+        if !self.particles.is_empty() {
+            let particles = self.particles;
+            graph.add_pass("Particles").read(hdr).write(hdr).execute(move |ctx| {
+                let target = ctx.target(hdr);
+                renderer.render_particles(ctx.encoder, target, particles);
+            });
+        }
+
+        if !self.lines.is_empty() {
+            let lines = self.lines;
+            graph.add_pass("Lines").read(hdr).write(hdr).execute(move |ctx| {
+                let target = ctx.target(hdr);
+                renderer.render_lines(ctx.encoder, target, lines);
+            });
+        }
+
+        if !self.sprites.is_empty() {
+            let sprites = self.sprites;
+            graph.add_pass("Sprites").read(hdr).write(hdr).execute(move |ctx| {
+                let target = ctx.target(hdr);
+                renderer.render_sprites(ctx.encoder, target, sprites);
+            });
+        }
+
+        graph.add_pass("Tone Mapping").read(hdr).write(output).execute(move |ctx| {
+            tone_mapper.set_source(ctx.device, ctx.view(hdr));
+            let output = ctx.view(output);
+            tone_mapper.render(ctx.encoder, output);
+        });
+
+        hdr
+    }
+
+    /// Add the scene passes into a transient scene layer, then mix it with
+    /// the mixer's other layers into `output`
+    ///
+    /// Returns the scene layer, so outputs can show it on its own. `layers`
+    /// must already be prepared for this frame.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_mixed_passes(
+        self,
+        graph: &mut RenderGraph<'a>,
+        tone_mapper: &'a mut ToneMapper,
+        compositor: &'a mut Compositor,
+        mixer: &'a MixerSettings,
+        layers: &'a LayerSources,
+        output: ResourceId,
+    ) -> ResourceId {
+        let desc = TargetDesc::new(self.width, self.height, compositor.output_format());
+        let scene_layer = graph.create_target("Scene Layer", desc);
+        self.add_passes(graph, tone_mapper, scene_layer);
+
+        // The scene passes are culled when no layer shows the scene
+        let mut mix_pass = graph.add_pass("Layer Mix").write(output);
+        if mixer.uses_scene() {
+            mix_pass = mix_pass.read(scene_layer);
+        }
+        mix_pass.execute(move |ctx| {
+            let scene_view = mixer.uses_scene().then(|| ctx.view(scene_layer));
+            let output = ctx.view(output);
+            compositor.render(
+                ctx.device,
+                ctx.queue,
+                ctx.encoder,
+                mixer,
+                |source| match source {
+                    LayerSource::Scene => scene_view,
+                    other => layers.view(other),
+                },
+                output,
+            );
+        });
+
+        scene_layer
+    }
+}
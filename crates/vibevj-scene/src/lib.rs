@@ -11,9 +11,13 @@ pub mod scene;
 pub mod component;
 pub mod graph;
 pub mod renderer;
+pub mod frame_graph;
+pub mod runtime;
 
 pub use node::{SceneNode, NodeId};
-pub use scene::Scene;
+pub use scene::{Scene, SceneSettings};
 pub use component::{Component, ComponentType};
 pub use graph::{NodeGraph, GraphNode};
 pub use renderer::SceneRenderer;
+pub use frame_graph::SceneFrame;
+pub use runtime::SceneRuntime;
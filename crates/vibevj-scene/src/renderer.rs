@@ -1,4 +1,6 @@
-use vibevj_engine::{Camera, CameraUniform, RenderObject};
+use vibevj_common::{AudioFeatures, TimeInfo};
+use vibevj_engine::{Camera, CameraUniform, FrameUniform, InstanceData, InstancedRenderObject, LineBatch, LineInstance};
+use vibevj_engine::{Material, ParticleSystem, RenderMode, RenderObject, RenderTarget, ShaderType, SpriteBatch, SpriteRenderer, TextureSlot, Vertex};
 use wgpu::util::DeviceExt;
 
 /// Manages rendering of 3D scenes
@@ -8,9 +10,48 @@ pub struct SceneRenderer {
     camera_buffer: wgpu::Buffer,
     camera_bind_group: wgpu::BindGroup,
     camera_bind_group_layout: wgpu::BindGroupLayout,
+    frame_uniform: FrameUniform,
+    frame_buffer: wgpu::Buffer,
     material_bind_group_layout: wgpu::BindGroupLayout,
     model_bind_group_layout: wgpu::BindGroupLayout,
-    render_pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    line_pipeline_layout: wgpu::PipelineLayout,
+    shaders: SceneShaders,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    pipelines: ScenePipelines,
+    sprites: SpriteRenderer,
+}
+
+/// Shader modules for every scene pipeline
+struct SceneShaders {
+    basic: wgpu::ShaderModule,
+    pbr: wgpu::ShaderModule,
+    instanced: wgpu::ShaderModule,
+    wireframe: wgpu::ShaderModule,
+    points: wgpu::ShaderModule,
+    lines: wgpu::ShaderModule,
+}
+
+/// Render pipelines, rebuilt together when the sample count changes
+struct ScenePipelines {
+    basic: wgpu::RenderPipeline,
+    pbr: wgpu::RenderPipeline,
+    instanced: wgpu::RenderPipeline,
+    wireframe: wgpu::RenderPipeline,
+    points: wgpu::RenderPipeline,
+    edges: wgpu::RenderPipeline,
+    lines: wgpu::RenderPipeline,
+}
+
+/// What differs between the scene's pipelines
+struct MeshPipelineDesc<'a> {
+    label: &'a str,
+    shader: &'a wgpu::ShaderModule,
+    vertex_entry: &'a str,
+    fragment_entry: &'a str,
+    buffers: &'a [wgpu::VertexBufferLayout<'a>],
+    cull_mode: Option<wgpu::Face>,
 }
 
 impl SceneRenderer {
@@ -29,19 +70,39 @@ impl SceneRenderer {
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
         
+        let frame_uniform = FrameUniform::new();
+        let frame_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Frame Buffer"),
+            contents: bytemuck::cast_slice(&[frame_uniform]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        
         // Create bind group layouts
+        // Group 0: camera, then per-frame time and audio
         let camera_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Camera Bind Group Layout"),
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
                 },
-                count: None,
-            }],
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
         });
         
         let model_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -58,38 +119,94 @@ impl SceneRenderer {
             }],
         });
         
-        let material_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("Material Bind Group Layout"),
-            entries: &[wgpu::BindGroupLayoutEntry {
+        // Material: uniform (also read by vertex displacement), sampler, then one texture per `TextureSlot`
+        let mut material_entries = vec![
+            wgpu::BindGroupLayoutEntry {
                 binding: 0,
-                visibility: wgpu::ShaderStages::FRAGMENT,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
                 ty: wgpu::BindingType::Buffer {
                     ty: wgpu::BufferBindingType::Uniform,
                     has_dynamic_offset: false,
                     min_binding_size: None,
                 },
                 count: None,
-            }],
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ];
+        for i in 0..TextureSlot::ALL.len() as u32 {
+            material_entries.push(wgpu::BindGroupLayoutEntry {
+                binding: 2 + i,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            });
+        }
+        
+        let material_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Material Bind Group Layout"),
+            entries: &material_entries,
         });
         
         // Create camera bind group
         let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Camera Bind Group"),
             layout: &camera_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: camera_buffer.as_entire_binding(),
-            }],
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: frame_buffer.as_entire_binding(),
+                },
+            ],
         });
         
-        // Load shader
-        let shader_source = include_str!("../../../assets/shaders/basic.wgsl");
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Basic Shader"),
-            source: wgpu::ShaderSource::Wgsl(shader_source.into()),
-        });
+        // Load shaders; mesh shaders share the displacement functions
+        let load = |label: &str, source: &'static str| {
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(label),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            })
+        };
+        let shaders = SceneShaders {
+            basic: load("Basic Shader", concat!(
+                include_str!("../../../assets/shaders/displacement.wgsl"),
+                include_str!("../../../assets/shaders/basic.wgsl"),
+            )),
+            pbr: load("PBR Shader", concat!(
+                include_str!("../../../assets/shaders/displacement.wgsl"),
+                include_str!("../../../assets/shaders/pbr.wgsl"),
+            )),
+            instanced: load("Instanced Shader", concat!(
+                include_str!("../../../assets/shaders/displacement.wgsl"),
+                include_str!("../../../assets/shaders/instanced.wgsl"),
+            )),
+            wireframe: load("Wireframe Shader", concat!(
+                include_str!("../../../assets/shaders/displacement.wgsl"),
+                include_str!("../../../assets/shaders/wireframe.wgsl"),
+            )),
+            points: load("Points Shader", concat!(
+                include_str!("../../../assets/shaders/displacement.wgsl"),
+                include_str!("../../../assets/shaders/points.wgsl"),
+            )),
+            lines: load("Lines Shader", concat!(
+                include_str!("../../../assets/shaders/displacement.wgsl"),
+                include_str!("../../../assets/shaders/lines.wgsl"),
+            )),
+        };
         
-        // Create render pipeline
+        // Create render pipelines
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
             bind_group_layouts: &[
@@ -100,62 +217,84 @@ impl SceneRenderer {
             push_constant_ranges: &[],
         });
         
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[vibevj_engine::Vertex::desc()],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: wgpu::PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-            cache: None,
+        // World-space polylines only read the camera and frame
+        let line_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Line Pipeline Layout"),
+            bind_group_layouts: &[&camera_bind_group_layout],
+            push_constant_ranges: &[],
         });
         
+        let sample_count = 1;
+        let pipelines = ScenePipelines::new(
+            device,
+            &pipeline_layout,
+            &line_pipeline_layout,
+            &shaders,
+            surface_format,
+            sample_count,
+        );
+        let sprites = SpriteRenderer::new(device, surface_format, sample_count);
+        
         Self {
             camera,
             camera_uniform,
             camera_buffer,
             camera_bind_group,
             camera_bind_group_layout,
+            frame_uniform,
+            frame_buffer,
             material_bind_group_layout,
             model_bind_group_layout,
-            render_pipeline,
+            pipeline_layout,
+            line_pipeline_layout,
+            shaders,
+            format: surface_format,
+            sample_count,
+            pipelines,
+            sprites,
+        }
+    }
+    
+    /// Rebuild pipelines for a new MSAA sample count
+    /// The sample count must match the `RenderTarget` passed to `render`
+    pub fn set_sample_count(&mut self, device: &wgpu::Device, sample_count: u32) {
+        if self.sample_count == sample_count {
+            return;
         }
+        
+        self.sample_count = sample_count;
+        self.pipelines = ScenePipelines::new(
+            device,
+            &self.pipeline_layout,
+            &self.line_pipeline_layout,
+            &self.shaders,
+            self.format,
+            sample_count,
+        );
+        self.sprites.set_sample_count(device, sample_count);
     }
     
+    /// Get the MSAA sample count pipelines are built for
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+    
+    /// Get the 2D sprite layer renderer, to set its camera and load textures
+    pub fn sprite_renderer_mut(&mut self) -> &mut SpriteRenderer {
+        &mut self.sprites
+    }
+    
+    /// Select the pipeline matching a material's render mode and shader type
+    fn pipeline_for(&self, material: &Material) -> &wgpu::RenderPipeline {
+        match (material.render_mode, material.shader_type) {
+            (RenderMode::Wireframe { .. }, _) => &self.pipelines.wireframe,
+            (RenderMode::Points { .. }, _) => &self.pipelines.points,
+            (RenderMode::Edges { .. }, _) => &self.pipelines.edges,
+            (RenderMode::Solid, ShaderType::PBR) => &self.pipelines.pbr,
+            (RenderMode::Solid, _) => &self.pipelines.basic,
+        }
+    }
+
     /// Get a reference to the camera
     pub fn camera(&self) -> &Camera {
         &self.camera
@@ -166,6 +305,11 @@ impl SceneRenderer {
         &mut self.camera
     }
     
+    /// Get the camera and frame bind group layout (group 0 of every scene pipeline)
+    pub fn camera_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.camera_bind_group_layout
+    }
+    
     /// Get the material bind group layout
     pub fn material_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
         &self.material_bind_group_layout
@@ -182,28 +326,52 @@ impl SceneRenderer {
         queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[self.camera_uniform]));
     }
     
-    /// Render objects to a texture view
+    /// Time and audio uploaded by the last `update_frame`
+    pub fn frame_uniform(&self) -> &FrameUniform {
+        &self.frame_uniform
+    }
+    
+    /// Update the per-frame time and audio uniform used by displacement
+    pub fn update_frame(&mut self, queue: &wgpu::Queue, time: &TimeInfo, audio: &AudioFeatures) {
+        self.frame_uniform.update(time, audio);
+        queue.write_buffer(&self.frame_buffer, 0, bytemuck::cast_slice(&[self.frame_uniform]));
+    }
+    
+    /// Set the render target size that line widths and point sizes are measured in
+    pub fn set_viewport_size(&mut self, queue: &wgpu::Queue, width: u32, height: u32) {
+        self.frame_uniform.resolution = [width.max(1) as f32, height.max(1) as f32];
+        queue.write_buffer(&self.frame_buffer, 0, bytemuck::cast_slice(&[self.frame_uniform]));
+    }
+    
+    /// Render objects to a render target
+    /// With MSAA the multisampled color is resolved into `target.view`
     pub fn render(
         &self,
         encoder: &mut wgpu::CommandEncoder,
-        view: &wgpu::TextureView,
-        depth_view: &wgpu::TextureView,
+        target: &RenderTarget,
         objects: &[&RenderObject],
         clear_color: wgpu::Color,
     ) {
+        self.render_with_instances(encoder, target, objects, &[], clear_color);
+    }
+    
+    /// Render objects and instanced objects to a texture view
+    /// Each instanced object is drawn with a single `draw_indexed` call
+    pub fn render_with_instances(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &RenderTarget,
+        objects: &[&RenderObject],
+        instanced: &[&InstancedRenderObject],
+        clear_color: wgpu::Color,
+    ) {
+        debug_assert_eq!(target.sample_count, self.sample_count, "render target and pipeline sample counts differ");
+        
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Scene Render Pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(clear_color),
-                    store: wgpu::StoreOp::Store,
-                },
-                depth_slice: None,
-            })],
+            color_attachments: &[Some(target.color_attachment(wgpu::LoadOp::Clear(clear_color)))],
             depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                view: depth_view,
+                view: &target.depth_view,
                 depth_ops: Some(wgpu::Operations {
                     load: wgpu::LoadOp::Clear(1.0),
                     store: wgpu::StoreOp::Store,
@@ -214,22 +382,282 @@ impl SceneRenderer {
             occlusion_query_set: None,
         });
         
-        render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
         
+        let mut current_pipeline: Option<&wgpu::RenderPipeline> = None;
         for object in objects {
-            if let (Some(vertex_buffer), Some(index_buffer), Some(model_bind_group), Some(material_bind_group)) = (
+            let pipeline = self.pipeline_for(&object.material);
+            if !current_pipeline.is_some_and(|current| std::ptr::eq(current, pipeline)) {
+                render_pass.set_pipeline(pipeline);
+                current_pipeline = Some(pipeline);
+            }
+            
+            if let (Some(model_bind_group), Some(material_bind_group)) = (&object.model_bind_group, &object.material_bind_group) {
+                render_pass.set_bind_group(1, model_bind_group, &[]);
+                render_pass.set_bind_group(2, material_bind_group, &[]);
+                Self::draw_object(&mut render_pass, object);
+            }
+        }
+        
+        if instanced.is_empty() {
+            return;
+        }
+        
+        render_pass.set_pipeline(&self.pipelines.instanced);
+        for object in instanced {
+            if object.instances.is_empty() {
+                continue;
+            }
+            
+            if let (Some(vertex_buffer), Some(index_buffer), Some(instance_buffer), Some(model_bind_group), Some(material_bind_group)) = (
                 &object.vertex_buffer,
                 &object.index_buffer,
+                &object.instance_buffer,
                 &object.model_bind_group,
                 &object.material_bind_group,
             ) {
                 render_pass.set_bind_group(1, model_bind_group, &[]);
                 render_pass.set_bind_group(2, material_bind_group, &[]);
                 render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
                 render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-                render_pass.draw_indexed(0..object.mesh.indices.len() as u32, 0, 0..1);
+                render_pass.draw_indexed(0..object.mesh.indices.len() as u32, 0, 0..object.instance_count());
             }
         }
     }
+    
+    /// Issue the draw call for an object's render mode
+    /// Objects missing the buffers their mode needs are skipped
+    fn draw_object(render_pass: &mut wgpu::RenderPass, object: &RenderObject) {
+        match object.material.render_mode {
+            RenderMode::Solid => {
+                if let (Some(vertex_buffer), Some(index_buffer)) = (&object.vertex_buffer, &object.index_buffer) {
+                    render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                    render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                    render_pass.draw_indexed(0..object.mesh.indices.len() as u32, 0, 0..1);
+                }
+            }
+            RenderMode::Wireframe { .. } => {
+                if let Some(wireframe_buffer) = &object.wireframe_buffer {
+                    render_pass.set_vertex_buffer(0, wireframe_buffer.slice(..));
+                    render_pass.draw(0..object.mesh.indices.len() as u32, 0..1);
+                }
+            }
+            RenderMode::Points { .. } => {
+                // The vertex buffer steps per instance, one sprite quad each
+                if let Some(vertex_buffer) = &object.vertex_buffer {
+                    render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                    render_pass.draw(0..6, 0..object.mesh.vertices.len() as u32);
+                }
+            }
+            RenderMode::Edges { .. } => {
+                if let Some(edge_buffer) = &object.edge_buffer {
+                    render_pass.set_vertex_buffer(0, edge_buffer.slice(..));
+                    render_pass.draw(0..6, 0..object.edge_count);
+                }
+            }
+        }
+    }
+    
+    /// Draw world-space polylines on top of an already rendered scene
+    /// Color and depth are loaded, so lines are occluded by scene geometry
+    pub fn render_lines(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &RenderTarget,
+        batches: &[&LineBatch],
+    ) {
+        if batches.iter().all(|batch| batch.segment_count() == 0) {
+            return;
+        }
+        
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Line Render Pass"),
+            color_attachments: &[Some(target.color_attachment(wgpu::LoadOp::Load))],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &target.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        
+        render_pass.set_pipeline(&self.pipelines.lines);
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        for batch in batches {
+            batch.draw(&mut render_pass);
+        }
+    }
+    
+    /// Draw 2D sprites on top of an already rendered scene
+    /// Sprites ignore scene depth and cover everything drawn before them
+    pub fn render_sprites(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &RenderTarget,
+        batches: &[&SpriteBatch],
+    ) {
+        self.sprites.render(encoder, target, batches);
+    }
+    
+    /// Draw particle systems on top of an already rendered scene
+    /// Color and depth are loaded, so particles are occluded by scene geometry
+    pub fn render_particles(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &RenderTarget,
+        systems: &[&ParticleSystem],
+    ) {
+        if systems.is_empty() {
+            return;
+        }
+        
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Particle Render Pass"),
+            color_attachments: &[Some(target.color_attachment(wgpu::LoadOp::Load))],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &target.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        for system in systems {
+            system.draw(&mut render_pass);
+        }
+    }
+}
+
+impl ScenePipelines {
+    fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        line_layout: &wgpu::PipelineLayout,
+        shaders: &SceneShaders,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        let mesh = |label, shader, buffers: &[wgpu::VertexBufferLayout], cull_mode| {
+            Self::create_pipeline(
+                device,
+                layout,
+                &MeshPipelineDesc {
+                    label,
+                    shader,
+                    vertex_entry: "vs_main",
+                    fragment_entry: "fs_main",
+                    buffers,
+                    cull_mode,
+                },
+                format,
+                sample_count,
+            )
+        };
+        let back = Some(wgpu::Face::Back);
+        
+        Self {
+            basic: mesh("Render Pipeline", &shaders.basic, &[Vertex::desc()], back),
+            pbr: mesh("PBR Render Pipeline", &shaders.pbr, &[Vertex::desc()], back),
+            instanced: mesh(
+                "Instanced Render Pipeline",
+                &shaders.instanced,
+                &[Vertex::desc(), InstanceData::desc()],
+                back,
+            ),
+            // Lines and points have no facing; wireframes show their back edges
+            wireframe: mesh("Wireframe Render Pipeline", &shaders.wireframe, &[Vertex::desc()], None),
+            points: mesh("Points Render Pipeline", &shaders.points, &[Vertex::instance_desc()], None),
+            edges: Self::create_pipeline(
+                device,
+                layout,
+                &MeshPipelineDesc {
+                    label: "Edges Render Pipeline",
+                    shader: &shaders.lines,
+                    vertex_entry: "vs_edges",
+                    fragment_entry: "fs_edges",
+                    buffers: &[LineInstance::desc()],
+                    cull_mode: None,
+                },
+                format,
+                sample_count,
+            ),
+            lines: Self::create_pipeline(
+                device,
+                line_layout,
+                &MeshPipelineDesc {
+                    label: "Line Render Pipeline",
+                    shader: &shaders.lines,
+                    vertex_entry: "vs_main",
+                    fragment_entry: "fs_main",
+                    buffers: &[LineInstance::desc()],
+                    cull_mode: None,
+                },
+                format,
+                sample_count,
+            ),
+        }
+    }
+    
+    /// Create a triangle pipeline drawing into the HDR target with depth
+    fn create_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        desc: &MeshPipelineDesc,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(desc.label),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: desc.shader,
+                entry_point: Some(desc.vertex_entry),
+                buffers: desc.buffers,
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: desc.shader,
+                entry_point: Some(desc.fragment_entry),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: desc.cull_mode,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
 }
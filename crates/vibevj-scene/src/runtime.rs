@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use vibevj_common::{AudioFeatures, TimeInfo};
+use vibevj_engine::{EmitterShape, ImportedModel, Mesh, ParticleSystem, RenderTarget, SpriteBatch, TextureCache};
+use vibevj_engine::{VideoPlayback, VideoPlayer, VideoTexture};
+use crate::component::Component;
+use crate::node::NodeId;
+use crate::renderer::SceneRenderer;
+use crate::scene::Scene;
+
+/// Video played by a scene node
+struct NodeVideo {
+    path: String,
+    player: VideoPlayer,
+    texture: VideoTexture,
+    /// Size of `texture` when its view was bound for sprites
+    bound_size: Option<(u32, u32)>,
+}
+
+/// GPU resources driven by a scene's components
+///
+/// The main view and the exporter both keep one beside their `Scene`, so
+/// they play and draw the same things. Resources follow the scene: nodes
+/// that gain a component get them on the next update, and removed nodes,
+/// changed paths or disabled emitters drop them.
+pub struct SceneRuntime {
+    videos: HashMap<NodeId, NodeVideo>,
+    /// Paths that failed to open, so each is logged once
+    failed: HashMap<NodeId, String>,
+    sprites: SpriteBatch,
+    /// Particle systems of emitter nodes, in scene order
+    particles: Vec<(NodeId, ParticleSystem)>,
+    /// Emitter surface meshes by name, `None` when loading failed
+    surface_meshes: HashMap<String, Option<Mesh>>,
+    video_wait: Option<Duration>,
+}
+
+impl SceneRuntime {
+    pub fn new() -> Self {
+        Self {
+            videos: HashMap::new(),
+            failed: HashMap::new(),
+            sprites: SpriteBatch::new(),
+            particles: Vec::new(),
+            surface_meshes: HashMap::new(),
+            video_wait: None,
+        }
+    }
+
+    /// Block up to `timeout` for each video frame rather than keep showing
+    /// the previous one, e.g. for offline rendering
+    pub fn with_video_wait(mut self, timeout: Duration) -> Self {
+        self.video_wait = Some(timeout);
+        self
+    }
+
+    /// The sprite layer built by the last `prepare`
+    pub fn sprites(&self) -> &SpriteBatch {
+        &self.sprites
+    }
+
+    /// Particle systems of enabled emitters on visible nodes
+    pub fn particles(&self) -> impl Iterator<Item = &ParticleSystem> {
+        self.particles.iter().map(|(_, system)| system)
+    }
+
+    /// Player of node `id`'s video, for seeking
+    pub fn video_mut(&mut self, id: NodeId) -> Option<&mut VideoPlayer> {
+        self.videos.get_mut(&id).map(|video| &mut video.player)
+    }
+
+    /// Forget videos and emitter meshes that failed to load so they are retried
+    pub fn retry_failed(&mut self) {
+        self.failed.clear();
+        self.surface_meshes.retain(|_, mesh| mesh.is_some());
+        // Emitters that fell back to a point are rebuilt on the next update
+        self.particles.retain(|(_, system)| {
+            !matches!(system.settings.emitter, EmitterShape::MeshSurface { .. }) || system.surface_triangle_count() > 0
+        });
+    }
+
+    /// Match particle systems to the scene's emitters and advance them by
+    /// `delta` seconds; call once per frame before simulating them
+    ///
+    /// Systems are created at the node's world transform and recreated when
+    /// `max_particles` or the emitter shape changes, as their buffers are
+    /// built on creation. Mesh surface emitters name a model mesh like
+    /// `MeshRenderer` does (see `Scene::model_mesh`); an empty name uses the
+    /// node's own `MeshRenderer` mesh.
+    pub fn update_particles(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        scene: &Scene,
+        renderer: &SceneRenderer,
+        delta: f32,
+        audio: &AudioFeatures,
+    ) {
+        let mut emitters = Vec::new();
+        scene.visit_visible(|node, world| {
+            let settings = node.components.iter().find_map(|component| match component {
+                Component::ParticleEmitter { settings, enabled: true } => Some(settings),
+                _ => None,
+            });
+            let Some(settings) = settings else {
+                return;
+            };
+            let surface = match &settings.emitter {
+                EmitterShape::MeshSurface { mesh } if mesh.is_empty() => {
+                    node.components.iter().find_map(|component| match component {
+                        Component::MeshRenderer { mesh, .. } => Some(mesh.as_str()),
+                        _ => None,
+                    })
+                }
+                EmitterShape::MeshSurface { mesh } => Some(mesh.as_str()),
+                _ => None,
+            };
+            emitters.push((node.id, settings, surface, world));
+        });
+
+        let mut previous: HashMap<NodeId, ParticleSystem> = self.particles.drain(..).collect();
+        for (id, settings, surface, world) in emitters {
+            let mut system = match previous.remove(&id) {
+                Some(system)
+                    if system.capacity() == settings.max_particles.max(1)
+                        && system.settings.emitter == settings.emitter =>
+                {
+                    system
+                }
+                _ => ParticleSystem::new(
+                    device,
+                    renderer.camera_bind_group_layout(),
+                    RenderTarget::HDR_FORMAT,
+                    renderer.sample_count(),
+                    settings.clone(),
+                    surface.and_then(|name| Self::surface_mesh(&mut self.surface_meshes, name)),
+                ),
+            };
+            if system.settings != *settings {
+                system.settings = settings.clone();
+            }
+            system.transform = world;
+            system.set_sample_count(device, renderer.sample_count());
+            system.update(queue, delta, renderer.camera(), audio);
+            self.particles.push((id, system));
+        }
+    }
+
+    /// Mesh `name` from the cache, loading its model on first use
+    fn surface_mesh<'m>(meshes: &'m mut HashMap<String, Option<Mesh>>, name: &str) -> Option<&'m Mesh> {
+        meshes
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                let Some((source, index)) = Scene::parse_model_mesh(name) else {
+                    log::error!("Particle emitter mesh '{}' is not a model mesh", name);
+                    return None;
+                };
+                match ImportedModel::load(source) {
+                    Ok(mut model) if index < model.meshes.len() => Some(model.meshes.swap_remove(index).mesh),
+                    Ok(_) => {
+                        log::error!("Particle emitter mesh '{}' not found in its model", name);
+                        None
+                    }
+                    Err(e) => {
+                        log::error!("Failed to load particle emitter mesh '{}': {}", name, e);
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+
+    /// Advance node videos to `time` and rebuild the sprite layer, viewed
+    /// with `aspect` width / height
+    #[allow(clippy::too_many_arguments)]
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        scene: &Scene,
+        renderer: &mut SceneRenderer,
+        textures: &mut TextureCache,
+        time: &TimeInfo,
+        aspect: f32,
+    ) {
+        let sprite_renderer = renderer.sprite_renderer_mut();
+
+        // One player per node with a video component, reopened when its path changes
+        let wanted: HashMap<NodeId, (&str, VideoPlayback)> = scene
+            .nodes()
+            .filter_map(|node| {
+                node.components.iter().find_map(|component| match component {
+                    Component::VideoPlayer { video_path, .. } => {
+                        Some((node.id, (video_path.as_str(), component.video_playback()?)))
+                    }
+                    _ => None,
+                })
+            })
+            .collect();
+        self.videos.retain(|id, video| {
+            let keep = wanted.get(id).is_some_and(|&(path, _)| path == video.path);
+            if !keep {
+                sprite_renderer.unbind(&Scene::video_texture(*id));
+            }
+            keep
+        });
+        self.failed.retain(|id, failed| wanted.get(id).is_some_and(|&(path, _)| path == failed));
+
+        for (&id, &(path, playback)) in &wanted {
+            if !self.videos.contains_key(&id) && !self.failed.contains_key(&id) {
+                match VideoPlayer::open(path) {
+                    Ok(player) => {
+                        let texture = VideoTexture::new(device, player.info().width, player.info().height, Some(path));
+                        self.videos.insert(id, NodeVideo {
+                            path: path.to_string(),
+                            player,
+                            texture,
+                            bound_size: None,
+                        });
+                    }
+                    Err(e) => {
+                        log::error!("Failed to open video '{}': {}", path, e);
+                        self.failed.insert(id, path.to_string());
+                    }
+                }
+            }
+            let Some(video) = self.videos.get_mut(&id) else {
+                continue;
+            };
+
+            let mut frame = video.player.update(&playback, time);
+            if let Some(timeout) = self.video_wait {
+                frame = frame.or_else(|| video.player.wait(&playback, timeout));
+            }
+            if let Some(frame) = frame {
+                video.texture.upload(device, queue, encoder, &frame);
+            }
+            // The texture is recreated when the video changes size
+            let size = (video.texture.target.width, video.texture.target.height);
+            if video.bound_size != Some(size) {
+                sprite_renderer.bind_view(device, &Scene::video_texture(id), &video.texture.target.view);
+                video.bound_size = Some(size);
+            }
+        }
+
+        self.sprites.set_sprites(device, queue, &scene.sprites());
+        sprite_renderer.set_camera(queue, &scene.settings.sprite_camera, aspect);
+        sprite_renderer.prepare(device, queue, textures, &[&self.sprites]);
+    }
+}
+
+impl Default for SceneRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
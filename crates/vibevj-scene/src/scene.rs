@@ -1,13 +1,54 @@
+use crate::component::Component;
 use crate::node::{SceneNode, NodeId};
-use vibevj_common::{Result, VibeVJError};
+use vibevj_common::{Result, Transform, VibeVJError};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use vibevj_engine::{CameraRig, ColorGrading, ImportedModel, MixerSettings, OutputSettings, OutputWindowSettings, Sprite, SpriteCamera, ToneMapSettings};
+
+/// Render settings stored with a scene
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SceneSettings {
+    /// MSAA sample count (1, 2, 4 or 8), applied at runtime when changed
+    pub msaa_samples: u32,
+    /// HDR to display conversion
+    pub tone_mapping: ToneMapSettings,
+    /// Grade and LUT applied after tone mapping
+    pub color_grading: ColorGrading,
+    /// Camera controller, shake and projection
+    pub camera: CameraRig,
+    /// Layer decks and A/B crossfader
+    pub mixer: MixerSettings,
+    /// Output resolution, render scale and aspect handling
+    pub output: OutputSettings,
+    /// Output windows, each on its own monitor
+    pub outputs: Vec<OutputWindowSettings>,
+    /// Orthographic camera of the 2D sprite layer
+    pub sprite_camera: SpriteCamera,
+}
+
+impl Default for SceneSettings {
+    fn default() -> Self {
+        Self {
+            msaa_samples: 4,
+            tone_mapping: ToneMapSettings::default(),
+            color_grading: ColorGrading::default(),
+            camera: CameraRig::default(),
+            mixer: MixerSettings::default(),
+            output: OutputSettings::default(),
+            outputs: Vec::new(),
+            sprite_camera: SpriteCamera::default(),
+        }
+    }
+}
 
 /// Scene containing a hierarchy of nodes
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Scene {
     pub name: String,
     pub root: NodeId,
+    #[serde(default)]
+    pub settings: SceneSettings,
     nodes: HashMap<NodeId, SceneNode>,
     next_id: u64,
 }
@@ -24,6 +65,7 @@ impl Scene {
         Self {
             name,
             root: root_id,
+            settings: SceneSettings::default(),
             nodes,
             next_id: 1,
         }
@@ -110,6 +152,75 @@ impl Scene {
         self.nodes.keys().copied()
     }
 
+    /// Collect the sprites of visible nodes, placed by their world transforms
+    ///
+    /// Hidden nodes hide their whole subtree. A node's video replaces the
+    /// texture of its sprites, or is drawn as a plain sprite if it has none.
+    pub fn sprites(&self) -> Vec<Sprite> {
+        let mut sprites = Vec::new();
+        self.visit_visible(|node, world| Self::collect_sprites(node, world, &mut sprites));
+        sprites
+    }
+
+    /// Call `visit` with each visible node and its world transform, parents
+    /// before children
+    ///
+    /// Hidden nodes hide their whole subtree.
+    pub fn visit_visible<'s>(&'s self, mut visit: impl FnMut(&'s SceneNode, glam::Mat4)) {
+        self.visit_subtree(self.root, glam::Mat4::IDENTITY, &mut visit);
+    }
+
+    fn visit_subtree<'s>(&'s self, id: NodeId, parent: glam::Mat4, visit: &mut impl FnMut(&'s SceneNode, glam::Mat4)) {
+        let Some(node) = self.nodes.get(&id).filter(|node| node.visible) else {
+            return;
+        };
+        let world = parent * node.transform.to_matrix();
+        visit(node, world);
+        for &child in &node.children {
+            self.visit_subtree(child, world, visit);
+        }
+    }
+
+    fn collect_sprites(node: &SceneNode, world: glam::Mat4, sprites: &mut Vec<Sprite>) {
+        let id = node.id;
+        let (scale, rotation, position) = world.to_scale_rotation_translation();
+        let (_, _, angle) = rotation.to_euler(glam::EulerRot::XYZ);
+
+        let place = |texture: String| {
+            Sprite::new(texture, position.truncate(), scale.truncate())
+                .with_depth(-position.z)
+                .with_rotation(angle)
+        };
+        let video = node.components.iter().any(|component| component.video_playback().is_some());
+        let mut textured = false;
+        for component in &node.components {
+            if let Component::SpriteRenderer { texture, color, pivot, region } = component {
+                let texture = if video { Self::video_texture(id) } else { texture.clone() };
+                sprites.push(place(texture).with_pivot((*pivot).into()).with_region(*region).with_color(*color));
+                textured = true;
+            }
+        }
+        if video && !textured {
+            sprites.push(place(Self::video_texture(id)));
+        }
+    }
+
+    /// Sprite texture name of the video played by node `id`
+    pub fn video_texture(id: NodeId) -> String {
+        format!("#video{}", id.0)
+    }
+
+    /// Mesh name of mesh `index` of the model loaded from `source`
+    pub fn model_mesh(source: &str, index: usize) -> String {
+        format!("{}#mesh{}", source, index)
+    }
+
+    /// Split a mesh name made by `model_mesh` into the model source and mesh index
+    pub fn parse_model_mesh(name: &str) -> Option<(&str, usize)> {
+        let (source, index) = name.rsplit_once("#mesh")?;
+        Some((source, index.parse().ok()?))
+    }
+
     /// Clear the scene (except root)
     pub fn clear(&mut self) {
         let root = self.nodes.remove(&self.root).unwrap();
@@ -118,6 +229,86 @@ impl Scene {
         self.next_id = 1;
     }
 
+    /// Add an imported model's node hierarchy to the scene
+    ///
+    /// A node named `name` is created under `parent` (or the root) and the
+    /// model's nodes are added below it with their local transforms. Meshes
+    /// become `MeshRenderer` components referencing `<source>#mesh<index>` and
+    /// `<source>#material<index>`, indices into the `ImportedModel`. Nodes with
+    /// several meshes get one child node per mesh.
+    ///
+    /// Nothing is added if the model's hierarchy is invalid.
+    pub fn add_model(
+        &mut self,
+        model: &ImportedModel,
+        source: &str,
+        name: String,
+        parent: Option<NodeId>,
+    ) -> Result<NodeId> {
+        let model_root = self.create_node(name, parent)?;
+        if let Err(e) = self.add_model_nodes(model, source, model_root) {
+            self.remove_node(model_root)?;
+            return Err(e);
+        }
+        Ok(model_root)
+    }
+
+    fn add_model_nodes(&mut self, model: &ImportedModel, source: &str, model_root: NodeId) -> Result<()> {
+        let mut stack: Vec<(usize, NodeId)> = model.roots.iter().rev().map(|&root| (root, model_root)).collect();
+        let mut visited = HashSet::new();
+        while let Some((index, parent_id)) = stack.pop() {
+            let imported = model.nodes.get(index).ok_or_else(|| {
+                VibeVJError::SceneError(format!("Model node {} not found", index))
+            })?;
+            if !visited.insert(index) {
+                return Err(VibeVJError::SceneError(format!(
+                    "Model node {} is reached twice; the node hierarchy has a cycle",
+                    index
+                )));
+            }
+
+            let id = self.create_node(imported.name.clone(), Some(parent_id))?;
+            let (scale, rotation, position) = imported.transform.to_scale_rotation_translation();
+            let (x, y, z) = rotation.to_euler(glam::EulerRot::XYZ);
+
+            let mesh_renderers: Vec<(String, Component)> = imported
+                .meshes
+                .iter()
+                .filter_map(|&mesh_index| {
+                    let mesh = model.meshes.get(mesh_index)?;
+                    let component = Component::MeshRenderer {
+                        mesh: Self::model_mesh(source, mesh_index),
+                        material: mesh
+                            .material
+                            .map(|material| format!("{}#material{}", source, material))
+                            .unwrap_or_default(),
+                    };
+                    Some((mesh.name.clone(), component))
+                })
+                .collect();
+            let split = mesh_renderers.len() > 1;
+
+            if let Some(node) = self.nodes.get_mut(&id) {
+                node.transform = Transform::new(position, glam::Vec3::new(x, y, z), scale);
+                if !split {
+                    node.components.extend(mesh_renderers.iter().map(|(_, c)| c.clone()));
+                }
+            }
+            if split {
+                for (mesh_name, component) in mesh_renderers {
+                    let child = self.create_node(mesh_name, Some(id))?;
+                    if let Some(node) = self.nodes.get_mut(&child) {
+                        node.add_component(component);
+                    }
+                }
+            }
+
+            stack.extend(imported.children.iter().rev().map(|&child| (child, id)));
+        }
+
+        Ok(())
+    }
+
     /// Serialize the scene to JSON
     pub fn to_json(&self) -> Result<String> {
         serde_json::to_string_pretty(self)
@@ -136,3 +327,88 @@ impl Default for Scene {
         Self::new("Untitled Scene".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vibevj_engine::ImportedNode;
+
+    fn model(children: [Vec<usize>; 2]) -> ImportedModel {
+        let [a, b] = children;
+        let node = |name: &str, children| ImportedNode {
+            name: name.to_string(),
+            transform: glam::Mat4::IDENTITY,
+            meshes: Vec::new(),
+            children,
+        };
+        ImportedModel {
+            nodes: vec![node("a", a), node("b", b)],
+            roots: vec![0],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn add_model_keeps_the_hierarchy() {
+        let mut scene = Scene::new("Test".to_string());
+        let root = scene.add_model(&model([vec![1], vec![]]), "test.gltf", "Model".to_string(), None).expect("add model");
+        let a = scene.get_node(root).unwrap().children[0];
+        assert_eq!(scene.get_node(a).unwrap().name, "a");
+        let b = scene.get_node(a).unwrap().children[0];
+        assert_eq!(scene.get_node(b).unwrap().name, "b");
+    }
+
+    #[test]
+    fn model_mesh_names_round_trip() {
+        let name = Scene::model_mesh("models/a#b.glb", 3);
+        assert_eq!(Scene::parse_model_mesh(&name), Some(("models/a#b.glb", 3)));
+        assert_eq!(Scene::parse_model_mesh("cube"), None);
+        assert_eq!(Scene::parse_model_mesh("a.obj#mesh"), None);
+    }
+
+    #[test]
+    fn add_model_rejects_cycles_without_adding_nodes() {
+        let mut scene = Scene::new("Test".to_string());
+        let before = scene.nodes().count();
+        assert!(scene.add_model(&model([vec![1], vec![0]]), "test.gltf", "Model".to_string(), None).is_err());
+        assert_eq!(scene.nodes().count(), before);
+        assert!(scene.get_node(scene.root).unwrap().children.is_empty());
+    }
+
+    #[test]
+    fn videos_replace_sprite_textures() {
+        let mut scene = Scene::new("Test".to_string());
+        let video = Component::VideoPlayer {
+            video_path: "clip.y4m".to_string(),
+            playing: true,
+            loop_enabled: true,
+            reverse: false,
+            speed: 1.0,
+        };
+        let sprite = Component::SpriteRenderer {
+            texture: "logo.png".to_string(),
+            color: vibevj_common::Color::RED,
+            pivot: [0.0, 1.0],
+            region: [0.0, 0.0, 1.0, 1.0],
+        };
+        let plain = scene.create_node("Plain".to_string(), None).unwrap();
+        scene.get_node_mut(plain).unwrap().add_component(video.clone());
+        let tinted = scene.create_node("Tinted".to_string(), None).unwrap();
+        scene.get_node_mut(tinted).unwrap().add_component(sprite.clone());
+        scene.get_node_mut(tinted).unwrap().add_component(video);
+        let logo = scene.create_node("Logo".to_string(), None).unwrap();
+        scene.get_node_mut(logo).unwrap().add_component(sprite);
+
+        let mut sprites = scene.sprites();
+        sprites.sort_by(|a, b| a.texture.cmp(&b.texture));
+        let textures: Vec<&str> = sprites.iter().map(|sprite| sprite.texture.as_str()).collect();
+        let mut expected = [Scene::video_texture(plain), Scene::video_texture(tinted), "logo.png".to_string()];
+        expected.sort();
+        assert_eq!(textures, expected);
+
+        let tinted = sprites.iter().find(|sprite| sprite.texture == Scene::video_texture(tinted)).unwrap();
+        assert_eq!((tinted.color, tinted.pivot), (vibevj_common::Color::RED, glam::Vec2::new(0.0, 1.0)));
+        let plain = sprites.iter().find(|sprite| sprite.texture == Scene::video_texture(plain)).unwrap();
+        assert_eq!((plain.color, plain.pivot), (vibevj_common::Color::WHITE, glam::Vec2::splat(0.5)));
+    }
+}
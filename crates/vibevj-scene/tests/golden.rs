@@ -0,0 +1,958 @@
+//! Golden-image regression tests for the scene renderer
+//!
+//! Each test renders a small reference scene on the software adapter and
+//! compares it against `tests/golden/<name>.png`. Mismatches write the
+//! rendered image and a diff to `target/tmp/golden/`.
+//!
+//! To regenerate the references after an intentional change:
+//!
+//! ```text
+//! VIBEVJ_BLESS=1 cargo test -p vibevj-scene --test golden
+//! ```
+//!
+//! A missing software adapter fails every test. On machines that cannot
+//! provide one, set `VIBEVJ_SKIP_GOLDEN=1` to skip them instead.
+
+use glam::{Mat4, Vec2, Vec3};
+use vibevj_common::{AudioBand, AudioFeatures, Color, TimeInfo};
+use vibevj_engine::{
+    mesh_gen, AnimatedTexture, AnimationFrames, AspectMode, BlendMode, Blitter, Camera, ColorGrading, Compositor, CropRect, CubeLut, CrossfadeCurve, Crossfader, Deck, Displacement, EdgeBlend, EmitterShape, FrameUniform, GoldenImages, GoldenTolerance,
+    HeadlessOptions, HeadlessRenderer, Layer, LayerSource, LineBatch, Material, MixerSettings, OutputWindowSettings, ParticleSettings, ParticleSystem, Playback, PolygonMask, Polyline, Projection, RenderMode,
+    RenderGraph, RenderObject, RenderTarget, RenderTargetPool, ShaderLayer, SpectrumMapping, Sprite, SpriteBatch, SpriteCamera, TargetDesc, Texture, TextureCache, TextureSlot, ToneMapCurve,
+    ToneMapSettings, ToneMapper, VideoTexture, WarpHandles, WarpMesh, WarpSettings, Warper,
+};
+use vibevj_engine::{video, FrameWriter, Y4mWriter};
+use vibevj_scene::{Component, Scene, SceneFrame, SceneRenderer, SceneRuntime};
+
+const WIDTH: u32 = 128;
+const HEIGHT: u32 = 96;
+
+/// Renders reference scenes through the same HDR + tone mapping path as the app
+struct GoldenHarness {
+    headless: HeadlessRenderer,
+    scene_renderer: SceneRenderer,
+    texture_cache: TextureCache,
+    pool: RenderTargetPool,
+    output_target: RenderTarget,
+    tone_mapper: ToneMapper,
+    golden: GoldenImages,
+}
+
+impl GoldenHarness {
+    /// Create a harness on the software adapter
+    /// Returns `None` (and the test is skipped) when no such adapter exists
+    /// and `VIBEVJ_SKIP_GOLDEN` is set; panics otherwise
+    fn new(sample_count: u32) -> Option<Self> {
+        let headless = match pollster::block_on(HeadlessRenderer::with_options(HeadlessOptions::software())) {
+            Ok(headless) => headless,
+            Err(e) if std::env::var_os("VIBEVJ_SKIP_GOLDEN").is_some() => {
+                eprintln!("Skipping golden test, no software adapter: {}", e);
+                return None;
+            }
+            Err(e) => panic!("No software adapter for golden tests (set VIBEVJ_SKIP_GOLDEN=1 to skip): {}", e),
+        };
+        let device = &headless.device;
+
+        let camera = Camera::new(Vec3::new(0.0, 1.5, 6.0), Vec3::ZERO, WIDTH as f32 / HEIGHT as f32);
+        let mut scene_renderer = SceneRenderer::new(device, RenderTarget::HDR_FORMAT, camera);
+        let sample_count = RenderTarget::clamp_sample_count(
+            sample_count,
+            &headless.supported_sample_counts(RenderTarget::HDR_FORMAT),
+        );
+        scene_renderer.set_sample_count(device, sample_count);
+
+        scene_renderer.set_viewport_size(&headless.queue, WIDTH, HEIGHT);
+        let output_target = headless.create_render_target(WIDTH, HEIGHT, wgpu::TextureFormat::Rgba8UnormSrgb);
+        let tone_mapper = ToneMapper::new(device, &headless.queue, output_target.format, ToneMapSettings::default());
+
+        let texture_cache = TextureCache::new(device, &headless.queue).expect("texture cache");
+        let golden = GoldenImages::new(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden"),
+            concat!(env!("CARGO_TARGET_TMPDIR"), "/golden"),
+        );
+
+        Some(Self {
+            headless,
+            scene_renderer,
+            texture_cache,
+            pool: RenderTargetPool::new(),
+            output_target,
+            tone_mapper,
+            golden,
+        })
+    }
+
+    fn object(&self, mesh: vibevj_engine::Mesh, material: Material, transform: Mat4) -> RenderObject {
+        let mut object = RenderObject::new(mesh, material, transform);
+        object.upload(
+            &self.headless.device,
+            self.scene_renderer.material_bind_group_layout(),
+            self.scene_renderer.model_bind_group_layout(),
+            &self.texture_cache,
+        );
+        object
+    }
+
+    /// Render `objects` with the given tone mapping and read the result as RGBA8
+    fn render(&mut self, objects: &[RenderObject], tone_mapping: ToneMapSettings) -> Vec<u8> {
+        self.render_layers(objects, &[], &[], &[], tone_mapping)
+    }
+
+    /// Render `objects`, then `particles`, `lines` and `sprites` over them, and read the result as RGBA8
+    fn render_layers(
+        &mut self,
+        objects: &[RenderObject],
+        particles: &[&ParticleSystem],
+        lines: &[&LineBatch],
+        sprites: &[&SpriteBatch],
+        tone_mapping: ToneMapSettings,
+    ) -> Vec<u8> {
+        let queue = &self.headless.queue;
+        self.scene_renderer.update_camera(queue);
+        self.tone_mapper.update(queue, tone_mapping);
+
+        let mut encoder = self.headless.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Golden Encoder"),
+        });
+        let object_refs: Vec<&RenderObject> = objects.iter().collect();
+        let mut graph = RenderGraph::new();
+        let output = graph.import_target("Golden Output", &self.output_target);
+        let frame = SceneFrame {
+            renderer: &self.scene_renderer,
+            objects: &object_refs,
+            particles,
+            lines,
+            sprites,
+            clear_color: wgpu::Color {
+                r: 0.05,
+                g: 0.05,
+                b: 0.08,
+                a: 1.0,
+            },
+            width: WIDTH,
+            height: HEIGHT,
+        };
+        frame.add_passes(&mut graph, &mut self.tone_mapper, output);
+        graph
+            .execute(&self.headless.device, queue, &mut encoder, &mut self.pool)
+            .expect("execute render graph");
+        queue.submit(Some(encoder.finish()));
+
+        self.headless.read_rgba(&self.output_target).expect("read back render target")
+    }
+
+    fn check(&self, name: &str, rgba: &[u8]) {
+        if let Err(e) = self.golden.check(name, rgba, WIDTH, HEIGHT, &GoldenTolerance::default()) {
+            panic!("{}", e);
+        }
+    }
+}
+
+fn untonemapped() -> ToneMapSettings {
+    ToneMapSettings {
+        curve: ToneMapCurve::None,
+        white_point: 1.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn primitives_unlit() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    let objects = vec![
+        harness.object(
+            mesh_gen::create_cube(1.0),
+            Material::unlit(Color::new(1.0, 0.5, 0.2, 1.0)),
+            Mat4::from_translation(Vec3::new(-2.4, 0.0, 0.0)) * Mat4::from_rotation_y(0.6),
+        ),
+        harness.object(
+            mesh_gen::create_sphere(0.6, 24, 12),
+            Material::unlit(Color::new(0.2, 0.8, 0.3, 1.0)),
+            Mat4::from_translation(Vec3::new(-0.8, 0.0, 0.0)),
+        ),
+        harness.object(
+            mesh_gen::create_cylinder(0.5, 1.2, 24),
+            Material::unlit(Color::new(0.2, 0.4, 1.0, 1.0)),
+            Mat4::from_translation(Vec3::new(0.8, 0.0, 0.0)),
+        ),
+        harness.object(
+            mesh_gen::create_plane(1.2, 1.2, 1, 1),
+            Material::unlit(Color::new(0.9, 0.9, 0.9, 1.0)),
+            Mat4::from_translation(Vec3::new(2.4, 0.0, 0.0)) * Mat4::from_rotation_x(0.8),
+        ),
+    ];
+
+    let rgba = harness.render(&objects, untonemapped());
+    harness.check("primitives_unlit", &rgba);
+}
+
+#[test]
+fn pbr_lighting() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    // Metallic increases left to right, roughness top to bottom
+    let mut objects = Vec::new();
+    for row in 0..2 {
+        for column in 0..4 {
+            let metallic = column as f32 / 3.0;
+            let roughness = 0.2 + row as f32 * 0.6;
+            objects.push(harness.object(
+                mesh_gen::create_sphere(0.55, 24, 12),
+                Material::pbr(Color::new(0.9, 0.6, 0.3, 1.0), metallic, roughness),
+                Mat4::from_translation(Vec3::new(-1.8 + column as f32 * 1.2, 0.7 - row as f32 * 1.3, 0.0)),
+            ));
+        }
+    }
+
+    let rgba = harness.render(&objects, ToneMapSettings::default());
+    harness.check("pbr_lighting", &rgba);
+}
+
+#[test]
+fn procedural_meshes() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    let star = mesh_gen::Superformula { m: 5.0, n1: 2.0, n2: 7.0, n3: 7.0, a: 1.0, b: 1.0 };
+    let meshes = [
+        mesh_gen::create_torus(0.4, 0.15, 32, 12),
+        mesh_gen::create_torus_knot(0.5, 0.1, 2, 3, 96, 8),
+        mesh_gen::create_icosphere(0.5, 2),
+        mesh_gen::create_cone(0.45, 0.9, 24),
+        mesh_gen::create_capsule(0.3, 0.5, 24, 6),
+        mesh_gen::create_platonic_solid(mesh_gen::PlatonicSolid::Dodecahedron, 0.5),
+        mesh_gen::create_supershape(0.5, star, mesh_gen::Superformula::default(), 48, 24),
+        mesh_gen::create_terrain(&mesh_gen::Heightmap::from_noise(17, 17, 3, 3.0, 3), 1.0, 1.0, 0.3),
+    ];
+
+    let objects: Vec<_> = meshes
+        .into_iter()
+        .enumerate()
+        .map(|(i, mesh)| {
+            let (column, row) = ((i % 4) as f32, (i / 4) as f32);
+            harness.object(
+                mesh,
+                Material::pbr(Color::new(0.8, 0.8, 0.8, 1.0), 0.0, 0.5),
+                Mat4::from_translation(Vec3::new(-1.8 + column * 1.2, 0.7 - row * 1.3, 0.0))
+                    * Mat4::from_rotation_x(0.5),
+            )
+        })
+        .collect();
+
+    let rgba = harness.render(&objects, ToneMapSettings::default());
+    harness.check("procedural_meshes", &rgba);
+}
+
+#[test]
+fn vertex_displacement() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    // Fixed time and audio so noise, wave and spectrum land in a known state
+    let audio = AudioFeatures {
+        bands: [0.9, 0.7, 0.5, 0.3, 0.6, 0.8, 1.0],
+        beat: false,
+    };
+    harness
+        .scene_renderer
+        .update_frame(&harness.headless.queue, &TimeInfo::fixed_step(30, 30.0), &audio);
+
+    let material = |displacement: Displacement| {
+        Material::pbr(Color::new(0.8, 0.8, 0.8, 1.0), 0.0, 0.5).with_displacement(displacement)
+    };
+    let objects = vec![
+        harness.object(
+            mesh_gen::create_icosphere(0.7, 4),
+            material(Displacement::pulse(0.06, 2.0, AudioBand::Bass)),
+            Mat4::from_translation(Vec3::new(-2.0, 0.0, 0.0)),
+        ),
+        harness.object(
+            mesh_gen::create_cylinder(0.5, 1.6, 32),
+            material(Displacement {
+                wave_amplitude: 0.1,
+                wave_frequency: 8.0,
+                wave_speed: 2.0,
+                ..Default::default()
+            }),
+            Mat4::IDENTITY,
+        ),
+        harness.object(
+            mesh_gen::create_sphere(0.6, 48, 24),
+            material(Displacement {
+                spectrum_amplitude: 0.3,
+                spectrum_mapping: SpectrumMapping::Height { min: -0.6, max: 0.6 },
+                ..Default::default()
+            }),
+            Mat4::from_translation(Vec3::new(2.0, 0.0, 0.0)),
+        ),
+    ];
+
+    let rgba = harness.render(&objects, ToneMapSettings::default());
+    harness.check("vertex_displacement", &rgba);
+}
+
+#[test]
+fn render_modes() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    let material = |render_mode: RenderMode| Material::unlit(Color::new(0.9, 0.9, 0.9, 1.0)).with_render_mode(render_mode);
+    let objects = vec![
+        harness.object(
+            mesh_gen::create_icosphere(0.7, 1),
+            material(RenderMode::Wireframe { thickness: 1.5 }),
+            Mat4::from_translation(Vec3::new(-2.0, 0.4, 0.0)),
+        ),
+        harness.object(
+            mesh_gen::create_sphere(0.7, 16, 8),
+            material(RenderMode::Points { size: 3.0 }),
+            Mat4::from_translation(Vec3::new(0.0, 0.4, 0.0)),
+        ),
+        harness.object(
+            mesh_gen::create_cube(1.0),
+            material(RenderMode::Edges { thickness: 2.0, crease_angle: 30.0 }),
+            Mat4::from_translation(Vec3::new(2.0, 0.4, 0.0)) * Mat4::from_rotation_y(0.6) * Mat4::from_rotation_x(0.4),
+        ),
+    ];
+
+    // A closed zigzag below the objects, partly behind the ground line
+    let points = (0..8)
+        .map(|i| Vec3::new(-2.8 + i as f32 * 0.8, -1.2 + (i % 2) as f32 * 0.4, 0.0))
+        .collect();
+    let mut lines = LineBatch::new();
+    lines.set_polylines(
+        &harness.headless.device,
+        &harness.headless.queue,
+        &[
+            Polyline::new(points, Color::new(1.0, 0.4, 0.1, 1.0), 3.0),
+            Polyline::new(
+                vec![Vec3::new(-3.0, -1.5, 0.0), Vec3::new(3.0, -1.5, 0.0)],
+                Color::new(0.2, 0.6, 1.0, 1.0),
+                1.0,
+            ),
+        ],
+    );
+
+    let rgba = harness.render_layers(&objects, &[], &[&lines], &[], untonemapped());
+    harness.check("render_modes", &rgba);
+}
+
+#[test]
+fn orthographic_projection() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    // A row of equal cubes receding in depth keeps the same size on screen
+    harness.scene_renderer.camera_mut().projection = Projection::Orthographic { height: 4.0 };
+    let objects: Vec<_> = (0..4)
+        .map(|i| {
+            harness.object(
+                mesh_gen::create_cube(0.8),
+                Material::pbr(Color::new(0.8, 0.8, 0.8, 1.0), 0.0, 0.5),
+                Mat4::from_translation(Vec3::new(-2.1 + i as f32 * 1.4, 0.0, -(i as f32) * 2.0))
+                    * Mat4::from_rotation_y(0.4),
+            )
+        })
+        .collect();
+
+    let rgba = harness.render(&objects, ToneMapSettings::default());
+    harness.check("orthographic_projection", &rgba);
+}
+
+#[test]
+fn sprite_batching() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+    let device = &harness.headless.device;
+    let queue = &harness.headless.queue;
+
+    // A 2x2 atlas of red, green, blue and white cells, and a soft-edged disc
+    let atlas = image::RgbaImage::from_fn(16, 16, |x, y| match (x / 8, y / 8) {
+        (0, 0) => image::Rgba([255, 0, 0, 255]),
+        (1, 0) => image::Rgba([0, 255, 0, 255]),
+        (0, 1) => image::Rgba([0, 0, 255, 255]),
+        _ => image::Rgba([255, 255, 255, 255]),
+    });
+    let disc = image::RgbaImage::from_fn(16, 16, |x, y| {
+        let distance = Vec2::new(x as f32 - 7.5, y as f32 - 7.5).length();
+        image::Rgba([255, 255, 255, ((8.0 - distance).clamp(0.0, 1.0) * 255.0) as u8])
+    });
+    for (name, image) in [("atlas", &atlas), ("disc", &disc)] {
+        let texture = Texture::from_image(device, queue, image, Some(name)).expect("sprite texture");
+        harness.texture_cache.insert(name, texture);
+    }
+
+    // The red cell sits behind a translucent disc; the missing texture falls back to white
+    let sprites = [
+        Sprite::new("disc", Vec2::new(24.0, 48.0), Vec2::splat(24.0)).with_color(Color::new(0.0, 1.0, 1.0, 0.5)),
+        Sprite::new("atlas", Vec2::new(24.0, 48.0), Vec2::splat(32.0)).with_grid_cell(2, 2, 0).with_depth(1.0),
+        Sprite::new("atlas", Vec2::new(64.0, 48.0), Vec2::splat(28.0)).with_grid_cell(2, 2, 3).with_rotation(std::f32::consts::FRAC_PI_4),
+        Sprite::new("missing.png", Vec2::new(64.0, 12.0), Vec2::new(24.0, 6.0)).with_color(Color::new(1.0, 0.5, 0.0, 1.0)),
+        Sprite::new("atlas", Vec2::new(96.0, 56.0), Vec2::splat(20.0)).with_grid_cell(2, 2, 1).with_pivot(Vec2::new(0.0, 1.0)),
+        Sprite::new("atlas", Vec2::new(104.0, 32.0), Vec2::splat(20.0)).with_grid_cell(2, 2, 2),
+    ];
+    let mut batch = SpriteBatch::new();
+    batch.set_sprites(device, queue, &sprites);
+
+    let sprite_renderer = harness.scene_renderer.sprite_renderer_mut();
+    sprite_renderer.set_camera(queue, &SpriteCamera::pixels(WIDTH, HEIGHT), WIDTH as f32 / HEIGHT as f32);
+    sprite_renderer.prepare(device, queue, &mut harness.texture_cache, &[&batch]);
+
+    // The 2D layer covers the 3D cube regardless of depth
+    let cube = harness.object(
+        mesh_gen::create_cube(1.4),
+        Material::unlit(Color::new(0.3, 0.3, 0.35, 1.0)),
+        Mat4::from_rotation_y(0.6),
+    );
+    let rgba = harness.render_layers(&[cube], &[], &[], &[&batch], untonemapped());
+
+    // Red atlas cell corner outside the disc, at world (10, 34)
+    let pixel = |x: u32, y: u32| {
+        let offset = (((HEIGHT - 1 - y) * WIDTH + x) * 4) as usize;
+        &rgba[offset..offset + 3]
+    };
+    assert_eq!(pixel(10, 34), [255, 0, 0]);
+    harness.check("sprite_batching", &rgba);
+}
+
+#[test]
+fn tone_mapping_curves() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    // Bright emissive values well above 1.0 exercise each curve's shoulder
+    let objects = vec![
+        harness.object(
+            mesh_gen::create_sphere(0.8, 24, 12),
+            Material::emissive(Color::new(1.0, 0.6, 0.2, 1.0), 6.0),
+            Mat4::from_translation(Vec3::new(-1.2, 0.0, 0.0)),
+        ),
+        harness.object(
+            mesh_gen::create_sphere(0.8, 24, 12),
+            Material::pbr(Color::new(0.3, 0.5, 1.0, 1.0), 0.0, 0.4),
+            Mat4::from_translation(Vec3::new(1.2, 0.0, 0.0)),
+        ),
+    ];
+
+    for curve in ToneMapCurve::ALL {
+        let settings = ToneMapSettings {
+            curve,
+            exposure: 1.0,
+            ..Default::default()
+        };
+        let rgba = harness.render(&objects, settings);
+        harness.check(&format!("tonemap_{}", curve.name().to_lowercase()), &rgba);
+    }
+}
+
+/// 2³ `.cube` LUT inverting the colours, in Resolve's input range form
+const INVERT_LUT: &str = r#"
+# Inverts RGB
+TITLE "Invert"
+LUT_3D_SIZE 2
+LUT_3D_INPUT_RANGE 0.0 1.0
+
+1.0 1.0 1.0
+0.0 1.0 1.0
+1.0 0.0 1.0
+0.0 0.0 1.0
+1.0 1.0 0.0
+0.0 1.0 0.0
+1.0 0.0 0.0
+0.0 0.0 0.0
+"#;
+
+#[test]
+fn color_grading_and_lut() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    let lut = CubeLut::parse(INVERT_LUT).expect("parse .cube");
+
+    let objects = vec![
+        harness.object(
+            mesh_gen::create_sphere(0.8, 24, 12),
+            Material::emissive(Color::new(1.0, 0.6, 0.2, 1.0), 2.0),
+            Mat4::from_translation(Vec3::new(-1.2, 0.0, 0.0)),
+        ),
+        harness.object(
+            mesh_gen::create_sphere(0.8, 24, 12),
+            Material::pbr(Color::new(0.3, 0.5, 1.0, 1.0), 0.0, 0.4),
+            Mat4::from_translation(Vec3::new(1.2, 0.0, 0.0)),
+        ),
+    ];
+    let device = harness.headless.device.clone();
+    let queue = harness.headless.queue.clone();
+
+    // Warm lift, punchy contrast and a hue shift
+    let grading = ColorGrading {
+        lift: [0.08, 0.02, -0.04],
+        gamma: [1.0, 1.1, 1.0],
+        gain: [1.1, 1.0, 0.9],
+        saturation: 1.4,
+        hue: 30.0,
+        contrast: 1.2,
+        ..Default::default()
+    };
+    harness.tone_mapper.update_grading(&device, &queue, &grading);
+    let rgba = harness.render(&objects, ToneMapSettings::default());
+    harness.check("grading_controls", &rgba);
+
+    // Mostly inverted by the LUT, over the ungraded image
+    harness.tone_mapper.set_lut(&device, &queue, Some(lut));
+    harness.tone_mapper.update_grading(&device, &queue, &ColorGrading {
+        lut_intensity: 0.75,
+        ..Default::default()
+    });
+    let rgba = harness.render(&objects, ToneMapSettings::default());
+    harness.check("grading_lut", &rgba);
+}
+
+#[test]
+fn msaa_edges() {
+    let Some(mut harness) = GoldenHarness::new(4) else { return };
+
+    let objects = vec![harness.object(
+        mesh_gen::create_cube(2.0),
+        Material::unlit(Color::new(1.0, 1.0, 1.0, 1.0)),
+        Mat4::from_rotation_z(0.3) * Mat4::from_rotation_y(0.5),
+    )];
+
+    let rgba = harness.render(&objects, untonemapped());
+    harness.check("msaa_edges", &rgba);
+}
+
+/// Colour ramp with darker bands, so every blend mode and the luma key differ
+const GRADIENT_LAYER: &str = "
+fn shade(uv: vec2<f32>) -> vec4<f32> {
+    let band = step(0.5, fract(uv.y * 4.0));
+    return vec4<f32>(vec3<f32>(uv.x, 0.2 + 0.6 * band, 1.0 - uv.x) * (0.3 + 0.7 * band), 1.0);
+}
+";
+
+#[test]
+fn compositor_blend_modes() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    let objects = vec![harness.object(
+        mesh_gen::create_sphere(1.5, 32, 16),
+        Material::pbr(Color::new(0.9, 0.5, 0.2, 1.0), 0.0, 0.4),
+        Mat4::IDENTITY,
+    )];
+    harness.render(&objects, ToneMapSettings::default());
+
+    let device = &harness.headless.device;
+    let queue = &harness.headless.queue;
+    let gradient = ShaderLayer::new(device, GRADIENT_LAYER, WIDTH, HEIGHT, "Gradient Layer").expect("gradient layer");
+    let output = harness.headless.create_render_target(WIDTH, HEIGHT, wgpu::TextureFormat::Rgba8UnormSrgb);
+    let mut compositor = Compositor::new(device, output.format, WIDTH, HEIGHT);
+    let shader_source = LayerSource::Shader { path: "gradient".to_string() };
+
+    let mut composite = |mixer: &MixerSettings| {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Golden Composite Encoder"),
+        });
+        gradient.render(queue, &mut encoder, &FrameUniform::new());
+        compositor.render(
+            device,
+            queue,
+            &mut encoder,
+            mixer,
+            |source| match source {
+                LayerSource::Scene => Some(&harness.output_target.view),
+                _ => Some(&gradient.target.view),
+            },
+            &output.view,
+        );
+        queue.submit(Some(encoder.finish()));
+        harness.headless.read_rgba(&output).expect("read back composite")
+    };
+
+    // Gradient over the scene at 75% opacity in each blend mode
+    for mode in BlendMode::ALL {
+        let mut mixer = MixerSettings::default();
+        mixer.deck_a.layers.push(Layer::new("Gradient", shader_source.clone()).with_blend_mode(mode).with_opacity(0.75));
+        let rgba = composite(&mixer);
+        harness.check(&format!("composite_{}", mode.name().to_lowercase().replace(' ', "_")), &rgba);
+    }
+
+    // Equal-power crossfade halfway from the scene (deck A) to the gradient (deck B)
+    let mixer = MixerSettings {
+        deck_b: Deck::new(vec![Layer::new("Gradient", shader_source)]),
+        crossfader: Crossfader {
+            position: 0.5,
+            curve: CrossfadeCurve::EqualPower,
+        },
+        ..Default::default()
+    };
+    let rgba = composite(&mixer);
+    harness.check("composite_crossfade", &rgba);
+}
+
+#[test]
+fn render_graph_order_and_culling() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    let objects = vec![harness.object(
+        mesh_gen::create_torus(1.0, 0.4, 32, 16),
+        Material::pbr(Color::new(0.3, 0.7, 0.9, 1.0), 0.0, 0.3),
+        Mat4::from_rotation_x(0.9),
+    )];
+    let rgba = harness.render(&objects, ToneMapSettings::default());
+    harness.check("render_graph_order", &rgba);
+
+    // Same frame with the mix declared before the scene passes it reads, and a
+    // branch nothing reads, which must be culled
+    let device = &harness.headless.device;
+    let queue = &harness.headless.queue;
+    let mut compositor = Compositor::new(device, harness.output_target.format, WIDTH, HEIGHT);
+    let mixer = MixerSettings::default();
+    let object_refs: Vec<&RenderObject> = objects.iter().collect();
+
+    let mut graph = RenderGraph::new();
+    let output = graph.import_target("Golden Output", &harness.output_target);
+    let scene_layer = graph.create_target("Scene Layer", TargetDesc::new(WIDTH, HEIGHT, harness.output_target.format));
+    graph.add_pass("Mix").read(scene_layer).write(output).execute(|ctx| {
+        let scene_view = ctx.view(scene_layer);
+        let output = ctx.view(output);
+        compositor.render(ctx.device, ctx.queue, ctx.encoder, &mixer, |_| Some(scene_view), output);
+    });
+    let unused = graph.create_target("Unused", TargetDesc::new(WIDTH, HEIGHT, RenderTarget::HDR_FORMAT));
+    graph.add_pass("Unused").write(unused).execute(|_| panic!("culled pass was executed"));
+    let frame = SceneFrame {
+        renderer: &harness.scene_renderer,
+        objects: &object_refs,
+        particles: &[],
+        lines: &[],
+        sprites: &[],
+        clear_color: wgpu::Color {
+            r: 0.05,
+            g: 0.05,
+            b: 0.08,
+            a: 1.0,
+        },
+        width: WIDTH,
+        height: HEIGHT,
+    };
+    frame.add_passes(&mut graph, &mut harness.tone_mapper, scene_layer);
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Golden Graph Encoder"),
+    });
+    graph.execute(device, queue, &mut encoder, &mut harness.pool).expect("execute render graph");
+    queue.submit(Some(encoder.finish()));
+
+    let rgba = harness.headless.read_rgba(&harness.output_target).expect("read back render target");
+    harness.check("render_graph_order", &rgba);
+}
+
+#[test]
+fn blit_aspect_modes() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    let objects = vec![harness.object(
+        mesh_gen::create_cube(2.0),
+        Material::pbr(Color::new(0.4, 0.8, 0.3, 1.0), 0.0, 0.5),
+        Mat4::from_rotation_y(0.6),
+    )];
+    harness.render(&objects, ToneMapSettings::default());
+
+    // The 4:3 frame shown in a square window
+    const SIZE: u32 = 64;
+    let device = &harness.headless.device;
+    let window = harness.headless.create_render_target(SIZE, SIZE, harness.output_target.format);
+    let blitter = Blitter::new(device, window.format);
+    for mode in AspectMode::ALL {
+        let fit = mode.fit((WIDTH as f32, HEIGHT as f32), (SIZE as f32, SIZE as f32));
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Golden Blit Encoder"),
+        });
+        blitter.blit(device, &mut encoder, &harness.output_target.view, &window.view, (SIZE, SIZE), &fit);
+        let rgba = harness.headless.finish_frame(encoder, &window).expect("read back blit");
+        let name = format!("blit_{}", mode.name().to_lowercase());
+        if let Err(e) = harness.golden.check(&name, &rgba, SIZE, SIZE, &GoldenTolerance::default()) {
+            panic!("{}", e);
+        }
+    }
+
+    // Left half of the canvas, as one of two projectors spanning it
+    let mut output = OutputWindowSettings::new("Left");
+    output.crop = CropRect {
+        width: 0.5,
+        ..CropRect::FULL
+    };
+    let fit = output.fit(AspectMode::Letterbox, (WIDTH as f32, HEIGHT as f32), (SIZE as f32, SIZE as f32));
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Golden Blit Encoder"),
+    });
+    blitter.blit(device, &mut encoder, &harness.output_target.view, &window.view, (SIZE, SIZE), &fit);
+    let rgba = harness.headless.finish_frame(encoder, &window).expect("read back blit");
+    if let Err(e) = harness.golden.check("blit_crop_rect", &rgba, SIZE, SIZE, &GoldenTolerance::default()) {
+        panic!("{}", e);
+    }
+}
+
+/// GIF of `count` 16x12 frames shown for 0.1s each, frame `i` lighting
+/// columns `0..=i` over a gradient
+fn test_gif(count: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = image::codecs::gif::GifEncoder::new(&mut bytes);
+        let frames = (0..count).map(|index| {
+            let image = image::RgbaImage::from_fn(16, 12, |x, y| {
+                if x / 4 <= index {
+                    image::Rgba([255, 200 - 60 * (x / 4) as u8, 40, 255])
+                } else {
+                    image::Rgba([20, 20 * y as u8, 120, 255])
+                }
+            });
+            image::Frame::from_parts(image, 0, 0, image::Delay::from_numer_denom_ms(100, 1))
+        });
+        encoder.encode_frames(frames).expect("encode gif");
+    }
+    bytes
+}
+
+#[test]
+fn animated_texture_playback() {
+    let frames = AnimationFrames::decode(&test_gif(4)).expect("decode gif");
+    let Some(harness) = GoldenHarness::new(1) else { return };
+    let device = &harness.headless.device;
+    let queue = &harness.headless.queue;
+
+    // Only beat frames step the texture, and a repeated frame number is ignored
+    let mut texture = AnimatedTexture::new(device, queue, frames.clone(), Some("Golden GIF")).expect("animated texture");
+    let beat = AudioFeatures { beat: true, ..Default::default() };
+    let playback = Playback { beat_step: true, ..Playback::default() };
+    for frame in [0, 1, 1, 2] {
+        texture.update(queue, &playback, &TimeInfo::fixed_step(frame, 60.0), &beat);
+    }
+    texture.update(queue, &playback, &TimeInfo::fixed_step(3, 60.0), &AudioFeatures::default());
+    assert_eq!(texture.current_frame(), 3);
+
+    const SIZE: (u32, u32) = (64, 48);
+    let window = harness.headless.create_render_target(SIZE.0, SIZE.1, harness.output_target.format);
+    let blitter = Blitter::new(device, window.format);
+    let fit = AspectMode::Stretch.fit((16.0, 12.0), (SIZE.0 as f32, SIZE.1 as f32));
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Golden Animation Encoder"),
+    });
+    blitter.blit(device, &mut encoder, &texture.texture.view, &window.view, SIZE, &fit);
+    let rgba = harness.headless.finish_frame(encoder, &window).expect("read back animation frame");
+    if let Err(e) = harness.golden.check("animated_texture_frame", &rgba, SIZE.0, SIZE.1, &GoldenTolerance::default()) {
+        panic!("{}", e);
+    }
+}
+
+/// Frame `index` of the test video: a lit bar moving across a gradient
+fn test_video_frame(index: u32) -> image::RgbaImage {
+    image::RgbaImage::from_fn(16, 12, |x, y| {
+        if x / 4 == index % 4 {
+            image::Rgba([240, 180, 40, 255])
+        } else {
+            image::Rgba([20 * y as u8, 60, 200 - 12 * x as u8, 255])
+        }
+    })
+}
+
+/// Assert every pixel of `rgba` is within `tolerance` of `expected`
+fn assert_pixels_near(rgba: &[u8], expected: &image::RgbaImage, tolerance: u8) {
+    for (index, (actual, expected)) in rgba.chunks_exact(4).zip(expected.pixels()).enumerate() {
+        let close = actual.iter().zip(expected.0).all(|(&a, e)| a.abs_diff(e) <= tolerance);
+        assert!(close, "pixel {} is {:?}, expected {:?}", index, actual, expected.0);
+    }
+}
+
+#[test]
+fn video_yuv_conversion() {
+    let directory = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("video");
+    std::fs::create_dir_all(&directory).expect("create video directory");
+
+    // Y4M written by the exporter, with the alpha plane it adds for transparency
+    let y4m_path = directory.join("bars.y4m");
+    let mut writer = Y4mWriter::new(&y4m_path, 16, 12, 10, true).expect("create y4m");
+    for index in 0..6 {
+        writer.write_frame(&test_video_frame(index)).expect("write y4m frame");
+    }
+    writer.finish().expect("finish y4m");
+
+    // Pure red as a JPEG decoder gives it, full-range BT.601 with 4:2:0 chroma
+    let red = video::VideoFrame {
+        index: 0,
+        width: 16,
+        height: 12,
+        chroma_width: 8,
+        chroma_height: 6,
+        y: vec![76; 16 * 12],
+        u: vec![85; 8 * 6],
+        v: vec![255; 8 * 6],
+        matrix: video::YuvMatrix::Bt601,
+        full_range: true,
+    };
+
+    let Some(harness) = GoldenHarness::new(1) else { return };
+    let device = &harness.headless.device;
+    let queue = &harness.headless.queue;
+
+    // Limited-range BT.709 and full-range BT.601 both convert back to the source colours
+    let mut texture = VideoTexture::new(device, 16, 12, Some("Golden Video"));
+    let mut decoder = video::open(&y4m_path).expect("open y4m");
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Golden Video Encoder"),
+    });
+    texture.upload(device, queue, &mut encoder, &red);
+    let rgba = harness.headless.finish_frame(encoder, &texture.target).expect("read back red frame");
+    assert_pixels_near(&rgba, &image::RgbaImage::from_pixel(16, 12, image::Rgba([255, 0, 0, 255])), 4);
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Golden Video Encoder"),
+    });
+    texture.upload(device, queue, &mut encoder, &decoder.decode(3).expect("decode y4m frame"));
+    let rgba = harness.headless.finish_frame(encoder, &texture.target).expect("read back y4m frame");
+    assert_pixels_near(&rgba, &test_video_frame(3), 3);
+
+    const SIZE: (u32, u32) = (64, 48);
+    let window = harness.headless.create_render_target(SIZE.0, SIZE.1, harness.output_target.format);
+    let blitter = Blitter::new(device, window.format);
+    let fit = AspectMode::Stretch.fit((16.0, 12.0), (SIZE.0 as f32, SIZE.1 as f32));
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Golden Video Encoder"),
+    });
+    blitter.blit(device, &mut encoder, &texture.target.view, &window.view, SIZE, &fit);
+    let rgba = harness.headless.finish_frame(encoder, &window).expect("read back video frame");
+    if let Err(e) = harness.golden.check("video_frame", &rgba, SIZE.0, SIZE.1, &GoldenTolerance::default()) {
+        panic!("{}", e);
+    }
+}
+
+#[test]
+fn warp_projection_mapping() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+
+    let objects = vec![harness.object(
+        mesh_gen::create_cube(2.0),
+        Material::pbr(Color::new(0.4, 0.8, 0.3, 1.0), 0.0, 0.5),
+        Mat4::from_rotation_y(0.6),
+    )];
+    harness.render(&objects, ToneMapSettings::default());
+
+    let (width, height) = (80, 60);
+    let device = &harness.headless.device;
+    let window = harness.headless.create_render_target(width, height, harness.output_target.format);
+    let warper = Warper::new(device, window.format);
+    let fit = AspectMode::Stretch.fit((WIDTH as f32, HEIGHT as f32), (width as f32, height as f32));
+
+    // Keystoned onto a tilted surface, bulged in the middle, blended on the
+    // left for an overlapping projector and masked in the bottom-right corner
+    let mut mesh = WarpMesh::grid(3, 3);
+    mesh.points[4] = [0.6, 0.4];
+    let settings = WarpSettings {
+        corners: [[0.1, 0.05], [0.9, 0.15], [0.95, 0.95], [0.05, 0.85]],
+        mesh,
+        edge_blend: EdgeBlend {
+            left: 0.3,
+            ..Default::default()
+        },
+        masks: vec![PolygonMask {
+            points: vec![[1.0, 0.6], [1.0, 1.0], [0.6, 1.0]],
+            invert: false,
+        }],
+    };
+
+    let render = |name: &str, handles: &[[f32; 2]], selected: Option<usize>| {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Golden Warp Encoder"),
+        });
+        warper.render(device, &mut encoder, &harness.output_target.view, &window.view, (width, height), &fit, &settings, handles, selected);
+        let rgba = harness.headless.finish_frame(encoder, &window).expect("read back warp");
+        if let Err(e) = harness.golden.check(name, &rgba, width, height, &GoldenTolerance::default()) {
+            panic!("{}", e);
+        }
+    };
+    render("warp_projection", &[], None);
+
+    // Mesh handles in edit mode follow the corner pin
+    let handles = settings.handles(WarpHandles::Mesh, &fit, (width, height));
+    assert_eq!(handles.len(), 9);
+    render("warp_handles", &handles, Some(4));
+}
+
+#[test]
+fn particle_mesh_surface() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+    let device = &harness.headless.device;
+    let queue = &harness.headless.queue;
+
+    // A thin strip above the origin, so particles emitted from a point would miss it
+    let directory = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("particles");
+    std::fs::create_dir_all(&directory).expect("create particle directory");
+    let strip = directory.join("strip.obj");
+    std::fs::write(&strip, "v -2 0.9 0\nv 2 0.9 0\nv 2 1.1 0\nv -2 1.1 0\nf 1 2 3\nf 1 3 4\n").expect("write obj");
+
+    // The emitter names no mesh, so it uses the node's MeshRenderer
+    let mut scene = Scene::new("Particles".to_string());
+    let id = scene.create_node("Emitter".to_string(), None).expect("create node");
+    let node = scene.get_node_mut(id).expect("emitter node");
+    node.add_component(Component::MeshRenderer {
+        mesh: Scene::model_mesh(&strip.to_string_lossy(), 0),
+        material: String::new(),
+    });
+    let mut settings = ParticleSettings {
+        max_particles: 512,
+        emitter: EmitterShape::MeshSurface { mesh: String::new() },
+        emission_rate: 2000.0,
+        lifetime: [10.0, 10.0],
+        initial_speed: 0.0,
+        speed_variance: 0.0,
+        ..Default::default()
+    };
+    settings.forces.gravity = [0.0; 3];
+    node.add_component(Component::ParticleEmitter { settings, enabled: true });
+
+    let mut runtime = SceneRuntime::new();
+    for _ in 0..4 {
+        runtime.update_particles(device, queue, &scene, &harness.scene_renderer, 0.1, &AudioFeatures::default());
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Golden Particle Encoder"),
+        });
+        for system in runtime.particles() {
+            system.simulate(&mut encoder);
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+    let systems: Vec<&ParticleSystem> = runtime.particles().collect();
+    assert_eq!(systems.iter().map(|system| system.surface_triangle_count()).collect::<Vec<_>>(), [2]);
+
+    let rgba = harness.render_layers(&[], &systems, &[], &[], untonemapped());
+    harness.check("particle_mesh_surface", &rgba);
+
+    // Changing the shape rebuilds the system without the surface
+    for component in &mut scene.get_node_mut(id).expect("emitter node").components {
+        if let Component::ParticleEmitter { settings, .. } = component {
+            settings.emitter = EmitterShape::Point;
+        }
+    }
+    let (device, queue) = (&harness.headless.device, &harness.headless.queue);
+    runtime.update_particles(device, queue, &scene, &harness.scene_renderer, 0.1, &AudioFeatures::default());
+    assert_eq!(runtime.particles().map(|system| system.surface_triangle_count()).collect::<Vec<_>>(), [0]);
+}
+
+#[test]
+fn texture_cache_remembers_failed_loads() {
+    let Some(mut harness) = GoldenHarness::new(1) else { return };
+    let device = &harness.headless.device;
+    let queue = &harness.headless.queue;
+
+    let directory = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("textures");
+    std::fs::create_dir_all(&directory).expect("create texture directory");
+    let path = directory.join("late.png");
+    let _ = std::fs::remove_file(&path);
+    let name = path.to_str().expect("utf-8 path");
+    let material = Material::unlit(Color::WHITE).with_texture(TextureSlot::Albedo, name);
+
+    harness.texture_cache.load_material_textures(device, queue, &material);
+    assert!(harness.texture_cache.get(name).is_none());
+
+    // The failure is cached, so a file appearing later is not picked up until removed
+    image::RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255])).save(&path).expect("write png");
+    harness.texture_cache.load_material_textures(device, queue, &material);
+    assert!(harness.texture_cache.get(name).is_none());
+
+    harness.texture_cache.remove(name);
+    harness.texture_cache.load_material_textures(device, queue, &material);
+    assert!(harness.texture_cache.get(name).is_some());
+}
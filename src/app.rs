@@ -9,14 +9,15 @@ use winit::{
     window::Window,
 };
 
-use vibevj_common::TimeInfo;
-use vibevj_engine::{Renderer, RenderObject, Material, mesh_gen, Camera, RenderTarget};
-use vibevj_gui::GuiApp;
-use vibevj_audio::{AudioInput, AudioAnalyzer, FrequencyBands};
-use vibevj_scene::{Scene, SceneRenderer};
+use vibevj_common::{AudioFeatures, TimeInfo};
+use vibevj_engine::{Renderer, RenderObject, Camera, CameraInput, RenderTarget, TextureCache, ToneMapper, ParticleSystem};
+use vibevj_engine::{Compositor, LayerSource, LayerSources, OutputSource, RenderGraph, TargetDesc, RenderTargetPool};
+use vibevj_gui::{CameraMenu, GuiApp};
+use vibevj_audio::{AudioInput, AudioAnalyzer, BeatDetector, FrequencyBands};
+use vibevj_scene::{Scene, SceneFrame, SceneRenderer, SceneRuntime};
 use vibevj_scripting::ScriptEngine;
-use glam::{Mat4, Vec3};
-use crate::preview_window::PreviewWindow;
+use glam::Vec3;
+use crate::output_manager::OutputManager;
 use crate::scene_state::SceneState;
 
 /// Custom event for animation timer
@@ -31,16 +32,22 @@ pub struct VibeVJApp {
     renderer: Option<Renderer>,
     gui: Option<GuiApp>,
     egui_state: Option<egui_winit::State>,
-    wgpu_instance: wgpu::Instance,
     
     // 3D rendering
     scene_renderer: Option<SceneRenderer>,
     scene_state: SceneState,
+    // Players and sprites driven by the scene's components
+    scene_runtime: SceneRuntime,
     render_target: Option<RenderTarget>,
+    tone_mapper: Option<ToneMapper>,
+    graph_pool: RenderTargetPool,
+    compositor: Option<Compositor>,
+    layer_sources: Option<LayerSources>,
+    texture_cache: Option<TextureCache>,
+    supported_sample_counts: Vec<u32>,
     
-    // Preview window
-    preview_window: Option<PreviewWindow>,
-    show_preview_window: bool,
+    // Output windows
+    output_manager: OutputManager,
     
     // Application state
     scene: Scene,
@@ -53,33 +60,36 @@ pub struct VibeVJApp {
     start_time: Instant,
     last_frame_time: Instant,
     frame_count: u64,
+    // Time of the frame being rendered, for animated layers
+    time_info: TimeInfo,
     
     // Audio data
     frequency_bands: FrequencyBands,
+    beat_detector: BeatDetector,
+    audio_features: AudioFeatures,
 }
 
 impl VibeVJApp {
     /// Create a new VibeVJ application
     pub fn new() -> Result<Self> {
-        // Create WGPU instance once for the entire application
-        let wgpu_instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            ..Default::default()
-        });
-        
         Ok(Self {
             window: None,
             renderer: None,
             gui: None,
             egui_state: None,
-            wgpu_instance,
             
             scene_renderer: None,
             scene_state: SceneState::new(),
+            scene_runtime: SceneRuntime::new(),
             render_target: None,
+            tone_mapper: None,
+            graph_pool: RenderTargetPool::new(),
+            compositor: None,
+            layer_sources: None,
+            texture_cache: None,
+            supported_sample_counts: vec![1],
             
-            preview_window: None,
-            show_preview_window: false,
+            output_manager: OutputManager::new(),
             
             scene: Scene::new("Main Scene".to_string()),
             audio_input: AudioInput::default(),
@@ -90,8 +100,11 @@ impl VibeVJApp {
             start_time: Instant::now(),
             last_frame_time: Instant::now(),
             frame_count: 0,
+            time_info: TimeInfo::default(),
             
             frequency_bands: FrequencyBands::default(),
+            beat_detector: BeatDetector::default(),
+            audio_features: AudioFeatures::default(),
         })
     }
     
@@ -156,45 +169,47 @@ impl VibeVJApp {
         let camera = Camera::new(
             Vec3::new(3.0, 2.0, 5.0),
             Vec3::ZERO,
-            renderer.aspect_ratio(),
+            self.scene.settings.output.aspect_ratio(),
         );
-        let scene_renderer = SceneRenderer::new(&renderer.device, surface_format, camera);
+        // The scene renders in HDR and is tone mapped to the surface format
+        let mut scene_renderer = SceneRenderer::new(&renderer.device, RenderTarget::HDR_FORMAT, camera);
         
-        // Create render target for the 3D scene
-        let render_target = RenderTarget::new(
-            &renderer.device,
-            1280,  // Default render size
-            720,
-            surface_format,
-            Some("Scene Render Target"),
-        );
+        // Pick the scene's MSAA sample count, limited to what the adapter supports
+        let supported_sample_counts = renderer.supported_sample_counts(RenderTarget::HDR_FORMAT);
+        let sample_count = RenderTarget::clamp_sample_count(self.scene.settings.msaa_samples, &supported_sample_counts);
+        scene_renderer.set_sample_count(&renderer.device, sample_count);
+        gui.set_msaa_options(supported_sample_counts.clone(), sample_count);
+        self.supported_sample_counts = supported_sample_counts;
         
-        // Create some test objects
-        let mut cube = RenderObject::new(
-            mesh_gen::create_cube(1.0),
-            Material::unlit(vibevj_common::Color::new(1.0, 0.5, 0.2, 1.0)),
-            Mat4::from_translation(Vec3::new(0.0, 0.0, 0.0)),
-        );
-        cube.upload(
+        // Create display render target at the output render size; the HDR scene
+        // and the layers mixed into it are transient render graph targets of the same size
+        let max_dimension = renderer.device.limits().max_texture_dimension_2d;
+        let (render_width, render_height) = self.scene.settings.output.render_size_within(max_dimension);
+        let render_target = self.graph_pool.acquire(
             &renderer.device,
-            scene_renderer.material_bind_group_layout(),
-            scene_renderer.model_bind_group_layout(),
+            TargetDesc::new(render_width, render_height, surface_format),
+            "Scene Render Target",
         );
+        scene_renderer.set_viewport_size(&renderer.queue, render_target.width, render_target.height);
+        let compositor = Compositor::new(&renderer.device, surface_format, render_target.width, render_target.height);
+        let layer_sources = LayerSources::new(render_target.width, render_target.height);
+        gui.set_mixer(self.scene.settings.mixer.clone());
+        gui.set_output(self.scene.settings.output);
+        gui.set_outputs(self.scene.settings.outputs.clone());
         
-        let mut sphere = RenderObject::new(
-            mesh_gen::create_sphere(0.8, 32, 16),
-            Material::unlit(vibevj_common::Color::new(0.2, 0.5, 1.0, 1.0)),
-            Mat4::from_translation(Vec3::new(-2.5, 0.0, 0.0)),
-        );
-        sphere.upload(
-            &renderer.device,
-            scene_renderer.material_bind_group_layout(),
-            scene_renderer.model_bind_group_layout(),
-        );
+        let tone_mapper = ToneMapper::new(&renderer.device, &renderer.queue, surface_format, self.scene.settings.tone_mapping);
+        gui.set_tone_mapping(self.scene.settings.tone_mapping);
+        gui.set_color_grading(self.scene.settings.color_grading.clone());
+        gui.set_camera(CameraMenu::from_rig(&self.scene.settings.camera));
+        
+        // Create texture cache with fallback textures for materials
+        let texture_cache = TextureCache::new(&renderer.device, &renderer.queue)?;
+        
+        // Create the demo scene
+        self.scene_state.load_demo(&renderer.device, &scene_renderer, &texture_cache, sample_count);
         
-        // Initialize scene state with render objects
-        self.scene_state.render_objects = vec![cube, sphere];
         self.scene_renderer = Some(scene_renderer);
+        self.texture_cache = Some(texture_cache);
         
         // Register render target texture with egui
         let texture_id = gui.register_render_texture(
@@ -206,6 +221,9 @@ impl VibeVJApp {
         log::info!("Registered render texture with ID: {:?}", texture_id);
         
         self.render_target = Some(render_target);
+        self.tone_mapper = Some(tone_mapper);
+        self.compositor = Some(compositor);
+        self.layer_sources = Some(layer_sources);
 
         self.renderer = Some(renderer);
         self.gui = Some(gui);
@@ -232,6 +250,7 @@ impl VibeVJApp {
             delta,
             frame: self.frame_count,
         };
+        self.time_info = time_info;
 
         // Update audio analysis
         let samples = self.audio_input.get_samples();
@@ -240,9 +259,13 @@ impl VibeVJApp {
                 self.frequency_bands = bands;
             }
         }
+        let beat = self.beat_detector.detect(self.frequency_bands.energy(), delta);
+        self.audio_features = self.frequency_bands.to_features(beat);
 
         // Update GUI
         let mut audio_device_to_select: Option<String> = None;
+        let mut camera_change: Option<CameraMenu> = None;
+        let mut camera_input = CameraInput::default();
         
         // Check if we need to populate audio devices
         let needs_audio_devices = self.gui.as_ref().map(|g| !g.has_audio_devices()).unwrap_or(false);
@@ -272,20 +295,35 @@ impl VibeVJApp {
                 audio_device_to_select = Some(device_name.replace(" (Default)", ""));
             }
             
-            gui.update(&time_info);
-            
-            // Check if preview window toggle state has changed
-            let should_show = gui.should_show_preview_window();
-            if should_show != self.show_preview_window {
-                self.show_preview_window = should_show;
-                if !should_show {
-                    // Close preview window
-                    self.preview_window = None;
-                    log::info!("Preview window closed");
+            // MSAA is a scene setting, applied below
+            if let Some(sample_count) = gui.take_msaa_change() {
+                self.scene.settings.msaa_samples = sample_count;
+            }
+            if let Some(tone_mapping) = gui.take_tone_mapping_change() {
+                self.scene.settings.tone_mapping = tone_mapping;
+            }
+            if let Some(color_grading) = gui.take_color_grading_change() {
+                self.scene.settings.color_grading = color_grading;
+            }
+            camera_change = gui.take_camera_change();
+            camera_input = gui.take_camera_input();
+            if let Some(mixer) = gui.take_mixer_change() {
+                self.scene.settings.mixer = mixer;
+            }
+            if let Some(path) = gui.take_video_restart() {
+                if let Some(player) = self.layer_sources.as_mut().and_then(|sources| sources.video_mut(&path)) {
+                    player.seek(0.0);
                 }
-                // Note: Preview window creation is handled in the event loop
-                // because we need access to the EventLoopWindowTarget
             }
+            if let Some(output) = gui.take_output_change() {
+                self.scene.settings.output = output;
+            }
+            // Output windows are opened and closed in the event loop, which can create windows
+            if let Some(outputs) = gui.take_outputs_change() {
+                self.scene.settings.outputs = outputs;
+            }
+            
+            gui.update(&time_info);
         }
         
         // Handle audio device selection outside of GUI borrow
@@ -298,40 +336,100 @@ impl VibeVJApp {
         
         // Preview window texture will be updated in render() method
         
-        // Update scene state
-        self.scene_state.update(elapsed as f32);
-        
-        // Update 3D objects - rotate them
-        if let Some(renderer) = &self.renderer {
-            let rotation_speed = 1.0;
-            let angle = elapsed as f32 * rotation_speed;
-            
-            // Rotate cube around Y axis
-            if !self.scene_state.render_objects.is_empty() {
-                let transform = Mat4::from_translation(Vec3::new(0.0, 0.0, 0.0))
-                    * Mat4::from_rotation_y(angle)
-                    * Mat4::from_rotation_x(angle * 0.5);
-                self.scene_state.render_objects[0].update_transform(&renderer.queue, transform);
+        // Apply scene render settings that changed (from the GUI or a loaded scene)
+        self.apply_msaa_setting();
+        self.apply_output_setting();
+        if let (Some(renderer), Some(tone_mapper)) = (&self.renderer, &mut self.tone_mapper) {
+            if tone_mapper.settings != self.scene.settings.tone_mapping {
+                tone_mapper.update(&renderer.queue, self.scene.settings.tone_mapping);
             }
-            
-            // Rotate sphere around its own axis
-            if self.scene_state.render_objects.len() > 1 {
-                let transform = Mat4::from_translation(Vec3::new(-2.5, 0.0, 0.0))
-                    * Mat4::from_rotation_z(angle * 1.5);
-                self.scene_state.render_objects[1].update_transform(&renderer.queue, transform);
+            // Each scene carries its own grade and LUT
+            if tone_mapper.grading != self.scene.settings.color_grading {
+                tone_mapper.update_grading(&renderer.device, &renderer.queue, &self.scene.settings.color_grading);
             }
         }
         
-        // Update preview window's scene transforms to match main scene
-        if let Some(preview_window) = &mut self.preview_window {
-            let transforms: Vec<_> = self.scene_state.render_objects.iter().map(|obj| obj.transform).collect();
-            preview_window.update_scene(transforms);
+        // Animate the scene with this frame's audio features
+        if let (Some(renderer), Some(scene_renderer)) = (&self.renderer, &mut self.scene_renderer) {
+            // Camera menu changes continue from the current view
+            let rig = &mut self.scene.settings.camera;
+            if let Some(camera) = camera_change {
+                rig.set_controller_kind(camera.kind, scene_renderer.camera());
+                rig.projection = camera.projection;
+                rig.shake.beat_trauma = camera.beat_shake;
+            }
+            rig.update(scene_renderer.camera_mut(), &camera_input, &time_info, &self.audio_features);
+            
+            scene_renderer.update_frame(&renderer.queue, &time_info, &self.audio_features);
+            self.scene_state.animate(
+                &renderer.queue,
+                elapsed,
+                delta,
+                scene_renderer.camera(),
+                &self.audio_features,
+            );
+            self.scene_runtime.update_particles(
+                &renderer.device,
+                &renderer.queue,
+                &self.scene,
+                scene_renderer,
+                delta,
+                &self.audio_features,
+            );
         }
 
         self.last_frame_time = now;
         self.frame_count += 1;
     }
 
+    /// Rebuild render targets and pipelines if the scene's MSAA sample count changed
+    fn apply_msaa_setting(&mut self) {
+        let sample_count = RenderTarget::clamp_sample_count(self.scene.settings.msaa_samples, &self.supported_sample_counts);
+        
+        if let (Some(renderer), Some(scene_renderer)) = (&self.renderer, &mut self.scene_renderer) {
+            if scene_renderer.sample_count() == sample_count {
+                return;
+            }
+            
+            // The render graph picks up the new sample count for its HDR target
+            log::info!("Switching MSAA to {}x", sample_count);
+            scene_renderer.set_sample_count(&renderer.device, sample_count);
+            for system in &mut self.scene_state.particle_systems {
+                system.set_sample_count(&renderer.device, sample_count);
+            }
+            if let Some(gui) = &mut self.gui {
+                gui.set_msaa_options(self.supported_sample_counts.clone(), sample_count);
+            }
+        }
+    }
+
+    /// Resize the render targets and camera if the scene's output settings changed
+    fn apply_output_setting(&mut self) {
+        let output = self.scene.settings.output;
+        
+        if let (Some(renderer), Some(gui), Some(scene_renderer), Some(render_target), Some(compositor), Some(layer_sources)) = (
+            &self.renderer,
+            &mut self.gui,
+            &mut self.scene_renderer,
+            &mut self.render_target,
+            &mut self.compositor,
+            &mut self.layer_sources,
+        ) {
+            // Large resolutions times the render scale can exceed the device's texture limit
+            let (width, height) = output.render_size_within(renderer.device.limits().max_texture_dimension_2d);
+            scene_renderer.camera_mut().update_aspect(output.aspect_ratio());
+            let desc = TargetDesc::new(width, height, render_target.format);
+            if self.graph_pool.resize(&renderer.device, render_target, desc, "Scene Render Target") {
+                log::info!("Rendering at {}x{}", width, height);
+                scene_renderer.set_viewport_size(&renderer.queue, width, height);
+                compositor.resize(&renderer.device, width, height);
+                layer_sources.resize(&renderer.device, width, height);
+                gui.register_render_texture(&renderer.device, &renderer.queue, &render_target.texture, [width, height]);
+            }
+            gui.set_output(output);
+        }
+    }
+
     /// Render a frame
     fn render(&mut self) -> Result<()> {
         let renderer = self.renderer.as_mut().unwrap();
@@ -401,33 +499,114 @@ impl VibeVJApp {
             &screen_descriptor,
         );
 
-        // Render 3D scene to main render target
-        if let (Some(scene_renderer), Some(render_target)) = (&mut self.scene_renderer, &self.render_target) {
-            // Update camera
+        // Node videos and the 2D sprite layer, viewed with the output's aspect
+        if let (Some(scene_renderer), Some(texture_cache)) = (&mut self.scene_renderer, &mut self.texture_cache) {
+            self.scene_runtime.prepare(
+                &renderer.device,
+                &renderer.queue,
+                &mut encoder,
+                &self.scene,
+                scene_renderer,
+                texture_cache,
+                &self.time_info,
+                self.scene.settings.output.aspect_ratio(),
+            );
+        }
+        
+        // Simulate particles before the graph draws them
+        let particle_refs: Vec<&ParticleSystem> =
+            self.scene_state.particle_systems.iter().chain(self.scene_runtime.particles()).collect();
+        for system in &particle_refs {
+            system.simulate(&mut encoder);
+        }
+        let object_refs: Vec<&RenderObject> = self.scene_state.render_objects.iter().collect();
+        let sprite_refs = [self.scene_runtime.sprites()];
+        
+        // Output windows are drawn by this frame's graph and presented with the main window
+        let output_frames = self.output_manager.acquire_frames(&renderer.device);
+        
+        // Frame graph: HDR scene -> tone mapped scene layer -> layer mix into the
+        // main render target -> GUI to the window, and the mix or a layer to each
+        // output window. Scene passes are culled when nothing shows the scene.
+        let mut graph = RenderGraph::new();
+        let surface = graph.import_view("Surface", &view);
+        let mut mixed = None;
+        if let (Some(scene_renderer), Some(render_target), Some(tone_mapper), Some(compositor), Some(layer_sources)) = (
+            &mut self.scene_renderer,
+            &self.render_target,
+            &mut self.tone_mapper,
+            &mut self.compositor,
+            &mut self.layer_sources,
+        ) {
             scene_renderer.update_camera(&renderer.queue);
             
-            // Render 3D objects to render target
-            let object_refs: Vec<&RenderObject> = self.scene_state.render_objects.iter().collect();
-            scene_renderer.render(
+            let mix = graph.import_target("Mix", render_target);
+            mixed = Some(mix);
+            
+            // Shader layers are rendered up front, as both the mix and outputs may show them
+            let mixer = &self.scene.settings.mixer;
+            let outputs = &self.scene.settings.outputs;
+            let output_layers = outputs.iter().filter(|output| output.enabled).filter_map(|output| match &output.source {
+                OutputSource::Layer(source) => Some(source),
+                OutputSource::Mix => None,
+            });
+            layer_sources.prepare(
+                &renderer.device,
+                &renderer.queue,
                 &mut encoder,
-                &render_target.view,
-                &render_target.depth_view,
-                &object_refs,
-                wgpu::Color {
+                mixer.sources().chain(output_layers),
+                scene_renderer.frame_uniform(),
+                &self.time_info,
+                &self.audio_features,
+            );
+            let layer_sources = &*layer_sources;
+            
+            let frame = SceneFrame {
+                renderer: scene_renderer,
+                objects: &object_refs,
+                particles: &particle_refs,
+                lines: &[],
+                sprites: &sprite_refs,
+                clear_color: wgpu::Color {
                     r: 0.1,
                     g: 0.1,
                     b: 0.1,
                     a: 1.0,
                 },
+                width: render_target.width,
+                height: render_target.height,
+            };
+            let scene_layer = frame.add_mixed_passes(&mut graph, tone_mapper, compositor, mixer, layer_sources, mix);
+            
+            let render_size = (render_target.width, render_target.height);
+            self.output_manager.add_passes(
+                &mut graph,
+                &output_frames,
+                outputs,
+                self.scene.settings.output.aspect_mode,
+                |graph, source| match source {
+                    OutputSource::Mix => Some((mix, render_size)),
+                    OutputSource::Layer(LayerSource::Scene) => Some((scene_layer, render_size)),
+                    OutputSource::Layer(layer) => {
+                        let view = layer_sources.view(layer)?;
+                        Some((graph.import_view(layer.name(), view), layer_sources.size(layer)?))
+                    }
+                },
             );
         }
-
-        // Render GUI to window
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        
+        // Render GUI to window, after the mix it displays
+        let mut gui_pass = graph.add_pass("GUI").write(surface);
+        if let Some(mix) = mixed {
+            gui_pass = gui_pass.read(mix);
+        }
+        let egui_renderer = gui.renderer_mut();
+        gui_pass.execute(move |ctx| {
+            let surface_view = ctx.view(surface);
+            let mut render_pass = ctx.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("GUI Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
+                    view: surface_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color {
@@ -452,12 +631,14 @@ impl VibeVJApp {
             let render_pass_static: &mut wgpu::RenderPass<'static> = unsafe {
                 std::mem::transmute(&mut render_pass)
             };
-            gui.renderer_mut().render(
+            egui_renderer.render(
                 render_pass_static,
                 &clipped_primitives,
                 &screen_descriptor,
             );
-        } // render_pass dropped here
+        }); // render_pass dropped here
+        graph.execute(&renderer.device, &renderer.queue, &mut encoder, &mut self.graph_pool)?;
+        self.graph_pool.end_frame();
 
         // Free egui textures
         for id in &full_output.textures_delta.free {
@@ -467,6 +648,7 @@ impl VibeVJApp {
         // Submit commands
         renderer.queue.submit(Some(encoder.finish()));
         output.present();
+        OutputManager::present(output_frames);
 
         Ok(())
     }
@@ -488,9 +670,6 @@ impl VibeVJApp {
                         if let Some(window) = &self.window {
                             window.request_redraw();
                         }
-                        if let Some(preview_window) = &self.preview_window {
-                            preview_window.window.request_redraw();
-                        }
                     }
                 }
                 Event::Resumed => {
@@ -512,43 +691,18 @@ impl VibeVJApp {
                     }
                 }
                 Event::WindowEvent { event, window_id } => {
-                    // Check if this is the preview window
-                    let is_preview_window = self.preview_window.as_ref()
-                        .map(|pw| pw.window.id() == window_id)
-                        .unwrap_or(false);
-                    
-                    if is_preview_window {
-                        // Handle preview window events
-                        // First, handle close request which will set preview_window to None
-                        if matches!(event, WindowEvent::CloseRequested) {
-                            self.preview_window = None;
-                            self.show_preview_window = false;
-                            if let Some(gui) = &mut self.gui {
-                                gui.set_show_preview_window(false);
-                            }
-                            log::info!("Preview window closed by user");
-                            return;
-                        }
-                        
-                        // For all other events, only process if preview window still exists
-                        if let Some(preview_window) = &mut self.preview_window {
-                            // Handle input (fullscreen toggle)
-                            preview_window.handle_input(&event);
-                            
-                            match event {
-                                WindowEvent::Resized(physical_size) => {
-                                    preview_window.resize(physical_size);
-                                }
-                                WindowEvent::RedrawRequested => {
-                                    log::debug!("Preview window: RedrawRequested");
-                                    if let Err(e) = preview_window.render() {
-                                        log::error!("Preview window render error: {}", e);
-                                    }
-                                    // Note: Redraw requests now handled by AnimationTick from timer thread
+                    // Output window events
+                    if let Some(index) = self.output_manager.output_index(window_id) {
+                        let outputs = &mut self.scene.settings.outputs;
+                        if let (Some(renderer), Some(settings)) = (&self.renderer, outputs.get_mut(index)) {
+                            // Closing the window or editing its warp changes the output settings
+                            if self.output_manager.handle_window_event(index, &renderer.device, &event, settings) {
+                                if let Some(gui) = &mut self.gui {
+                                    gui.set_outputs(outputs.clone());
                                 }
-                                _ => {}
                             }
                         }
+                        // Output windows are drawn with the main window's frame
                         return;
                     }
                     
@@ -588,48 +742,14 @@ impl VibeVJApp {
                     }
                 }
                 Event::AboutToWait => {
-                    // Create preview window if needed
-                    if self.show_preview_window && self.preview_window.is_none() && self.renderer.is_some() {
-                        let renderer = self.renderer.as_ref().unwrap();
-                        let instance = &self.wgpu_instance;
-                        
-                        // Create window for preview
-                        let window_attributes = winit::window::Window::default_attributes()
-                            .with_title("VibeVJ - Preview (Press F for fullscreen)")
-                            .with_inner_size(winit::dpi::LogicalSize::new(1280, 720));
-                        
-                        match elwt.create_window(window_attributes) {
-                            Ok(window) => {
-                                let window = Arc::new(window);
-                                pollster::block_on(async {
-                                    match PreviewWindow::new(window, &renderer.device, instance).await {
-                                        Ok(mut pw) => {
-                                            // Initialize preview window with scene objects
-                                            let mesh_material_data: Vec<_> = self.scene_state.render_objects.iter().map(|obj| {
-                                                (obj.mesh.clone(), obj.material.clone(), obj.transform)
-                                            }).collect();
-                                            pw.init_scene_objects(mesh_material_data);
-                                            
-                                            log::info!("Preview window created successfully");
-                                            self.preview_window = Some(pw);
-                                        }
-                                        Err(e) => {
-                                            log::error!("Failed to create preview window: {}", e);
-                                            self.show_preview_window = false;
-                                            if let Some(gui) = &mut self.gui {
-                                                gui.set_show_preview_window(false);
-                                            }
-                                        }
-                                    }
-                                });
-                            }
-                            Err(e) => {
-                                log::error!("Failed to create preview window: {}", e);
-                                self.show_preview_window = false;
-                                if let Some(gui) = &mut self.gui {
-                                    gui.set_show_preview_window(false);
-                                }
+                    // Open, close and place output windows to match the scene settings
+                    if let Some(renderer) = &self.renderer {
+                        let disabled = self.output_manager.sync(elwt, renderer, &mut self.scene.settings.outputs);
+                        if let Some(gui) = &mut self.gui {
+                            if disabled {
+                                gui.set_outputs(self.scene.settings.outputs.clone());
                             }
+                            gui.set_monitors(OutputManager::monitor_names(elwt));
                         }
                     }
                     
@@ -0,0 +1,227 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use vibevj_audio::FileAudioSource;
+use vibevj_common::{AudioFeatures, TimeInfo};
+use vibevj_engine::{Camera, CameraController, CameraControllerKind, CameraInput, CameraRig, ExportFormat, ExportSettings, HeadlessRenderer, RenderObject, RenderTarget};
+use vibevj_engine::{Compositor, LayerSources, ParticleSystem, RenderGraph, TextureCache, ToneMapper, RenderTargetPool};
+use vibevj_scene::{Scene, SceneFrame, SceneRenderer, SceneRuntime};
+use glam::Vec3;
+use crate::scene_state::SceneState;
+
+/// Longest wait for a video frame to decode before reusing the previous one
+const EXPORT_VIDEO_WAIT: Duration = Duration::from_secs(5);
+
+/// Parse `--export` command line options
+/// Returns `None` when the application should start normally
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Option<ExportSettings>> {
+    let mut args = args.into_iter();
+    let mut settings = ExportSettings::default();
+    let mut export = false;
+    let mut format_override = None;
+    let mut camera_kind = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| anyhow!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--export" => {
+                settings.output = PathBuf::from(value()?);
+                export = true;
+            }
+            "--format" => {
+                format_override = Some(match value()?.as_str() {
+                    "png" => ExportFormat::PngSequence,
+                    "y4m" => ExportFormat::Y4m,
+                    other => bail!("Unknown export format '{}' (expected png or y4m)", other),
+                })
+            }
+            "--width" => settings.width = value()?.parse().context("Invalid --width")?,
+            "--height" => settings.height = value()?.parse().context("Invalid --height")?,
+            "--fps" => settings.fps = value()?.parse().context("Invalid --fps")?,
+            "--duration" => settings.duration = value()?.parse().context("Invalid --duration")?,
+            "--start-frame" => settings.start_frame = value()?.parse().context("Invalid --start-frame")?,
+            "--end-frame" => settings.end_frame = Some(value()?.parse().context("Invalid --end-frame")?),
+            "--audio" => settings.audio = Some(PathBuf::from(value()?)),
+            "--scene" => settings.scene = Some(PathBuf::from(value()?)),
+            "--alpha" => settings.alpha = true,
+            "--camera" => {
+                camera_kind = Some(match value()?.as_str() {
+                    "static" => CameraControllerKind::Static,
+                    "orbit" => CameraControllerKind::Orbit,
+                    "path" => CameraControllerKind::Path,
+                    "beat-orbit" => CameraControllerKind::BeatOrbit,
+                    "beat-cuts" => CameraControllerKind::BeatCuts,
+                    other => bail!("Unknown camera '{}' (expected static, orbit, path, beat-orbit or beat-cuts)", other),
+                })
+            }
+            _ => {}
+        }
+    }
+
+    if !export {
+        return Ok(None);
+    }
+    if settings.width == 0 || settings.height == 0 || settings.fps == 0 {
+        bail!("Export width, height and fps must be greater than zero");
+    }
+    settings.format = format_override.unwrap_or_else(|| ExportFormat::from_path(&settings.output));
+    if let Some(kind) = camera_kind {
+        settings.camera = Some(CameraRig {
+            controller: CameraController::from_camera(kind, &export_camera(&settings)),
+            ..Default::default()
+        });
+    }
+    Ok(Some(settings))
+}
+
+/// Render the scene offline, frame by frame
+///
+/// Frames go through the same passes as the main view: the scene with its
+/// sprites and videos, tone mapped and graded with the scene's settings,
+/// then mixed with the other layers of its mixer. Time advances by exactly
+/// `1 / fps` per frame and audio features come from the given file (or
+/// silence), so the same settings always produce the same frames. Frames
+/// before `start_frame` are simulated but not written, which keeps
+/// particles and beat detection identical for partial renders.
+pub fn run(settings: &ExportSettings) -> Result<()> {
+    let range = settings.frame_range();
+    log::info!(
+        "Exporting frames {}..{} at {}x{}, {} fps to {}",
+        range.start,
+        range.end,
+        settings.width,
+        settings.height,
+        settings.fps,
+        settings.output.display()
+    );
+
+    let headless = HeadlessRenderer::new_blocking()?;
+    let device = &headless.device;
+    let queue = &headless.queue;
+
+    let mut audio = match &settings.audio {
+        Some(path) => Some(FileAudioSource::open_wav(path)?),
+        None => None,
+    };
+
+    let scene = match &settings.scene {
+        Some(path) => {
+            let json = std::fs::read_to_string(path).with_context(|| format!("Failed to read scene {}", path.display()))?;
+            Scene::from_json(&json)?
+        }
+        None => Scene::new("Main Scene".to_string()),
+    };
+
+    let mut scene_renderer = SceneRenderer::new(device, RenderTarget::HDR_FORMAT, export_camera(settings));
+    let supported_sample_counts = headless.supported_sample_counts(RenderTarget::HDR_FORMAT);
+    let sample_count = RenderTarget::clamp_sample_count(scene.settings.msaa_samples, &supported_sample_counts);
+    scene_renderer.set_sample_count(device, sample_count);
+
+    scene_renderer.set_viewport_size(queue, settings.width, settings.height);
+    let output_target = headless.create_render_target(
+        settings.width,
+        settings.height,
+        wgpu::TextureFormat::Rgba8UnormSrgb,
+    );
+
+    let mut tone_mapper = ToneMapper::new(device, queue, output_target.format, scene.settings.tone_mapping);
+    tone_mapper.update_grading(device, queue, &scene.settings.color_grading);
+    let mut compositor = Compositor::new(device, output_target.format, settings.width, settings.height);
+    let mut graph_pool = RenderTargetPool::new();
+
+    let mut texture_cache = TextureCache::new(device, queue)?;
+    let mut scene_state = SceneState::new();
+    scene_state.load_demo(device, &scene_renderer, &texture_cache, sample_count);
+    // Videos block for each frame so every export shows the same ones
+    let mut scene_runtime = SceneRuntime::new().with_video_wait(EXPORT_VIDEO_WAIT);
+    let mut layer_sources = LayerSources::new(settings.width, settings.height).with_video_wait(EXPORT_VIDEO_WAIT);
+    let aspect = settings.width as f32 / settings.height as f32;
+
+    let clear_color = wgpu::Color {
+        r: 0.1,
+        g: 0.1,
+        b: 0.1,
+        a: if settings.alpha { 0.0 } else { 1.0 },
+    };
+
+    let mut writer = settings.create_writer()?;
+    let mut camera_rig = settings.camera.clone().unwrap_or_else(|| scene.settings.camera.clone());
+
+    for frame in 0..range.end {
+        let time = TimeInfo::fixed_step(frame, settings.fps as f64);
+        let features = match &mut audio {
+            Some(source) => source.features_at(&time)?,
+            None => AudioFeatures::default(),
+        };
+
+        // Move the camera first, as the main view does, so animation and
+        // particle billboards see this frame's camera
+        camera_rig.update(scene_renderer.camera_mut(), &CameraInput::default(), &time, &features);
+        scene_renderer.update_frame(queue, &time, &features);
+        scene_state.animate(queue, time.elapsed, time.delta, scene_renderer.camera(), &features);
+        scene_runtime.update_particles(device, queue, &scene, &scene_renderer, time.delta, &features);
+        scene_renderer.update_camera(queue);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Export Encoder"),
+        });
+        for system in scene_state.particle_systems.iter().chain(scene_runtime.particles()) {
+            system.simulate(&mut encoder);
+        }
+        scene_runtime.prepare(device, queue, &mut encoder, &scene, &mut scene_renderer, &mut texture_cache, &time, aspect);
+        let mixer = &scene.settings.mixer;
+        layer_sources.prepare(
+            device,
+            queue,
+            &mut encoder,
+            mixer.sources(),
+            scene_renderer.frame_uniform(),
+            &time,
+            &features,
+        );
+
+        if frame < range.start {
+            // Pre-roll: advance the simulation without rendering
+            queue.submit(Some(encoder.finish()));
+            continue;
+        }
+
+        let object_refs: Vec<&RenderObject> = scene_state.render_objects.iter().collect();
+        let particle_refs: Vec<&ParticleSystem> = scene_state.particle_systems.iter().chain(scene_runtime.particles()).collect();
+        let sprite_refs = [scene_runtime.sprites()];
+        let mut graph = RenderGraph::new();
+        let output = graph.import_target("Export Output", &output_target);
+        let scene_frame = SceneFrame {
+            renderer: &scene_renderer,
+            objects: &object_refs,
+            particles: &particle_refs,
+            lines: &[],
+            sprites: &sprite_refs,
+            clear_color,
+            width: settings.width,
+            height: settings.height,
+        };
+        scene_frame.add_mixed_passes(&mut graph, &mut tone_mapper, &mut compositor, mixer, &layer_sources, output);
+        graph.execute(device, queue, &mut encoder, &mut graph_pool)?;
+
+        queue.submit(Some(encoder.finish()));
+        let pixels = headless.read_rgba(&output_target)?;
+        writer.write_frame(&pixels)?;
+
+        log::info!("Exported frame {} / {}", frame + 1 - range.start, range.end - range.start);
+    }
+
+    writer.finish()?;
+    log::info!("Export finished: {}", settings.output.display());
+    Ok(())
+}
+
+/// Starting camera for an export, at the output aspect ratio
+fn export_camera(settings: &ExportSettings) -> Camera {
+    Camera::new(
+        Vec3::new(3.0, 2.0, 5.0),
+        Vec3::ZERO,
+        settings.width as f32 / settings.height as f32,
+    )
+}
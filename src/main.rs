@@ -1,5 +1,7 @@
 mod app;
-mod preview_window;
+mod export;
+mod output_manager;
+mod output_window;
 mod scene_state;
 
 use app::{VibeVJApp, AppEvent};
@@ -16,6 +18,11 @@ fn main() -> Result<()> {
 
     log::info!("Starting VibeVJ v{}", env!("CARGO_PKG_VERSION"));
 
+    // Offline export runs headless and exits without opening a window
+    if let Some(settings) = export::parse_args(std::env::args().skip(1))? {
+        return export::run(&settings);
+    }
+
     // Create event loop with custom events
     let event_loop = EventLoop::<AppEvent>::with_user_event().build()?;
     let event_loop_proxy = event_loop.create_proxy();
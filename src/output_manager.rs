@@ -0,0 +1,192 @@
+use anyhow::Result;
+use std::sync::Arc;
+use winit::event::WindowEvent;
+use winit::event_loop::ActiveEventLoop;
+use winit::monitor::MonitorHandle;
+use winit::window::{Window, WindowId};
+use vibevj_engine::{AspectMode, OutputSource, OutputWindowSettings, RenderGraph, Renderer, ResourceId};
+use crate::output_window::OutputWindow;
+
+/// Surface texture of an output window, acquired for one frame
+pub struct OutputFrame {
+    index: usize,
+    texture: wgpu::SurfaceTexture,
+    view: wgpu::TextureView,
+}
+
+/// Keeps one window open per enabled output in the scene settings
+///
+/// Windows line up with the settings list by index. Each frame the main
+/// render graph gets a pass per window that warps the output's source.
+#[derive(Default)]
+pub struct OutputManager {
+    windows: Vec<Option<OutputWindow>>,
+}
+
+impl OutputManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open and close windows to match `outputs` and move them to their monitors
+    /// Outputs whose window fails to open are disabled; returns true if any were
+    pub fn sync(&mut self, event_loop: &ActiveEventLoop, renderer: &Renderer, outputs: &mut [OutputWindowSettings]) -> bool {
+        let monitors: Vec<MonitorHandle> = event_loop.available_monitors().collect();
+        self.windows.resize_with(outputs.len(), || None);
+
+        let mut disabled = false;
+        for (slot, settings) in self.windows.iter_mut().zip(outputs.iter_mut()) {
+            if !settings.enabled {
+                if slot.take().is_some() {
+                    log::info!("Output window '{}' closed", settings.name);
+                }
+                continue;
+            }
+            if slot.is_none() {
+                match Self::open(event_loop, renderer, settings) {
+                    Ok(window) => {
+                        log::info!("Output window '{}' opened", settings.name);
+                        *slot = Some(window);
+                    }
+                    Err(e) => {
+                        log::error!("Failed to open output window '{}': {}", settings.name, e);
+                        settings.enabled = false;
+                        disabled = true;
+                        continue;
+                    }
+                }
+            }
+            if let Some(window) = slot {
+                window.apply_settings(settings, &monitors);
+            }
+        }
+        disabled
+    }
+
+    fn open(event_loop: &ActiveEventLoop, renderer: &Renderer, settings: &OutputWindowSettings) -> Result<OutputWindow> {
+        let window_attributes = Window::default_attributes()
+            .with_title(OutputWindow::title(&settings.name))
+            .with_inner_size(winit::dpi::LogicalSize::new(1280, 720));
+        let window = Arc::new(event_loop.create_window(window_attributes)?);
+        OutputWindow::new(window, renderer)
+    }
+
+    /// Names of the connected monitors, for choosing where outputs open
+    pub fn monitor_names(event_loop: &ActiveEventLoop) -> Vec<String> {
+        event_loop.available_monitors().filter_map(|monitor| monitor.name()).collect()
+    }
+
+    /// Index of the output shown in a window
+    pub fn output_index(&self, window_id: WindowId) -> Option<usize> {
+        self.windows
+            .iter()
+            .position(|window| window.as_ref().is_some_and(|window| window.window.id() == window_id))
+    }
+
+    /// Handle an event of output `index`'s window, whose settings are `settings`
+    /// Returns true if the settings changed: the user closed the window or edited its warp
+    pub fn handle_window_event(
+        &mut self,
+        index: usize,
+        device: &wgpu::Device,
+        event: &WindowEvent,
+        settings: &mut OutputWindowSettings,
+    ) -> bool {
+        let Some(slot) = self.windows.get_mut(index) else { return false };
+        if matches!(event, WindowEvent::CloseRequested) {
+            // Closing the window disables its output
+            *slot = None;
+            settings.enabled = false;
+            return true;
+        }
+        let Some(window) = slot else { return false };
+        match event {
+            WindowEvent::Resized(physical_size) => {
+                window.resize(device, *physical_size);
+                false
+            }
+            _ => {
+                window.handle_input(event);
+                window.handle_warp_input(event, &mut settings.warp)
+            }
+        }
+    }
+
+    /// Get the next surface texture of every open window
+    pub fn acquire_frames(&mut self, device: &wgpu::Device) -> Vec<OutputFrame> {
+        let mut frames = Vec::new();
+        for (index, window) in self.windows.iter_mut().enumerate() {
+            let Some(window) = window else { continue };
+            match window.acquire(device) {
+                Ok(texture) => {
+                    let view = texture.texture.create_view(&wgpu::TextureViewDescriptor::default());
+                    frames.push(OutputFrame { index, texture, view });
+                }
+                Err(e) => log::error!("Output window render error: {}", e),
+            }
+        }
+        frames
+    }
+
+    /// Add a pass per frame that warps the output's source into its window
+    ///
+    /// `source` adds or looks up the graph resource of an output source and
+    /// returns it with its size, or `None` if it has nothing to show.
+    pub fn add_passes<'a>(
+        &'a mut self,
+        graph: &mut RenderGraph<'a>,
+        frames: &'a [OutputFrame],
+        outputs: &[OutputWindowSettings],
+        aspect_mode: AspectMode,
+        mut source: impl FnMut(&mut RenderGraph<'a>, &OutputSource) -> Option<(ResourceId, (u32, u32))>,
+    ) {
+        for (index, window) in self.windows.iter_mut().enumerate() {
+            let (Some(window), Some(frame), Some(settings)) = (
+                window.as_mut(),
+                frames.iter().find(|frame| frame.index == index),
+                outputs.get(index),
+            ) else {
+                continue;
+            };
+            let target = graph.import_view(&settings.name, &frame.view);
+            let input = source(graph, &settings.source);
+            let window_size = window.size();
+            let fit = input.map(|(_, size)| {
+                settings.fit(
+                    aspect_mode,
+                    (size.0 as f32, size.1 as f32),
+                    (window_size.0 as f32, window_size.1 as f32),
+                )
+            });
+            window.fit = fit;
+            let (handles, selected) = match (&window.editor, &fit) {
+                (Some(editor), Some(fit)) => (settings.warp.handles(editor.handles, fit, window_size), editor.selected),
+                _ => (Vec::new(), None),
+            };
+
+            let window: &'a OutputWindow = window;
+            let warp = settings.warp.clone();
+            let mut pass = graph.add_pass(&format!("Output {}", settings.name)).write(target);
+            match (input, fit) {
+                (Some((input, _)), Some(fit)) => {
+                    pass = pass.read(input);
+                    pass.execute(move |ctx| {
+                        let (source, target) = (ctx.view(input), ctx.view(target));
+                        window.warper.render(ctx.device, ctx.encoder, source, target, window_size, &fit, &warp, &handles, selected);
+                    });
+                }
+                _ => pass.execute(move |ctx| {
+                    let target = ctx.view(target);
+                    window.warper.clear(ctx.encoder, target);
+                }),
+            }
+        }
+    }
+
+    /// Show the frames after the graph that drew them was submitted
+    pub fn present(frames: Vec<OutputFrame>) {
+        for frame in frames {
+            frame.texture.present();
+        }
+    }
+}
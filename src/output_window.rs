@@ -0,0 +1,218 @@
+use anyhow::Result;
+use std::sync::Arc;
+use winit::monitor::MonitorHandle;
+use winit::window::{Window, Fullscreen};
+use winit::event::{ElementState, MouseButton, WindowEvent, KeyEvent};
+use winit::keyboard::{KeyCode, PhysicalKey};
+use vibevj_engine::{AspectFit, OutputWindowSettings, Renderer, WarpHandles, WarpSettings, Warper};
+
+/// Warp handles being edited in an output window
+#[derive(Debug, Default)]
+pub struct WarpEditor {
+    pub handles: WarpHandles,
+    pub selected: Option<usize>,
+    dragging: bool,
+    /// Last cursor position in window coordinates (0..1)
+    cursor: [f32; 2],
+}
+
+/// A window showing one output of the show
+///
+/// The window presents with the main renderer's device; the main render
+/// graph warps the output's source into its surface each frame.
+pub struct OutputWindow {
+    pub window: Arc<Window>,
+    surface: wgpu::Surface<'static>,
+    config: wgpu::SurfaceConfiguration,
+
+    // Draws the output source to the surface with its aspect handling and warp
+    pub warper: Warper,
+    /// Where the source was placed last frame, used to map handle drags
+    pub fit: Option<AspectFit>,
+    /// Set while the warp edit mode is on
+    pub editor: Option<WarpEditor>,
+
+    // Name, monitor and fullscreen state the window was last set up with
+    name: String,
+    placement: Option<(Option<String>, bool)>,
+}
+
+impl OutputWindow {
+    /// Create an output window presenting with the renderer's device
+    pub fn new(window: Arc<Window>, renderer: &Renderer) -> Result<Self> {
+        let (surface, config) = renderer.create_window_surface(window.clone())?;
+        let warper = Warper::new(&renderer.device, config.format);
+
+        Ok(Self {
+            window,
+            surface,
+            config,
+            warper,
+            fit: None,
+            editor: None,
+            name: String::new(),
+            placement: None,
+        })
+    }
+
+    /// Window title for an output
+    pub fn title(name: &str) -> String {
+        format!("VibeVJ - {} (F: fullscreen, E: edit warp, Tab: switch handles)", name)
+    }
+
+    /// Rename the window and move it to its monitor when the settings changed
+    pub fn apply_settings(&mut self, settings: &OutputWindowSettings, monitors: &[MonitorHandle]) {
+        if self.name != settings.name {
+            self.name = settings.name.clone();
+            self.window.set_title(&Self::title(&self.name));
+        }
+
+        let placement = (settings.monitor.clone(), settings.fullscreen);
+        if self.placement.as_ref() == Some(&placement) {
+            return;
+        }
+        let monitor = settings
+            .monitor
+            .as_ref()
+            .and_then(|name| monitors.iter().find(|monitor| monitor.name().as_ref() == Some(name)))
+            .cloned();
+        if let Some(monitor) = &monitor {
+            self.window.set_outer_position(monitor.position());
+        } else if settings.monitor.is_some() {
+            log::warn!("Monitor for output '{}' is not connected", settings.name);
+        }
+        self.window.set_fullscreen(settings.fullscreen.then(|| Fullscreen::Borderless(monitor)));
+        self.placement = Some(placement);
+    }
+
+    /// Surface size in pixels
+    pub fn size(&self) -> (u32, u32) {
+        (self.config.width, self.config.height)
+    }
+
+    /// Get the window's next surface texture, reconfiguring the surface if it changed
+    pub fn acquire(&mut self, device: &wgpu::Device) -> Result<wgpu::SurfaceTexture> {
+        match self.surface.get_current_texture() {
+            Ok(texture) => Ok(texture),
+            Err(e) => {
+                // Surface has changed, need to reconfigure
+                let err_str = e.to_string();
+                if err_str.contains("surface has changed") ||
+                   err_str.contains("swap chain must be updated") ||
+                   err_str.contains("Timeout") ||
+                   err_str.contains("outdated") {
+                    log::info!("Output window surface changed, reconfiguring...");
+                    let size = self.window.inner_size();
+                    self.resize(device, size);
+                    // Try again after reconfiguration
+                    self.surface
+                        .get_current_texture()
+                        .map_err(|e| anyhow::anyhow!("Failed to acquire surface texture after reconfigure: {}", e))
+                } else {
+                    Err(anyhow::anyhow!("Failed to acquire surface texture: {}", e))
+                }
+            }
+        }
+    }
+
+    /// Handle keyboard input for fullscreen toggle
+    pub fn handle_input(&self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::KeyboardInput {
+                event: KeyEvent {
+                    physical_key: PhysicalKey::Code(KeyCode::KeyF),
+                    state: winit::event::ElementState::Pressed,
+                    ..
+                },
+                ..
+            } => {
+                // Toggle fullscreen
+                if self.window.fullscreen().is_some() {
+                    self.window.set_fullscreen(None);
+                    log::info!("Output window '{}': Exited fullscreen", self.name);
+                } else {
+                    let monitor = self.window.current_monitor();
+                    self.window.set_fullscreen(Some(Fullscreen::Borderless(monitor)));
+                    log::info!("Output window '{}': Entered fullscreen", self.name);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Edit the warp with the mouse while edit mode is on
+    /// Returns true if `warp` changed
+    pub fn handle_warp_input(&mut self, event: &WindowEvent, warp: &mut WarpSettings) -> bool {
+        if let WindowEvent::KeyboardInput {
+            event: KeyEvent {
+                physical_key: PhysicalKey::Code(code),
+                state: ElementState::Pressed,
+                ..
+            },
+            ..
+        } = event
+        {
+            match code {
+                KeyCode::KeyE => {
+                    self.editor = match self.editor {
+                        Some(_) => None,
+                        None => Some(WarpEditor::default()),
+                    };
+                    log::info!("Output window '{}': Warp edit mode {}", self.name, if self.editor.is_some() { "on" } else { "off" });
+                }
+                KeyCode::Tab => {
+                    if let Some(editor) = &mut self.editor {
+                        editor.handles = editor.handles.next();
+                        editor.selected = None;
+                    }
+                }
+                _ => {}
+            }
+            return false;
+        }
+
+        let (Some(editor), Some(fit)) = (&mut self.editor, self.fit) else { return false };
+        let size = (self.config.width, self.config.height);
+        match event {
+            WindowEvent::CursorMoved { position, .. } => {
+                editor.cursor = [position.x as f32 / size.0 as f32, position.y as f32 / size.1 as f32];
+                if let (true, Some(index)) = (editor.dragging, editor.selected) {
+                    warp.move_handle(editor.handles, index, editor.cursor, &fit, size);
+                    return true;
+                }
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                editor.dragging = *state == ElementState::Pressed;
+                if editor.dragging {
+                    // Pick the nearest handle under the cursor
+                    let pick_radius = Warper::HANDLE_SIZE * 1.5;
+                    let cursor = [editor.cursor[0] * size.0 as f32, editor.cursor[1] * size.1 as f32];
+                    editor.selected = warp
+                        .handles(editor.handles, &fit, size)
+                        .iter()
+                        .map(|handle| (handle[0] * size.0 as f32 - cursor[0]).hypot(handle[1] * size.1 as f32 - cursor[1]))
+                        .enumerate()
+                        .filter(|&(_, distance)| distance <= pick_radius)
+                        .min_by(|a, b| a.1.total_cmp(&b.1))
+                        .map(|(index, _)| index);
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Handle window resize
+    pub fn resize(&mut self, device: &wgpu::Device, new_size: winit::dpi::PhysicalSize<u32>) {
+        if new_size.width > 0 && new_size.height > 0 {
+            self.config.width = new_size.width;
+            self.config.height = new_size.height;
+            self.surface.configure(device, &self.config);
+        }
+    }
+}
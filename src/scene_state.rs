@@ -1,10 +1,15 @@
-use vibevj_engine::{RenderObject, Camera};
+use glam::{Mat4, Vec3};
+use vibevj_common::{AudioBand, AudioFeatures, Color};
+use vibevj_engine::{RenderObject, Camera, ParticleSystem, Material, Displacement, mesh_gen, TextureCache, RenderTarget};
+use vibevj_engine::{ParticleSettings, EmitterShape, BurstTrigger};
+use vibevj_scene::SceneRenderer;
 
 /// Shared state for rendering the 3D scene
 /// This allows rendering the same scene on multiple devices
 pub struct SceneState {
     pub camera: Camera,
     pub render_objects: Vec<RenderObject>,
+    pub particle_systems: Vec<ParticleSystem>,
     pub time: f32,
 }
 
@@ -20,6 +25,7 @@ impl SceneState {
         Self {
             camera,
             render_objects: Vec::new(),
+            particle_systems: Vec::new(),
             time: 0.0,
         }
     }
@@ -29,6 +35,98 @@ impl SceneState {
     pub fn update(&mut self, time: f32) {
         self.time = time;
     }
+
+    /// Replace the scene contents with the demo objects
+    /// (a lit cube, a displaced PBR sphere and audio-reactive particles around it)
+    pub fn load_demo(
+        &mut self,
+        device: &wgpu::Device,
+        scene_renderer: &SceneRenderer,
+        texture_cache: &TextureCache,
+        sample_count: u32,
+    ) {
+        let mut cube = RenderObject::new(
+            mesh_gen::create_cube(1.0),
+            Material::unlit(Color::new(1.0, 0.5, 0.2, 1.0)),
+            Mat4::from_translation(Vec3::new(0.0, 0.0, 0.0)),
+        );
+        cube.upload(
+            device,
+            scene_renderer.material_bind_group_layout(),
+            scene_renderer.model_bind_group_layout(),
+            texture_cache,
+        );
+
+        // Noise-deformed sphere pulsing with the bass
+        let mut sphere = RenderObject::new(
+            mesh_gen::create_icosphere(0.8, 5),
+            Material::pbr(Color::new(0.2, 0.5, 1.0, 1.0), 0.8, 0.3)
+                .with_displacement(Displacement::pulse(0.08, 1.5, AudioBand::Bass)),
+            Mat4::from_translation(Vec3::new(-2.5, 0.0, 0.0)),
+        );
+        sphere.upload(
+            device,
+            scene_renderer.material_bind_group_layout(),
+            scene_renderer.model_bind_group_layout(),
+            texture_cache,
+        );
+
+        // Audio-reactive particles around the sphere: bass drives the rate, beats burst
+        let mut particles = ParticleSystem::new(
+            device,
+            scene_renderer.camera_bind_group_layout(),
+            RenderTarget::HDR_FORMAT,
+            sample_count,
+            ParticleSettings {
+                emitter: EmitterShape::Sphere { radius: 0.9 },
+                rate_band: Some(AudioBand::Bass),
+                rate_audio_gain: 4.0,
+                burst_trigger: BurstTrigger::Beat,
+                ..Default::default()
+            },
+            None,
+        );
+        particles.transform = Mat4::from_translation(Vec3::new(-2.5, 0.0, 0.0));
+
+        self.render_objects = vec![cube, sphere];
+        self.particle_systems = vec![particles];
+    }
+
+    /// Animate the demo objects and advance particles by `delta` seconds
+    /// Depends only on its inputs, so offline export reproduces it exactly
+    pub fn animate(
+        &mut self,
+        queue: &wgpu::Queue,
+        elapsed: f64,
+        delta: f32,
+        camera: &Camera,
+        audio: &AudioFeatures,
+    ) {
+        self.update(elapsed as f32);
+
+        let rotation_speed = 1.0;
+        let angle = elapsed as f32 * rotation_speed;
+
+        // Rotate cube around Y axis
+        if !self.render_objects.is_empty() {
+            let transform = Mat4::from_translation(Vec3::new(0.0, 0.0, 0.0))
+                * Mat4::from_rotation_y(angle)
+                * Mat4::from_rotation_x(angle * 0.5);
+            self.render_objects[0].update_transform(queue, transform);
+        }
+
+        // Rotate sphere around its own axis
+        if self.render_objects.len() > 1 {
+            let transform = Mat4::from_translation(Vec3::new(-2.5, 0.0, 0.0))
+                * Mat4::from_rotation_z(angle * 1.5);
+            self.render_objects[1].update_transform(queue, transform);
+        }
+
+        // Advance particle emission with this frame's audio features
+        for system in &mut self.particle_systems {
+            system.update(queue, delta, camera, audio);
+        }
+    }
 }
 
 impl Default for SceneState {